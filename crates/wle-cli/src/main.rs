@@ -1,265 +1,4203 @@
-use clap::{Args as ClapArgs, Parser, Subcommand};
-use std::path::PathBuf;
+use clap::{Args as ClapArgs, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  success
+  1  the check found something: diff saw changes, info/validate/verify saw problems
+  2  an input could not be read or parsed, or the command line is invalid
+  3  not found or invalid: pointer, mission, stat, wrong file type, bad value
+  4  the edit could not be applied
+  5  writing the output, a backup or the save failed
+  130  cancelled with Ctrl+C while reading, converting or encoding a save
+
+With --format json or compact, errors are printed to stderr as
+{\"error\": \"...\", \"code\": N}.";
 
 #[derive(Parser, Debug)]
 #[command(
     name = "wle-cli",
     about = "Dump and edit Wobbly Life saves via generic JSON Pointer API",
-    version
+    version,
+    after_help = EXIT_CODES_HELP
 )]
 struct Cli {
+    /// pretty: text tables and indented JSON; json / compact: JSON results
+    /// (indented / one line) and JSON error objects, for scripts
+    #[arg(long, global = true, value_enum, default_value_t = Format::Pretty)]
+    format: Format,
+    /// Suppress progress notes on stderr (backups taken, files written); errors still print
+    #[arg(long, short, global = true)]
+    quiet: bool,
+    /// Parse and edit as usual but write nothing; print the changes instead
+    /// (set, remove, apply, import, write, restore, new, money, unlock,
+    /// missions complete/reset on a slot, appearance set)
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Log parse, dump, edit and write steps to stderr with sizes, record
+    /// counts and timings, for bug reports; -vv also logs every record read
+    #[arg(long, short, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Inspect only: every write to a save, slot or backup is refused,
+    /// whatever the command; dumps and exports to other files still work
+    #[arg(long, global = true)]
+    read_only: bool,
     #[command(subcommand)]
     cmd: Option<Cmd>,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Pretty,
+    Json,
+    Compact,
+}
+
+// Stable process exit codes, documented in EXIT_CODES_HELP
+mod exit {
+    pub const FINDINGS: i32 = 1;
+    pub const INPUT: i32 = 2;
+    pub const INVALID: i32 = 3;
+    pub const EDIT: i32 = 4;
+    pub const WRITE: i32 = 5;
+    pub const CANCELLED: i32 = 130;
+}
+
+// Global flags, set once in main
+struct Output {
+    format: Format,
+    quiet: bool,
+    dry_run: bool,
+}
+
+static OUTPUT: OnceLock<Output> = OnceLock::new();
+
+fn format() -> Format {
+    OUTPUT.get().map_or(Format::Pretty, |o| o.format)
+}
+
+// --format json or compact: results and errors as JSON instead of text
+fn structured() -> bool {
+    format() != Format::Pretty
+}
+
+fn quiet() -> bool {
+    OUTPUT.get().is_some_and(|o| o.quiet)
+}
+
+fn dry_run() -> bool {
+    OUTPUT.get().is_some_and(|o| o.dry_run)
+}
+
+// Persistent defaults from the config file (see `config_path`); flags win
+#[derive(Debug, Default)]
+struct Config {
+    /// GameSaves folder used instead of auto-detection
+    save_root: Option<PathBuf>,
+    /// Folder for slot backup zips instead of next to the slot
+    backup_dir: Option<PathBuf>,
+    /// Default --max-array and --max-depth
+    max_array: Option<usize>,
+    max_depth: Option<usize>,
+    /// `"preset": "full"`: `dump` defaults to --full
+    full: bool,
+    /// Default --bytes-full
+    bytes_full: bool,
+    /// Folder of transform files for `transform`
+    transforms_dir: Option<PathBuf>,
+    /// Folder of rule files for `migrate`
+    migrations_dir: Option<PathBuf>,
+    /// Folder of templates for `template`, `new` and `player add`
+    templates_dir: Option<PathBuf>,
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+// Print a JSON result per --format
+fn print_json(v: &serde_json::Value) {
+    if format() == Format::Compact {
+        println!("{}", v);
+    } else {
+        println!("{}", serde_json::to_string_pretty(v).unwrap());
+    }
+}
+
+// Report an error on stderr (text or a JSON object) and exit with `code`
+fn fail_with(code: i32, msg: String) -> ! {
+    report_with(code, msg);
+    std::process::exit(code)
+}
+
+// `fail_with` without exiting, for commands that carry on (e.g. `watch`)
+fn report_with(code: i32, msg: String) {
+    if format() == Format::Pretty {
+        eprintln!("{}", msg);
+    } else {
+        let msg = msg.strip_prefix("error: ").unwrap_or(&msg);
+        eprintln!("{}", serde_json::json!({"error": msg, "code": code}));
+    }
+}
+
+macro_rules! fail {
+    ($code:expr, $($arg:tt)*) => {
+        fail_with($code, format!($($arg)*))
+    };
+}
+
+macro_rules! report {
+    ($code:expr, $($arg:tt)*) => {
+        report_with($code, format!($($arg)*))
+    };
+}
+
+// Ctrl+C stops the parse, conversion or encoding `cancellable` is running with
+// a "cancelled" error. Anything else, or a second press, exits at once as it
+// would without a handler; files are always written whole or not at all.
+static CANCEL: std::sync::LazyLock<wle_core::binfmt::CancellationToken> =
+    std::sync::LazyLock::new(Default::default);
+static IN_CANCELLABLE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+fn on_ctrl_c() {
+    if !IN_CANCELLABLE.load(std::sync::atomic::Ordering::SeqCst) || CANCEL.is_cancelled() {
+        std::process::exit(exit::CANCELLED);
+    }
+    CANCEL.cancel();
+    eprintln!("cancelling… (Ctrl+C again to quit now)");
+}
+
+fn cancellable<T>(
+    f: impl FnOnce(&wle_core::binfmt::CancellationToken) -> Result<T, String>,
+) -> Result<T, String> {
+    IN_CANCELLABLE.store(true, std::sync::atomic::Ordering::SeqCst);
+    let res = f(&CANCEL);
+    IN_CANCELLABLE.store(false, std::sync::atomic::Ordering::SeqCst);
+    match res {
+        Err(_) if CANCEL.is_cancelled() => fail!(exit::CANCELLED, "cancelled"),
+        res => res,
+    }
+}
+
+// A one-line progress bar on stderr while `work` runs, once it has taken long
+// enough to need one; none with --quiet, JSON output or stderr redirected
+fn with_progress_bar<T>(
+    label: &str,
+    status: impl Fn() -> String + Sync,
+    work: impl FnOnce() -> T,
+) -> T {
+    use std::io::IsTerminal;
+    use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+    if quiet() || structured() || !std::io::stderr().is_terminal() {
+        return work();
+    }
+    let done = AtomicBool::new(false);
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            let start = std::time::Instant::now();
+            let mut shown = false;
+            while !done.load(SeqCst) {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                if !done.load(SeqCst) && start.elapsed().as_millis() >= 500 {
+                    eprint!("\r{} {}\x1b[K", label, status());
+                    shown = true;
+                }
+            }
+            if shown {
+                eprint!("\r\x1b[K");
+            }
+        });
+        let res = work();
+        done.store(true, SeqCst);
+        res
+    })
+}
+
+fn display_name(path: &std::path::Path) -> String {
+    if is_stdio(path) {
+        "stdin".into()
+    } else {
+        path.file_name()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+// Parse `path`'s contents through `f`, with a progress bar and Ctrl+C
+fn read_with_progress<T>(
+    path: &std::path::Path,
+    f: impl FnOnce(std::sync::Arc<wle_core::binfmt::ParseProgress>) -> Result<T, String>,
+) -> Result<T, String> {
+    cancellable(|c| {
+        let progress = std::sync::Arc::new(wle_core::binfmt::ParseProgress::new(c.clone()));
+        let status = || match progress.fraction() {
+            f if f >= 1.0 => format!("100% ({} records), converting…", progress.records()),
+            f => format!("{:.0}% ({} records)", f * 100.0, progress.records()),
+        };
+        with_progress_bar(&format!("reading {}", display_name(path)), status, || {
+            f(progress.clone())
+        })
+    })
+}
+
+// Encode `v` as a .sav for `path`, with a progress bar and Ctrl+C
+fn encode_with_progress(path: &std::path::Path, v: &serde_json::Value) -> Result<Vec<u8>, String> {
+    cancellable(|c| {
+        let progress = std::sync::Arc::new(wle_core::WriteProgress::new(c.clone()));
+        let status = || {
+            format!(
+                "{:.0}% ({} objects, {} KiB)",
+                progress.fraction() * 100.0,
+                progress.objects(),
+                progress.bytes() / 1024
+            )
+        };
+        with_progress_bar(&format!("writing {}", display_name(path)), status, || {
+            wle_core::write_binfmt_from_json_with_progress(v, progress.clone())
+        })
+    })
+}
+
+// Progress note on stderr, silenced by --quiet
+macro_rules! note {
+    ($($arg:tt)*) => {
+        if !quiet() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
 #[derive(Subcommand, Debug)]
 enum Cmd {
     /// Dump a file or directory as JSON
     Dump(DumpArgs),
-    /// Get value at JSON pointer
-    Get(EditArgs),
+    /// Get the value at a JSON pointer, or a pointer/value table for several (--ptr repeated or --ptrs)
+    Get(GetArgs),
     /// List children at JSON pointer
     List(EditArgs),
-    /// Set value (raw JSON) at JSON pointer; prints or writes with --out
+    /// Set value (raw JSON) at JSON pointer; prints, writes JSON with --out, or edits the file with --in-place
     Set(SetArgs),
-    /// Remove key or array element at JSON pointer; prints or writes with --out
+    /// Remove key or array element at JSON pointer; prints, writes JSON with --out, or edits the file with --in-place
     Remove(RemoveArgs),
+    /// Run an edit script (set/insert/remove/copy lines, or a JSON Patch .json) in one load/write
+    Apply(ApplyArgs),
+    /// Run a Rhai script that reads and edits the document (get/set/remove/list/search)
+    #[cfg(feature = "script")]
+    Run(RunArgs),
+    /// Flatten an array or flat object at a pointer into CSV/TSV for a spreadsheet
+    Export(ExportArgs),
+    /// Apply an edited CSV/TSV from `export` back onto the same pointer
+    Import(ImportArgs),
+    /// Browse and edit a file interactively (ls, cd, get, set, rm, undo, save)
+    Browse(BrowseArgs),
     /// Write a JSON file (produced by dump) back to a BinaryFormatter .sav
     Write(WriteArgs),
+    /// Restore a slot from a backup zip (the current slot is backed up first)
+    Restore(RestoreArgs),
+    /// Create, list, restore, verify and prune slot backup zips
+    #[command(subcommand, alias = "backup")]
+    Backups(BackupsCmd),
+    /// Manage SaveSlot_N folders
+    #[command(subcommand)]
+    Slot(SlotCmd),
+    /// Show a summary table of every slot in a GameSaves folder
+    Slots(SlotsArgs),
+    /// Show SlotInfo details, players, per-file health and backups of one slot
+    Info(InfoArgs),
+    /// Check that a .sav (or every .sav in a slot) parses and writes back unchanged; exits 1 on errors
+    Validate(ValidateArgs),
+    /// Print a line whenever a .sav in the slot changes on disk, optionally dumping it
+    /// (`--on-change dump --out-dir DIR`); Ctrl-C to stop
+    Watch(WatchArgs),
+    /// Copy a document, or the value at a JSON pointer, from one slot to another
+    Copy(CopyArgs),
+    /// List, complete or reset missions in MissionData.sav
+    #[command(subcommand)]
+    Missions(MissionsCmd),
+    /// List or set stats in StatsData.sav by name
+    #[command(subcommand)]
+    Stats(StatsCmd),
+    /// Index objects of a .sav (e.g. WorldData) by class and GUID
+    Objects(ObjectsArgs),
+    /// Show which parts of a .sav take its bytes, largest subtrees first (e.g. why
+    /// WorldData grew); `--by-class` adds them up per class
+    Size(SizeArgs),
+    /// Find objects that share a GUID, or a class and position (glitched copies, often in
+    /// WorldData); exits 1 when there are any. `--remove` deletes the extra copies
+    Duplicates(DuplicatesArgs),
+    /// List pointer-level changes between two saves (.sav or .json); exits 1 when they differ
+    Diff(DiffArgs),
+    /// Print a fingerprint of a file's (or each file of a slot's) parsed content; equal
+    /// hashes mean the same data even when the bytes differ
+    Hash(HashArgs),
+    /// Find keys, values or classes in a .sav; prints JSON pointers with previews
+    Search(SearchArgs),
+    /// Show or set a player's money (the file is backed up before writing)
+    Money(MoneyArgs),
+    /// Unlock every clothing piece, vehicle or present owned by any player in the saves
+    /// folder, e.g. `unlock clothing SaveSlot_1` (the file is backed up before writing)
+    Unlock(UnlockArgs),
+    /// Add, remove or swap PlayerData_N files of a slot
+    #[command(subcommand)]
+    Player(PlayerCmd),
+    /// Write a fresh document from a template, e.g. to replace a deleted or corrupted file
+    New(NewArgs),
+    /// Save a document as a template without its ids and timestamps, or list the templates
+    #[command(subcommand)]
+    Template(TemplateCmd),
+    /// Export or replace the slot preview image in SlotInfo.sav
+    #[command(subcommand)]
+    Thumbnail(ThumbnailCmd),
+    /// Save the byte array at a JSON pointer (a photo, image data) as a raw file, or
+    /// replace it with one
+    #[command(subcommand)]
+    Bytes(BytesCmd),
+    /// Show or change the clothes a player wears
+    #[command(subcommand)]
+    Appearance(AppearanceCmd),
+    /// Share a player's clothes, wardrobe and vehicles as a small file, or put one on a player
+    #[command(subcommand)]
+    Loadout(LoadoutCmd),
+    /// List or run document transforms defined in JSON files (see `transform list`)
+    #[command(subcommand)]
+    Transform(TransformCmd),
+    /// Copy a .sav or slot folder with names, Steam IDs and the thumbnail replaced
+    /// by placeholders, to attach to a bug report (the originals are not changed)
+    Anonymize(AnonymizeArgs),
+    /// Update a save from an older game version: apply the migrations whose
+    /// layout it has (each file is backed up before writing)
+    Migrate(MigrateArgs),
+    /// Print a shell completion script, e.g. `wle-cli completions bash > /etc/bash_completion.d/wle-cli`
+    Completions(CompletionsArgs),
+    /// Show where the config file is looked for and the settings it holds
+    Config,
+}
+
+#[derive(Subcommand, Debug)]
+enum AppearanceCmd {
+    /// List worn pieces and the wardrobe choices per slot
+    Show(AppearanceShowArgs),
+    /// Wear wardrobe pieces by index, e.g. `--hat 12 --outfit none`
+    Set(AppearanceSetArgs),
 }
 
 #[derive(ClapArgs, Debug)]
-struct DumpArgs {
-    /// File or directory to dump (defaults to reference-data/GameSaves/SaveSlot_1)
-    path: Option<PathBuf>,
-    /// Max array elements to include per array
-    #[arg(long, default_value_t = 128)]
-    max_array: usize,
-    /// Max recursion depth
-    #[arg(long, default_value_t = 16)]
-    max_depth: usize,
-    /// Emit full bytes instead of summaries
-    #[arg(long, default_value_t = false)]
-    bytes_full: bool,
+struct AppearanceShowArgs {
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// Player number (PlayerData_N.sav)
+    #[arg(long, default_value_t = 1)]
+    player: u32,
 }
 
 #[derive(ClapArgs, Debug)]
-struct EditArgs {
-    /// File to load (.sav or .json)
-    path: PathBuf,
-    /// JSON Pointer, e.g. /root/some/key
+struct AppearanceSetArgs {
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// Player number (PlayerData_N.sav)
+    #[arg(long, default_value_t = 1)]
+    player: u32,
+    /// Wardrobe index as listed by `appearance show`, or "none"
     #[arg(long)]
-    ptr: String,
-    /// Max array elements to include per array
-    #[arg(long, default_value_t = 128)]
-    max_array: usize,
-    /// Max recursion depth
-    #[arg(long, default_value_t = 16)]
-    max_depth: usize,
+    hat: Option<WardrobeChoice>,
+    #[arg(long)]
+    top: Option<WardrobeChoice>,
+    #[arg(long)]
+    bottom: Option<WardrobeChoice>,
+    #[arg(long)]
+    outfit: Option<WardrobeChoice>,
+}
+
+// A wardrobe index, or None for "none"
+#[derive(Debug, Clone, Copy)]
+struct WardrobeChoice(Option<usize>);
+
+impl std::str::FromStr for WardrobeChoice {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        if s == "none" {
+            return Ok(WardrobeChoice(None));
+        }
+        s.parse()
+            .map(|i| WardrobeChoice(Some(i)))
+            .map_err(|_| format!("expected a wardrobe index or \"none\", got '{}'", s))
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum LoadoutCmd {
+    /// Write a player's loadout to a file (or stdout with -)
+    Export(LoadoutExportArgs),
+    /// Dress a player from a loadout and add the pieces and vehicles they lack
+    /// (the file is backed up before writing)
+    Import(LoadoutImportArgs),
 }
 
 #[derive(ClapArgs, Debug)]
-struct SetArgs {
-    /// File to load (.sav or .json)
-    path: PathBuf,
-    /// JSON Pointer, e.g. /root/some/key
+struct LoadoutExportArgs {
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// Player number (PlayerData_N.sav)
+    #[arg(long, default_value_t = 1)]
+    player: u32,
+    /// Loadout file to write, or - for stdout
     #[arg(long)]
-    ptr: String,
-    /// New value as raw JSON (e.g., 123, true, "str", {"a":1})
+    out: PathBuf,
+    /// Name stored in the loadout (defaults to the file name)
     #[arg(long)]
-    value: String,
-    /// Optional output .json path to write; otherwise prints to stdout
+    name: Option<String>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct LoadoutImportArgs {
+    /// Loadout file, as `loadout export` writes it
+    file: PathBuf,
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// Player number (PlayerData_N.sav)
+    #[arg(long, default_value_t = 1)]
+    player: u32,
+    /// Only list the changes; nothing is written
     #[arg(long)]
-    out: Option<PathBuf>,
-    /// Max array elements to include per array
-    #[arg(long, default_value_t = 128)]
-    max_array: usize,
-    /// Max recursion depth
-    #[arg(long, default_value_t = 16)]
-    max_depth: usize,
+    plan: bool,
+    /// Print the JSON Patch operations instead of writing them
+    #[arg(long, conflicts_with = "plan")]
+    patch: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum ThumbnailCmd {
+    /// Save the slot thumbnail as a PNG
+    Export(ThumbnailArgs),
+    /// Replace the slot thumbnail with a PNG (scaled to fit; SlotInfo.sav is backed up first)
+    Import(ThumbnailArgs),
 }
 
 #[derive(ClapArgs, Debug)]
-struct RemoveArgs {
-    /// File to load (.sav or .json)
+struct ThumbnailArgs {
+    /// Slot directory, e.g. GameSaves/SaveSlot_1, or its SlotInfo.sav
+    slot: PathBuf,
+    /// PNG file
+    png: PathBuf,
+}
+
+#[derive(Subcommand, Debug)]
+enum BytesCmd {
+    /// Write the bytes at --ptr to --file
+    Export(BytesArgs),
+    /// Replace the bytes at --ptr with the content of --file, whatever its length (the
+    /// .sav is backed up first)
+    Import(BytesArgs),
+}
+
+#[derive(ClapArgs, Debug)]
+struct BytesArgs {
+    /// .sav file
     path: PathBuf,
-    /// JSON Pointer, e.g. /root/some/key or /root/arr/2
+    /// JSON pointer of the byte array, e.g. /root/smallImageData
     #[arg(long)]
     ptr: String,
-    /// Optional output .json path to write; otherwise prints to stdout
+    /// Raw file to write or read
     #[arg(long)]
-    out: Option<PathBuf>,
-    /// Max array elements to include per array
-    #[arg(long, default_value_t = 128)]
-    max_array: usize,
-    /// Max recursion depth
-    #[arg(long, default_value_t = 16)]
-    max_depth: usize,
+    file: PathBuf,
+}
+
+#[derive(Subcommand, Debug)]
+enum TransformCmd {
+    /// List the transforms found in the transforms folder
+    List(TransformListArgs),
+    /// Run a transform on a .sav (backed up before writing)
+    Run(TransformRunArgs),
 }
 
 #[derive(ClapArgs, Debug)]
-struct WriteArgs {
-    /// Input JSON path (from dump)
-    #[arg(long, value_name = "JSON")]
-    input: PathBuf,
-    /// Output .sav path
-    #[arg(long, value_name = "SAV")]
-    output: PathBuf,
+struct TransformListArgs {
+    /// Folder of transform files (defaults to the config's transforms_dir, else
+    /// wle-cli/transforms in the config folder)
+    #[arg(long)]
+    dir: Option<PathBuf>,
 }
 
-fn main() {
-    let cli = Cli::parse();
-    match cli.cmd.unwrap_or(Cmd::Dump(DumpArgs {
-        path: Some(PathBuf::from("reference-data/GameSaves/SaveSlot_1")),
-        max_array: 128,
-        max_depth: 16,
-        bytes_full: false,
-    })) {
-        Cmd::Dump(a) => cmd_dump(a),
-        Cmd::Get(a) => cmd_get(a),
-        Cmd::List(a) => cmd_list(a),
-        Cmd::Set(a) => cmd_set(a),
-        Cmd::Remove(a) => cmd_remove(a),
-        Cmd::Write(a) => cmd_write(a),
-    }
+#[derive(ClapArgs, Debug)]
+struct TransformRunArgs {
+    /// Transform name, as `transform list` shows it
+    name: String,
+    /// .sav file to change, e.g. SaveSlot_1/PlayerData_1.sav
+    path: PathBuf,
+    /// Folder of transform files (see `transform list`)
+    #[arg(long)]
+    dir: Option<PathBuf>,
+    /// Only list the changes; nothing is written
+    #[arg(long)]
+    plan: bool,
+    /// Print the JSON Patch operations instead of writing them
+    #[arg(long, conflicts_with = "plan")]
+    patch: bool,
 }
 
-fn cmd_dump(args: DumpArgs) {
-    let path = args
-        .path
-        .unwrap_or_else(|| PathBuf::from("reference-data/GameSaves/SaveSlot_1"));
-    let opts = wle_core::json::JsonOpts {
-        max_array_elems: args.max_array,
-        max_depth: args.max_depth,
-        bytes_summary: !args.bytes_full,
-    };
-    let p = path.as_path();
-    let res = if p.is_file() {
-        wle_core::json::dump_file_json(p, opts)
-    } else if p.is_dir() {
-        wle_core::json::dump_dir_map_json(p, opts)
-    } else {
-        Err(format!("not found: {}", p.display()))
-    };
-    match res {
-        Ok(s) => print!("{}", s),
-        Err(e) => {
-            eprintln!("error: {}", e);
-            std::process::exit(2);
-        }
-    }
+#[derive(Subcommand, Debug)]
+enum TemplateCmd {
+    /// Save a .sav as a template: its own ids, timestamps and positions are stripped
+    Extract(TemplateExtractArgs),
+    /// List the templates found in the templates folder
+    List(TemplateListArgs),
 }
 
-fn cmd_get(args: EditArgs) {
-    let opts = wle_core::json::JsonOpts {
-        max_array_elems: args.max_array,
-        max_depth: args.max_depth,
-        bytes_summary: true,
-    };
-    let v = wle_core::parse_file_to_json_value(&args.path, opts).unwrap_or_else(|e| {
-        eprintln!("error: {}", e);
-        std::process::exit(2);
-    });
-    match wle_core::get_by_pointer(&v, &args.ptr) {
-        Some(x) => println!("{}", serde_json::to_string_pretty(&x).unwrap()),
-        None => {
-            eprintln!("not found: {}", args.ptr);
-            std::process::exit(3);
-        }
-    }
+#[derive(ClapArgs, Debug)]
+struct TemplateExtractArgs {
+    /// .sav file to make the template from
+    path: PathBuf,
+    /// Template name, used by `new --from-template` and `player add --template`
+    name: String,
+    /// One line saying what the template is for
+    #[arg(long, default_value = "")]
+    description: String,
+    /// Folder of templates (defaults to the config's templates_dir, else
+    /// wle-templates in the config folder)
+    #[arg(long)]
+    dir: Option<PathBuf>,
+    /// Replace a template of the same name
+    #[arg(long)]
+    force: bool,
 }
 
-fn cmd_list(args: EditArgs) {
-    let opts = wle_core::json::JsonOpts {
-        max_array_elems: args.max_array,
-        max_depth: args.max_depth,
-        bytes_summary: true,
-    };
-    let v = wle_core::parse_file_to_json_value(&args.path, opts).unwrap_or_else(|e| {
-        eprintln!("error: {}", e);
-        std::process::exit(2);
-    });
-    match wle_core::list_children(&v, &args.ptr) {
-        Ok(children) => {
-            for c in children {
-                println!(
-                    "{}\t{:?}{}",
-                    c.key_or_index,
-                    c.kind,
-                    c.len.map(|n| format!("\t(len={})", n)).unwrap_or_default()
-                );
-            }
-        }
-        Err(e) => {
-            eprintln!("error: {}", e);
-            std::process::exit(3);
-        }
-    }
+#[derive(ClapArgs, Debug)]
+struct TemplateListArgs {
+    /// Folder of templates (see `template extract`)
+    #[arg(long)]
+    dir: Option<PathBuf>,
 }
 
-fn cmd_set(args: SetArgs) {
-    let opts = wle_core::json::JsonOpts {
-        max_array_elems: args.max_array,
-        max_depth: args.max_depth,
-        bytes_summary: true,
-    };
-    let mut v = wle_core::parse_file_to_json_value(&args.path, opts).unwrap_or_else(|e| {
-        eprintln!("error: {}", e);
-        std::process::exit(2);
-    });
-    let new_val: serde_json::Value = serde_json::from_str(&args.value).unwrap_or_else(|e| {
-        eprintln!("invalid --value JSON: {}", e);
-        std::process::exit(3);
-    });
-    wle_core::set_raw_by_pointer(&mut v, &args.ptr, new_val).unwrap_or_else(|e| {
-        eprintln!("error: {}", e);
-        std::process::exit(4);
-    });
-    if let Some(out) = args.out {
-        wle_core::write_json_to_file(&out, &v).unwrap_or_else(|e| {
-            eprintln!("error writing: {}", e);
-            std::process::exit(5);
-        });
-    } else {
-        println!("{}", serde_json::to_string_pretty(&v).unwrap());
-    }
+#[derive(ClapArgs, Debug)]
+struct AnonymizeArgs {
+    /// .sav file or slot folder
+    path: PathBuf,
+    /// Where to write the copy: a file for one .sav, a folder for a slot
+    #[arg(long)]
+    out: PathBuf,
+    /// Overwrite files already in OUT
+    #[arg(long)]
+    force: bool,
 }
 
-fn cmd_remove(args: RemoveArgs) {
-    let opts = wle_core::json::JsonOpts {
-        max_array_elems: args.max_array,
-        max_depth: args.max_depth,
-        bytes_summary: true,
-    };
-    let mut v = wle_core::parse_file_to_json_value(&args.path, opts).unwrap_or_else(|e| {
-        eprintln!("error: {}", e);
-        std::process::exit(2);
-    });
-    wle_core::remove_at_pointer(&mut v, &args.ptr).unwrap_or_else(|e| {
-        eprintln!("error: {}", e);
-        std::process::exit(4);
-    });
-    if let Some(out) = args.out {
-        wle_core::write_json_to_file(&out, &v).unwrap_or_else(|e| {
-            eprintln!("error writing: {}", e);
-            std::process::exit(5);
-        });
-    } else {
-        println!("{}", serde_json::to_string_pretty(&v).unwrap());
-    }
+#[derive(ClapArgs, Debug)]
+struct MigrateArgs {
+    /// .sav file or slot folder to migrate
+    #[arg(required_unless_present = "list")]
+    path: Option<PathBuf>,
+    /// Folder of rule files, run after the built-in rules (defaults to the
+    /// config's migrations_dir, else wle-cli/migrations in the config folder)
+    #[arg(long)]
+    dir: Option<PathBuf>,
+    /// Rule file to run after those; repeat for several
+    #[arg(long, value_name = "FILE")]
+    rules: Vec<PathBuf>,
+    /// List the migrations and where they come from instead
+    #[arg(long, conflicts_with_all = ["plan", "patch"])]
+    list: bool,
+    /// Only list the changes; nothing is written
+    #[arg(long)]
+    plan: bool,
+    /// Print the JSON Patch operations instead of writing them
+    #[arg(long, conflicts_with = "plan")]
+    patch: bool,
 }
 
-fn cmd_write(args: WriteArgs) {
-    let data = std::fs::read_to_string(&args.input).unwrap_or_else(|e| {
-        eprintln!("error reading JSON: {}", e);
-        std::process::exit(2);
+#[derive(Subcommand, Debug)]
+enum PlayerCmd {
+    /// Create PlayerData_N.sav, blank or copied from another player of the slot
+    Add(PlayerAddArgs),
+    /// Delete a player's files (a zip backup of the slot is taken first)
+    Remove(PlayerRemoveArgs),
+    /// Exchange two players' files (a zip backup of the slot is taken first)
+    Swap(PlayerSwapArgs),
+}
+
+#[derive(ClapArgs, Debug)]
+struct PlayerAddArgs {
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// Player number to create (1-4)
+    player: u32,
+    /// Copy this player instead of starting blank
+    #[arg(long)]
+    from: Option<u32>,
+    /// Start from this PlayerData template (see `template list`), with fresh ids
+    #[arg(long, conflicts_with = "from")]
+    template: Option<String>,
+    /// Folder of templates (see `template extract`)
+    #[arg(long, requires = "template")]
+    dir: Option<PathBuf>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct NewArgs {
+    /// Document to create: player-data or player-settings
+    kind: wle_core::saves::TemplateKind,
+    /// Output .sav path, or - for stdout
+    #[arg(long)]
+    out: PathBuf,
+    /// Template to start from: "default" or one saved with `template extract`
+    #[arg(long, default_value = "default")]
+    from_template: String,
+    /// Folder of templates (see `template extract`)
+    #[arg(long)]
+    dir: Option<PathBuf>,
+    /// Overwrite OUT if it already exists
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct PlayerRemoveArgs {
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// Player number to delete (1-4)
+    player: u32,
+    /// Required: confirm the deletion
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct PlayerSwapArgs {
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    a: u32,
+    b: u32,
+}
+
+#[derive(ClapArgs, Debug)]
+struct UnlockArgs {
+    /// clothing, vehicles or items
+    what: wle_core::cheats::Unlock,
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// Player number (PlayerData_N.sav)
+    #[arg(long, default_value_t = 1)]
+    player: u32,
+    /// Only list the changes; nothing is written
+    #[arg(long)]
+    plan: bool,
+    /// Print the JSON Patch operations instead of writing them
+    #[arg(long, conflicts_with = "plan")]
+    patch: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct MoneyArgs {
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// Player number (PlayerData_N.sav)
+    #[arg(long, default_value_t = 1)]
+    player: u32,
+    /// New amount; omit to print the current money
+    #[arg(long)]
+    set: Option<i64>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct DiffArgs {
+    /// Original file (.sav or .json)
+    a: PathBuf,
+    /// Changed file (.sav or .json)
+    b: PathBuf,
+    /// Only compare below this JSON pointer
+    #[arg(long, default_value = "")]
+    ptr: String,
+    /// Print a JSON Patch turning A into B instead of the change list
+    #[arg(long)]
+    patch: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct SearchArgs {
+    /// .sav file to search
+    path: PathBuf,
+    /// Case-insensitive text; numbers and true/false match values exactly
+    query: String,
+    /// Only match member names
+    #[arg(long, conflicts_with_all = ["values", "class"])]
+    keys: bool,
+    /// Only match values
+    #[arg(long, conflicts_with = "class")]
+    values: bool,
+    /// Only match object class names
+    #[arg(long)]
+    class: bool,
+    /// Stop after this many hits
+    #[arg(long, default_value_t = 200)]
+    limit: usize,
+}
+
+#[derive(ClapArgs, Debug)]
+struct ObjectsArgs {
+    /// .sav file to index
+    path: PathBuf,
+    /// List objects of this class (default: class counts)
+    #[arg(long)]
+    class: Option<String>,
+    /// Find the object owning this GUID
+    #[arg(long, conflicts_with = "class")]
+    guid: Option<wle_core::model::Guid>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct SizeArgs {
+    /// .sav file to profile
+    path: PathBuf,
+    /// How many subtrees (or classes) to show
+    #[arg(long, default_value_t = 20)]
+    top: usize,
+    /// Add up bytes per class instead of per subtree
+    #[arg(long)]
+    by_class: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct DuplicatesArgs {
+    /// .sav file to check
+    path: PathBuf,
+    /// Remove every copy but the first (the file is backed up first)
+    #[arg(long)]
+    remove: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum StatsCmd {
+    /// List every stat with its value
+    List(StatsListArgs),
+    /// Set a stat, e.g. `stats set StatsData.sav toxicWasteBarrelsFed 100`
+    Set(StatsSetArgs),
+}
+
+#[derive(ClapArgs, Debug)]
+struct StatsListArgs {
+    /// StatsData.sav
+    path: PathBuf,
+}
+
+#[derive(ClapArgs, Debug)]
+struct StatsSetArgs {
+    /// StatsData.sav
+    path: PathBuf,
+    /// Stat name as shown by `stats list`
+    name: String,
+    /// New value (integer or number, matching the stat)
+    value: String,
+    /// Print only the JSON Patch operations
+    #[arg(long)]
+    patch: bool,
+    /// Optional output .json path to write; otherwise prints to stdout
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum MissionsCmd {
+    /// List missions known to the save with their state
+    List(MissionsListArgs),
+    /// Mark a mission (or every known mission with --all) as completed
+    Complete(MissionEditArgs),
+    /// Clear completion and progress of a mission
+    Reset(MissionEditArgs),
+    /// Same as `complete --all SLOT`
+    #[command(hide = true)]
+    CompleteAll(MissionsSlotArgs),
+}
+
+#[derive(ClapArgs, Debug)]
+struct MissionsSlotArgs {
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+}
+
+#[derive(ClapArgs, Debug)]
+struct MissionsListArgs {
+    /// MissionData.sav
+    path: PathBuf,
+}
+
+#[derive(ClapArgs, Debug)]
+struct MissionEditArgs {
+    /// MissionData.sav, or a slot directory to edit in place (backed up first)
+    path: PathBuf,
+    /// Mission GUID as shown by `missions list`
+    #[arg(required_unless_present = "all")]
+    id: Option<wle_core::model::Guid>,
+    /// Apply to every mission in the save (complete only)
+    #[arg(long)]
+    all: bool,
+    /// Print only the JSON Patch operations (MissionData.sav only)
+    #[arg(long)]
+    patch: bool,
+    /// Optional output .json path to write; otherwise prints to stdout
+    /// (MissionData.sav only)
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct CopyArgs {
+    /// Source slot directory
+    #[arg(long)]
+    from: PathBuf,
+    /// Destination slot directory (the target file is backed up first)
+    #[arg(long)]
+    to: PathBuf,
+    /// Document: slotinfo, player:N, settings:N, mission, stats or world
+    #[arg(long, default_value = "player:1")]
+    doc: wle_core::saves::DocKind,
+    /// JSON pointer to copy; omit to copy the whole file
+    #[arg(long)]
+    ptr: Option<String>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct WatchArgs {
+    /// Slot directory to watch, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// What to do each time files change, besides listing them
+    #[arg(long, value_enum, requires = "out_dir")]
+    on_change: Option<OnChange>,
+    /// Directory for --on-change dump; each change gets a <timestamp>/ folder
+    /// with one <name>.json per changed .sav, and a first one holds the whole slot
+    #[arg(long, requires = "on_change")]
+    out_dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum OnChange {
+    /// Dump the changed files as full JSON
+    Dump,
+}
+
+#[derive(ClapArgs, Debug)]
+struct SlotsArgs {
+    /// GameSaves folder containing SaveSlot_N directories (defaults to the detected one)
+    #[arg(conflicts_with = "auto")]
+    root: Option<PathBuf>,
+    /// Use the detected game save folder (the default when ROOT is omitted)
+    #[arg(long)]
+    auto: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct ValidateArgs {
+    /// .sav file or slot directory
+    path: PathBuf,
+    /// Also check values against the field catalog ranges and list sizes
+    #[arg(long)]
+    schema: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct InfoArgs {
+    /// Slot directory, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+}
+
+#[derive(Subcommand, Debug)]
+enum SlotCmd {
+    /// Copy a slot to a new SaveSlot_N (next free number by default)
+    Clone(SlotCloneArgs),
+    /// Delete a slot (a zip backup is taken first)
+    Delete(SlotDeleteArgs),
+    /// Renumber slots to SaveSlot_1..N (moved slots are backed up first)
+    Renumber(SlotRenumberArgs),
+}
+
+#[derive(ClapArgs, Debug)]
+struct SlotCloneArgs {
+    /// Slot directory to copy, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// GameSaves folder to create the copy in (defaults to the slot's parent)
+    #[arg(long)]
+    to: Option<PathBuf>,
+    /// Slot number for the copy (defaults to the next free one)
+    #[arg(long)]
+    index: Option<u32>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct SlotDeleteArgs {
+    /// Slot directory to delete, e.g. GameSaves/SaveSlot_2
+    slot: PathBuf,
+    /// Required: confirm the deletion
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct SlotRenumberArgs {
+    /// GameSaves folder containing SaveSlot_N directories
+    #[arg(required_unless_present = "auto")]
+    root: Option<PathBuf>,
+    /// Use the detected game save folder instead of ROOT
+    #[arg(long)]
+    auto: bool,
+    /// Required: confirm renaming slot folders
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum BackupsCmd {
+    /// Zip the slot next to itself as <SlotName>_<timestamp>.zip
+    Create(BackupCreateArgs),
+    /// List backups of a slot with time, size and contents
+    List(BackupsListArgs),
+    /// Delete old backups according to a retention policy
+    Prune(PruneArgs),
+    /// Check a backup zip against its integrity manifest
+    Verify(BackupsVerifyArgs),
+    /// Restore a slot from a backup zip (same as the top-level `restore`)
+    Restore(RestoreArgs),
+}
+
+#[derive(ClapArgs, Debug)]
+struct DumpArgs {
+    /// File or directory to dump, or - for stdin (defaults to reference-data/GameSaves/SaveSlot_1)
+    path: Option<PathBuf>,
+    /// Max array elements to include per array
+    #[arg(long, default_value_t = 128)]
+    max_array: usize,
+    /// Max recursion depth
+    #[arg(long, default_value_t = 16)]
+    max_depth: usize,
+    /// Emit full bytes instead of summaries
+    #[arg(long, default_value_t = false)]
+    bytes_full: bool,
+    /// Dump everything `write` needs to encode the data back: no truncation, raw bytes
+    #[arg(long, conflicts_with_all = ["max_array", "max_depth"])]
+    full: bool,
+    /// Dump the first slot of the detected game save folder
+    #[arg(long, conflicts_with = "path")]
+    auto: bool,
+    /// Dump GameSaves/SaveInfo.sav (PATH is the GameSaves folder)
+    #[arg(long)]
+    save_info: bool,
+    /// Write the JSON to this file instead of stdout
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Write one <name>.json per .sav into this directory (created if missing)
+    #[arg(long, conflicts_with_all = ["out", "save_info"])]
+    out_dir: Option<PathBuf>,
+    /// Write YAML instead of JSON (<name>.yaml with --out-dir), for line-by-line diffs
+    #[cfg(feature = "yaml")]
+    #[arg(long, conflicts_with = "report_unknown")]
+    yaml: bool,
+    /// Instead of dumping, list records and values the parser skipped or kept
+    /// raw, aggregated over PATH (a file, a slot, or a folder of slots); exits 1 if any
+    #[arg(long, conflicts_with_all = ["out", "out_dir", "save_info"])]
+    report_unknown: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct EditArgs {
+    /// File to load (.sav or .json), or - for stdin
+    path: PathBuf,
+    /// JSON Pointer, e.g. /root/some/key
+    #[arg(long)]
+    ptr: String,
+    /// Max array elements to include per array
+    #[arg(long, default_value_t = 128)]
+    max_array: usize,
+    /// Max recursion depth below the pointer
+    #[arg(long, default_value_t = 16)]
+    max_depth: usize,
+}
+
+#[derive(ClapArgs, Debug)]
+struct GetArgs {
+    /// File to load (.sav or .json), or - for stdin
+    path: PathBuf,
+    /// JSON Pointer, e.g. /root/some/key; repeat for a table of several values
+    #[arg(long, required_unless_present = "ptrs")]
+    ptr: Vec<String>,
+    /// File with one pointer per line (blank lines and # comments ignored)
+    #[arg(long, value_name = "FILE")]
+    ptrs: Option<PathBuf>,
+    /// Max array elements to include per array
+    #[arg(long, default_value_t = 128)]
+    max_array: usize,
+    /// Max recursion depth below each pointer
+    #[arg(long, default_value_t = 16)]
+    max_depth: usize,
+}
+
+#[derive(ClapArgs, Debug)]
+struct SetArgs {
+    /// File to load (.sav or .json), or - for stdin
+    path: PathBuf,
+    /// JSON Pointer, e.g. /root/some/key
+    #[arg(long)]
+    ptr: String,
+    /// New value as raw JSON (e.g., 123, -5, true, "str", {"a":1})
+    #[arg(long, allow_hyphen_values = true)]
+    value: String,
+    /// A number outside the field's catalog limits (e.g. negative money): off writes it
+    /// anyway, clamp writes the nearest allowed value, reject refuses it
+    #[arg(long, default_value = "off")]
+    limits: wle_core::Limits,
+    /// Optional output .json path to write; otherwise prints to stdout
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Write the edited document back to PATH (re-encoded as .sav) instead of printing it
+    #[arg(long, conflicts_with = "out")]
+    in_place: bool,
+    /// With --in-place, copy the original next to itself as <name>.<timestamp>.bak first
+    #[arg(long, requires = "in_place")]
+    backup: bool,
+    /// Ignored: edits always load the whole document (kept for old scripts)
+    #[arg(long, hide = true)]
+    max_array: Option<usize>,
+    /// Ignored, as --max-array
+    #[arg(long, hide = true)]
+    max_depth: Option<usize>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct RemoveArgs {
+    /// File to load (.sav or .json), or - for stdin
+    path: PathBuf,
+    /// JSON Pointer, e.g. /root/some/key or /root/arr/2
+    #[arg(long)]
+    ptr: String,
+    /// Optional output .json path to write; otherwise prints to stdout
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Write the edited document back to PATH (re-encoded as .sav) instead of printing it
+    #[arg(long, conflicts_with = "out")]
+    in_place: bool,
+    /// With --in-place, copy the original next to itself as <name>.<timestamp>.bak first
+    #[arg(long, requires = "in_place")]
+    backup: bool,
+    /// Ignored: edits always load the whole document (kept for old scripts)
+    #[arg(long, hide = true)]
+    max_array: Option<usize>,
+    /// Ignored, as --max-array
+    #[arg(long, hide = true)]
+    max_depth: Option<usize>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct ApplyArgs {
+    /// File to load (.sav or .json)
+    path: PathBuf,
+    /// Edit script: one `set|insert|remove|copy <ptr> ...` per line, or a JSON Patch array (.json)
+    #[arg(long)]
+    script: PathBuf,
+    /// Optional output .json path to write; otherwise prints to stdout
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Write the edited document back to PATH (re-encoded as .sav) instead of printing it
+    #[arg(long, conflicts_with = "out")]
+    in_place: bool,
+    /// With --in-place, copy the original next to itself as <name>.<timestamp>.bak first
+    #[arg(long, requires = "in_place")]
+    backup: bool,
+}
+
+#[cfg(feature = "script")]
+#[derive(ClapArgs, Debug)]
+struct RunArgs {
+    /// Rhai script (.rhai)
+    script: PathBuf,
+    /// File to load (.sav or .json)
+    path: PathBuf,
+    /// Optional output .json path to write; otherwise prints the edits as a JSON Patch
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Write the edited document back to PATH (re-encoded as .sav)
+    #[arg(long, conflicts_with = "out")]
+    in_place: bool,
+    /// With --in-place, copy the original next to itself as <name>.<timestamp>.bak first
+    #[arg(long, requires = "in_place")]
+    backup: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct ExportArgs {
+    /// File to load (.sav or .json), or - for stdin
+    path: PathBuf,
+    /// JSON Pointer to an array, HawkMessageList or object, e.g. /root/stats
+    #[arg(long)]
+    ptr: String,
+    /// Write CSV to this file (- for stdout, the default)
+    #[arg(long, value_name = "OUT")]
+    csv: Option<PathBuf>,
+    /// Write tab-separated values to this file (- for stdout)
+    #[arg(long, value_name = "OUT", conflicts_with = "csv")]
+    tsv: Option<PathBuf>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct ImportArgs {
+    /// File to edit (.sav or .json)
+    path: PathBuf,
+    /// JSON Pointer the table was exported from
+    #[arg(long)]
+    ptr: String,
+    /// CSV file to read (- for stdin)
+    #[arg(long, value_name = "IN", required_unless_present = "tsv")]
+    csv: Option<PathBuf>,
+    /// Tab-separated file to read (- for stdin)
+    #[arg(long, value_name = "IN", conflicts_with = "csv")]
+    tsv: Option<PathBuf>,
+    /// Print only the JSON Patch operations
+    #[arg(long, conflicts_with_all = ["out", "in_place"])]
+    patch: bool,
+    /// Optional output .json path to write; otherwise prints to stdout
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Write the edited document back to PATH (re-encoded as .sav) instead of printing it
+    #[arg(long, conflicts_with = "out")]
+    in_place: bool,
+    /// With --in-place, copy the original next to itself as <name>.<timestamp>.bak first
+    #[arg(long, requires = "in_place")]
+    backup: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct HashArgs {
+    /// .sav/.json file, - for stdin, or a slot directory
+    path: PathBuf,
+}
+
+#[derive(ClapArgs, Debug)]
+struct BrowseArgs {
+    /// File to open (.sav or .json)
+    path: PathBuf,
+    /// Copy the original next to itself as <name>.<timestamp>.bak before the first save
+    #[arg(long)]
+    backup: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct WriteArgs {
+    /// Input JSON path (from dump), or - for stdin
+    #[arg(long, value_name = "JSON")]
+    input: PathBuf,
+    /// Output .sav path, or - for stdout
+    #[arg(long, value_name = "SAV")]
+    output: PathBuf,
+}
+
+#[derive(ClapArgs, Debug)]
+struct RestoreArgs {
+    /// Backup zip created by the editor
+    zip: PathBuf,
+    /// Slot directory to restore into (defaults to the slot the zip was taken from)
+    slot: Option<PathBuf>,
+    /// Only restore these files (repeatable), e.g. --file PlayerData_1.sav
+    #[arg(long = "file", value_name = "NAME")]
+    files: Vec<String>,
+    /// Show what would be restored without touching the slot
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct BackupCreateArgs {
+    /// Slot directory to back up, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// Afterwards keep only this many newest backups per slot
+    #[arg(long)]
+    keep: Option<usize>,
+    /// Folder to write the zip to (defaults to the config's backup_dir, else next to the slot)
+    #[arg(long)]
+    to: Option<PathBuf>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct BackupsListArgs {
+    /// Slot directory whose backups to list, e.g. GameSaves/SaveSlot_1
+    slot: PathBuf,
+    /// Folder holding the zips (defaults to the config's backup_dir, else next to the slot)
+    #[arg(long)]
+    dir: Option<PathBuf>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct BackupsVerifyArgs {
+    /// Backup zip to verify
+    zip: PathBuf,
+}
+
+#[derive(ClapArgs, Debug)]
+struct PruneArgs {
+    /// GameSaves folder containing the SaveSlot_* backup zips
+    #[arg(required_unless_present = "auto")]
+    root: Option<PathBuf>,
+    /// Use the detected game save folder instead of ROOT
+    #[arg(long)]
+    auto: bool,
+    /// Keep at most this many newest backups per slot
+    #[arg(long)]
+    keep: Option<usize>,
+    /// Delete backups older than this many days
+    #[arg(long)]
+    days: Option<u32>,
+    /// Delete oldest backups until each slot's backups fit in this many MB
+    #[arg(long)]
+    max_mb: Option<u64>,
+}
+
+#[derive(ClapArgs, Debug)]
+struct CompletionsArgs {
+    shell: Shell,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+// Core spans and events to stderr, each span with its time when it closes
+fn init_logging(verbose: u8) {
+    use tracing_subscriber::filter::LevelFilter;
+    tracing_subscriber::fmt()
+        .with_max_level(if verbose > 1 {
+            LevelFilter::TRACE
+        } else {
+            LevelFilter::DEBUG
+        })
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .with_ansi(false)
+        .init();
+}
+
+fn main() {
+    // Piped into `head` and the like: end quietly when stdout closes, as
+    // other Unix tools do, instead of panicking in println!
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+    let loaded = config_path()
+        .map(|p| load_config(&p).unwrap_or_else(|e| fail!(exit::INPUT, "{}: {}", p.display(), e)))
+        .unwrap_or_default();
+    let matches = with_config_defaults(Cli::command(), &loaded).get_matches();
+    CONFIG.set(loaded).ok();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    // The config's full preset yields to limits given on the command line
+    if let Some(Cmd::Dump(args)) = &mut cli.cmd
+        && let Some(("dump", m)) = matches.subcommand()
+        && ["max_array", "max_depth"]
+            .iter()
+            .any(|id| m.value_source(id) == Some(clap::parser::ValueSource::CommandLine))
+    {
+        args.full = false;
+    }
+    if cli.verbose > 0 {
+        init_logging(cli.verbose);
+    }
+    ctrlc::set_handler(on_ctrl_c).ok();
+    wle_core::set_read_only(cli.read_only);
+    OUTPUT
+        .set(Output {
+            format: cli.format,
+            quiet: cli.quiet,
+            dry_run: cli.dry_run,
+        })
+        .ok();
+    let cmd = cli.cmd.unwrap_or(Cmd::Dump(DumpArgs {
+        path: Some(PathBuf::from("reference-data/GameSaves/SaveSlot_1")),
+        max_array: config().max_array.unwrap_or(128),
+        max_depth: config().max_depth.unwrap_or(16),
+        bytes_full: config().bytes_full,
+        full: config().full,
+        auto: false,
+        save_info: false,
+        out: None,
+        out_dir: None,
+        report_unknown: false,
+        #[cfg(feature = "yaml")]
+        yaml: false,
+    }));
+    // These write without an edit to preview; refuse rather than write anyway
+    if dry_run()
+        && matches!(
+            cmd,
+            Cmd::Browse(_)
+                | Cmd::Backups(BackupsCmd::Create(_) | BackupsCmd::Prune(_))
+                | Cmd::Slot(_)
+                | Cmd::Copy(_)
+                | Cmd::Player(_)
+                | Cmd::Thumbnail(ThumbnailCmd::Import(_))
+        )
+    {
+        fail!(exit::INVALID, "--dry-run is not supported by this command");
+    }
+    match cmd {
+        Cmd::Dump(a) => cmd_dump(a),
+        Cmd::Get(a) => cmd_get(a),
+        Cmd::List(a) => cmd_list(a),
+        Cmd::Set(a) => cmd_set(a),
+        Cmd::Remove(a) => cmd_remove(a),
+        Cmd::Apply(a) => cmd_apply(a),
+        #[cfg(feature = "script")]
+        Cmd::Run(a) => cmd_run(a),
+        Cmd::Export(a) => cmd_export(a),
+        Cmd::Import(a) => cmd_import(a),
+        Cmd::Browse(a) => cmd_browse(a),
+        Cmd::Write(a) => cmd_write(a),
+        Cmd::Restore(a) => cmd_restore(a),
+        Cmd::Backups(BackupsCmd::Create(a)) => cmd_backups_create(a),
+        Cmd::Backups(BackupsCmd::List(a)) => cmd_backups_list(a),
+        Cmd::Backups(BackupsCmd::Prune(a)) => cmd_backups_prune(a),
+        Cmd::Backups(BackupsCmd::Verify(a)) => cmd_backups_verify(a),
+        Cmd::Backups(BackupsCmd::Restore(a)) => cmd_restore(a),
+        Cmd::Slot(SlotCmd::Clone(a)) => cmd_slot_clone(a),
+        Cmd::Slots(a) => cmd_slots(a),
+        Cmd::Info(a) => cmd_info(a),
+        Cmd::Validate(a) => cmd_validate(a),
+        Cmd::Watch(a) => cmd_watch(a),
+        Cmd::Copy(a) => cmd_copy(a),
+        Cmd::Missions(MissionsCmd::List(a)) => cmd_missions_list(a),
+        Cmd::Missions(MissionsCmd::Complete(a)) => cmd_missions_edit(a, true),
+        Cmd::Missions(MissionsCmd::Reset(a)) => cmd_missions_edit(a, false),
+        Cmd::Missions(MissionsCmd::CompleteAll(a)) => cmd_missions_edit(
+            MissionEditArgs {
+                path: a.slot,
+                id: None,
+                all: true,
+                patch: false,
+                out: None,
+            },
+            true,
+        ),
+        Cmd::Stats(StatsCmd::List(a)) => cmd_stats_list(a),
+        Cmd::Stats(StatsCmd::Set(a)) => cmd_stats_set(a),
+        Cmd::Objects(a) => cmd_objects(a),
+        Cmd::Size(a) => cmd_size(a),
+        Cmd::Duplicates(a) => cmd_duplicates(a),
+        Cmd::Diff(a) => cmd_diff(a),
+        Cmd::Hash(a) => cmd_hash(a),
+        Cmd::Search(a) => cmd_search(a),
+        Cmd::Money(a) => cmd_money(a),
+        Cmd::Unlock(a) => cmd_unlock(a),
+        Cmd::Player(PlayerCmd::Add(a)) => cmd_player_add(a),
+        Cmd::Player(PlayerCmd::Remove(a)) => cmd_player_remove(a),
+        Cmd::Player(PlayerCmd::Swap(a)) => cmd_player_swap(a),
+        Cmd::New(a) => cmd_new(a),
+        Cmd::Thumbnail(ThumbnailCmd::Export(a)) => cmd_thumbnail(a, true),
+        Cmd::Thumbnail(ThumbnailCmd::Import(a)) => cmd_thumbnail(a, false),
+        Cmd::Bytes(BytesCmd::Export(a)) => cmd_bytes_export(a),
+        Cmd::Bytes(BytesCmd::Import(a)) => cmd_bytes_import(a),
+        Cmd::Appearance(AppearanceCmd::Show(a)) => cmd_appearance_show(a),
+        Cmd::Appearance(AppearanceCmd::Set(a)) => cmd_appearance_set(a),
+        Cmd::Transform(TransformCmd::List(a)) => cmd_transform_list(a),
+        Cmd::Transform(TransformCmd::Run(a)) => cmd_transform_run(a),
+        Cmd::Migrate(a) => cmd_migrate(a),
+        Cmd::Anonymize(a) => cmd_anonymize(a),
+        Cmd::Loadout(LoadoutCmd::Export(a)) => cmd_loadout_export(a),
+        Cmd::Loadout(LoadoutCmd::Import(a)) => cmd_loadout_import(a),
+        Cmd::Template(TemplateCmd::Extract(a)) => cmd_template_extract(a),
+        Cmd::Template(TemplateCmd::List(a)) => cmd_template_list(a),
+        Cmd::Completions(a) => cmd_completions(a),
+        Cmd::Config => cmd_config(),
+        Cmd::Slot(SlotCmd::Delete(a)) => cmd_slot_delete(a),
+        Cmd::Slot(SlotCmd::Renumber(a)) => cmd_slot_renumber(a),
+    }
+}
+
+fn cmd_dump(args: DumpArgs) {
+    #[cfg(feature = "yaml")]
+    let yaml = args.yaml;
+    #[cfg(not(feature = "yaml"))]
+    let yaml = false;
+    if args.save_info {
+        let root = if args.auto {
+            auto_root()
+        } else {
+            args.path
+                .unwrap_or_else(|| PathBuf::from("reference-data/GameSaves"))
+        };
+        let file = if root.is_file() {
+            root
+        } else {
+            root.join(wle_core::model::SaveInfo::FILE_NAME)
+        };
+        match wle_core::model::SaveInfo::load(&file) {
+            Ok(info) => emit_dump(
+                dump_text(info.to_json_string() + "\n", yaml),
+                args.out.as_deref(),
+            ),
+            Err(e) => {
+                fail!(exit::INPUT, "error: {}: {}", file.display(), e);
+            }
+        }
+        return;
+    }
+    let path = if args.auto {
+        let root = auto_root();
+        wle_core::saves::list_slots(&root)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| fail!(exit::INPUT, "no SaveSlot_* in {}", root.display()))
+    } else {
+        args.path
+            .unwrap_or_else(|| PathBuf::from("reference-data/GameSaves/SaveSlot_1"))
+    };
+    let opts = if args.full {
+        wle_core::json::JsonOpts::full()
+    } else {
+        wle_core::json::JsonOpts {
+            max_array_elems: args.max_array,
+            max_depth: args.max_depth,
+            bytes_summary: !args.bytes_full,
+        }
+    };
+    let p = path.as_path();
+    if args.report_unknown {
+        report_unknown(p);
+        return;
+    }
+    // A cut dump saved to disk tends to end up fed to `write`
+    if !opts.is_full()
+        && (args.out_dir.is_some() || args.out.as_deref().is_some_and(|o| !is_stdio(o)))
+    {
+        note!(
+            "note: arrays past --max-array {} and nodes below --max-depth {} are left out{}; use --full for JSON that `write` can encode back without losing data",
+            opts.max_array_elems,
+            opts.max_depth,
+            if opts.bytes_summary {
+                ", byte data summarised"
+            } else {
+                ""
+            }
+        );
+    }
+    if let Some(out_dir) = &args.out_dir
+        && p.is_dir()
+    {
+        let results = if yaml {
+            dump_dir_to_yaml_files(p, out_dir, opts)
+        } else {
+            wle_core::json::dump_dir_to_files(p, out_dir, opts)
+        }
+        .unwrap_or_else(|e| fail!(exit::WRITE, "error: {}: {}", out_dir.display(), e));
+        let mut failed = false;
+        for (src, res) in results {
+            match res {
+                Ok(dest) => note!("{} -> {}", src.display(), dest.display()),
+                Err(e) => {
+                    eprintln!("error: {}: {}", src.display(), e);
+                    failed = true;
+                }
+            }
+        }
+        if failed {
+            std::process::exit(exit::INPUT);
+        }
+        return;
+    }
+    let res = if is_stdio(p) || p.is_file() {
+        let data = read_input(p);
+        read_with_progress(p, |pr| {
+            wle_core::json::dump_bytes_json_with_progress(data, opts, pr)
+        })
+    } else if p.is_dir() {
+        wle_core::json::dump_dir_map_json(p, opts)
+    } else {
+        Err(format!("not found: {}", p.display()))
+    };
+    match res {
+        Ok(s) => {
+            // A single file with --out-dir lands where a directory dump would put it
+            let out = match (args.out, args.out_dir) {
+                (Some(o), _) => Some(o),
+                (None, Some(_)) if is_stdio(p) => {
+                    fail!(
+                        exit::INVALID,
+                        "--out-dir needs a file name; use --out with stdin"
+                    )
+                }
+                (None, Some(dir)) => {
+                    if let Err(e) = std::fs::create_dir_all(&dir) {
+                        fail!(exit::WRITE, "error: {}: {}", dir.display(), e);
+                    }
+                    Some(
+                        dir.join(p.file_stem().unwrap_or_default())
+                            .with_extension(if yaml { "yaml" } else { "json" }),
+                    )
+                }
+                (None, None) => None,
+            };
+            emit_dump(dump_text(s, yaml), out.as_deref())
+        }
+        Err(e) => {
+            fail!(exit::INPUT, "error: {}", e);
+        }
+    }
+}
+
+// Print dump output, or write it to `out`
+// The dump as printed: the JSON itself, or YAML for `dump --yaml`
+fn dump_text(json: String, yaml: bool) -> String {
+    if !yaml {
+        return json;
+    }
+    yaml_from_json_text(&json).unwrap_or_else(|e| fail!(exit::WRITE, "error: {}", e))
+}
+
+#[cfg(feature = "yaml")]
+fn yaml_from_json_text(json: &str) -> Result<String, String> {
+    let v: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    wle_core::yaml_from_json(&v)
+}
+
+#[cfg(not(feature = "yaml"))]
+fn yaml_from_json_text(_: &str) -> Result<String, String> {
+    Err("built without YAML support".into())
+}
+
+// `dump_dir_to_files` writing <name>.yaml instead
+fn dump_dir_to_yaml_files(
+    dir: &std::path::Path,
+    out_dir: &std::path::Path,
+    opts: wle_core::json::JsonOpts,
+) -> std::io::Result<Vec<(PathBuf, Result<PathBuf, String>)>> {
+    std::fs::create_dir_all(out_dir)?;
+    let mut out = Vec::new();
+    for f in wle_core::json::find_sav_files(dir) {
+        let dest = out_dir
+            .join(f.file_stem().unwrap_or_default())
+            .with_extension("yaml");
+        let res = wle_core::json::dump_file_json(&f, opts)
+            .and_then(|s| yaml_from_json_text(&s))
+            .and_then(|s| std::fs::write(&dest, s).map_err(|e| e.to_string()))
+            .map(|_| dest);
+        out.push((f, res));
+    }
+    Ok(out)
+}
+
+fn emit_dump(s: String, out: Option<&std::path::Path>) {
+    match out.filter(|p| !is_stdio(p)) {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, s) {
+                fail!(exit::WRITE, "error writing {}: {}", path.display(), e);
+            }
+        }
+        None => print!("{}", s),
+    }
+}
+
+// "-" as a file argument means stdin (input) or stdout (output)
+fn is_stdio(path: &std::path::Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+fn read_input(path: &std::path::Path) -> Vec<u8> {
+    use std::io::Read;
+    let mut data = Vec::new();
+    let res = if is_stdio(path) {
+        std::io::stdin().read_to_end(&mut data).map(|_| ())
+    } else {
+        std::fs::read(path).map(|d| data = d)
+    };
+    res.unwrap_or_else(|e| fail!(exit::INPUT, "error reading {}: {}", path.display(), e));
+    data
+}
+
+// Parse a .sav or .json file, or stdin for "-"
+fn load_json(path: &std::path::Path) -> serde_json::Value {
+    let data = read_input(path);
+    read_with_progress(path, |p| {
+        wle_core::parse_bytes_for_edit_with_progress(data, p)
+    })
+    .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e))
+}
+
+// A dumped document, as JSON or (with the yaml feature) YAML: a .yaml/.yml
+// file, or stdin that does not start with '{'
+fn text_document(path: &std::path::Path, data: &[u8]) -> Result<serde_json::Value, String> {
+    #[cfg(feature = "yaml")]
+    if wle_core::is_yaml_path(path)
+        || (is_stdio(path) && data.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'{'))
+    {
+        return wle_core::json_from_yaml(data);
+    }
+    let _ = path;
+    wle_core::json_from_bytes(data)
+}
+
+// Write edited JSON to `out`, or print it for "-"
+fn write_json_out(out: &std::path::Path, v: &serde_json::Value) {
+    if is_stdio(out) {
+        print_json(v);
+    } else {
+        wle_core::write_json_to_file(out, v)
+            .unwrap_or_else(|e| fail!(exit::WRITE, "error writing: {}", e));
+    }
+}
+
+// `dump --report-unknown`: a slot's files, or every slot's under a GameSaves folder
+fn report_unknown(path: &std::path::Path) {
+    let files = if path.is_dir() {
+        let mut files = wle_core::json::find_sav_files(path);
+        for slot in wle_core::saves::list_slots(path) {
+            files.extend(wle_core::json::find_sav_files(&slot));
+        }
+        files
+    } else if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        fail!(exit::INPUT, "not found: {}", path.display());
+    };
+    let report = wle_core::json::report_unknown(&files);
+    if structured() {
+        let entries: Vec<serde_json::Value> = report
+            .entries
+            .iter()
+            .map(|e| {
+                serde_json::json!({
+                    "what": e.what,
+                    "class": e.class,
+                    "count": e.count,
+                    "files": e.files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>(),
+                    "first": {"file": e.first.0.display().to_string(), "offset": e.first.1},
+                })
+            })
+            .collect();
+        let failed: Vec<serde_json::Value> = report
+            .failed
+            .iter()
+            .map(|(f, e)| serde_json::json!({"file": f.display().to_string(), "error": e}))
+            .collect();
+        print_json(&serde_json::json!({
+            "files": report.files,
+            "unknown": entries,
+            "failed": failed,
+        }));
+    } else {
+        for e in &report.entries {
+            println!(
+                "{:>6}  {:<40}  {:<32}  {} file(s), first {}@{:#x}",
+                e.count,
+                e.what,
+                e.class.as_deref().unwrap_or("-"),
+                e.files.len(),
+                e.first.0.file_name().unwrap_or_default().to_string_lossy(),
+                e.first.1
+            );
+        }
+        for (f, e) in &report.failed {
+            println!("failed  {}: {}", f.display(), e);
+        }
+        note!(
+            "{} file(s) read, {} kind(s) of unknown data, {} failed",
+            report.files,
+            report.entries.len(),
+            report.failed.len()
+        );
+    }
+    if !report.entries.is_empty() || !report.failed.is_empty() {
+        std::process::exit(exit::FINDINGS);
+    }
+}
+
+fn cmd_get(args: GetArgs) {
+    let mut ptrs = args.ptr;
+    if let Some(file) = &args.ptrs {
+        let text = std::fs::read_to_string(file)
+            .unwrap_or_else(|e| fail!(exit::INPUT, "error reading {}: {}", file.display(), e));
+        ptrs.extend(
+            text.lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(String::from),
+        );
+    }
+    let opts = wle_core::json::JsonOpts {
+        max_array_elems: args.max_array,
+        max_depth: args.max_depth,
+        bytes_summary: true,
+    };
+    // Only the asked-for nodes are converted, so this stays quick on WorldData
+    let found = wle_core::parse_bytes_at_pointers(read_input(&args.path), &ptrs, opts)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    if let [ptr] = ptrs.as_slice() {
+        match &found[0] {
+            Some(x) => print_json(x),
+            None => {
+                fail!(exit::INVALID, "not found: {}", ptr);
+            }
+        }
+        return;
+    }
+    // One parse, many values; missing pointers are listed, then fail the run
+    let values: Vec<(&String, Option<serde_json::Value>)> = ptrs.iter().zip(found).collect();
+    if structured() {
+        let map: serde_json::Map<String, serde_json::Value> = values
+            .iter()
+            .map(|(p, x)| ((*p).clone(), x.clone().unwrap_or_default()))
+            .collect();
+        print_json(&map.into());
+    } else {
+        let width = ptrs.iter().map(|p| p.len()).max().unwrap_or(0);
+        for (p, x) in &values {
+            let shown = x
+                .as_ref()
+                .map_or("<not found>".to_string(), wle_core::value_preview);
+            println!("{:<width$}  {}", p, shown, width = width);
+        }
+    }
+    let missing = values.iter().filter(|(_, x)| x.is_none()).count();
+    if missing > 0 {
+        fail!(exit::INVALID, "{} pointer(s) not found", missing);
+    }
+}
+
+fn cmd_list(args: EditArgs) {
+    let opts = wle_core::json::JsonOpts {
+        max_array_elems: args.max_array,
+        max_depth: args.max_depth,
+        bytes_summary: true,
+    };
+    let found = wle_core::parse_bytes_at_pointers(
+        read_input(&args.path),
+        std::slice::from_ref(&args.ptr),
+        opts,
+    )
+    .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    let node = found.into_iter().next().flatten();
+    match node
+        .ok_or_else(|| format!("json pointer not found: {}", args.ptr))
+        .and_then(|v| wle_core::list_children(&v, ""))
+    {
+        Ok(children) if structured() => {
+            let arr: Vec<serde_json::Value> = children
+                .iter()
+                .map(|c| {
+                    serde_json::json!({
+                        "key": c.key_or_index,
+                        "kind": format!("{:?}", c.kind),
+                        "len": c.len,
+                    })
+                })
+                .collect();
+            print_json(&arr.into());
+        }
+        Ok(children) => {
+            for c in children {
+                println!(
+                    "{}\t{:?}{}",
+                    c.key_or_index,
+                    c.kind,
+                    c.len.map(|n| format!("\t(len={})", n)).unwrap_or_default()
+                );
+            }
+        }
+        Err(e) => {
+            fail!(exit::INVALID, "error: {}", e);
+        }
+    }
+}
+
+fn cmd_set(args: SetArgs) {
+    let mut v = load_for_edit(&args.path, args.in_place);
+    let before = dry_run().then(|| v.clone());
+    let new_val: serde_json::Value = serde_json::from_str(&args.value)
+        .unwrap_or_else(|e| fail!(exit::INVALID, "invalid --value JSON: {}", e));
+    let typed = match &new_val {
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Some(wle_core::JsonEditValue::Int(i)),
+            None => n.as_f64().map(wle_core::JsonEditValue::Float),
+        },
+        _ => None,
+    };
+    match typed {
+        Some(typed) if args.limits != wle_core::Limits::Off => {
+            let wrote = wle_core::set_typed_by_pointer(&mut v, &args.ptr, typed, args.limits)
+                .unwrap_or_else(|e| fail!(exit::EDIT, "error: {}", e));
+            let wrote = serde_json::Value::from(&wrote);
+            if wrote != new_val {
+                note!("{} clamped to {}", new_val, wrote);
+            }
+        }
+        _ => wle_core::set_raw_by_pointer(&mut v, &args.ptr, new_val)
+            .unwrap_or_else(|e| fail!(exit::EDIT, "error: {}", e)),
+    }
+    if let Some(before) = before {
+        show_dry_run(wle_core::diff(&before, &v, ""));
+    } else if args.in_place {
+        save_in_place(&args.path, &v, args.backup);
+    } else if let Some(out) = args.out {
+        write_json_out(&out, &v);
+    } else {
+        print_json(&v);
+    }
+}
+
+fn cmd_remove(args: RemoveArgs) {
+    let mut v = load_for_edit(&args.path, args.in_place);
+    let before = dry_run().then(|| v.clone());
+    wle_core::remove_at_pointer(&mut v, &args.ptr)
+        .unwrap_or_else(|e| fail!(exit::EDIT, "error: {}", e));
+    if let Some(before) = before {
+        show_dry_run(wle_core::diff(&before, &v, ""));
+    } else if args.in_place {
+        save_in_place(&args.path, &v, args.backup);
+    } else if let Some(out) = args.out {
+        write_json_out(&out, &v);
+    } else {
+        print_json(&v);
+    }
+}
+
+fn cmd_apply(args: ApplyArgs) {
+    let text = std::fs::read_to_string(&args.script).unwrap_or_else(|e| {
+        fail!(
+            exit::INPUT,
+            "error reading {}: {}",
+            args.script.display(),
+            e
+        )
+    });
+    let is_json = args
+        .script
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    let ops = if is_json {
+        serde_json::from_str(&text)
+            .map_err(|e| e.to_string())
+            .and_then(|v| wle_core::parse_patch(&v))
+    } else {
+        wle_core::parse_edit_script(&text)
+    }
+    .unwrap_or_else(|e| fail!(exit::INVALID, "{}: {}", args.script.display(), e));
+    // Scripts address whole arrays (e.g. `/-` appends), so never truncate
+    let mut v = load_json(&args.path);
+    let before = dry_run().then(|| v.clone());
+    wle_core::apply_patch(&mut v, &ops).unwrap_or_else(|e| fail!(exit::EDIT, "error: {}", e));
+    if let Some(before) = before {
+        show_dry_run(wle_core::diff(&before, &v, ""));
+    } else if args.in_place {
+        save_in_place(&args.path, &v, args.backup);
+        note!("applied {} ops to {}", ops.len(), args.path.display());
+    } else if let Some(out) = args.out {
+        write_json_out(&out, &v);
+    } else {
+        print_json(&v);
+    }
+}
+
+#[cfg(feature = "script")]
+fn cmd_run(args: RunArgs) {
+    let text = std::fs::read_to_string(&args.script).unwrap_or_else(|e| {
+        fail!(
+            exit::INPUT,
+            "error reading {}: {}",
+            args.script.display(),
+            e
+        )
     });
-    let value: serde_json::Value = serde_json::from_str(&data).unwrap_or_else(|e| {
-        eprintln!("invalid JSON: {}", e);
-        std::process::exit(3);
+    let mut v = load_json(&args.path);
+    let run = wle_core::script::run_script(&text, &v)
+        .unwrap_or_else(|e| fail!(exit::EDIT, "{}: {}", args.script.display(), e));
+    // Script output is the result the user asked for, so it goes to stdout
+    for line in &run.output {
+        println!("{}", line);
+    }
+    let before = dry_run().then(|| v.clone());
+    wle_core::apply_patch(&mut v, &run.ops).unwrap_or_else(|e| fail!(exit::EDIT, "error: {}", e));
+    if let Some(before) = before {
+        show_dry_run(wle_core::diff(&before, &v, ""));
+    } else if run.ops.is_empty() {
+        note!("{} made no edits", args.script.display());
+    } else if args.in_place {
+        save_in_place(&args.path, &v, args.backup);
+        note!("applied {} ops to {}", run.ops.len(), args.path.display());
+    } else if let Some(out) = args.out {
+        write_json_out(&out, &v);
+    } else {
+        print_json(&serde_json::Value::Array(
+            run.ops.iter().map(|o| o.to_json()).collect(),
+        ));
+    }
+}
+
+fn cmd_export(args: ExportArgs) {
+    let v = load_json(&args.path);
+    let table = wle_core::table::export_table(&v, &args.ptr)
+        .unwrap_or_else(|e| fail!(exit::INVALID, "error: {}", e));
+    let (text, out) = match (args.csv, args.tsv) {
+        (_, Some(out)) => (table.to_text('\t'), out),
+        (out, None) => (
+            table.to_text(','),
+            out.unwrap_or_else(|| PathBuf::from("-")),
+        ),
+    };
+    if is_stdio(&out) {
+        print!("{}", text);
+    } else {
+        std::fs::write(&out, text)
+            .unwrap_or_else(|e| fail!(exit::WRITE, "error writing {}: {}", out.display(), e));
+        note!("{} rows -> {}", table.rows.len(), out.display());
+    }
+}
+
+fn cmd_import(args: ImportArgs) {
+    let (input, sep) = match (args.csv, args.tsv) {
+        (_, Some(p)) => (p, '\t'),
+        (Some(p), None) => (p, ','),
+        (None, None) => unreachable!("clap requires --csv or --tsv"),
+    };
+    let text = String::from_utf8(read_input(&input))
+        .unwrap_or_else(|_| fail!(exit::INPUT, "{}: not UTF-8 text", input.display()));
+    let table = wle_core::table::Table::parse(&text, sep)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "{}: {}", input.display(), e));
+    if args.in_place && is_stdio(&args.path) {
+        fail!(exit::INVALID, "--in-place needs a file, not stdin");
+    }
+    let mut v = load_json(&args.path);
+    let ops = wle_core::table::import_table(&v, &args.ptr, &table)
+        .unwrap_or_else(|e| fail!(exit::INVALID, "{}: {}", input.display(), e));
+    if args.patch {
+        let arr: Vec<serde_json::Value> = ops.iter().map(|op| op.to_json()).collect();
+        print_json(&arr.into());
+        return;
+    }
+    let before = dry_run().then(|| v.clone());
+    wle_core::apply_patch(&mut v, &ops).unwrap_or_else(|e| fail!(exit::EDIT, "error: {}", e));
+    if let Some(before) = before {
+        show_dry_run(wle_core::diff(&before, &v, ""));
+    } else if args.in_place {
+        if ops.is_empty() {
+            note!("no changes");
+            return;
+        }
+        save_in_place(&args.path, &v, args.backup);
+        note!("changed {} values in {}", ops.len(), args.path.display());
+    } else if let Some(out) = args.out {
+        write_json_out(&out, &v);
+    } else {
+        print_json(&v);
+    }
+}
+
+const BROWSE_HELP: &str = "\
+ls [PTR]           list children
+cd [PTR]           change the current pointer (.. goes up, / is the document root)
+get [PTR]          print the value
+set PTR JSON       replace a value, e.g. `set money 50000`
+rm PTR             remove a key or array element
+undo               revert the last set/rm
+save               write the file back
+quit               leave (asks again when there are unsaved changes)
+Pointers not starting with / are relative to the current one.";
+
+fn cmd_browse(args: BrowseArgs) {
+    use std::io::{BufRead, Write};
+    let mut session = wle_core::EditSession::open(&args.path)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    let name = args
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut cwd = String::from("/root");
+    let mut backed_up = !args.backup;
+    let mut warned_unsaved = false;
+    println!("{} — type `help` for commands", args.path.display());
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        let dirty = if session.is_dirty() { "*" } else { "" };
+        print!(
+            "{}{}:{}> ",
+            name,
+            dirty,
+            if cwd.is_empty() { "/" } else { &cwd }
+        );
+        std::io::stdout().flush().ok();
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            break;
+        };
+        let line = line.trim();
+        let (verb, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        if verb != "quit" && verb != "exit" {
+            warned_unsaved = false;
+        }
+        let res: Result<(), String> = match verb {
+            "" => Ok(()),
+            "help" | "?" => {
+                println!("{}", BROWSE_HELP);
+                Ok(())
+            }
+            "ls" => wle_core::list_children(session.value(), &resolve_pointer(&cwd, rest)).map(
+                |children| {
+                    for c in children {
+                        println!(
+                            "{}\t{:?}{}",
+                            c.key_or_index,
+                            c.kind,
+                            c.len.map(|n| format!("\t(len={})", n)).unwrap_or_default()
+                        );
+                    }
+                },
+            ),
+            "cd" => {
+                let ptr = resolve_pointer(&cwd, if rest.is_empty() { "/root" } else { rest });
+                match session.value().pointer(&ptr) {
+                    Some(v) if v.is_object() || v.is_array() => {
+                        cwd = ptr;
+                        Ok(())
+                    }
+                    Some(_) => Err(format!("not an object or array: {}", ptr)),
+                    None => Err(format!("json pointer not found: {}", ptr)),
+                }
+            }
+            "get" => {
+                let ptr = resolve_pointer(&cwd, rest);
+                match session.value().pointer(&ptr) {
+                    Some(v) => {
+                        print_json(v);
+                        Ok(())
+                    }
+                    None => Err(format!("json pointer not found: {}", ptr)),
+                }
+            }
+            "set" => match rest.split_once(char::is_whitespace) {
+                Some((ptr, json)) => serde_json::from_str(json.trim())
+                    .map_err(|e| format!("invalid JSON: {}", e))
+                    .and_then(|value| {
+                        session.apply(&[wle_core::PatchOp::replace(
+                            resolve_pointer(&cwd, ptr),
+                            value,
+                        )])
+                    }),
+                None => Err("usage: set PTR JSON".into()),
+            },
+            "rm" if !rest.is_empty() => session.apply(&[wle_core::PatchOp::Remove {
+                path: resolve_pointer(&cwd, rest),
+            }]),
+            "rm" => Err("usage: rm PTR".into()),
+            "undo" => {
+                if session.undo() {
+                    // The current pointer may have been created by the undone edit
+                    while session.value().pointer(&cwd).is_none() {
+                        cwd = resolve_pointer(&cwd, "..");
+                    }
+                    Ok(())
+                } else {
+                    Err("nothing to undo".into())
+                }
+            }
+            "save" => {
+                if !backed_up {
+                    match wle_core::editor::backup_file(session.path(), false) {
+                        Ok(b) => {
+                            println!("backup: {}", b.display());
+                            backed_up = true;
+                        }
+                        Err(e) => {
+                            eprintln!("backup error: {}", e);
+                            continue;
+                        }
+                    }
+                }
+                session
+                    .save()
+                    .map(|_| println!("saved {}", session.path().display()))
+            }
+            "quit" | "exit" => {
+                if session.is_dirty() && !warned_unsaved {
+                    warned_unsaved = true;
+                    Err("unsaved changes; `save` first or quit again to discard them".into())
+                } else {
+                    break;
+                }
+            }
+            other => Err(format!("unknown command '{}' (try `help`)", other)),
+        };
+        if let Err(e) = res {
+            eprintln!("error: {}", e);
+        }
+    }
+}
+
+// Join a browse argument onto the current pointer: absolute when it starts
+// with '/', otherwise segments relative to `cwd` with `.` and `..`
+fn resolve_pointer(cwd: &str, arg: &str) -> String {
+    let (mut parts, arg): (Vec<&str>, &str) = match arg.strip_prefix('/') {
+        Some(abs) => (Vec::new(), abs),
+        None => (cwd.split('/').skip(1).collect(), arg),
+    };
+    for seg in arg.split('/').filter(|s| !s.is_empty()) {
+        match seg {
+            "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(seg),
+        }
+    }
+    parts.iter().map(|p| format!("/{}", p)).collect()
+}
+
+// Edits load the whole document, printed or written back: truncated arrays
+// or depth-limited objects could not be encoded back
+fn load_for_edit(path: &std::path::Path, in_place: bool) -> serde_json::Value {
+    if in_place && is_stdio(path) {
+        fail!(exit::INVALID, "--in-place needs a file, not stdin");
+    }
+    load_json(path)
+}
+
+// Write an edited document back over its source: .json stays JSON (and
+// .yaml YAML), anything else is re-encoded as BinaryFormatter
+fn save_in_place(path: &std::path::Path, v: &serde_json::Value, backup: bool) {
+    if let Err(e) = wle_core::saves::check_writable(path.parent().unwrap_or("".as_ref())) {
+        fail!(exit::WRITE, "error: {}", e);
+    }
+    if backup {
+        match wle_core::editor::backup_file(path, false) {
+            Ok(b) => note!("backup: {}", b.display()),
+            Err(e) => fail!(exit::WRITE, "backup error: {}", e),
+        }
+    }
+    let is_text = wle_core::is_yaml_path(path)
+        || path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    let res = if is_text {
+        wle_core::write_json_to_file(path, v)
+    } else {
+        encode_with_progress(path, v)
+            .and_then(|bytes| wle_core::write_file_atomic(path, &bytes).map_err(|e| e.to_string()))
+    };
+    if let Err(e) = res {
+        fail!(exit::WRITE, "error writing: {}", e);
+    }
+}
+
+fn cmd_write(args: WriteArgs) {
+    let data = read_input(&args.input);
+    let value = text_document(&args.input, &data)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "invalid input: {}", e));
+    if dry_run() {
+        // Encode anyway so a document the writer rejects fails the dry run too
+        let bytes = wle_core::write_binfmt_from_json(&value)
+            .unwrap_or_else(|e| fail!(exit::WRITE, "write error: {}", e));
+        let opts = wle_core::json::JsonOpts::full();
+        match wle_core::parse_file_to_json_value(&args.output, opts) {
+            Ok(old) if !is_stdio(&args.output) => {
+                let new = wle_core::parse_bytes_to_json_value(bytes, opts)
+                    .unwrap_or_else(|e| fail!(exit::WRITE, "write error: {}", e));
+                show_dry_run(wle_core::diff(&old, &new, ""));
+            }
+            _ => note!(
+                "dry run: would write {} bytes to {}",
+                bytes.len(),
+                args.output.display()
+            ),
+        }
+        return;
+    }
+    let bytes = encode_with_progress(&args.output, &value)
+        .unwrap_or_else(|e| fail!(exit::WRITE, "write error: {}", e));
+    if is_stdio(&args.output) {
+        use std::io::Write;
+        std::io::stdout()
+            .write_all(&bytes)
+            .unwrap_or_else(|e| fail!(exit::WRITE, "write error: {}", e));
+    } else {
+        wle_core::write_file_atomic(&args.output, &bytes)
+            .unwrap_or_else(|e| fail!(exit::WRITE, "write error: {}", e));
+    }
+}
+
+fn cmd_restore(args: RestoreArgs) {
+    let slot = args
+        .slot
+        .or_else(|| wle_core::editor::backup_slot_dir(&args.zip))
+        .unwrap_or_else(|| {
+            fail!(
+                exit::INPUT,
+                "cannot tell the slot from {}; pass the slot directory",
+                args.zip.display()
+            )
+        });
+    let opts = wle_core::editor::RestoreOpts {
+        dry_run: args.dry_run || dry_run(),
+        files: if args.files.is_empty() {
+            None
+        } else {
+            Some(args.files)
+        },
+        backup_dir: Some(backup_dir(&slot, None)),
+    };
+    let report = wle_core::editor::restore_backup(&args.zip, &slot, opts)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "restore error: {}", e));
+    if let Some(pre) = &report.pre_restore_backup {
+        println!("pre-restore backup: {}", pre.display());
+    }
+    let verb = if args.dry_run {
+        "would restore"
+    } else {
+        "restored"
+    };
+    for name in &report.restored {
+        println!("{}: {}", verb, name);
+    }
+}
+
+fn cmd_backups_create(args: BackupCreateArgs) {
+    let policy = wle_core::editor::RetentionPolicy {
+        keep_last: args.keep,
+        ..Default::default()
+    };
+    let dir = backup_dir(&args.slot, args.to);
+    let (zip, pruned) = wle_core::editor::zip_backup_slot_pruned_to(&args.slot, &dir, policy)
+        .unwrap_or_else(|e| fail!(exit::WRITE, "backup error: {}", e));
+    println!("{}", zip.display());
+    for p in &pruned {
+        note!("pruned: {}", p.display());
+    }
+}
+
+fn cmd_backups_list(args: BackupsListArgs) {
+    let dir = backup_dir(&args.slot, args.dir);
+    let list = wle_core::editor::list_backups_in(&args.slot, &dir)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    if structured() {
+        let arr: Vec<serde_json::Value> = list
+            .iter()
+            .map(|b| {
+                serde_json::json!({
+                    "path": b.path,
+                    "timestamp": b.timestamp.map(|t| t.format("%Y-%m-%dT%H:%M:%S").to_string()),
+                    "slot_date_time": b.slot_date_time,
+                    "size": b.size,
+                    "files": b.files,
+                })
+            })
+            .collect();
+        print_json(&arr.into());
+        return;
+    }
+    println!(
+        "{:<19}  {:<19}  {:>10}  {:>5}  FILE",
+        "BACKUP TIME", "IN-GAME TIME", "SIZE", "FILES"
+    );
+    for b in &list {
+        println!(
+            "{:<19}  {:<19}  {:>10}  {:>5}  {}",
+            b.timestamp
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "-".into()),
+            b.slot_date_time.as_deref().unwrap_or("-"),
+            b.size,
+            b.files.len(),
+            b.path.file_name().unwrap_or_default().to_string_lossy()
+        );
+    }
+}
+
+fn cmd_slots(args: SlotsArgs) {
+    let root = args.root.unwrap_or_else(auto_root);
+    let mut slots: Vec<(u32, PathBuf)> = wle_core::saves::list_slots(&root)
+        .into_iter()
+        .map(|p| (wle_core::saves::slot_index(&p).unwrap_or(u32::MAX), p))
+        .collect();
+    slots.sort();
+    if structured() {
+        let arr: Vec<serde_json::Value> = slots
+            .iter()
+            .map(|(_, p)| match wle_core::saves::slot_summary(p) {
+                Ok(sum) => slot_summary_json(&sum),
+                Err(e) => serde_json::json!({"slot": p, "error": e.to_string()}),
+            })
+            .collect();
+        print_json(&arr.into());
+        return;
+    }
+    println!(
+        "{:<12}  {:<22}  {:>6}  {:<9}  {:>10}  STATUS",
+        "SLOT", "DATE/TIME", "PLAYER", "PLAYERS", "SIZE"
+    );
+    for (_, p) in slots {
+        let name = p.file_name().unwrap_or_default().to_string_lossy();
+        let sum = match wle_core::saves::slot_summary(&p) {
+            Ok(s) => s,
+            Err(e) => {
+                println!("{:<12}  error: {}", name, e);
+                continue;
+            }
+        };
+        let players: Vec<String> = sum.players.iter().map(|n| n.to_string()).collect();
+        let bad: Vec<&str> = sum
+            .files
+            .iter()
+            .filter(|f| f.error.is_some())
+            .map(|f| f.name.as_str())
+            .collect();
+        println!(
+            "{:<12}  {:<22}  {:>6}  {:<9}  {:>10}  {}",
+            name,
+            sum.date_time.as_deref().unwrap_or("-"),
+            sum.last_selected_player
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "-".into()),
+            players.join(","),
+            sum.total_size(),
+            if bad.is_empty() {
+                "ok".to_string()
+            } else {
+                format!("unreadable: {}", bad.join(", "))
+            }
+        );
+    }
+}
+
+fn cmd_info(args: InfoArgs) {
+    let sum = wle_core::saves::slot_summary(&args.slot)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    let backups =
+        wle_core::editor::find_backup_zips_in(&args.slot, &backup_dir(&args.slot, None)).len();
+    if structured() {
+        let mut v = slot_summary_json(&sum);
+        v["backups"] = backups.into();
+        print_json(&v);
+    } else {
+        print_slot_info(&sum, backups);
+    }
+    if !sum.is_healthy() {
+        std::process::exit(exit::FINDINGS);
+    }
+}
+
+fn cmd_validate(args: ValidateArgs) {
+    use wle_core::validate::{Severity, ValidateOpts, validate_dir, validate_file};
+    let opts = ValidateOpts {
+        schema: args.schema,
+    };
+    let reports = if args.path.is_dir() {
+        validate_dir(&args.path, opts)
+    } else if args.path.is_file() {
+        vec![validate_file(&args.path, opts)]
+    } else {
+        fail!(exit::INPUT, "not found: {}", args.path.display());
+    };
+    if structured() {
+        let arr: Vec<serde_json::Value> = reports
+            .iter()
+            .map(|r| {
+                let issues: Vec<serde_json::Value> = r
+                    .issues
+                    .iter()
+                    .map(|i| {
+                        serde_json::json!({
+                            "severity": i.severity.to_string(),
+                            "pointer": i.pointer,
+                            "message": i.message,
+                        })
+                    })
+                    .collect();
+                serde_json::json!({"file": r.path, "issues": issues})
+            })
+            .collect();
+        print_json(&arr.into());
+    } else {
+        for r in &reports {
+            let name = r.path.file_name().unwrap_or_default().to_string_lossy();
+            if r.issues.is_empty() {
+                println!("{:<28}  ok", name);
+            }
+            for i in &r.issues {
+                let at = if i.pointer.is_empty() {
+                    "-"
+                } else {
+                    &i.pointer
+                };
+                println!("{:<28}  {:<7}  {}  {}", name, i.severity, at, i.message);
+            }
+        }
+    }
+    if reports.iter().any(|r| r.worst() == Some(Severity::Error)) {
+        std::process::exit(exit::FINDINGS);
+    }
+}
+
+fn slot_summary_json(sum: &wle_core::saves::SlotSummary) -> serde_json::Value {
+    let files: Vec<serde_json::Value> = sum
+        .files
+        .iter()
+        .map(|f| serde_json::json!({"name": f.name, "size": f.size, "kind": f.kind, "error": f.error}))
+        .collect();
+    serde_json::json!({
+        "slot": sum.slot_dir,
+        "index": sum.index,
+        "date_time": sum.date_time,
+        "last_selected_player": sum.last_selected_player,
+        "players": sum.players,
+        "size": sum.total_size(),
+        "healthy": sum.is_healthy(),
+        "files": files,
+    })
+}
+
+fn print_slot_info(sum: &wle_core::saves::SlotSummary, backups: usize) {
+    let players: Vec<String> = sum.players.iter().map(|n| n.to_string()).collect();
+    println!("slot:        {}", sum.slot_dir.display());
+    println!(
+        "index:       {}",
+        sum.index
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "-".into())
+    );
+    println!("date/time:   {}", sum.date_time.as_deref().unwrap_or("-"));
+    println!(
+        "last player: {}",
+        sum.last_selected_player
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "-".into())
+    );
+    println!("players:     {}", players.join(","));
+    println!("size:        {}", sum.total_size());
+    println!("backups:     {}", backups);
+    println!();
+    println!("{:<28}  {:>10}  {:<24}  STATUS", "FILE", "SIZE", "KIND");
+    for f in &sum.files {
+        println!(
+            "{:<28}  {:>10}  {:<24}  {}",
+            f.name,
+            f.size,
+            f.kind.as_deref().unwrap_or("-"),
+            f.error.as_deref().unwrap_or("ok")
+        );
+    }
+}
+
+fn cmd_backups_prune(args: PruneArgs) {
+    let policy = wle_core::editor::RetentionPolicy {
+        keep_last: args.keep,
+        keep_days: args.days,
+        max_total_bytes: args.max_mb.map(|m| m * 1024 * 1024),
+    };
+    if policy.is_unbounded() {
+        fail!(
+            exit::INVALID,
+            "nothing to do: pass --keep, --days and/or --max-mb"
+        );
+    }
+    let root = args.root.unwrap_or_else(auto_root);
+    // Backups live next to the slots, in the configured backup_dir, or both
+    let mut dirs = vec![root];
+    if let Some(dir) = config().backup_dir.clone()
+        && dir.is_dir()
+        && !dirs.contains(&dir)
+    {
+        dirs.push(dir);
+    }
+    for dir in dirs {
+        let removed = wle_core::editor::prune_backups(&dir, policy)
+            .unwrap_or_else(|e| fail!(exit::INPUT, "prune error: {}", e));
+        for p in &removed {
+            println!("removed: {}", p.display());
+        }
+    }
+}
+
+fn cmd_backups_verify(args: BackupsVerifyArgs) {
+    let report = wle_core::editor::verify_backup(&args.zip)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    if !report.has_manifest {
+        println!("note: no manifest in this backup; only zip CRCs were checked");
+    }
+    for name in &report.verified {
+        println!("ok: {}", name);
+    }
+    for p in &report.problems {
+        println!("BAD: {}", p);
+    }
+    if !report.is_ok() {
+        std::process::exit(exit::FINDINGS);
+    }
+}
+
+fn cmd_slot_clone(args: SlotCloneArgs) {
+    let root = args.to.unwrap_or_else(|| {
+        args.slot
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."))
     });
-    wle_core::write_binfmt_file_from_json(&args.output, &value).unwrap_or_else(|e| {
-        eprintln!("write error: {}", e);
-        std::process::exit(4);
+    match wle_core::saves::clone_slot(&args.slot, &root, args.index) {
+        Ok(dest) => println!("{}", dest.display()),
+        Err(e) => {
+            fail!(exit::INPUT, "clone error: {}", e);
+        }
+    }
+}
+
+fn cmd_slot_delete(args: SlotDeleteArgs) {
+    if !args.force {
+        fail!(
+            exit::INVALID,
+            "refusing to delete {} without --force",
+            args.slot.display()
+        );
+    }
+    match wle_core::saves::delete_slot_to(&args.slot, &backup_dir(&args.slot, None)) {
+        Ok(backup) => println!(
+            "deleted {} (backup: {})",
+            args.slot.display(),
+            backup.display()
+        ),
+        Err(e) => {
+            fail!(exit::INPUT, "delete error: {}", e);
+        }
+    }
+}
+
+// A user template of the kind `want`, instantiated and encoded
+fn template_bytes(dir: Option<PathBuf>, name: &str, want: wle_core::model::DocKind) -> Vec<u8> {
+    let template = wle_core::templates::find_template(&templates_dir(dir), name)
+        .unwrap_or_else(|e| fail!(exit::INVALID, "error: {} (see `template list`)", e));
+    if template.kind() != want {
+        fail!(
+            exit::INVALID,
+            "template {} is a {} document, not {}",
+            name,
+            template.kind(),
+            want
+        );
+    }
+    template
+        .to_bytes()
+        .unwrap_or_else(|e| fail!(exit::INPUT, "template {} does not encode: {}", name, e))
+}
+
+fn cmd_new(args: NewArgs) {
+    use wle_core::model::DocKind;
+    use wle_core::saves::TemplateKind;
+    let data = if args.kind.names().contains(&args.from_template.as_str()) {
+        args.kind
+            .template(&args.from_template)
+            .unwrap_or_else(|e| fail!(exit::INVALID, "error: {}", e))
+            .to_vec()
+    } else {
+        let want = match args.kind {
+            TemplateKind::PlayerData => DocKind::PlayerData,
+            TemplateKind::PlayerSettings => DocKind::PlayerSettings,
+        };
+        template_bytes(args.dir.clone(), &args.from_template, want)
+    };
+    let data = data.as_slice();
+    // Templates ship inside the binary; never hand out one the reader rejects
+    if let Err(e) = wle_core::binfmt::Parser::new(data).parse_stream() {
+        fail!(
+            exit::INPUT,
+            "template {} does not parse: {}",
+            args.from_template,
+            e
+        );
+    }
+    if is_stdio(&args.out) {
+        use std::io::Write as _;
+        std::io::stdout()
+            .write_all(data)
+            .unwrap_or_else(|e| fail!(exit::WRITE, "error writing: {}", e));
+        return;
+    }
+    if args.out.exists() && !args.force {
+        fail!(
+            exit::INVALID,
+            "refusing to overwrite {} without --force",
+            args.out.display()
+        );
+    }
+    if dry_run() {
+        note!(
+            "dry run: would write {} from template {}",
+            args.out.display(),
+            args.from_template
+        );
+        return;
+    }
+    wle_core::write_file_atomic(&args.out, data)
+        .unwrap_or_else(|e| fail!(exit::WRITE, "error writing: {}", e));
+    note!(
+        "wrote {} from template {}",
+        args.out.display(),
+        args.from_template
+    );
+}
+
+fn cmd_player_add(args: PlayerAddArgs) {
+    let created = match &args.template {
+        Some(name) => {
+            let data = template_bytes(args.dir, name, wle_core::model::DocKind::PlayerData);
+            wle_core::saves::create_player_from(&args.slot, args.player, &data, None)
+        }
+        None => wle_core::saves::create_player(&args.slot, args.player, args.from),
+    };
+    match created {
+        Ok(p) => println!("{}", p.display()),
+        Err(e) => {
+            fail!(exit::INPUT, "player error: {}", e);
+        }
+    }
+}
+
+fn cmd_player_remove(args: PlayerRemoveArgs) {
+    if !args.force {
+        fail!(
+            exit::INVALID,
+            "refusing to remove player {} without --force",
+            args.player
+        );
+    }
+    let dir = backup_dir(&args.slot, None);
+    match wle_core::saves::remove_player_to(&args.slot, args.player, &dir) {
+        Ok(backup) => println!(
+            "removed player {} (backup: {})",
+            args.player,
+            backup.display()
+        ),
+        Err(e) => {
+            fail!(exit::INPUT, "player error: {}", e);
+        }
+    }
+}
+
+fn cmd_player_swap(args: PlayerSwapArgs) {
+    let dir = backup_dir(&args.slot, None);
+    match wle_core::saves::swap_players_to(&args.slot, args.a, args.b, &dir) {
+        Ok(backup) => println!(
+            "swapped players {} and {} (backup: {})",
+            args.a,
+            args.b,
+            backup.display()
+        ),
+        Err(e) => {
+            fail!(exit::INPUT, "player error: {}", e);
+        }
+    }
+}
+
+fn cmd_thumbnail(args: ThumbnailArgs, export: bool) {
+    let slot = if args.slot.is_file() {
+        thumbnail_slot(&args.slot)
+    } else {
+        args.slot
+    };
+    let res = if export {
+        wle_core::saves::export_thumbnail(&slot, &args.png).map(|_| None)
+    } else {
+        wle_core::saves::import_thumbnail(&slot, &args.png).map(Some)
+    };
+    match res {
+        Ok(backup) => {
+            println!("{}", args.png.display());
+            if let Some(b) = backup {
+                note!("backup: {}", b.display());
+            }
+        }
+        Err(e) => {
+            fail!(exit::INPUT, "thumbnail error: {}", e);
+        }
+    }
+}
+
+fn cmd_bytes_export(args: BytesArgs) {
+    let v = load_json(&args.path);
+    let bytes = wle_core::get_bytes_at(&v, &args.ptr)
+        .unwrap_or_else(|e| fail!(exit::INVALID, "error: {}", e));
+    std::fs::write(&args.file, &bytes)
+        .unwrap_or_else(|e| fail!(exit::WRITE, "{}: {}", args.file.display(), e));
+    println!("{}", args.file.display());
+    note!("{} bytes", bytes.len());
+}
+
+fn cmd_bytes_import(args: BytesArgs) {
+    let bytes = std::fs::read(&args.file)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "{}: {}", args.file.display(), e));
+    let v = load_json(&args.path);
+    let op = wle_core::set_bytes_op(&v, &args.ptr, &bytes)
+        .unwrap_or_else(|e| fail!(exit::INVALID, "error: {}", e));
+    let old = wle_core::get_bytes_at(&v, &args.ptr).ok();
+    if old.as_ref() == Some(&bytes) {
+        note!("{} already holds these bytes", args.ptr);
+        return;
+    }
+    let change = match old {
+        Some(old) => format!("{}: {} -> {} bytes", args.ptr, old.len(), bytes.len()),
+        None => format!("{}: {} bytes", args.ptr, bytes.len()),
+    };
+    if dry_run() {
+        println!("{}", change);
+        note!("dry run: nothing written");
+        return;
+    }
+    let edit = wle_core::cheats::PendingEdit {
+        path: args.path.clone(),
+        ops: vec![op],
+    };
+    match edit.apply() {
+        Ok(backup) => {
+            println!("{}", change);
+            note!("backup: {}", backup.display());
+        }
+        Err(e) => fail!(exit::EDIT, "error: {}", e),
+    }
+}
+
+// The slot of a SlotInfo.sav given in place of its folder; other documents
+// have no thumbnail
+fn thumbnail_slot(file: &std::path::Path) -> PathBuf {
+    use wle_core::model::DocKind;
+    let kind = wle_core::json::parse_binary(file)
+        .map(|doc| wle_core::model::detect_kind(&doc))
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}: {}", file.display(), e));
+    if kind != DocKind::SlotInfo {
+        fail!(
+            exit::INVALID,
+            "{} is a {} document; thumbnails live in a slot's SlotInfo.sav",
+            file.display(),
+            kind
+        );
+    }
+    if DocKind::expected_for(file) != DocKind::SlotInfo {
+        fail!(
+            exit::INVALID,
+            "{} is not named SlotInfo.sav; pass the slot folder instead",
+            file.display()
+        );
+    }
+    file.parent()
+        .map(|p| p.to_path_buf())
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn cmd_slot_renumber(args: SlotRenumberArgs) {
+    let root = args.root.unwrap_or_else(auto_root);
+    if !args.force {
+        fail!(
+            exit::INVALID,
+            "refusing to renumber slots in {} without --force",
+            root.display()
+        );
+    }
+    let dir = config().backup_dir.clone().unwrap_or_else(|| root.clone());
+    match wle_core::saves::renumber_slots_to(&root, &dir) {
+        Ok(moved) if moved.is_empty() => println!("slots already numbered 1..N"),
+        Ok(moved) => {
+            for (from, to) in moved {
+                println!("{} -> {}", from.display(), to.display());
+            }
+        }
+        Err(e) => {
+            fail!(exit::INPUT, "renumber error: {}", e);
+        }
+    }
+}
+
+// Folder for a slot's backup zips: the flag, the config's backup_dir, or next to the slot
+fn backup_dir(slot: &Path, flag: Option<PathBuf>) -> PathBuf {
+    flag.or_else(|| config().backup_dir.clone())
+        .unwrap_or_else(|| slot.parent().unwrap_or(Path::new(".")).to_path_buf())
+}
+
+// Resolve --auto: the config's save_root, else the first detected GameSaves
+// folder, announced on stderr
+fn auto_root() -> PathBuf {
+    if let Some(root) = &config().save_root {
+        note!("using {} (config)", root.display());
+        return root.clone();
+    }
+    let found = wle_core::saves::detect_save_roots();
+    let Some(first) = found.first() else {
+        fail!(
+            exit::INPUT,
+            "no Wobbly Life save folder found; pass the path explicitly"
+        );
+    };
+    note!("using {} ({})", first.path.display(), first.label);
+    for other in &found[1..] {
+        note!("  also found: {} ({})", other.path.display(), other.label);
+    }
+    first.path.clone()
+}
+
+fn cmd_watch(args: WatchArgs) {
+    let mut watcher = wle_core::saves::SlotWatcher::new(&args.slot)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "watch error: {}", e));
+    let dump_to = match args.on_change {
+        Some(OnChange::Dump) => args.out_dir.as_deref(),
+        None => None,
+    };
+    // Baseline to compare the first change against
+    if let Some(out_dir) = dump_to {
+        watch_dump(out_dir, &wle_core::json::find_sav_files(&args.slot));
+    }
+    note!("watching {}", args.slot.display());
+    while let Some(changes) = watcher.wait_changes() {
+        let now = chrono::Local::now().format("%H:%M:%S");
+        for c in &changes {
+            let kind = match c.kind {
+                wle_core::saves::SlotChangeKind::Created => "created",
+                wle_core::saves::SlotChangeKind::Modified => "modified",
+                wle_core::saves::SlotChangeKind::Removed => "removed",
+            };
+            println!(
+                "{} {:<8} {}",
+                now,
+                kind,
+                c.path.file_name().unwrap_or_default().to_string_lossy()
+            );
+        }
+        if let Some(out_dir) = dump_to {
+            let files: Vec<PathBuf> = changes
+                .into_iter()
+                .filter(|c| c.kind != wle_core::saves::SlotChangeKind::Removed)
+                .map(|c| c.path)
+                .collect();
+            if !files.is_empty() {
+                watch_dump(out_dir, &files);
+            }
+        }
+    }
+}
+
+// Dump `files` into a new out_dir/<timestamp>/ folder. Errors are reported
+// but do not stop watching; the game may still be writing the file.
+fn watch_dump(out_dir: &std::path::Path, files: &[PathBuf]) {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut dir = out_dir.join(&stamp);
+    let mut n = 2;
+    while dir.exists() {
+        dir = out_dir.join(format!("{}-{}", stamp, n));
+        n += 1;
+    }
+    let results =
+        match wle_core::json::dump_files_to_dir(files, &dir, wle_core::json::JsonOpts::full()) {
+            Ok(r) => r,
+            Err(e) => {
+                report!(exit::WRITE, "error: {}: {}", dir.display(), e);
+                return;
+            }
+        };
+    for (src, res) in results {
+        match res {
+            Ok(dest) => note!("{} -> {}", src.display(), dest.display()),
+            Err(e) => report!(exit::INPUT, "error: {}: {}", src.display(), e),
+        }
+    }
+}
+
+fn cmd_copy(args: CopyArgs) {
+    let backup = match &args.ptr {
+        Some(ptr) => wle_core::saves::copy_pointer(&args.from, &args.to, args.doc, ptr)
+            .map(Some)
+            .unwrap_or_else(|e| fail!(exit::EDIT, "copy error: {}", e)),
+        None => {
+            wle_core::saves::copy_document(&args.from, &args.to, args.doc).unwrap_or_else(|e| {
+                // Only a missing source is the caller's fault; the rest is backup or write
+                let code = if e.kind() == std::io::ErrorKind::NotFound {
+                    exit::INPUT
+                } else {
+                    exit::WRITE
+                };
+                fail!(code, "copy error: {}", e)
+            })
+        }
+    };
+    println!("{}", args.to.join(args.doc.file_name()).display());
+    if let Some(b) = backup {
+        note!("backup: {}", b.display());
+    }
+}
+
+fn cmd_money(args: MoneyArgs) {
+    use wle_core::cheats;
+    match args.set {
+        None => match cheats::get_money(&args.slot, args.player) {
+            Ok(m) => println!("{}", m),
+            Err(e) => {
+                fail!(exit::INPUT, "error: {}", e);
+            }
+        },
+        // The game stores money as an Int32
+        Some(amount) if !(0..=i32::MAX as i64).contains(&amount) => {
+            fail!(exit::INVALID, "money must be between 0 and {}", i32::MAX);
+        }
+        Some(amount) => {
+            let edit = cheats::money_edit(&args.slot, args.player, amount)
+                .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+            if dry_run() {
+                return preview_edit(Ok(edit));
+            }
+            let backup = edit
+                .apply()
+                .unwrap_or_else(|e| fail!(exit::WRITE, "money error: {}", e));
+            println!("{}", amount);
+            note!("backup: {}", backup.display());
+        }
+    }
+}
+
+fn cmd_unlock(args: UnlockArgs) {
+    let changes = wle_core::cheats::plan_unlock(&args.slot, args.player, args.what)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    if args.patch {
+        let ops: Vec<serde_json::Value> = changes.iter().map(|c| c.op.to_json()).collect();
+        print_json(&ops.into());
+        return;
+    }
+    for c in &changes {
+        println!("{}", c.summary);
+    }
+    if changes.is_empty() {
+        note!("nothing to unlock");
+    } else if args.plan {
+        note!(
+            "{} change(s); re-run without --plan to write them",
+            changes.len()
+        );
+    } else if dry_run() {
+        preview_edit(Ok(wle_core::cheats::unlock_edit(
+            &args.slot,
+            args.player,
+            &changes,
+        )));
+    } else {
+        match wle_core::cheats::apply_changes(&args.slot, args.player, &changes) {
+            Ok(backup) => note!(
+                "applied {} change(s); backup: {}",
+                changes.len(),
+                backup.display()
+            ),
+            Err(e) => {
+                fail!(exit::EDIT, "unlock error: {}", e);
+            }
+        }
+    }
+}
+
+fn cmd_loadout_export(args: LoadoutExportArgs) {
+    let name = args.name.unwrap_or_else(|| {
+        let stem = args.out.file_stem().filter(|_| !is_stdio(&args.out));
+        stem.map_or("loadout".into(), |s| s.to_string_lossy().into_owned())
     });
+    let loadout = wle_core::loadout::export(&args.slot, args.player, &name)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    let text = loadout.to_json().to_string();
+    if is_stdio(&args.out) {
+        println!("{}", text);
+        return;
+    }
+    if dry_run() {
+        note!(
+            "dry run: would write loadout {} to {}",
+            name,
+            args.out.display()
+        );
+        return;
+    }
+    std::fs::write(&args.out, format!("{}\n", text))
+        .unwrap_or_else(|e| fail!(exit::WRITE, "error writing: {}", e));
+    let owned: usize = loadout.wardrobe.iter().map(Vec::len).sum();
+    note!(
+        "wrote loadout {} ({} worn, {} wardrobe piece(s), {} vehicle(s)) to {}",
+        name,
+        loadout
+            .worn
+            .iter()
+            .filter(|(_, p)| !p.prefab.is_nil())
+            .count(),
+        owned,
+        loadout.vehicles.len(),
+        args.out.display()
+    );
+}
+
+fn cmd_loadout_import(args: LoadoutImportArgs) {
+    let loadout = wle_core::loadout::load(&args.file)
+        .unwrap_or_else(|e| fail!(exit::INVALID, "error: {}", e));
+    let (changes, edit) = wle_core::loadout::import_edit(&args.slot, args.player, &loadout)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    if args.patch {
+        let ops: Vec<serde_json::Value> = edit.ops.iter().map(|op| op.to_json()).collect();
+        print_json(&ops.into());
+        return;
+    }
+    for c in &changes {
+        println!("{}", c.summary);
+    }
+    if changes.is_empty() {
+        note!("nothing to change");
+    } else if args.plan {
+        note!(
+            "{} change(s); re-run without --plan to write them",
+            changes.len()
+        );
+    } else if dry_run() {
+        preview_edit(Ok(edit));
+    } else {
+        match edit.apply() {
+            Ok(backup) => note!(
+                "applied loadout {} ({} change(s)); backup: {}",
+                loadout.name,
+                changes.len(),
+                backup.display()
+            ),
+            Err(e) => fail!(exit::EDIT, "loadout error: {}", e),
+        }
+    }
+}
+
+// --dir, else the config's transforms_dir, else wle-cli/transforms next to the config
+fn transforms_dir(dir: Option<PathBuf>) -> PathBuf {
+    dir.or_else(|| config().transforms_dir.clone())
+        .or_else(|| {
+            wle_core::saves::user_config_dir().map(|b| b.join("wle-cli").join("transforms"))
+        })
+        .unwrap_or_else(|| PathBuf::from("transforms"))
+}
+
+fn cmd_transform_list(args: TransformListArgs) {
+    use wle_core::plugin::Transform;
+    let dir = transforms_dir(args.dir);
+    let files = wle_core::plugin::load_transform_dir(&dir);
+    if structured() {
+        let builtin = serde_json::json!({
+            "file": null,
+            "transforms": wle_core::plugin::builtin().iter().map(|t| serde_json::json!({
+                "name": t.name(),
+                "description": t.description(),
+            })).collect::<Vec<_>>(),
+        });
+        let arr: Vec<serde_json::Value> = std::iter::once(builtin)
+            .chain(files.iter().map(|(path, res)| match res {
+                Ok(list) => serde_json::json!({
+                    "file": path,
+                    "transforms": list.iter().map(|t| serde_json::json!({
+                        "name": t.name,
+                        "description": t.description,
+                    })).collect::<Vec<_>>(),
+                }),
+                Err(e) => serde_json::json!({"file": path, "error": e}),
+            }))
+            .collect();
+        print_json(&arr.into());
+        return;
+    }
+    for t in wle_core::plugin::builtin() {
+        println!(
+            "{:<28}  {:<20}  {}",
+            t.name(),
+            "(built-in)",
+            t.description()
+        );
+    }
+    if files.is_empty() {
+        note!("no transform files in {}", dir.display());
+    }
+    for (path, res) in &files {
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        match res {
+            Ok(list) => {
+                for t in list {
+                    println!("{:<28}  {:<20}  {}", t.name(), file, t.description());
+                }
+            }
+            Err(e) => eprintln!("error: {}: {}", path.display(), e),
+        }
+    }
+}
+
+fn cmd_transform_run(args: TransformRunArgs) {
+    use wle_core::plugin::Transform;
+    let dir = transforms_dir(args.dir);
+    let files = wle_core::plugin::load_transform_dir(&dir)
+        .into_iter()
+        .filter_map(|(_, res)| res.ok())
+        .flatten()
+        .map(|t| Box::new(t) as Box<dyn Transform>);
+    let transform = wle_core::plugin::builtin()
+        .into_iter()
+        .chain(files)
+        .find(|t| t.name().eq_ignore_ascii_case(&args.name))
+        .unwrap_or_else(|| {
+            fail!(
+                exit::INVALID,
+                "no transform '{}' in {} (see `transform list`)",
+                args.name,
+                dir.display()
+            )
+        });
+    let edit = wle_core::plugin::plan(transform.as_ref(), &args.path)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    if args.patch {
+        let ops: Vec<serde_json::Value> = edit.ops.iter().map(|op| op.to_json()).collect();
+        print_json(&ops.into());
+        return;
+    }
+    if edit.ops.is_empty() {
+        note!("nothing to change");
+    } else if args.plan || dry_run() {
+        preview_edit(Ok(edit));
+    } else {
+        match edit.apply() {
+            Ok(backup) => note!(
+                "applied {} change(s); backup: {}",
+                edit.ops.len(),
+                backup.display()
+            ),
+            Err(e) => fail!(exit::EDIT, "transform error: {}", e),
+        }
+    }
+}
+
+// --dir, else the config's templates_dir, else the folder the GUI shares
+fn templates_dir(dir: Option<PathBuf>) -> PathBuf {
+    dir.or_else(|| config().templates_dir.clone())
+        .or_else(wle_core::templates::default_dir)
+        .unwrap_or_else(|| PathBuf::from("templates"))
+}
+
+fn cmd_template_extract(args: TemplateExtractArgs) {
+    let doc = load_json(&args.path);
+    let kind = wle_core::model::detect_kind_json(&doc);
+    let (document, stripped) = wle_core::templates::extract(&doc);
+    let template = wle_core::templates::Template {
+        name: args.name,
+        description: args.description,
+        document,
+    };
+    let dir = templates_dir(args.dir);
+    if dry_run() {
+        note!(
+            "dry run: would save {} template {} to {} ({} value(s) stripped)",
+            kind,
+            template.name,
+            dir.display(),
+            stripped
+        );
+        return;
+    }
+    match wle_core::templates::save_template(&dir, &template, args.force) {
+        Ok(path) => note!(
+            "saved {} template {} ({} value(s) stripped): {}",
+            kind,
+            template.name,
+            stripped,
+            path.display()
+        ),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            fail!(exit::INVALID, "error: {} (pass --force to replace it)", e)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidInput => {
+            fail!(exit::INVALID, "error: {}", e)
+        }
+        Err(e) => fail!(exit::WRITE, "error writing template: {}", e),
+    }
+}
+
+fn cmd_template_list(args: TemplateListArgs) {
+    let dir = templates_dir(args.dir);
+    let files = wle_core::templates::list_templates(&dir);
+    if structured() {
+        let arr: Vec<serde_json::Value> = files
+            .iter()
+            .map(|(path, res)| match res {
+                Ok(t) => serde_json::json!({
+                    "file": path,
+                    "name": t.name,
+                    "kind": t.kind().name(),
+                    "description": t.description,
+                }),
+                Err(e) => serde_json::json!({"file": path, "error": e}),
+            })
+            .collect();
+        print_json(&arr.into());
+        return;
+    }
+    if files.is_empty() {
+        note!("no templates in {}", dir.display());
+    }
+    for (path, res) in &files {
+        match res {
+            Ok(t) => println!("{:<20}  {:<18}  {}", t.name, t.kind(), t.description),
+            Err(e) => eprintln!("error: {}: {}", path.display(), e),
+        }
+    }
+}
+
+fn cmd_anonymize(args: AnonymizeArgs) {
+    use wle_core::model::DocKind;
+    let files: Vec<(PathBuf, PathBuf)> = if args.path.is_dir() {
+        if !dry_run() {
+            std::fs::create_dir_all(&args.out).unwrap_or_else(|e| {
+                fail!(exit::WRITE, "error creating {}: {}", args.out.display(), e)
+            });
+        }
+        wle_core::json::find_sav_files(&args.path)
+            .into_iter()
+            .map(|f| {
+                let dest = args.out.join(f.file_name().unwrap_or_default());
+                (f, dest)
+            })
+            .collect()
+    } else {
+        vec![(args.path.clone(), args.out.clone())]
+    };
+    for (src, dest) in &files {
+        let same = std::fs::canonicalize(src).ok() == std::fs::canonicalize(dest).ok();
+        if same {
+            fail!(
+                exit::INVALID,
+                "refusing to anonymize {} over itself; use `transform run anonymize` to change it in place",
+                src.display()
+            );
+        }
+        if dest.exists() && !args.force {
+            fail!(
+                exit::INVALID,
+                "refusing to overwrite {} without --force",
+                dest.display()
+            );
+        }
+    }
+    for (src, dest) in files {
+        let name = display_name(&src);
+        // SaveInfo.sav is plain JSON with nothing personal in it
+        if DocKind::expected_for(&src) == DocKind::SaveInfo {
+            if !dry_run() {
+                std::fs::copy(&src, &dest)
+                    .unwrap_or_else(|e| fail!(exit::WRITE, "error writing: {}", e));
+            }
+            note!("{}: copied", name);
+            continue;
+        }
+        if dry_run() {
+            let edit = wle_core::plugin::plan(&wle_core::plugin::Anonymize, &src)
+                .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}: {}", src.display(), e));
+            note!(
+                "dry run: {}: would replace {} value(s) in {}",
+                name,
+                edit.ops.len(),
+                dest.display()
+            );
+            continue;
+        }
+        match wle_core::plugin::write_transformed(&wle_core::plugin::Anonymize, &src, &dest) {
+            Ok(n) => note!("{}: replaced {} value(s)", name, n),
+            Err(e) => fail!(exit::WRITE, "error: {}: {}", src.display(), e),
+        }
+    }
+}
+
+// The built-in migrations, then each rule file of the folder, then --rules,
+// each with a label for `migrate --list`
+fn migration_rules(args: &MigrateArgs) -> Vec<(String, wle_core::migrate::RuleSet)> {
+    let dir = args
+        .dir
+        .clone()
+        .or_else(|| config().migrations_dir.clone())
+        .or_else(|| {
+            wle_core::saves::user_config_dir().map(|b| b.join("wle-cli").join("migrations"))
+        })
+        .unwrap_or_else(|| PathBuf::from("migrations"));
+    let files = wle_core::migrate::load_rule_dir(&dir).into_iter().chain(
+        args.rules
+            .iter()
+            .map(|p| (p.clone(), wle_core::migrate::load_rules(p))),
+    );
+    let mut sets = vec![("built-in".to_string(), wle_core::migrate::builtin())];
+    for (path, res) in files {
+        match res {
+            Ok(set) => sets.push((path.display().to_string(), set)),
+            Err(e) => fail!(exit::INPUT, "error: {}: {}", path.display(), e),
+        }
+    }
+    sets
+}
+
+fn cmd_migrate(args: MigrateArgs) {
+    let sets = migration_rules(&args);
+    if args.list {
+        if structured() {
+            let arr: Vec<serde_json::Value> = sets
+                .iter()
+                .map(|(from, set)| {
+                    serde_json::json!({
+                        "from": from,
+                        "version": set.version,
+                        "migrations": set.migrations.iter().map(|m| serde_json::json!({
+                            "id": m.id,
+                            "description": m.description,
+                        })).collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            print_json(&arr.into());
+            return;
+        }
+        for (from, set) in &sets {
+            println!("{} (version {})", from, set.version);
+            if set.migrations.is_empty() {
+                println!("  (none)");
+            }
+            for m in &set.migrations {
+                println!("  {:<28}  {}", m.id, m.description);
+            }
+        }
+        return;
+    }
+    let rules: Vec<_> = sets.into_iter().flat_map(|(_, s)| s.migrations).collect();
+    let path = args.path.unwrap_or_default();
+    let files = if path.is_dir() {
+        wle_core::json::find_sav_files(&path)
+            .into_iter()
+            .filter(|p| {
+                wle_core::model::DocKind::expected_for(p) != wle_core::model::DocKind::SaveInfo
+            })
+            .collect()
+    } else {
+        vec![path]
+    };
+    let mut patches = serde_json::Map::new();
+    let mut pending = Vec::new();
+    for file in files {
+        let name = display_name(&file);
+        let (applied, edit) = wle_core::migrate::plan(&rules, &file)
+            .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}: {}", file.display(), e));
+        if args.patch {
+            let ops: Vec<serde_json::Value> = edit.ops.iter().map(|op| op.to_json()).collect();
+            patches.insert(file.display().to_string(), ops.into());
+        } else if edit.ops.is_empty() {
+            note!("{}: up to date", name);
+        } else if args.plan || dry_run() {
+            println!("{}: {}", name, applied.join(", "));
+            preview_edit(Ok(edit));
+        } else {
+            pending.push((name, applied, edit));
+        }
+    }
+    if args.patch {
+        print_json(&patches.into());
+    }
+    // A whole slot is migrated in one transaction: all files or none
+    if !pending.is_empty() {
+        let edits: Vec<_> = pending.iter().map(|(_, _, e)| e.clone()).collect();
+        let backups = wle_core::cheats::apply_all(&edits)
+            .unwrap_or_else(|e| fail!(exit::EDIT, "migrate error: {}", e));
+        for ((name, applied, edit), backup) in pending.iter().zip(backups) {
+            note!(
+                "{}: applied {} ({} change(s)); backup: {}",
+                name,
+                applied.join(", "),
+                edit.ops.len(),
+                backup.display()
+            );
+        }
+    }
+}
+
+fn cmd_appearance_show(args: AppearanceShowArgs) {
+    use wle_core::model::ClothingSlot;
+    let path = args
+        .slot
+        .join(wle_core::saves::DocKind::Player(args.player).file_name());
+    let doc = load_doc(&path);
+    let Some(look) = doc.as_character_appearance() else {
+        fail!(exit::INVALID, "not a PlayerData file: {}", path.display());
+    };
+    for slot in ClothingSlot::ALL {
+        let worn = match (look.worn(slot), look.worn_index(slot)) {
+            (Some(p), _) if p.prefab.is_nil() => "none".to_string(),
+            (Some(p), Some(i)) => format!("#{} {}", i, p.prefab),
+            (Some(p), None) => format!("{} (not in wardrobe)", p.prefab),
+            (None, _) => "-".to_string(),
+        };
+        println!("{:<7} {}", slot.name(), worn);
+        for (i, p) in look.options(slot).iter().enumerate() {
+            let [r, g, b, _] = p.color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+            println!("  {:>4}  {}  #{:02x}{:02x}{:02x}", i, p.prefab, r, g, b);
+        }
+    }
+}
+
+fn cmd_appearance_set(args: AppearanceSetArgs) {
+    use wle_core::model::ClothingSlot;
+    let choices: Vec<(ClothingSlot, Option<usize>)> = [
+        (ClothingSlot::Hat, args.hat),
+        (ClothingSlot::Top, args.top),
+        (ClothingSlot::Bottom, args.bottom),
+        (ClothingSlot::Outfit, args.outfit),
+    ]
+    .into_iter()
+    .filter_map(|(s, c)| c.map(|c| (s, c.0)))
+    .collect();
+    if choices.is_empty() {
+        fail!(
+            exit::INVALID,
+            "nothing to change (use --hat, --top, --bottom or --outfit)"
+        );
+    }
+    if dry_run() {
+        preview_edit(wle_core::cheats::appearance_edit(
+            &args.slot,
+            args.player,
+            &choices,
+        ));
+        return;
+    }
+    match wle_core::cheats::set_appearance(&args.slot, args.player, &choices) {
+        Ok(backup) => note!("backup: {}", backup.display()),
+        Err(e) => fail!(exit::EDIT, "appearance error: {}", e),
+    }
+}
+
+fn load_doc(path: &std::path::Path) -> wle_core::binfmt::Document<'static> {
+    wle_core::json::parse_binary(path).unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e))
+}
+
+fn cmd_missions_list(args: MissionsListArgs) {
+    let doc = load_doc(&args.path);
+    let Some(missions) = doc.as_mission_data() else {
+        fail!(
+            exit::INVALID,
+            "not a MissionData file: {}",
+            args.path.display()
+        );
+    };
+    let state = |m: &wle_core::model::MissionEntry| match m.state {
+        wle_core::model::MissionState::Completed => "completed",
+        wle_core::model::MissionState::Started => "started",
+        wle_core::model::MissionState::NotStarted => "not-started",
+    };
+    if structured() {
+        let arr: Vec<serde_json::Value> = missions
+            .missions
+            .iter()
+            .map(|m| {
+                serde_json::json!({
+                    "id": m.id.to_string(),
+                    "state": state(m),
+                    "progress": m.progress,
+                })
+            })
+            .collect();
+        print_json(&arr.into());
+        return;
+    }
+    println!("{:<36}  {:<11}  PROGRESS", "MISSION", "STATE");
+    for m in &missions.missions {
+        println!(
+            "{:<36}  {:<11}  {}",
+            m.id,
+            state(m),
+            m.progress.unwrap_or("-")
+        );
+    }
+}
+
+fn cmd_missions_edit(args: MissionEditArgs, complete: bool) {
+    if args.path.is_dir() {
+        cmd_missions_edit_slot(args, complete);
+        return;
+    }
+    let doc = load_doc(&args.path);
+    let Some(missions) = doc.as_mission_data() else {
+        fail!(
+            exit::INVALID,
+            "not a MissionData file: {}",
+            args.path.display()
+        );
+    };
+    let ops = match (complete, args.all, args.id) {
+        (true, true, _) => Ok(missions.complete_all()),
+        (false, true, _) => Err("--all is only supported by `missions complete`".to_string()),
+        (true, false, Some(id)) => missions.complete_mission(id),
+        (false, false, Some(id)) => missions.reset_mission(id),
+        (_, false, None) => Err("pass a mission id or --all".to_string()),
+    }
+    .unwrap_or_else(|e| fail!(exit::EDIT, "error: {}", e));
+    emit_patched(&args.path, &ops, args.patch, args.out);
+}
+
+// `missions complete|reset` on a slot: write its MissionData.sav in place
+fn cmd_missions_edit_slot(args: MissionEditArgs, complete: bool) {
+    if args.patch || args.out.is_some() {
+        fail!(
+            exit::INVALID,
+            "--patch and --out take a MissionData.sav, not a slot directory"
+        );
+    }
+    let slot = &args.path;
+    let (count, edit) = match (complete, args.all, args.id) {
+        (true, true, _) => wle_core::cheats::complete_all_missions_edit(slot),
+        (false, true, _) => Err("--all is only supported by `missions complete`".to_string()),
+        (_, false, Some(id)) => wle_core::cheats::mission_edit(slot, id, complete).map(|e| (1, e)),
+        (_, false, None) => Err("pass a mission id or --all".to_string()),
+    }
+    .unwrap_or_else(|e| fail!(exit::EDIT, "missions error: {}", e));
+    if count == 0 {
+        note!("every mission is already completed");
+        return;
+    }
+    if dry_run() {
+        preview_edit(Ok(edit));
+        return;
+    }
+    let backup = edit
+        .apply()
+        .unwrap_or_else(|e| fail!(exit::EDIT, "missions error: {}", e));
+    println!("{}", count);
+    note!(
+        "{} {} mission(s); backup: {}",
+        if complete { "completed" } else { "reset" },
+        count,
+        backup.display()
+    );
+}
+
+// Print the patch ops, or apply them and print / write the patched JSON
+fn emit_patched(
+    path: &std::path::Path,
+    ops: &[wle_core::PatchOp],
+    patch_only: bool,
+    out: Option<PathBuf>,
+) {
+    if patch_only {
+        let arr: Vec<serde_json::Value> = ops.iter().map(|op| op.to_json()).collect();
+        print_json(&arr.into());
+        return;
+    }
+    let mut v =
+        wle_core::parse_file_for_edit(path).unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    wle_core::apply_patch(&mut v, ops).unwrap_or_else(|e| fail!(exit::EDIT, "error: {}", e));
+    if let Some(out) = out {
+        wle_core::write_json_to_file(&out, &v)
+            .unwrap_or_else(|e| fail!(exit::WRITE, "error writing: {}", e));
+    } else {
+        print_json(&v);
+    }
+}
+
+fn cmd_stats_list(args: StatsListArgs) {
+    let doc = load_doc(&args.path);
+    let Some(stats) = doc.as_stats_data() else {
+        fail!(
+            exit::INVALID,
+            "not a StatsData file: {}",
+            args.path.display()
+        );
+    };
+    if structured() {
+        let map: serde_json::Map<String, serde_json::Value> = stats
+            .stats
+            .iter()
+            .map(|(name, value)| (name.to_string(), (*value).into()))
+            .collect();
+        print_json(&map.into());
+        return;
+    }
+    for (name, value) in &stats.stats {
+        println!("{}\t{}", name, value);
+    }
+}
+
+fn cmd_stats_set(args: StatsSetArgs) {
+    let doc = load_doc(&args.path);
+    let Some(stats) = doc.as_stats_data() else {
+        fail!(
+            exit::INVALID,
+            "not a StatsData file: {}",
+            args.path.display()
+        );
+    };
+    let op = stats
+        .set(&args.name, &args.value)
+        .unwrap_or_else(|e| fail!(exit::EDIT, "error: {}", e));
+    emit_patched(&args.path, &[op], args.patch, args.out);
+}
+
+fn cmd_hash(args: HashArgs) {
+    let hash = |path: &std::path::Path| wle_core::content_hash(&load_json(path));
+    if !args.path.is_dir() {
+        let h = hash(&args.path);
+        if structured() {
+            print_json(&serde_json::json!({ "hash": h }));
+        } else {
+            println!("{}  {}", h, args.path.display());
+        }
+        return;
+    }
+    let files: Vec<(String, String)> = wle_core::json::find_sav_files(&args.path)
+        .iter()
+        .map(|f| {
+            let name = f
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            (name, hash(f))
+        })
+        .collect();
+    // The slot's hash covers file names too, so a renamed player differs
+    let listing: String = files
+        .iter()
+        .map(|(name, h)| format!("{}  {}\n", h, name))
+        .collect();
+    let total = wle_core::editor::sha256_hex(listing.as_bytes());
+    if structured() {
+        let map: serde_json::Map<String, serde_json::Value> = files
+            .into_iter()
+            .map(|(name, h)| (name, h.into()))
+            .collect();
+        print_json(&serde_json::json!({ "hash": total, "files": map }));
+    } else {
+        print!("{}", listing);
+        println!("{}  {}", total, args.path.display());
+    }
+}
+
+fn cmd_diff(args: DiffArgs) {
+    let load = |path: &std::path::Path| {
+        let v = wle_core::parse_file_for_edit(path)
+            .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}: {}", path.display(), e));
+        v.pointer(&args.ptr).cloned().unwrap_or_else(|| {
+            fail!(
+                exit::INVALID,
+                "not found in {}: {}",
+                path.display(),
+                args.ptr
+            )
+        })
+    };
+    let (a, b) = (load(&args.a), load(&args.b));
+    let changes = wle_core::diff(&a, &b, &args.ptr);
+    if args.patch {
+        let arr: Vec<serde_json::Value> =
+            changes.iter().map(|c| c.to_patch_op().to_json()).collect();
+        print_json(&arr.into());
+    } else {
+        print_changes(&changes);
+    }
+    if !changes.is_empty() {
+        std::process::exit(exit::FINDINGS);
+    }
+}
+
+// `~ ptr: old -> new` / `+` / `-` lines, or a JSON array with --format json
+fn print_changes(changes: &[wle_core::Change]) {
+    if structured() {
+        let arr: Vec<serde_json::Value> = changes
+            .iter()
+            .map(|c| serde_json::json!({"pointer": c.pointer, "old": c.old, "new": c.new}))
+            .collect();
+        print_json(&arr.into());
+        return;
+    }
+    for c in changes {
+        match (&c.old, &c.new) {
+            (Some(o), Some(n)) => println!(
+                "~ {}: {} -> {}",
+                c.pointer,
+                wle_core::value_preview(o),
+                wle_core::value_preview(n)
+            ),
+            (None, Some(n)) => println!("+ {}: {}", c.pointer, wle_core::value_preview(n)),
+            (Some(o), None) => println!("- {}: {}", c.pointer, wle_core::value_preview(o)),
+            (None, None) => {}
+        }
+    }
+}
+
+// --dry-run: print what an edit would change instead of writing it
+fn show_dry_run(changes: Vec<wle_core::Change>) {
+    print_changes(&changes);
+    note!("dry run: {} change(s), nothing written", changes.len());
+}
+
+fn preview_edit(edit: Result<wle_core::cheats::PendingEdit, String>) {
+    let changes = edit
+        .and_then(|e| e.preview())
+        .unwrap_or_else(|e| fail!(exit::EDIT, "error: {}", e));
+    show_dry_run(changes);
+}
+
+fn cmd_search(args: SearchArgs) {
+    let v = wle_core::parse_file_for_edit(&args.path)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    let scope = if args.keys {
+        wle_core::SearchScope::Keys
+    } else if args.values {
+        wle_core::SearchScope::Values
+    } else if args.class {
+        wle_core::SearchScope::Class
+    } else {
+        wle_core::SearchScope::All
+    };
+    let hits = wle_core::search(&v, &args.query, scope, args.limit);
+    if hits.is_empty() {
+        fail!(exit::INVALID, "no matches for '{}'", args.query);
+    }
+    if structured() {
+        let arr: Vec<serde_json::Value> = hits
+            .iter()
+            .map(|h| serde_json::json!({"pointer": h.pointer, "preview": h.preview}))
+            .collect();
+        print_json(&arr.into());
+    } else {
+        for h in &hits {
+            println!("{}\t{}", h.pointer, h.preview);
+        }
+    }
+    if hits.len() >= args.limit {
+        note!(
+            "(stopped after {} hits; raise --limit for more)",
+            args.limit
+        );
+    }
+}
+
+fn cmd_objects(args: ObjectsArgs) {
+    let doc = load_doc(&args.path);
+    let index = wle_core::model::ObjectIndex::build(&doc);
+    if let Some(guid) = args.guid {
+        match index.by_guid(guid) {
+            Some(o) => println!("{}\t{}", o.class_name, o.pointer),
+            None => {
+                fail!(exit::INVALID, "no object with GUID {}", guid);
+            }
+        }
+    } else if let Some(class) = &args.class {
+        for o in index.by_class(class) {
+            let guid = o.guid.map(|g| g.to_string()).unwrap_or_else(|| "-".into());
+            println!("{:<36}  {}", guid, o.pointer);
+        }
+    } else {
+        if let Some(world) = doc.as_world_data() {
+            for s in &world.scenes {
+                println!(
+                    "scene {}: time of day {:.1}, weather {}",
+                    s.scene, s.time_of_day, s.weather_index
+                );
+            }
+        }
+        for (class, n) in index.classes() {
+            println!("{:>6}  {}", n, class);
+        }
+    }
+}
+
+// A class name without the assemblies of its type arguments:
+// Dictionary`2[[System.Guid],[PlayerActiveExternalData]]
+fn short_class(class: &str) -> String {
+    class
+        .split(']')
+        .map(|part| part.find(", ").map_or(part, |i| &part[..i]))
+        .collect::<Vec<_>>()
+        .join("]")
+}
+
+fn cmd_size(args: SizeArgs) {
+    use wle_core::profile::{SizeNode, parent};
+    let data = std::fs::read(&args.path)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "{}: {}", args.path.display(), e));
+    let prof = wle_core::profile::profile_bytes(&data)
+        .unwrap_or_else(|e| fail!(exit::INPUT, "error: {}", e));
+    let classes = prof.by_class();
+    let classes = &classes[..args.top.min(classes.len())];
+    let top = prof.top(args.top);
+    if structured() {
+        let nodes: Vec<serde_json::Value> = top
+            .iter()
+            .map(|n| serde_json::json!({"pointer": n.pointer, "class": n.class, "own": n.own, "total": n.total}))
+            .collect();
+        let classes: Vec<serde_json::Value> = classes
+            .iter()
+            .map(|c| serde_json::json!({"class": c.class, "count": c.count, "bytes": c.bytes}))
+            .collect();
+        print_json(&serde_json::json!({
+            "file": args.path,
+            "size": prof.file_size,
+            "other": prof.other(),
+            "nodes": nodes,
+            "classes": classes,
+        }));
+        return;
+    }
+    let share = |bytes: usize| bytes as f64 / prof.file_size.max(1) as f64;
+    let line = |bytes: usize, label: &str| {
+        let bar = "#".repeat((share(bytes) * 20.0).round() as usize);
+        println!(
+            "{:>10}  {:>5.1}%  {:<20}  {}",
+            bytes,
+            share(bytes) * 100.0,
+            bar,
+            label
+        );
+    };
+    println!("{}: {} bytes", display_name(&args.path), prof.file_size);
+    if args.by_class {
+        for c in classes {
+            line(c.bytes, &format!("{} ({})", short_class(&c.class), c.count));
+        }
+    } else {
+        // Each node under its parent, largest first
+        fn show(n: &SizeNode, top: &[SizeNode], depth: usize, line: &dyn Fn(usize, &str)) {
+            let name = n.pointer.rsplit('/').next().unwrap_or_default();
+            let class = n
+                .class
+                .as_deref()
+                .map(|c| format!("  {}", short_class(c)))
+                .unwrap_or_default();
+            line(n.total, &format!("{}{}{}", "  ".repeat(depth), name, class));
+            for c in top
+                .iter()
+                .filter(|c| parent(&c.pointer) == Some(&n.pointer))
+            {
+                show(c, top, depth + 1, line);
+            }
+        }
+        if let Some(root) = top.first() {
+            show(root, top, 0, &line);
+        }
+    }
+    line(prof.other(), "(header, class metadata, list wrappers)");
+}
+
+fn cmd_duplicates(args: DuplicatesArgs) {
+    use wle_core::validate::{DuplicateKind, find_duplicates, remove_duplicates_ops};
+    let v = load_json(&args.path);
+    let groups = find_duplicates(&v);
+    let ops = remove_duplicates_ops(&v, &groups);
+    if args.remove {
+        if ops.is_empty() {
+            note!("no copies to remove");
+            return;
+        }
+        let edit = wle_core::cheats::PendingEdit {
+            path: args.path.clone(),
+            ops,
+        };
+        if dry_run() {
+            return preview_edit(Ok(edit));
+        }
+        match edit.apply() {
+            Ok(backup) => note!(
+                "removed copies from {} group(s); backup: {}",
+                groups.len(),
+                backup.display()
+            ),
+            Err(e) => fail!(exit::EDIT, "error: {}", e),
+        }
+        return;
+    }
+    if structured() {
+        let arr: Vec<serde_json::Value> = groups
+            .iter()
+            .map(|g| {
+                let mut o = match &g.kind {
+                    DuplicateKind::SameGuid(guid) => serde_json::json!({"guid": guid.to_string()}),
+                    DuplicateKind::SamePlace { class, position } => {
+                        serde_json::json!({"class": class, "position": position})
+                    }
+                };
+                o["pointers"] = serde_json::json!(g.pointers);
+                o
+            })
+            .collect();
+        print_json(&arr.into());
+    } else {
+        for g in &groups {
+            println!("{}", g);
+            for (i, p) in g.pointers.iter().enumerate() {
+                println!("  {}  {}", if i == 0 { "keep" } else { "copy" }, p);
+            }
+        }
+        if !groups.is_empty() {
+            note!(
+                "{} group(s); `--remove` deletes {} copies",
+                groups.len(),
+                ops.iter()
+                    .filter(|op| matches!(op, wle_core::PatchOp::Remove { .. }))
+                    .count()
+            );
+        }
+    }
+    if !groups.is_empty() {
+        std::process::exit(exit::FINDINGS);
+    }
+}
+
+// $WLE_CLI_CONFIG, else wle-cli/config.json in the platform's config folder
+fn config_path() -> Option<PathBuf> {
+    if let Some(p) = std::env::var_os("WLE_CLI_CONFIG") {
+        return Some(PathBuf::from(p));
+    }
+    wle_core::saves::user_config_dir().map(|b| b.join("wle-cli").join("config.json"))
+}
+
+// A missing file is an empty config; unknown keys are errors so typos show up
+fn load_config(path: &Path) -> Result<Config, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let v: serde_json::Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    let map = v.as_object().ok_or("expected a JSON object")?;
+    let dir = |v: &serde_json::Value| v.as_str().map(PathBuf::from);
+    let count = |v: &serde_json::Value| v.as_u64().map(|n| n as usize);
+    let mut config = Config::default();
+    for (key, value) in map {
+        let ok = match key.as_str() {
+            "save_root" => dir(value).map(|d| config.save_root = Some(d)),
+            "backup_dir" => dir(value).map(|d| config.backup_dir = Some(d)),
+            "max_array" => count(value).map(|n| config.max_array = Some(n)),
+            "max_depth" => count(value).map(|n| config.max_depth = Some(n)),
+            "preset" => value
+                .as_str()
+                .filter(|p| matches!(*p, "default" | "full"))
+                .map(|p| config.full = p == "full"),
+            "bytes_full" => value.as_bool().map(|b| config.bytes_full = b),
+            "transforms_dir" => dir(value).map(|d| config.transforms_dir = Some(d)),
+            "migrations_dir" => dir(value).map(|d| config.migrations_dir = Some(d)),
+            "templates_dir" => dir(value).map(|d| config.templates_dir = Some(d)),
+            _ => return Err(format!("unknown setting '{}'", key)),
+        };
+        if ok.is_none() {
+            return Err(format!("bad value for {}: {}", key, value));
+        }
+    }
+    Ok(config)
+}
+
+// Make the config's numbers the defaults of every --max-array/--max-depth,
+// so explicit flags still win and --help shows the effective default
+fn with_config_defaults(cmd: clap::Command, config: &Config) -> clap::Command {
+    let cmd = cmd.mut_subcommands(|c| with_config_defaults(c, config));
+    cmd.mut_args(|a| {
+        let value = match a.get_id().as_str() {
+            "max_array" => config.max_array.map(|n| n.to_string()),
+            "max_depth" => config.max_depth.map(|n| n.to_string()),
+            "full" if config.full => Some("true".into()),
+            "bytes_full" if config.bytes_full => Some("true".into()),
+            _ => None,
+        };
+        match value {
+            Some(v) => a.default_value(v),
+            None => a,
+        }
+    })
+}
+
+fn cmd_config() {
+    let path = config_path();
+    let c = config();
+    if structured() {
+        print_json(&serde_json::json!({
+            "path": path,
+            "exists": path.as_ref().is_some_and(|p| p.is_file()),
+            "save_root": c.save_root,
+            "backup_dir": c.backup_dir,
+            "max_array": c.max_array,
+            "max_depth": c.max_depth,
+            "preset": if c.full { "full" } else { "default" },
+            "bytes_full": c.bytes_full,
+            "transforms_dir": c.transforms_dir,
+            "migrations_dir": c.migrations_dir,
+            "templates_dir": c.templates_dir,
+        }));
+        return;
+    }
+    match &path {
+        Some(p) if p.is_file() => println!("config: {}", p.display()),
+        Some(p) => println!("config: {} (not present)", p.display()),
+        None => println!("config: none (set WLE_CLI_CONFIG)"),
+    }
+    let show = |v: Option<String>| v.unwrap_or_else(|| "-".into());
+    let dir = |d: &Option<PathBuf>| d.as_ref().map(|d| d.display().to_string());
+    println!("save_root   {}", show(dir(&c.save_root)));
+    println!("backup_dir  {}", show(dir(&c.backup_dir)));
+    println!("max_array   {}", show(c.max_array.map(|n| n.to_string())));
+    println!("max_depth   {}", show(c.max_depth.map(|n| n.to_string())));
+    println!("preset      {}", if c.full { "full" } else { "default" });
+    println!("bytes_full  {}", c.bytes_full);
+    println!("transforms_dir  {}", show(dir(&c.transforms_dir)));
+    println!("migrations_dir  {}", show(dir(&c.migrations_dir)));
+    println!("templates_dir   {}", show(dir(&c.templates_dir)));
+}
+
+// Each command path ("" for the top level, "backups create", ...) with the
+// words to offer after it, and the values of every option that takes a fixed set
+struct CompletionTree {
+    paths: Vec<(String, Vec<String>)>,
+    values: std::collections::BTreeMap<String, std::collections::BTreeSet<String>>,
+}
+
+fn completion_tree() -> CompletionTree {
+    fn walk(cmd: &clap::Command, path: &str, tree: &mut CompletionTree) {
+        let mut words = Vec::new();
+        for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+            words.push(sub.get_name().to_string());
+            words.extend(sub.get_visible_aliases().map(str::to_string));
+        }
+        for arg in cmd.get_arguments().filter(|a| !a.is_hide_set()) {
+            let values = arg
+                .get_possible_values()
+                .into_iter()
+                .filter(|v| !v.is_hide_set())
+                .map(|v| v.get_name().to_string());
+            match arg.get_long() {
+                Some(long) => {
+                    let flag = format!("--{}", long);
+                    tree.values.entry(flag.clone()).or_default().extend(values);
+                    words.push(flag);
+                }
+                None if arg.is_positional() => words.extend(values),
+                None => {}
+            }
+        }
+        tree.paths.push((path.to_string(), words));
+        // clap's `help` subcommand mirrors the tree; offering its name is enough
+        for sub in cmd
+            .get_subcommands()
+            .filter(|s| !s.is_hide_set() && s.get_name() != "help")
+        {
+            for name in std::iter::once(sub.get_name()).chain(sub.get_visible_aliases()) {
+                let child = format!("{} {}", path, name);
+                walk(sub, child.trim_start(), tree);
+            }
+        }
+    }
+    let mut cmd = Cli::command();
+    // Propagates global flags and adds help/version to every subcommand
+    cmd.build();
+    let mut tree = CompletionTree {
+        paths: Vec::new(),
+        values: Default::default(),
+    };
+    walk(&cmd, "", &mut tree);
+    tree.values.retain(|_, v| !v.is_empty());
+    tree
+}
+
+fn cmd_completions(args: CompletionsArgs) {
+    let tree = completion_tree();
+    let script = match args.shell {
+        Shell::Bash => bash_completions(&tree),
+        // zsh runs the bash function through bashcompinit
+        Shell::Zsh => format!(
+            "#compdef wle-cli\nautoload -U +X bashcompinit && bashcompinit\n{}",
+            bash_completions(&tree)
+        ),
+        Shell::Fish => fish_completions(&tree),
+        Shell::Powershell => powershell_completions(&tree),
+    };
+    print!("{}", script);
+}
+
+fn bash_completions(tree: &CompletionTree) -> String {
+    let mut s = String::from(
+        "_wle_cli() {\n    local cur=\"${COMP_WORDS[COMP_CWORD]}\" prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n    local path=\"\" w i words=\"\"\n    for ((i = 1; i < COMP_CWORD; i++)); do\n        w=\"${COMP_WORDS[i]}\"\n        case \"${path:+$path }$w\" in\n",
+    );
+    let subpaths: Vec<&str> = tree.paths[1..].iter().map(|(p, _)| p.as_str()).collect();
+    for p in &subpaths {
+        s.push_str(&format!("            \"{}\") path=\"{}\" ;;\n", p, p));
+    }
+    s.push_str("        esac\n    done\n    case \"$prev\" in\n");
+    for (flag, values) in &tree.values {
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+        s.push_str(&format!(
+            "        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return ;;\n",
+            flag,
+            values.join(" ")
+        ));
+    }
+    s.push_str("    esac\n    case \"$path\" in\n");
+    for (p, words) in &tree.paths {
+        s.push_str(&format!(
+            "        \"{}\") words=\"{}\" ;;\n",
+            p,
+            words.join(" ")
+        ));
+    }
+    s.push_str("    esac\n    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))\n}\ncomplete -o default -F _wle_cli wle-cli\n");
+    s
+}
+
+fn fish_completions(tree: &CompletionTree) -> String {
+    let mut s = String::from(
+        "function __wle_cli_path\n    set -l path ''\n    for w in (commandline -opc)[2..-1]\n        set -l next (string trim -- \"$path $w\")\n        if contains -- $next $__wle_cli_paths\n            set path $next\n        end\n    end\n    echo $path\nend\n",
+    );
+    let subpaths: Vec<String> = tree.paths[1..]
+        .iter()
+        .map(|(p, _)| format!("'{}'", p))
+        .collect();
+    s.push_str(&format!("set -g __wle_cli_paths {}\n", subpaths.join(" ")));
+    for (p, words) in &tree.paths {
+        let (flags, names): (Vec<&String>, Vec<&String>) =
+            words.iter().partition(|w| w.starts_with("--"));
+        let cond = format!("test (__wle_cli_path) = '{}'", p);
+        if !names.is_empty() {
+            let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+            s.push_str(&format!(
+                "complete -c wle-cli -n \"{}\" -a '{}'\n",
+                cond,
+                names.join(" ")
+            ));
+        }
+        for flag in flags {
+            let long = &flag[2..];
+            match tree.values.get(flag.as_str()) {
+                Some(values) => {
+                    let values: Vec<&str> = values.iter().map(String::as_str).collect();
+                    s.push_str(&format!(
+                        "complete -c wle-cli -n \"{}\" -l {} -x -a '{}'\n",
+                        cond,
+                        long,
+                        values.join(" ")
+                    ));
+                }
+                None => s.push_str(&format!(
+                    "complete -c wle-cli -n \"{}\" -l {}\n",
+                    cond, long
+                )),
+            }
+        }
+    }
+    s
+}
+
+fn powershell_completions(tree: &CompletionTree) -> String {
+    let quote = |w: &str| format!("'{}'", w.replace('\'', "''"));
+    let mut s = String::from("$wleCliWords = @{\n");
+    for (p, words) in &tree.paths {
+        let words: Vec<String> = words.iter().map(|w| quote(w)).collect();
+        s.push_str(&format!("    {} = @({})\n", quote(p), words.join(", ")));
+    }
+    s.push_str("}\n$wleCliValues = @{\n");
+    for (flag, values) in &tree.values {
+        let values: Vec<String> = values.iter().map(|v| quote(v)).collect();
+        s.push_str(&format!("    {} = @({})\n", quote(flag), values.join(", ")));
+    }
+    s.push_str(
+        "}\nRegister-ArgumentCompleter -Native -CommandName wle-cli -ScriptBlock {\n    param($wordToComplete, $commandAst, $cursorPosition)\n    $path = ''\n    $prev = ''\n    foreach ($e in $commandAst.CommandElements | Select-Object -Skip 1) {\n        $w = \"$e\"\n        if ($w -eq $wordToComplete) { break }\n        $next = \"$path $w\".Trim()\n        if ($wleCliWords.ContainsKey($next)) { $path = $next }\n        $prev = $w\n    }\n    $words = if ($wleCliValues.ContainsKey($prev)) { $wleCliValues[$prev] } else { $wleCliWords[$path] }\n    $words | Where-Object { $_ -like \"$wordToComplete*\" } | ForEach-Object {\n        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)\n    }\n}\n",
+    );
+    s
 }
@@ -0,0 +1,96 @@
+// Parse, dump and write on synthetic saves sized like the big real ones: a
+// SlotInfo with a large thumbnail (one huge byte array) and a WorldData-like
+// document with many small objects. Run with `cargo bench -p wle-core`; save a
+// baseline before a redesign (`-- --save-baseline before`) and compare after
+// (`-- --baseline before`).
+
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use wle_core::json::JsonOpts;
+
+// SlotInfo whose thumbnail is a `side`² RGB image
+fn slot_info(side: usize) -> Vec<u8> {
+    let img: Vec<u8> = (0..side * side * 3).map(|i| (i % 251) as u8).collect();
+    wle_core::editor::build_slot_info_bytes(1, "2025-09-22 12:00", &img)
+}
+
+// A world with `n` placed objects, each with a transform and a few fields
+fn world(n: usize) -> Vec<u8> {
+    let mut b = wle_core::DocumentBuilder::new();
+    b.begin_object("WorldSaveData").begin_array("objects");
+    for i in 0..n {
+        b.begin_object("PlacedObjectData")
+            .member_i32("id", i as i32)
+            .member_str("prefab", &format!("Vehicle_{}", i % 40))
+            .member_bool("bLocked", i % 3 == 0)
+            .member_i64("ownerPlayerId", (i % 4) as i64)
+            .member_object("transform", "TransformData")
+            .member_f32_array("position", &[i as f32, 1.5, -(i as f32)])
+            .member_f32_array("rotation", &[0.0, 0.5, 0.0, 0.866])
+            .end_object()
+            .end_object();
+    }
+    b.end_array().end_object();
+    b.finish().unwrap()
+}
+
+fn inputs() -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        ("slot_info_512px", slot_info(512)),
+        ("world_1k", world(1_000)),
+        ("world_20k", world(20_000)),
+    ]
+}
+
+fn parse(c: &mut Criterion) {
+    let mut g = c.benchmark_group("parse");
+    for (name, data) in inputs() {
+        g.throughput(Throughput::Bytes(data.len() as u64));
+        g.bench_with_input(BenchmarkId::from_parameter(name), &data, |b, data| {
+            b.iter(|| {
+                wle_core::binfmt::Parser::new(black_box(data))
+                    .parse_stream()
+                    .unwrap()
+            })
+        });
+    }
+    g.finish();
+}
+
+fn dump(c: &mut Criterion) {
+    let mut g = c.benchmark_group("dump");
+    for (name, data) in inputs() {
+        let doc = wle_core::binfmt::Parser::new(&data).parse_stream().unwrap();
+        g.throughput(Throughput::Bytes(data.len() as u64));
+        g.bench_function(BenchmarkId::new("json_value", name), |b| {
+            b.iter(|| wle_core::document_to_json_value(black_box(&doc), JsonOpts::full()))
+        });
+        g.bench_function(BenchmarkId::new("json_text", name), |b| {
+            b.iter(|| wle_core::json::dump_dynamic_json(black_box(&doc), JsonOpts::full()))
+        });
+    }
+    g.finish();
+}
+
+fn write(c: &mut Criterion) {
+    let mut g = c.benchmark_group("write");
+    for (name, data) in inputs() {
+        let v = wle_core::parse_bytes_to_json_value(data.clone(), JsonOpts::full()).unwrap();
+        g.throughput(Throughput::Bytes(data.len() as u64));
+        g.bench_with_input(BenchmarkId::from_parameter(name), &v, |b, v| {
+            b.iter(|| wle_core::write_binfmt_from_json(black_box(v)).unwrap())
+        });
+    }
+    g.finish();
+}
+
+// `get --ptr` on a big world: parse plus converting one node
+fn get(c: &mut Criterion) {
+    let data = world(20_000);
+    let ptrs = ["/root/objects/19999/transform".to_string()];
+    c.bench_function("get/world_20k", |b| {
+        b.iter(|| wle_core::parse_bytes_at_pointers(data.clone(), &ptrs, JsonOpts::full()).unwrap())
+    });
+}
+
+criterion_group!(benches, parse, dump, write, get);
+criterion_main!(benches);
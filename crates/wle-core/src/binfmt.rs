@@ -8,6 +8,100 @@ pub struct Parser<'a> {
     pos: usize,
     ctx: Context<'a>,
     root_id: Option<i32>,
+    // Class whose members are being read, innermost last
+    classes: Vec<&'a str>,
+    unknowns: Vec<Unknown>,
+    progress: Option<std::sync::Arc<ParseProgress>>,
+    cancel: Option<CancellationToken>,
+}
+
+/// A flag another thread sets to stop a parse, conversion or write early,
+/// which then fails with a "cancelled" error. Clones share the flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(std::sync::Arc<std::sync::atomic::AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// `Err("cancelled")` once cancelled, for `?` in long loops
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err("cancelled".into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Bytes and records read so far by a parse running on another thread, and
+/// a flag to stop it. Updated once per record, nested ones included.
+#[derive(Debug, Default)]
+pub struct ParseProgress {
+    done: std::sync::atomic::AtomicUsize,
+    total: std::sync::atomic::AtomicUsize,
+    records: std::sync::atomic::AtomicUsize,
+    cancelled: CancellationToken,
+}
+
+impl ParseProgress {
+    /// Progress whose `cancel` is `cancelled`, e.g. one shared with a write
+    pub fn new(cancelled: CancellationToken) -> Self {
+        Self {
+            cancelled,
+            ..Default::default()
+        }
+    }
+
+    /// Bytes read so far and the size of the input (0 before the parse starts)
+    pub fn bytes(&self) -> (usize, usize) {
+        use std::sync::atomic::Ordering::Relaxed;
+        (self.done.load(Relaxed), self.total.load(Relaxed))
+    }
+
+    /// Records read so far, nested ones included
+    pub fn records(&self) -> usize {
+        self.records.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Share of the input read, 0.0..=1.0 (0 before the parse starts)
+    pub fn fraction(&self) -> f32 {
+        use std::sync::atomic::Ordering::Relaxed;
+        match self.total.load(Relaxed) {
+            0 => 0.0,
+            total => self.done.load(Relaxed) as f32 / total as f32,
+        }
+    }
+
+    /// Make the parse stop at the next record with a "cancelled" error
+    pub fn cancel(&self) {
+        self.cancelled.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.is_cancelled()
+    }
+
+    /// The flag `cancel` sets, to stop the work that follows the parse too
+    pub fn token(&self) -> CancellationToken {
+        self.cancelled.clone()
+    }
+}
+
+/// Something the reader accepted without fully understanding it: a value
+/// kept as raw bits or a record that was skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unknown {
+    /// e.g. "Decimal kept as raw bytes" or "top-level MemberReference skipped"
+    pub what: String,
+    /// Class being read when it was met
+    pub class: Option<String>,
+    pub offset: usize,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -16,6 +110,18 @@ struct Context<'a> {
     strings: HashMap<i32, &'a str>,
     objects: HashMap<i32, Value<'a>>,        // objectId -> value
     class_meta: HashMap<i32, ClassMeta<'a>>, // metadataId -> class info
+    records: HashMap<usize, RecordSpan>,     // record start -> extent and member spans
+    record_at: HashMap<i32, usize>,          // objectId -> record start
+}
+
+/// Byte range `start..end` of the input a value was read from
+pub type ByteSpan = std::ops::Range<usize>;
+
+#[derive(Debug, Default, Clone)]
+struct RecordSpan {
+    end: usize,
+    // One span per member or array element, in order
+    children: Vec<ByteSpan>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -46,6 +152,7 @@ pub struct Document<'a> {
     root_id: Option<i32>,
     root: Option<Value<'a>>, // convenience snapshot of root
     ctx: Context<'a>,
+    unknowns: Vec<Unknown>,
 }
 
 impl<'a> Document<'a> {
@@ -75,6 +182,87 @@ impl<'a> Document<'a> {
     pub fn root_object_id(&self) -> Option<i32> {
         self.root_id
     }
+    pub fn unknowns(&self) -> &[Unknown] {
+        &self.unknowns
+    }
+
+    /// Byte span of every node of the edit JSON (`JsonOpts::full()`), keyed
+    /// by its JSON pointer. Objects and arrays cover their whole record; a
+    /// member holding a reference covers the reference, its `$value` the
+    /// referenced record.
+    pub fn byte_spans(&self) -> HashMap<String, ByteSpan> {
+        let mut out = HashMap::new();
+        if let (Some(root), Some(id)) = (&self.root, self.root_id)
+            && let Some(span) = self.record_span(id)
+        {
+            self.walk_spans(root, span, "/root".to_string(), 1, &mut out);
+        }
+        out
+    }
+
+    fn record_span(&self, id: i32) -> Option<ByteSpan> {
+        let start = *self.ctx.record_at.get(&id)?;
+        Some(start..self.ctx.records.get(&start)?.end)
+    }
+
+    // Mirrors the List`1 flattening and depth limit of document_to_json_value
+    fn walk_spans(
+        &self,
+        v: &Value<'a>,
+        span: ByteSpan,
+        ptr: String,
+        depth: usize,
+        out: &mut HashMap<String, ByteSpan>,
+    ) {
+        const MAX_DEPTH: usize = 64;
+        let children = || {
+            self.ctx
+                .records
+                .get(&span.start)
+                .map(|r| r.children.as_slice())
+                .unwrap_or_default()
+        };
+        match v {
+            Value::Array(items) => {
+                for (i, (it, s)) in items.iter().zip(children()).enumerate() {
+                    if depth < MAX_DEPTH {
+                        self.walk_spans(it, s.clone(), format!("{}/{}", ptr, i), depth + 1, out);
+                    }
+                }
+            }
+            Value::Object(obj) => {
+                for ((name, m), s) in obj.members.iter().zip(children()) {
+                    if depth < MAX_DEPTH {
+                        let p = format!("{}/{}", ptr, crate::edit::escape_token(name));
+                        self.walk_spans(m, s.clone(), p, depth + 1, out);
+                    }
+                }
+            }
+            Value::Ref(id) => {
+                if let Some(Value::Object(obj)) = self.get_object(*id)
+                    && obj
+                        .class_name
+                        .starts_with("System.Collections.Generic.List`1")
+                    && let Some((_, Value::Ref(items_id))) =
+                        obj.members.iter().find(|(name, _)| *name == "_items")
+                    && let Some(items) = self.get_object(*items_id)
+                    && let Some(items_span) = self.record_span(*items_id)
+                {
+                    return self.walk_spans(items, items_span, ptr, depth, out);
+                }
+                out.insert(format!("{}/$ref", ptr), span.clone());
+                if depth < MAX_DEPTH
+                    && let Some(target) = self.get_object(*id)
+                    && let Some(target_span) = self.record_span(*id)
+                {
+                    let p = format!("{}/$value", ptr);
+                    self.walk_spans(target, target_span, p, depth + 1, out);
+                }
+            }
+            _ => {}
+        }
+        out.insert(ptr, span);
+    }
 
     fn fmt_value(&self, v: &Value<'a>, indent: usize, out: &mut String) -> fmt::Result {
         let pad = |n: usize| -> String { " ".repeat(n) };
@@ -194,13 +382,46 @@ impl<'a> Parser<'a> {
             pos: 0,
             ctx: Context::default(),
             root_id: None,
+            classes: Vec::new(),
+            unknowns: Vec::new(),
+            progress: None,
+            cancel: None,
         }
     }
+    /// Stop at the next record once `cancel` is cancelled
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+    /// Report progress to (and honour cancellation from) `progress`
+    pub fn with_progress(mut self, progress: std::sync::Arc<ParseProgress>) -> Self {
+        progress
+            .total
+            .store(self.data.len(), std::sync::atomic::Ordering::Relaxed);
+        self.cancel = Some(progress.token());
+        self.progress = Some(progress);
+        self
+    }
     pub fn pos(&self) -> usize {
         self.pos
     }
 
+    #[tracing::instrument(level = "debug", name = "parse", skip_all, fields(bytes = self.data.len()))]
     pub fn parse_stream(&mut self) -> Result<Document<'a>, String> {
+        let res = self.parse_records();
+        match &res {
+            Ok(doc) => tracing::debug!(
+                records = doc.ctx.records.len(),
+                objects = doc.ctx.objects.len(),
+                unknowns = doc.unknowns.len(),
+                "parsed"
+            ),
+            Err(e) => tracing::debug!(pos = self.pos, error = %e, "parse failed"),
+        }
+        res
+    }
+
+    fn parse_records(&mut self) -> Result<Document<'a>, String> {
         let rec = self.read_u8()?;
         if rec != RecordType::SerializedStreamHeader as u8 {
             return Err(format!(
@@ -214,7 +435,9 @@ impl<'a> Parser<'a> {
         let _minor = self.read_i32()?;
         let mut root_snapshot: Option<Value<'a>> = None;
         loop {
+            self.tick()?;
             let rec = self.read_u8()?;
+            tracing::trace!(rec, at = self.pos - 1, "record");
             match rec {
                 x if x == RecordType::BinaryLibrary as u8 => {
                     let lib_id = self.read_i32()?;
@@ -262,17 +485,21 @@ impl<'a> Parser<'a> {
                     self.ctx.objects.insert(obj_id, Value::Object(obj));
                 }
                 x if x == RecordType::BinaryObjectString as u8 => {
+                    let start = self.pos - 1;
                     let id = self.read_i32()?;
                     let s = self.read_lp_string()?;
                     self.ctx.strings.insert(id, s);
                     self.ctx.objects.insert(id, Value::Str(s));
+                    self.note_record(id, start, Vec::new());
                 }
                 x if x == RecordType::MemberPrimitiveTyped as u8 => {
+                    self.note_unknown("top-level MemberPrimitiveTyped skipped", self.pos - 1);
                     let prim = self.read_primitive_type()?;
                     let v = self.read_inline_primitive(prim)?;
                     let _ = v;
                 }
                 x if x == RecordType::MemberReference as u8 => {
+                    self.note_unknown("top-level MemberReference skipped", self.pos - 1);
                     let _ = self.read_i32()?;
                 }
                 x if x == RecordType::ObjectNull as u8 => {}
@@ -303,6 +530,10 @@ impl<'a> Parser<'a> {
                     self.ctx.objects.insert(id, v);
                 }
                 x if x == RecordType::MessageEnd as u8 => {
+                    if self.pos < self.data.len() {
+                        let what = format!("{} bytes after MessageEnd", self.data.len() - self.pos);
+                        self.note_unknown(&what, self.pos);
+                    }
                     break;
                 }
                 other => {
@@ -313,26 +544,60 @@ impl<'a> Parser<'a> {
                 }
             }
         }
+        if let Some(p) = &self.progress {
+            p.done
+                .store(self.data.len(), std::sync::atomic::Ordering::Relaxed);
+        }
         Ok(Document {
             root_id: self.root_id,
             root: root_snapshot,
             ctx: std::mem::take(&mut self.ctx),
+            unknowns: std::mem::take(&mut self.unknowns),
         })
     }
 
+    // Remember where record `id` (starting at `start`) and its members were read
+    /// Report the position to the progress and stop if cancelled; called
+    /// before every record, nested ones included
+    fn tick(&self) -> Result<(), String> {
+        if let Some(c) = &self.cancel {
+            c.check()?;
+        }
+        if let Some(p) = &self.progress {
+            p.done.store(self.pos, std::sync::atomic::Ordering::Relaxed);
+            p.records.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn note_record(&mut self, id: i32, start: usize, children: Vec<ByteSpan>) {
+        let end = self.pos;
+        self.ctx.records.insert(start, RecordSpan { end, children });
+        self.ctx.record_at.insert(id, start);
+    }
+
+    fn note_unknown(&mut self, what: &str, offset: usize) {
+        self.unknowns.push(Unknown {
+            what: what.to_string(),
+            class: self.classes.last().map(|c| c.to_string()),
+            offset,
+        });
+    }
+
     fn read_class_with_members_and_types(&mut self) -> Result<(i32, DynObject<'a>), String> {
+        let start = self.pos - 1;
         let object_id = self.read_i32()?;
         let class_name = self.read_lp_string()?;
-        let member_count = self.read_i32()? as usize;
-        let mut member_names = Vec::with_capacity(member_count);
+        let member_count = self.read_count()?;
+        let mut member_names = Vec::with_capacity(self.capacity(member_count));
         for _ in 0..member_count {
             member_names.push(self.read_lp_string()?);
         }
-        let mut bin_types_raw = Vec::with_capacity(member_count);
+        let mut bin_types_raw = Vec::with_capacity(self.capacity(member_count));
         for _ in 0..member_count {
             bin_types_raw.push(self.read_u8()?);
         }
-        let mut bin_types: Vec<BinaryType> = Vec::with_capacity(member_count);
+        let mut bin_types: Vec<BinaryType> = Vec::with_capacity(self.capacity(member_count));
         for t in bin_types_raw {
             let bt = match t {
                 0 => {
@@ -362,9 +627,14 @@ impl<'a> Parser<'a> {
         }
         let library_id = self.read_i32()?;
 
-        let mut members: Vec<(&'a str, Value<'a>)> = Vec::with_capacity(member_count);
+        let mut members: Vec<(&'a str, Value<'a>)> =
+            Vec::with_capacity(self.capacity(member_count));
+
+        let mut spans = Vec::with_capacity(self.capacity(member_count));
+        self.classes.push(class_name);
         for (i, bt) in bin_types.iter().enumerate() {
             let name = member_names[i];
+            let at = self.pos;
             let val = match bt {
                 BinaryType::Primitive(p) => self.read_inline_primitive(*p)?,
                 BinaryType::String => self.read_next_string_like()?,
@@ -375,7 +645,9 @@ impl<'a> Parser<'a> {
                 BinaryType::SystemClass | BinaryType::Class => self.read_next_object_like()?,
             };
             members.push((name, val));
+            spans.push(at..self.pos);
         }
+        self.classes.pop();
         // Register metadata keyed by object_id as well
         self.ctx.class_meta.insert(
             object_id,
@@ -386,6 +658,7 @@ impl<'a> Parser<'a> {
                 member_types: Some(bin_types.clone()),
             },
         );
+        self.note_record(object_id, start, spans);
         Ok((
             object_id,
             DynObject {
@@ -397,10 +670,11 @@ impl<'a> Parser<'a> {
     }
 
     fn read_class_with_members(&mut self) -> Result<(i32, DynObject<'a>), String> {
+        let start = self.pos - 1;
         let object_id = self.read_i32()?;
         let class_name = self.read_lp_string()?;
-        let member_count = self.read_i32()? as usize;
-        let mut member_names = Vec::with_capacity(member_count);
+        let member_count = self.read_count()?;
+        let mut member_names = Vec::with_capacity(self.capacity(member_count));
         for _ in 0..member_count {
             member_names.push(self.read_lp_string()?);
         }
@@ -414,11 +688,18 @@ impl<'a> Parser<'a> {
                 member_types: None,
             },
         );
-        let mut members: Vec<(&'a str, Value<'a>)> = Vec::with_capacity(member_count);
+        let mut members: Vec<(&'a str, Value<'a>)> =
+            Vec::with_capacity(self.capacity(member_count));
+        let mut spans = Vec::with_capacity(self.capacity(member_count));
+        self.classes.push(class_name);
         for &name in member_names.iter().take(member_count) {
+            let at = self.pos;
             let val = self.read_next_any_value()?;
             members.push((name, val));
+            spans.push(at..self.pos);
         }
+        self.classes.pop();
+        self.note_record(object_id, start, spans);
         Ok((
             object_id,
             DynObject {
@@ -430,10 +711,11 @@ impl<'a> Parser<'a> {
     }
 
     fn read_system_class_with_members(&mut self) -> Result<(i32, DynObject<'a>), String> {
+        let start = self.pos - 1;
         let object_id = self.read_i32()?;
         let class_name = self.read_lp_string()?;
-        let member_count = self.read_i32()? as usize;
-        let mut member_names = Vec::with_capacity(member_count);
+        let member_count = self.read_count()?;
+        let mut member_names = Vec::with_capacity(self.capacity(member_count));
         for _ in 0..member_count {
             member_names.push(self.read_lp_string()?);
         }
@@ -446,11 +728,18 @@ impl<'a> Parser<'a> {
                 member_types: None,
             },
         );
-        let mut members: Vec<(&'a str, Value<'a>)> = Vec::with_capacity(member_count);
+        let mut members: Vec<(&'a str, Value<'a>)> =
+            Vec::with_capacity(self.capacity(member_count));
+        let mut spans = Vec::with_capacity(self.capacity(member_count));
+        self.classes.push(class_name);
         for &name in member_names.iter().take(member_count) {
+            let at = self.pos;
             let val = self.read_next_any_value()?;
             members.push((name, val));
+            spans.push(at..self.pos);
         }
+        self.classes.pop();
+        self.note_record(object_id, start, spans);
         Ok((
             object_id,
             DynObject {
@@ -462,6 +751,7 @@ impl<'a> Parser<'a> {
     }
 
     fn read_class_with_id(&mut self) -> Result<(i32, DynObject<'a>), String> {
+        let start = self.pos - 1;
         let object_id = self.read_i32()?;
         let metadata_id = self.read_i32()?;
         let meta = self
@@ -471,9 +761,12 @@ impl<'a> Parser<'a> {
             .ok_or_else(|| format!("unknown metadataId {} at {:#x}", metadata_id, self.pos - 4))?
             .clone();
         let mut members: Vec<(&'a str, Value<'a>)> = Vec::with_capacity(meta.member_names.len());
+        let mut spans = Vec::with_capacity(meta.member_names.len());
+        self.classes.push(meta.class_name);
         if let Some(types) = meta.member_types {
             for (i, bt) in types.iter().enumerate() {
                 let name = meta.member_names[i];
+                let at = self.pos;
                 let val = match bt {
                     BinaryType::Primitive(p) => self.read_inline_primitive(*p)?,
                     BinaryType::String => self.read_next_string_like()?,
@@ -484,13 +777,18 @@ impl<'a> Parser<'a> {
                     BinaryType::SystemClass | BinaryType::Class => self.read_next_object_like()?,
                 };
                 members.push((name, val));
+                spans.push(at..self.pos);
             }
         } else {
             for name in meta.member_names.iter().copied() {
+                let at = self.pos;
                 let val = self.read_next_any_value()?;
                 members.push((name, val));
+                spans.push(at..self.pos);
             }
         }
+        self.classes.pop();
+        self.note_record(object_id, start, spans);
         Ok((
             object_id,
             DynObject {
@@ -582,6 +880,7 @@ impl<'a> Parser<'a> {
     }
 
     fn read_next_object_like(&mut self) -> Result<Value<'a>, String> {
+        self.tick()?;
         let rec = self.peek_u8()?;
         match rec {
             x if x == RecordType::ClassWithMembersAndTypes as u8 => {
@@ -631,18 +930,19 @@ impl<'a> Parser<'a> {
     }
 
     fn read_system_class_with_members_and_types(&mut self) -> Result<(i32, DynObject<'a>), String> {
+        let start = self.pos - 1;
         let object_id = self.read_i32()?;
         let class_name = self.read_lp_string()?; // e.g., System.Guid
-        let member_count = self.read_i32()? as usize;
-        let mut member_names = Vec::with_capacity(member_count);
+        let member_count = self.read_count()?;
+        let mut member_names = Vec::with_capacity(self.capacity(member_count));
         for _ in 0..member_count {
             member_names.push(self.read_lp_string()?);
         }
-        let mut bin_types_raw = Vec::with_capacity(member_count);
+        let mut bin_types_raw = Vec::with_capacity(self.capacity(member_count));
         for _ in 0..member_count {
             bin_types_raw.push(self.read_u8()?);
         }
-        let mut bin_types: Vec<BinaryType> = Vec::with_capacity(member_count);
+        let mut bin_types: Vec<BinaryType> = Vec::with_capacity(self.capacity(member_count));
         for t in bin_types_raw {
             let bt = match t {
                 0 => BinaryType::Primitive(self.read_primitive_type()?),
@@ -664,9 +964,13 @@ impl<'a> Parser<'a> {
             };
             bin_types.push(bt);
         }
-        let mut members: Vec<(&'a str, Value<'a>)> = Vec::with_capacity(member_count);
+        let mut members: Vec<(&'a str, Value<'a>)> =
+            Vec::with_capacity(self.capacity(member_count));
+        let mut spans = Vec::with_capacity(self.capacity(member_count));
+        self.classes.push(class_name);
         for (i, bt) in bin_types.iter().enumerate() {
             let name = member_names[i];
+            let at = self.pos;
             let val = match bt {
                 BinaryType::Primitive(p) => self.read_inline_primitive(*p)?,
                 BinaryType::String => self.read_next_string_like()?,
@@ -677,7 +981,9 @@ impl<'a> Parser<'a> {
                 BinaryType::SystemClass | BinaryType::Class => self.read_next_object_like()?,
             };
             members.push((name, val));
+            spans.push(at..self.pos);
         }
+        self.classes.pop();
         self.ctx.class_meta.insert(
             object_id,
             ClassMeta {
@@ -687,6 +993,7 @@ impl<'a> Parser<'a> {
                 member_types: Some(bin_types.clone()),
             },
         );
+        self.note_record(object_id, start, spans);
         Ok((
             object_id,
             DynObject {
@@ -766,42 +1073,59 @@ impl<'a> Parser<'a> {
     }
 
     fn read_array_single_primitive(&mut self) -> Result<(i32, Value<'a>), String> {
+        let start = self.pos - 1;
         let object_id = self.read_i32()?;
-        let len = self.read_i32()? as usize;
+        let len = self.read_count()?;
         let prim = self.read_primitive_type()?;
-        let mut out = Vec::with_capacity(len);
+        self.check_null_items(prim, len)?;
+        let mut out = Vec::with_capacity(self.capacity(len));
+        let mut spans = Vec::with_capacity(self.capacity(len));
         for _ in 0..len {
+            let at = self.pos;
             out.push(self.read_inline_primitive(prim)?);
+            spans.push(at..self.pos);
         }
+        self.note_record(object_id, start, spans);
         Ok((object_id, Value::Array(out)))
     }
     fn read_array_single_string(&mut self) -> Result<(i32, Value<'a>), String> {
+        let start = self.pos - 1;
         let object_id = self.read_i32()?;
-        let len = self.read_i32()? as usize;
-        let mut out = Vec::with_capacity(len);
+        let len = self.read_count()?;
+        let mut out = Vec::with_capacity(self.capacity(len));
+        let mut spans = Vec::with_capacity(self.capacity(len));
         for _ in 0..len {
+            let at = self.pos;
             out.push(self.read_next_string_like()?);
+            spans.push(at..self.pos);
         }
+        self.note_record(object_id, start, spans);
         Ok((object_id, Value::Array(out)))
     }
     fn read_array_single_object(&mut self) -> Result<(i32, Value<'a>), String> {
+        let start = self.pos - 1;
         let object_id = self.read_i32()?;
-        let len = self.read_i32()? as usize;
-        let mut out = Vec::with_capacity(len);
+        let len = self.read_count()?;
+        let mut out = Vec::with_capacity(self.capacity(len));
+        let mut spans = Vec::with_capacity(self.capacity(len));
         for _ in 0..len {
+            let at = self.pos;
             out.push(self.read_next_object_like()?);
+            spans.push(at..self.pos);
         }
+        self.note_record(object_id, start, spans);
         Ok((object_id, Value::Array(out)))
     }
 
     fn read_binary_array(&mut self) -> Result<(i32, Value<'a>), String> {
+        let start = self.pos - 1;
         let object_id = self.read_i32()?;
         let array_type = self.read_u8()?; // 0: Single, 3: SingleOffset (others ignored)
-        let rank = self.read_i32()? as usize;
+        let rank = self.read_count()?;
         if rank != 1 {
             return Err("only rank-1 arrays supported".to_string());
         }
-        let len = self.read_i32()? as usize; // length for 1D
+        let len = self.read_count()?; // length for 1D
         if matches!(array_type, 3..=5) {
             let _lb = self.read_i32()?;
             let _ = _lb;
@@ -831,20 +1155,28 @@ impl<'a> Parser<'a> {
                 ));
             }
         };
-        let mut out = Vec::with_capacity(len);
+        let mut out = Vec::with_capacity(self.capacity(len));
+        let mut spans = Vec::with_capacity(self.capacity(len));
         match elem_type {
             BinaryType::Primitive(p) => {
+                self.check_null_items(p, len)?;
                 for _ in 0..len {
+                    let at = self.pos;
                     out.push(self.read_inline_primitive(p)?);
+                    spans.push(at..self.pos);
                 }
             }
             BinaryType::String => {
                 for _ in 0..len {
+                    let at = self.pos;
                     out.push(self.read_next_string_like()?);
+                    spans.push(at..self.pos);
                 }
             }
             BinaryType::Object | BinaryType::SystemClass | BinaryType::Class => {
                 while out.len() < len {
+                    // A run of nulls shares the span of the record that encodes it
+                    let at = self.pos;
                     let rec = self.peek_u8()?;
                     if rec == RecordType::ObjectNull as u8 {
                         let _ = self.read_u8()?;
@@ -855,7 +1187,10 @@ impl<'a> Parser<'a> {
                         out.extend(std::iter::repeat_n(Value::Null, cnt));
                     } else if rec == RecordType::ObjectNullMultiple as u8 {
                         let _ = self.read_u8()?;
-                        let cnt = self.read_i32()? as usize;
+                        let cnt = self.read_count()?;
+                        if cnt > len - out.len() {
+                            return Err(format!("null run past the end of the array at {:#x}", at));
+                        }
                         out.extend(std::iter::repeat_n(Value::Null, cnt));
                     } else if rec == RecordType::MemberReference as u8 {
                         let _ = self.read_u8()?;
@@ -866,6 +1201,7 @@ impl<'a> Parser<'a> {
                     } else {
                         out.push(self.read_next_object_like()?);
                     }
+                    spans.resize(out.len(), at..self.pos);
                 }
             }
             BinaryType::ObjectArray | BinaryType::StringArray | BinaryType::PrimitiveArray(_) => {
@@ -874,6 +1210,7 @@ impl<'a> Parser<'a> {
                 );
             }
         }
+        self.note_record(object_id, start, spans);
         Ok((object_id, Value::Array(out)))
     }
 
@@ -922,9 +1259,13 @@ impl<'a> Parser<'a> {
             PrimitiveType::UInt64 => Value::U64(self.read_u64()?),
             PrimitiveType::Single => Value::F32(self.read_f32()?),
             PrimitiveType::Double => Value::F64(self.read_f64()?),
-            PrimitiveType::TimeSpan | PrimitiveType::DateTime => Value::I64(self.read_i64()?),
+            PrimitiveType::TimeSpan | PrimitiveType::DateTime => {
+                self.note_unknown(&format!("{:?} kept as raw Int64", p), self.pos);
+                Value::I64(self.read_i64()?)
+            }
             PrimitiveType::Null => Value::Null,
             PrimitiveType::Decimal => {
+                self.note_unknown("Decimal kept as raw bytes", self.pos);
                 let s = self.read_slice(16)?;
                 Value::Bytes(s)
             }
@@ -937,6 +1278,24 @@ impl<'a> Parser<'a> {
     }
 
     // Low-level utilities
+    // A record's count field; only damaged files have negative ones
+    fn read_count(&mut self) -> Result<usize, String> {
+        let at = self.pos;
+        let n = self.read_i32()?;
+        usize::try_from(n).map_err(|_| format!("negative count {} at {:#x}", n, at))
+    }
+    // Room for `n` items, but no more than the bytes left could hold, so a
+    // damaged count ends in an eof error rather than a huge allocation
+    fn capacity(&self, n: usize) -> usize {
+        n.min(self.data.len() - self.pos)
+    }
+    // Null items take no bytes, so their count is checked against the file
+    fn check_null_items(&self, p: PrimitiveType, len: usize) -> Result<(), String> {
+        if matches!(p, PrimitiveType::Null) && len > self.data.len() - self.pos {
+            return Err(format!("{} Null items at {:#x}", len, self.pos));
+        }
+        Ok(())
+    }
     pub fn peek_u8(&self) -> Result<u8, String> {
         self.data
             .get(self.pos)
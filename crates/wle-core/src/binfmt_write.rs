@@ -1,7 +1,100 @@
-use crate::binfmt::PrimitiveType;
+use crate::binfmt::{CancellationToken, PrimitiveType};
 use serde_json::Value as J;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed};
 
 pub fn write_binfmt_from_json(root: &J) -> Result<Vec<u8>, String> {
+    write_binfmt(root, None, None)
+}
+
+/// Objects and bytes written so far by a write running on another thread,
+/// and a flag to stop it. Updated once per object.
+#[derive(Debug, Default)]
+pub struct WriteProgress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    bytes: AtomicUsize,
+    cancelled: CancellationToken,
+}
+
+impl WriteProgress {
+    /// Progress whose `cancel` is `cancelled`, e.g. one shared with a parse
+    pub fn new(cancelled: CancellationToken) -> Self {
+        Self {
+            cancelled,
+            ..Default::default()
+        }
+    }
+
+    /// Share of the document's objects written, 0.0..=1.0
+    pub fn fraction(&self) -> f32 {
+        match self.total.load(Relaxed) {
+            0 => 0.0,
+            total => (self.done.load(Relaxed) as f32 / total as f32).min(1.0),
+        }
+    }
+
+    /// Objects written so far
+    pub fn objects(&self) -> usize {
+        self.done.load(Relaxed)
+    }
+
+    /// Size of the output so far
+    pub fn bytes(&self) -> usize {
+        self.bytes.load(Relaxed)
+    }
+
+    /// Make the write stop at the next object with a "cancelled" error
+    pub fn cancel(&self) {
+        self.cancelled.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.is_cancelled()
+    }
+}
+
+/// `write_binfmt_from_json` reporting to (and honouring cancellation from)
+/// `progress`.
+pub fn write_binfmt_from_json_with_progress(
+    root: &J,
+    progress: Arc<WriteProgress>,
+) -> Result<Vec<u8>, String> {
+    if let Some(v) = root.get("root") {
+        progress.total.store(count_objects(v), Relaxed);
+    }
+    let cancel = progress.cancelled.clone();
+    write_binfmt(root, Some(cancel), Some(progress))
+}
+
+// Roughly the objects the writer will write a record for; `$ref` wrappers
+// and byte summaries are not objects of the save
+fn count_objects(v: &J) -> usize {
+    match v {
+        J::Array(items) => items.iter().map(count_objects).sum(),
+        J::Object(map) => {
+            let own = usize::from(!map.contains_key("$ref") && !map.contains_key("$type"));
+            own + map.values().map(count_objects).sum::<usize>()
+        }
+        _ => 0,
+    }
+}
+
+/// `write_binfmt_from_json` that gives up with a "cancelled" error once
+/// `cancel` is cancelled.
+pub fn write_binfmt_from_json_with_cancel(
+    root: &J,
+    cancel: &CancellationToken,
+) -> Result<Vec<u8>, String> {
+    write_binfmt(root, Some(cancel.clone()), None)
+}
+
+#[tracing::instrument(level = "debug", name = "write", skip_all, err(level = "debug"))]
+fn write_binfmt(
+    root: &J,
+    cancel: Option<CancellationToken>,
+    progress: Option<Arc<WriteProgress>>,
+) -> Result<Vec<u8>, String> {
     // Expect wrapper: { "$rootClass": string, "root": object-or-array }
     let obj = root
         .as_object()
@@ -14,6 +107,8 @@ pub fn write_binfmt_from_json(root: &J) -> Result<Vec<u8>, String> {
         .get("root")
         .ok_or_else(|| "missing 'root' field".to_string())?;
     let mut w = Writer::new();
+    w.cancel = cancel;
+    w.progress = progress;
     w.header();
     w.binary_library(
         2,
@@ -24,19 +119,547 @@ pub fn write_binfmt_from_json(root: &J) -> Result<Vec<u8>, String> {
         "mscorlib, Version=4.0.0.0, Culture=neutral, PublicKeyToken=b77a5c561934e089",
     );
     w.write_root(root_class, root_val)?;
+    w.write_pending()?;
     w.message_end();
+    if let Some(p) = &w.progress {
+        // `total` was an estimate; settle it so a finished write reads 1.0
+        p.total.store(p.done.load(Relaxed), Relaxed);
+        p.bytes.store(w.out.len(), Relaxed);
+    }
+    tracing::debug!(bytes = w.out.len(), "written");
     Ok(w.out)
 }
 
-pub fn write_binfmt_file_from_json(path: &std::path::Path, root: &J) -> Result<(), String> {
-    let data = write_binfmt_from_json(root)?;
-    std::fs::write(path, data).map_err(|e| e.to_string())
+// Why writing a .sav failed; `Locked` means another process (the game) holds
+// the file open and the user should close it before saving again, `ReadOnly`
+// that read-only mode is on (see `set_read_only`).
+#[derive(Debug)]
+pub enum WriteError {
+    Encode(String),
+    Locked(std::path::PathBuf),
+    ReadOnly,
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for WriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WriteError::Encode(e) => write!(f, "{}", e),
+            WriteError::Locked(p) => write!(
+                f,
+                "{} is in use by another program (close the game first)",
+                p.display()
+            ),
+            WriteError::ReadOnly => write!(f, "read-only mode is on; nothing was written"),
+            WriteError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for WriteError {}
+
+impl From<WriteError> for String {
+    fn from(e: WriteError) -> Self {
+        e.to_string()
+    }
+}
+
+// Encode, write to a temp file next to `path`, then rename over it so the
+// game never sees a half-written save.
+pub fn write_binfmt_file_from_json(path: &std::path::Path, root: &J) -> Result<(), WriteError> {
+    let data = write_binfmt_from_json(root).map_err(WriteError::Encode)?;
+    write_file_atomic(path, &data)
+}
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Turn read-only mode on or off for the whole process. While it is on, every
+/// function of this crate that writes, moves or deletes a save, a slot or a
+/// backup fails with `WriteError::ReadOnly` before touching anything (as an
+/// `io::ErrorKind::PermissionDenied` error where the function returns
+/// `io::Result`). Exports to files outside a save (dumps, CSV, PNG) still work.
+pub fn set_read_only(on: bool) {
+    READ_ONLY.store(on, Relaxed);
+    tracing::debug!(on, "read-only mode");
+}
+
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Relaxed)
+}
+
+pub(crate) fn check_read_only() -> Result<(), WriteError> {
+    if is_read_only() {
+        return Err(WriteError::ReadOnly);
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "fs"), allow(dead_code))]
+pub(crate) fn check_read_only_io() -> std::io::Result<()> {
+    check_read_only().map_err(|e| std::io::Error::new(std::io::ErrorKind::PermissionDenied, e))
+}
+
+#[tracing::instrument(level = "debug", name = "save", skip_all, fields(path = %path.display(), bytes = data.len()), err(level = "debug"))]
+pub fn write_file_atomic(path: &std::path::Path, data: &[u8]) -> Result<(), WriteError> {
+    use std::io::Write as _;
+    check_read_only()?;
+    let classify = |e: std::io::Error| {
+        if is_lock_error(&e) {
+            WriteError::Locked(path.to_path_buf())
+        } else {
+            WriteError::Io(e)
+        }
+    };
+    // Probe first: on Windows the game keeps saves open without write sharing
+    if path.exists() {
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map_err(classify)?;
+    }
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let res = std::fs::File::create(&tmp)
+        .and_then(|mut f| {
+            f.write_all(data)?;
+            f.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&tmp, path));
+    if let Err(e) = res {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(classify(e));
+    }
+    Ok(())
+}
+
+// ERROR_SHARING_VIOLATION / ERROR_LOCK_VIOLATION; Unix has no mandatory locks
+pub(crate) fn is_lock_error(e: &std::io::Error) -> bool {
+    cfg!(windows) && matches!(e.raw_os_error(), Some(32) | Some(33))
+}
+
+// Dumps wrap referenced objects as `{"$ref": id, "$value": ...}`. The writer
+// assigns its own ids, so only the value matters for type inference.
+fn deref(v: &J) -> &J {
+    match v {
+        J::Object(map) if map.contains_key("$ref") => map.get("$value").map(deref).unwrap_or(v),
+        _ => v,
+    }
+}
+
+// Objects and arrays behind a `$ref` wrapper are written as a MemberReference
+// plus a top-level record after the root, like the game does, so a re-dump
+// yields the same JSON pointers as the original file.
+fn deferred_value(v: &J) -> Option<&J> {
+    match v {
+        J::Object(map) if map.contains_key("$ref") => match map.get("$value")? {
+            J::Object(inner) if inner.contains_key("$ref") => deferred_value(map.get("$value")?),
+            inner @ (J::Object(_) | J::Array(_)) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// -------- Builder --------
+
+/// Writes a .sav straight from calls, without a JSON tree: open the root with
+/// `begin_object`, add members, nest objects with `member_object` and object
+/// arrays with `begin_array`, close each with `end_object`/`end_array` and
+/// take the bytes with `finish`.
+///
+/// ```
+/// let mut b = wle_core::DocumentBuilder::new();
+/// b.begin_object("PlayerData")
+///     .member_str("name", "Wobbly")
+///     .member_i32("money", 500)
+///     .begin_array("friends")
+///     .begin_object("Friend")
+///     .member_i32("id", 2)
+///     .end_object()
+///     .end_array()
+///     .end_object();
+/// let bytes = b.finish().unwrap();
+/// ```
+///
+/// Misuse, such as a member inside an array or an unclosed object, is
+/// reported by `finish`; calls after the first mistake are ignored.
+pub struct DocumentBuilder {
+    w: Writer,
+    stack: Vec<Frame>,
+    root_done: bool,
+    error: Option<String>,
+}
+
+// An object or array being built. Its values go to `w.out` while it is open;
+// `outer` holds what was written before it, put back when it closes.
+struct Frame {
+    id: i32,
+    // Member name in the parent object; None for the root and array elements
+    member: Option<String>,
+    outer: Vec<u8>,
+    kind: FrameKind,
+}
+
+enum FrameKind {
+    Object {
+        class: String,
+        names: Vec<String>,
+        types: Vec<(u8, Option<PrimitiveType>)>,
+    },
+    Array {
+        len: i32,
+    },
+}
+
+impl Default for DocumentBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DocumentBuilder {
+    pub fn new() -> Self {
+        let mut w = Writer::new();
+        w.header();
+        w.binary_library(
+            2,
+            "Game, Version=0.0.0.0, Culture=neutral, PublicKeyToken=null",
+        );
+        w.binary_library(
+            3,
+            "mscorlib, Version=4.0.0.0, Culture=neutral, PublicKeyToken=b77a5c561934e089",
+        );
+        Self {
+            w,
+            stack: Vec::new(),
+            root_done: false,
+            error: None,
+        }
+    }
+
+    /// Open the root object, or an element of the open array.
+    pub fn begin_object(&mut self, class: &str) -> &mut Self {
+        match self.stack.last() {
+            None if self.root_done => self.fail("the root object is already closed"),
+            None => self.open(None, FrameKind::object(class)),
+            Some(Frame {
+                kind: FrameKind::Array { .. },
+                ..
+            }) => self.open(None, FrameKind::object(class)),
+            Some(_) => self.fail(format!(
+                "begin_object({}) inside an object: use member_object",
+                class
+            )),
+        }
+        self
+    }
+
+    /// Open an object held by member `name` of the open object.
+    pub fn member_object(&mut self, name: &str, class: &str) -> &mut Self {
+        if self.in_object(name) {
+            self.open(Some(name), FrameKind::object(class));
+        }
+        self
+    }
+
+    pub fn end_object(&mut self) -> &mut Self {
+        self.close(true);
+        self
+    }
+
+    /// Open an array of objects held by member `name` of the open object.
+    pub fn begin_array(&mut self, name: &str) -> &mut Self {
+        if self.in_object(name) {
+            self.open(Some(name), FrameKind::Array { len: 0 });
+        }
+        self
+    }
+
+    pub fn end_array(&mut self) -> &mut Self {
+        self.close(false);
+        self
+    }
+
+    /// An empty slot in the open array.
+    pub fn null_element(&mut self) -> &mut Self {
+        match self.stack.last_mut() {
+            Some(Frame {
+                kind: FrameKind::Array { len },
+                ..
+            }) if self.error.is_none() => {
+                *len += 1;
+                self.w.push(10); // ObjectNull
+            }
+            _ => self.fail("null_element outside an array"),
+        }
+        self
+    }
+
+    pub fn member_bool(&mut self, name: &str, v: bool) -> &mut Self {
+        self.primitive(name, PrimitiveType::Boolean, |w| w.push(v as u8))
+    }
+    pub fn member_i32(&mut self, name: &str, v: i32) -> &mut Self {
+        self.primitive(name, PrimitiveType::Int32, |w| w.write_i32(v))
+    }
+    pub fn member_i64(&mut self, name: &str, v: i64) -> &mut Self {
+        self.primitive(name, PrimitiveType::Int64, |w| w.write_i64(v))
+    }
+    pub fn member_f32(&mut self, name: &str, v: f32) -> &mut Self {
+        self.primitive(name, PrimitiveType::Single, |w| {
+            w.out.extend_from_slice(&v.to_bits().to_le_bytes())
+        })
+    }
+    pub fn member_f64(&mut self, name: &str, v: f64) -> &mut Self {
+        self.primitive(name, PrimitiveType::Double, |w| w.write_f64(v))
+    }
+
+    pub fn member_u8(&mut self, name: &str, v: u8) -> &mut Self {
+        self.primitive(name, PrimitiveType::Byte, |w| w.push(v))
+    }
+    pub fn member_u32(&mut self, name: &str, v: u32) -> &mut Self {
+        self.primitive(name, PrimitiveType::UInt32, |w| {
+            w.out.extend_from_slice(&v.to_le_bytes())
+        })
+    }
+    pub fn member_u64(&mut self, name: &str, v: u64) -> &mut Self {
+        self.primitive(name, PrimitiveType::UInt64, |w| w.write_u64(v))
+    }
+
+    /// A member holding no object.
+    pub fn member_null(&mut self, name: &str) -> &mut Self {
+        if self.in_object(name) {
+            self.declare(name, 2, None); // Object
+            self.w.push(10); // ObjectNull
+        }
+        self
+    }
+
+    pub fn member_str(&mut self, name: &str, v: &str) -> &mut Self {
+        if self.in_object(name) {
+            self.declare(name, 1, None); // String
+            self.w.write_string_obj(v);
+        }
+        self
+    }
+
+    /// A byte array, e.g. thumbnail pixels.
+    pub fn member_bytes(&mut self, name: &str, v: &[u8]) -> &mut Self {
+        if self.in_object(name) {
+            self.declare(name, 7, Some(PrimitiveType::Byte)); // PrimitiveArray
+            self.w.write_primitive_array_u8(v);
+        }
+        self
+    }
+
+    pub fn member_i32_array(&mut self, name: &str, v: &[i32]) -> &mut Self {
+        self.primitive_array(name, PrimitiveType::Int32, v, |w, x| w.write_i32(*x))
+    }
+    pub fn member_i64_array(&mut self, name: &str, v: &[i64]) -> &mut Self {
+        self.primitive_array(name, PrimitiveType::Int64, v, |w, x| w.write_i64(*x))
+    }
+    pub fn member_f32_array(&mut self, name: &str, v: &[f32]) -> &mut Self {
+        self.primitive_array(name, PrimitiveType::Single, v, |w, x| {
+            w.out.extend_from_slice(&x.to_bits().to_le_bytes())
+        })
+    }
+    pub fn member_f64_array(&mut self, name: &str, v: &[f64]) -> &mut Self {
+        self.primitive_array(name, PrimitiveType::Double, v, |w, x| w.write_f64(*x))
+    }
+
+    pub fn member_str_array<S: AsRef<str>>(&mut self, name: &str, v: &[S]) -> &mut Self {
+        if self.in_object(name) {
+            self.declare(name, 6, None); // StringArray
+            self.w.push(17); // ArraySingleString
+            let id = self.w.alloc_obj_id();
+            self.w.write_i32(id);
+            self.w.write_i32(v.len() as i32);
+            for s in v {
+                self.w.write_string_obj(s.as_ref());
+            }
+        }
+        self
+    }
+
+    /// The .sav bytes, once the root object is closed.
+    pub fn finish(mut self) -> Result<Vec<u8>, String> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        if !self.stack.is_empty() {
+            return Err(format!(
+                "{} object(s) or array(s) not closed",
+                self.stack.len()
+            ));
+        }
+        if !self.root_done {
+            return Err("no root object".into());
+        }
+        self.w.message_end();
+        Ok(self.w.out)
+    }
+
+    fn fail(&mut self, e: impl Into<String>) {
+        self.error.get_or_insert_with(|| e.into());
+    }
+
+    // Whether member `name` can be added: the open frame is an object
+    fn in_object(&mut self, name: &str) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        match self.stack.last() {
+            Some(Frame {
+                kind: FrameKind::Object { .. },
+                ..
+            }) => true,
+            _ => {
+                self.fail(format!("member {} outside an object", name));
+                false
+            }
+        }
+    }
+
+    fn declare(&mut self, name: &str, code: u8, prim: Option<PrimitiveType>) {
+        if let Some(Frame {
+            kind: FrameKind::Object { names, types, .. },
+            ..
+        }) = self.stack.last_mut()
+        {
+            names.push(name.to_string());
+            types.push((code, prim));
+        }
+    }
+
+    fn primitive(
+        &mut self,
+        name: &str,
+        p: PrimitiveType,
+        write: impl FnOnce(&mut Writer),
+    ) -> &mut Self {
+        if self.in_object(name) {
+            self.declare(name, 0, Some(p)); // Primitive
+            write(&mut self.w);
+        }
+        self
+    }
+
+    fn primitive_array<T>(
+        &mut self,
+        name: &str,
+        p: PrimitiveType,
+        items: &[T],
+        write: impl Fn(&mut Writer, &T),
+    ) -> &mut Self {
+        if self.in_object(name) {
+            self.declare(name, 7, Some(p)); // PrimitiveArray
+            self.w.push(15); // ArraySinglePrimitive
+            let id = self.w.alloc_obj_id();
+            self.w.write_i32(id);
+            self.w.write_i32(items.len() as i32);
+            self.w.write_prim_type(p);
+            for x in items {
+                write(&mut self.w, x);
+            }
+        }
+        self
+    }
+
+    fn open(&mut self, member: Option<&str>, kind: FrameKind) {
+        if self.error.is_some() {
+            return;
+        }
+        let frame = Frame {
+            id: self.w.alloc_obj_id(),
+            member: member.map(str::to_string),
+            outer: std::mem::take(&mut self.w.out),
+            kind,
+        };
+        self.stack.push(frame);
+    }
+
+    // Close the open frame, which must be an object (`object`) or an array,
+    // and write its record into the frame around it
+    fn close(&mut self, object: bool) {
+        if self.error.is_some() {
+            return;
+        }
+        let Some(frame) = self
+            .stack
+            .pop_if(|f| matches!(f.kind, FrameKind::Object { .. }) == object)
+        else {
+            let what = if object { "end_object" } else { "end_array" };
+            return self.fail(format!("{} without a matching begin", what));
+        };
+        let values = std::mem::replace(&mut self.w.out, frame.outer);
+        match frame.kind {
+            FrameKind::Object {
+                class,
+                names,
+                types,
+            } => {
+                self.w.push(5); // ClassWithMembersAndTypes
+                self.w.write_i32(frame.id);
+                self.w.write_lp_str(&class);
+                self.w.write_i32(names.len() as i32);
+                for n in &names {
+                    self.w.write_lp_str(n);
+                }
+                for (code, _) in &types {
+                    self.w.push(*code);
+                }
+                for p in types.iter().filter_map(|(_, p)| *p) {
+                    self.w.write_prim_type(p);
+                }
+                self.w.write_i32(2); // library id
+            }
+            FrameKind::Array { len } => {
+                self.w.push(16); // ArraySingleObject
+                self.w.write_i32(frame.id);
+                self.w.write_i32(len);
+            }
+        }
+        self.w.out.extend_from_slice(&values);
+        match (self.stack.last_mut(), frame.member) {
+            (None, _) => self.root_done = true,
+            (
+                Some(Frame {
+                    kind: FrameKind::Array { len },
+                    ..
+                }),
+                _,
+            ) => *len += 1,
+            (_, Some(name)) => {
+                let code = if object { 2 } else { 5 }; // Object, ObjectArray
+                self.declare(&name, code, None);
+            }
+            (_, None) => {}
+        }
+    }
+}
+
+impl FrameKind {
+    fn object(class: &str) -> Self {
+        FrameKind::Object {
+            class: class.to_string(),
+            names: Vec::new(),
+            types: Vec::new(),
+        }
+    }
 }
 
 struct Writer {
     out: Vec<u8>,
     next_id: i32,
     next_str_id: i32,
+    // Referenced records still to be written after the current one
+    pending: Vec<(i32, J)>,
+    // Id reserved for the deferred record being written
+    reserved_id: Option<i32>,
+    cancel: Option<CancellationToken>,
+    progress: Option<Arc<WriteProgress>>,
 }
 impl Writer {
     fn new() -> Self {
@@ -44,6 +667,10 @@ impl Writer {
             out: Vec::with_capacity(1024),
             next_id: 1,
             next_str_id: 100,
+            pending: Vec::new(),
+            reserved_id: None,
+            cancel: None,
+            progress: None,
         }
     }
     fn push(&mut self, b: u8) {
@@ -90,6 +717,7 @@ impl Writer {
     }
 
     fn write_root(&mut self, class_name: &str, v: &J) -> Result<(), String> {
+        let v = deref(v);
         // Encode as ClassWithMembersAndTypes for the root
         let obj_id = self.alloc_obj_id();
         self.push(5); // ClassWithMembersAndTypes
@@ -98,7 +726,7 @@ impl Writer {
         match v {
             J::Object(map) => {
                 // Members exclude any special $class key
-                let mut pairs: Vec<(&str, &J)> = map
+                let pairs: Vec<(&str, &J)> = map
                     .iter()
                     .filter_map(|(k, vv)| {
                         if k == "$class" {
@@ -108,8 +736,6 @@ impl Writer {
                         }
                     })
                     .collect();
-                // stable order
-                pairs.sort_by(|a, b| a.0.cmp(b.0));
                 self.write_i32(pairs.len() as i32);
                 for (k, _) in &pairs {
                     self.write_lp_str(k);
@@ -164,8 +790,29 @@ impl Writer {
         }
     }
 
+    fn write_pending(&mut self) -> Result<(), String> {
+        while !self.pending.is_empty() {
+            let batch = std::mem::take(&mut self.pending);
+            for (id, v) in batch {
+                self.reserved_id = Some(id);
+                self.write_member_value(&v)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_reference(&mut self, v: &J) {
+        let id = self.alloc_obj_id();
+        self.push(9); // MemberReference
+        self.write_i32(id);
+        self.pending.push((id, v.clone()));
+    }
+
     fn bin_type_code(&self, v: &J) -> u8 {
-        match v {
+        if deferred_value(v).is_some() {
+            return 2; // Object: the member holds a MemberReference
+        }
+        match deref(v) {
             J::Null | J::Bool(_) | J::Number(_) => 0, // Primitive
             J::String(_) => 1,                        // String
             J::Array(a) => {
@@ -187,7 +834,10 @@ impl Writer {
         }
     }
     fn maybe_prim_type(&self, v: &J) -> Option<PrimitiveType> {
-        match v {
+        if deferred_value(v).is_some() {
+            return None;
+        }
+        match deref(v) {
             J::Null => Some(PrimitiveType::Null),
             J::Bool(_) => Some(PrimitiveType::Boolean),
             J::Number(n) => {
@@ -235,7 +885,11 @@ impl Writer {
     }
 
     fn write_member_value(&mut self, v: &J) -> Result<(), String> {
-        match v {
+        if let Some(inner) = deferred_value(v) {
+            self.write_reference(inner);
+            return Ok(());
+        }
+        match deref(v) {
             J::Null => Ok(()),
             J::Bool(b) => {
                 self.push(if *b { 1 } else { 0 });
@@ -370,7 +1024,11 @@ impl Writer {
         self.write_i32(id);
         self.write_i32(a.len() as i32);
         for v in a {
-            match v {
+            if let Some(inner) = deferred_value(v) {
+                self.write_reference(inner);
+                continue;
+            }
+            match deref(v) {
                 J::Object(map) => {
                     let class_name = map
                         .get("$class")
@@ -378,6 +1036,8 @@ impl Writer {
                         .unwrap_or("Object");
                     self.write_object(map, class_name)?;
                 }
+                // Unused List`1 capacity slots
+                J::Null => self.push(10), // ObjectNull
                 _ => return Err("array element is not object".into()),
             }
         }
@@ -389,11 +1049,18 @@ impl Writer {
         map: &serde_json::Map<String, J>,
         class_name: &str,
     ) -> Result<(), String> {
+        if let Some(c) = &self.cancel {
+            c.check()?;
+        }
+        if let Some(p) = &self.progress {
+            p.done.fetch_add(1, Relaxed);
+            p.bytes.store(self.out.len(), Relaxed);
+        }
         let id = self.alloc_obj_id();
         self.push(5); // ClassWithMembersAndTypes
         self.write_i32(id);
         self.write_lp_str(class_name);
-        let mut pairs: Vec<(&str, &J)> = map
+        let pairs: Vec<(&str, &J)> = map
             .iter()
             .filter_map(|(k, v)| {
                 if k == "$class" {
@@ -403,7 +1070,6 @@ impl Writer {
                 }
             })
             .collect();
-        pairs.sort_by(|a, b| a.0.cmp(b.0));
         self.write_i32(pairs.len() as i32);
         for (k, _) in &pairs {
             self.write_lp_str(k);
@@ -492,6 +1158,9 @@ impl Writer {
     }
 
     fn alloc_obj_id(&mut self) -> i32 {
+        if let Some(id) = self.reserved_id.take() {
+            return id;
+        }
         let id = self.next_id;
         self.next_id += 1;
         id
@@ -510,4 +1179,7 @@ impl Writer {
 //   - Summary `{ "$type": "bytes", "len": N }` (writer fills zeros), or
 //   - Primitive array of 0..=255 integers (preferred for exact roundtrip).
 // This is a pragmatic encoder to enable roundtrips for editing workflows.
-// It does not reconstruct shared references or advanced .NET types.
+// `$ref` wrappers are written as references, but each occurrence gets its own
+// record; it does not reconstruct shared references or advanced .NET types.
+// Members are emitted in JSON key order (serde_json `preserve_order`), so a
+// dump → write cycle keeps the original member layout of each class.
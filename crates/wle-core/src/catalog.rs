@@ -0,0 +1,138 @@
+// Friendly names for raw save members, shipped as data/field_catalog.json.
+// Entries are keyed by class, then member; generic classes are listed under
+// their name without type arguments (e.g. HawkNetworking.HawkMessageList`1).
+// `min`, `max` and `allowed` (a list of values) are the numbers the game
+// accepts; `edit::set_typed_by_pointer` can hold writes to them.
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const CATALOG_JSON: &str = include_str!("../data/field_catalog.json");
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldInfo {
+    pub label: String,
+    pub description: Option<String>,
+    pub unit: Option<String>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// The only values the game knows (empty: any in range)
+    pub allowed: Vec<f64>,
+}
+
+impl FieldInfo {
+    pub fn in_range(&self, v: f64) -> bool {
+        self.min.is_none_or(|m| v >= m) && self.max.is_none_or(|m| v <= m)
+    }
+
+    /// In range and, when the field has allowed values, one of them.
+    pub fn allows(&self, v: f64) -> bool {
+        self.in_range(v) && (self.allowed.is_empty() || self.allowed.contains(&v))
+    }
+
+    /// The nearest value `allows` accepts: `v` moved into the range, or the
+    /// closest allowed value (the lower one on a tie).
+    pub fn clamp(&self, v: f64) -> f64 {
+        let v = self.max.map_or(v, |m| v.min(m));
+        let v = self.min.map_or(v, |m| v.max(m));
+        self.allowed
+            .iter()
+            .copied()
+            .min_by(|a, b| (a - v).abs().total_cmp(&(b - v).abs()).then(a.total_cmp(b)))
+            .unwrap_or(v)
+    }
+
+    /// What `allows` accepts, e.g. "0..=3" or "one of 0, 1, 2"; None when
+    /// the field has no limits.
+    pub fn limits(&self) -> Option<String> {
+        if !self.allowed.is_empty() {
+            let values: Vec<String> = self.allowed.iter().map(|v| v.to_string()).collect();
+            return Some(format!("one of {}", values.join(", ")));
+        }
+        let end = |b: Option<f64>| b.map(|x| x.to_string()).unwrap_or_default();
+        (self.min.is_some() || self.max.is_some())
+            .then(|| format!("{}..={}", end(self.min), end(self.max)))
+    }
+
+    // One-line hover text: description, unit and range
+    pub fn tooltip(&self) -> String {
+        let mut parts: Vec<String> = self.description.iter().cloned().collect();
+        if let Some(u) = &self.unit {
+            parts.push(format!("Unit: {}", u));
+        }
+        match (self.min, self.max) {
+            (Some(a), Some(b)) => parts.push(format!("Range: {}..={}", a, b)),
+            (Some(a), None) => parts.push(format!("Minimum: {}", a)),
+            (None, Some(b)) => parts.push(format!("Maximum: {}", b)),
+            (None, None) => {}
+        }
+        if !self.allowed.is_empty() {
+            let values: Vec<String> = self.allowed.iter().map(|v| v.to_string()).collect();
+            parts.push(format!("Values: {}", values.join(", ")));
+        }
+        parts.join("\n")
+    }
+}
+
+type Catalog = HashMap<String, HashMap<String, FieldInfo>>;
+
+fn catalog() -> &'static Catalog {
+    static CATALOG: OnceLock<Catalog> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        let root: serde_json::Value =
+            serde_json::from_str(CATALOG_JSON).expect("field_catalog.json is valid JSON");
+        let mut out = Catalog::new();
+        for (class, members) in root.as_object().into_iter().flatten() {
+            let entry = out.entry(class.clone()).or_default();
+            for (member, info) in members.as_object().into_iter().flatten() {
+                let text = |k: &str| info.get(k).and_then(|v| v.as_str()).map(String::from);
+                entry.insert(
+                    member.clone(),
+                    FieldInfo {
+                        label: text("label").unwrap_or_else(|| member.clone()),
+                        description: text("description"),
+                        unit: text("unit"),
+                        min: info.get("min").and_then(|v| v.as_f64()),
+                        max: info.get("max").and_then(|v| v.as_f64()),
+                        allowed: info
+                            .get("allowed")
+                            .and_then(|v| v.as_array())
+                            .map(|a| a.iter().filter_map(|x| x.as_f64()).collect())
+                            .unwrap_or_default(),
+                    },
+                );
+            }
+        }
+        out
+    })
+}
+
+/// Look up a member of a class. Assembly-qualified generic class names match
+/// their bare entry.
+pub fn field_info(class: &str, member: &str) -> Option<&'static FieldInfo> {
+    let cat = catalog();
+    let bare = class.split('[').next().unwrap_or(class);
+    cat.get(class)
+        .or_else(|| cat.get(bare))
+        .and_then(|m| m.get(member))
+}
+
+/// The entry of the member `pointer` (into the edit JSON `root`) names: the
+/// class is that of the object holding it, `$rootClass` for the root.
+pub fn field_at(root: &serde_json::Value, pointer: &str) -> Option<&'static FieldInfo> {
+    let (parent, member) = pointer.rsplit_once('/')?;
+    let member = member.replace("~1", "/").replace("~0", "~");
+    let class = match root.pointer(parent)?.get("$class") {
+        Some(c) => c,
+        None if parent.trim_end_matches("/$value") == "/root" => root.get("$rootClass")?,
+        None => return None,
+    };
+    field_info(class.as_str()?, &member)
+}
+
+/// Friendly label for a member, falling back to the raw name.
+pub fn label<'a>(class: &str, member: &'a str) -> std::borrow::Cow<'a, str> {
+    match field_info(class, member) {
+        Some(info) => info.label.clone().into(),
+        None => member.into(),
+    }
+}
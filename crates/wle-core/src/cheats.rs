@@ -0,0 +1,501 @@
+use crate::edit::{PatchOp, unwrap_ref};
+use crate::model::{ClothingSlot, Guid, PlayerData};
+use serde_json::Value as J;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+pub(crate) fn player_file(slot: &Path, player: u32) -> PathBuf {
+    slot.join(crate::saves::DocKind::Player(player).file_name())
+}
+
+pub(crate) fn load_player_json(slot: &Path, player: u32) -> Result<J, String> {
+    crate::edit::parse_file_for_edit(&player_file(slot, player))
+}
+
+/// Operations for one save file, worked out but not yet written, so callers
+/// can show them (`preview`, e.g. for a dry run) before writing (`apply`).
+#[derive(Debug, Clone)]
+pub struct PendingEdit {
+    pub path: PathBuf,
+    pub ops: Vec<PatchOp>,
+}
+
+impl PendingEdit {
+    /// The pointer-level changes the edit would make; nothing is written.
+    pub fn preview(&self) -> Result<Vec<crate::edit::Change>, String> {
+        let before = crate::edit::parse_file_for_edit(&self.path)?;
+        let mut after = before.clone();
+        crate::edit::apply_patch(&mut after, &self.ops)?;
+        Ok(crate::edit::diff(&before, &after, ""))
+    }
+
+    /// Back up, write and verify the file. Returns the backup.
+    pub fn apply(&self) -> Result<PathBuf, String> {
+        patch_file(&self.path, &self.ops)
+    }
+}
+
+/// Apply edits to several files as one `saves::Transaction`: every file is
+/// backed up and encoded first, then all are written together and verified.
+/// If any write or check fails, every file is restored. Returns the backups,
+/// in the order of `edits`.
+pub fn apply_all(edits: &[PendingEdit]) -> Result<Vec<PathBuf>, String> {
+    for edit in edits {
+        crate::saves::check_writable(edit.path.parent().unwrap_or(Path::new("")))
+            .map_err(|e| e.to_string())?;
+    }
+    let mut patched = Vec::new();
+    let mut tx = crate::saves::Transaction::new();
+    for edit in edits {
+        let mut json = crate::edit::parse_file_for_edit(&edit.path)?;
+        crate::edit::apply_patch(&mut json, &edit.ops)
+            .map_err(|e| format!("{}: {}", edit.path.display(), e))?;
+        tx.write(
+            &edit.path,
+            crate::binfmt_write::write_binfmt_from_json(&json)?,
+        );
+        patched.push(json);
+    }
+    let backups = edits
+        .iter()
+        .map(|e| crate::editor::backup_file(&e.path, false).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    tx.commit().map_err(|e| e.to_string())?;
+    let failed = edits.iter().zip(&patched).find_map(|(edit, json)| {
+        verify_written(&edit.path, json, &edit.ops)
+            .err()
+            .map(|e| format!("{}: {}", edit.path.display(), e))
+    });
+    if let Some(e) = failed {
+        let mut undo = crate::saves::Transaction::new();
+        for (edit, backup) in edits.iter().zip(&backups) {
+            let bytes =
+                std::fs::read(backup).map_err(|r| format!("{}; restoring failed: {}", e, r))?;
+            undo.write(&edit.path, bytes);
+        }
+        undo.commit()
+            .map_err(|r| format!("{}; restoring failed: {}", e, r))?;
+        return Err(format!("{}; the originals were restored", e));
+    }
+    Ok(backups)
+}
+
+// Apply `ops` to a .sav with a backup next to it, then read the written file
+// back and check every edited value survived. On a mismatch the backup is
+// copied over the file again. Returns the backup.
+fn patch_file(path: &Path, ops: &[PatchOp]) -> Result<PathBuf, String> {
+    crate::saves::check_writable(path.parent().unwrap_or(Path::new("")))
+        .map_err(|e| e.to_string())?;
+    let mut json = crate::edit::parse_file_for_edit(path)?;
+    crate::edit::apply_patch(&mut json, ops)?;
+    let backup = crate::editor::backup_file(path, false).map_err(|e| e.to_string())?;
+    crate::binfmt_write::write_binfmt_file_from_json(path, &json)?;
+    if let Err(e) = verify_written(path, &json, ops) {
+        std::fs::copy(&backup, path).map_err(|c| format!("{}; restoring failed: {}", e, c))?;
+        return Err(format!("{}; the original was restored", e));
+    }
+    Ok(backup)
+}
+
+fn verify_written(path: &Path, expected: &J, ops: &[PatchOp]) -> Result<(), String> {
+    let written = crate::edit::parse_file_for_edit(path)
+        .map_err(|e| format!("written file does not parse: {}", e))?;
+    for op in ops {
+        let target = match op {
+            PatchOp::Add { path, .. }
+            | PatchOp::Replace { path, .. }
+            | PatchOp::Remove { path }
+            | PatchOp::Copy { path, .. } => path,
+        };
+        // Appends and removals are checked through the containing value
+        let ptr = match op {
+            PatchOp::Remove { .. } => crate::edit::parent_pointer(target).map_or("", |(p, _)| p),
+            _ => target.strip_suffix("/-").unwrap_or(target),
+        };
+        match (expected.pointer(ptr), written.pointer(ptr)) {
+            (None, None) => {}
+            (Some(a), Some(b)) if crate::validate::same_value(a, b) => {}
+            _ => return Err(format!("write verification failed at {}", ptr)),
+        }
+    }
+    Ok(())
+}
+
+pub fn get_money(slot: &Path, player: u32) -> Result<i64, String> {
+    let path = player_file(slot, player);
+    let doc = crate::json::parse_binary(&path)?;
+    doc.as_player_data()
+        .ok_or_else(|| format!("{} is not player data", path.display()))?
+        .money
+        .ok_or_else(|| "no money field in player data".to_string())
+}
+
+// Set a player's money. The field is an Int32 in the game, so the amount must
+// fit; the file is backed up next to itself before writing. Returns the backup.
+pub fn set_money(slot: &Path, player: u32, amount: i64) -> Result<PathBuf, String> {
+    money_edit(slot, player, amount)?.apply()
+}
+
+pub fn money_edit(slot: &Path, player: u32, amount: i64) -> Result<PendingEdit, String> {
+    if !(0..=i32::MAX as i64).contains(&amount) {
+        return Err(format!("money must be between 0 and {}", i32::MAX));
+    }
+    // Fails early with a clear message when the file or field is missing
+    get_money(slot, player)?;
+    Ok(PendingEdit {
+        path: player_file(slot, player),
+        ops: vec![PlayerData::set_money(amount)],
+    })
+}
+
+/// Dress a player: each choice is a wardrobe index for the slot (see
+/// `CharacterAppearance::options`) or None to wear nothing. Returns the backup.
+pub fn set_appearance(
+    slot: &Path,
+    player: u32,
+    choices: &[(ClothingSlot, Option<usize>)],
+) -> Result<PathBuf, String> {
+    appearance_edit(slot, player, choices)?.apply()
+}
+
+pub fn appearance_edit(
+    slot: &Path,
+    player: u32,
+    choices: &[(ClothingSlot, Option<usize>)],
+) -> Result<PendingEdit, String> {
+    let path = player_file(slot, player);
+    let doc = crate::json::parse_binary(&path)?;
+    let look = doc
+        .as_character_appearance()
+        .ok_or_else(|| format!("{} is not player data", path.display()))?;
+    let mut ops = Vec::new();
+    for (s, index) in choices {
+        ops.extend(look.wear(*s, *index)?);
+    }
+    Ok(PendingEdit { path, ops })
+}
+
+/// Complete every mission not yet completed in a slot's MissionData.sav
+/// (backed up first). Returns how many were completed and the backup, which
+/// is None when there was nothing to do.
+pub fn complete_all_missions(slot: &Path) -> Result<(usize, Option<PathBuf>), String> {
+    match complete_all_missions_edit(slot)? {
+        (0, _) => Ok((0, None)),
+        (count, edit) => Ok((count, Some(edit.apply()?))),
+    }
+}
+
+/// The edit behind `complete_all_missions`, with how many missions it completes.
+pub fn complete_all_missions_edit(slot: &Path) -> Result<(usize, PendingEdit), String> {
+    let path = slot.join(crate::saves::DocKind::Mission.file_name());
+    let doc = crate::json::parse_binary(&path)?;
+    let missions = doc
+        .as_mission_data()
+        .ok_or_else(|| format!("{} is not mission data", path.display()))?;
+    let count = missions
+        .missions
+        .iter()
+        .filter(|m| m.state != crate::model::MissionState::Completed)
+        .count();
+    let ops = missions.complete_all();
+    Ok((count, PendingEdit { path, ops }))
+}
+
+/// Complete one mission of a slot's MissionData.sav, or with `complete`
+/// false reset it so it starts over.
+pub fn mission_edit(slot: &Path, id: Guid, complete: bool) -> Result<PendingEdit, String> {
+    let path = slot.join(crate::saves::DocKind::Mission.file_name());
+    let doc = crate::json::parse_binary(&path)?;
+    let missions = doc
+        .as_mission_data()
+        .ok_or_else(|| format!("{} is not mission data", path.display()))?;
+    let ops = if complete {
+        missions.complete_mission(id)?
+    } else {
+        missions.reset_mission(id)?
+    };
+    Ok(PendingEdit { path, ops })
+}
+
+/// Set stats of a slot's StatsData.sav by name, each value read as the
+/// stat's own kind (see `StatsData::set`).
+pub fn stats_edit(slot: &Path, values: &[(String, String)]) -> Result<PendingEdit, String> {
+    let path = slot.join(crate::saves::DocKind::Stats.file_name());
+    let doc = crate::json::parse_binary(&path)?;
+    let stats = doc
+        .as_stats_data()
+        .ok_or_else(|| format!("{} is not stats data", path.display()))?;
+    let ops = values
+        .iter()
+        .map(|(name, value)| stats.set(name, value))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(PendingEdit { path, ops })
+}
+
+/// What an unlock preset adds to a player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unlock {
+    /// Wardrobe hats, tops, bottoms and outfits
+    Clothing,
+    Vehicles,
+    /// Presents (collectible gifts) in PlayerMiscData
+    Items,
+}
+
+impl std::str::FromStr for Unlock {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clothing" => Ok(Unlock::Clothing),
+            "vehicles" => Ok(Unlock::Vehicles),
+            "items" => Ok(Unlock::Items),
+            _ => Err(format!(
+                "unknown unlock '{}' (expected clothing, vehicles or items)",
+                s
+            )),
+        }
+    }
+}
+
+/// One step of an unlock plan, shown to the user before anything is written.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub summary: String,
+    pub op: PatchOp,
+}
+
+const WARDROBE: &str = "/root/<WardrobeData>k__BackingField/$value";
+// In `ClothingSlot::ALL` order
+pub(crate) const WARDROBE_LISTS: [(&str, &str); 4] = [
+    ("WardrobeHats", "hat"),
+    ("WardrobeTops", "top"),
+    ("WardrobeBottoms", "bottom"),
+    ("WardrobeOutfits", "outfit"),
+];
+pub(crate) const VEHICLES: &str = "/root/<VehiclesData>k__BackingField/$value/Vehicles";
+const PRESENTS: &str = "/root/<MiscData>k__BackingField/$value/presentsUnlocked";
+
+pub(crate) fn array_at<'a>(player: &'a J, ptr: &str) -> Result<&'a [J], String> {
+    player
+        .pointer(ptr)
+        .map(unwrap_ref)
+        .and_then(|v| v.as_array())
+        .map(|a| a.as_slice())
+        .ok_or_else(|| format!("player data has no list at {}", ptr))
+}
+
+pub(crate) fn clothing_guid(piece: &J) -> Option<Guid> {
+    Guid::from_json(unwrap_ref(piece).get("clothingPrefabGUID")?).filter(|g| !g.is_nil())
+}
+
+pub(crate) fn vehicle_guid(vehicle: &J) -> Option<Guid> {
+    Guid::from_json(unwrap_ref(vehicle).get("VehicleID")?).filter(|g| !g.is_nil())
+}
+
+/// Everything a preset can unlock. Saves carry no list of all game content, so
+/// the catalog is built from what any player already owns; entries keep the
+/// owner's JSON (colours, customisation) as the template for the new copy.
+#[derive(Debug, Clone, Default)]
+pub struct UnlockCatalog {
+    /// (wardrobe list member, ClothingPieceData)
+    pub clothing: Vec<(&'static str, J)>,
+    /// VehicleData entries
+    pub vehicles: Vec<J>,
+    pub items: Vec<Guid>,
+    seen: HashSet<Guid>,
+}
+
+impl UnlockCatalog {
+    /// Collect from every PlayerData_N.sav of every slot under a GameSaves folder.
+    /// Files that fail to parse are skipped.
+    pub fn scan(root: &Path) -> Self {
+        let mut cat = UnlockCatalog::default();
+        for slot in crate::saves::list_slots(root) {
+            let Ok(rd) = std::fs::read_dir(&slot) else {
+                continue;
+            };
+            let mut files: Vec<PathBuf> = rd
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with("PlayerData_") && n.ends_with(".sav"))
+                })
+                .collect();
+            files.sort();
+            for f in files {
+                if let Ok(json) = crate::edit::parse_file_for_edit(&f) {
+                    cat.add_player(&json);
+                }
+            }
+        }
+        cat
+    }
+
+    /// Add what one player's edit JSON owns.
+    pub fn add_player(&mut self, player: &J) {
+        for (list, _) in WARDROBE_LISTS {
+            let Ok((items, size)) = wardrobe_list(player, list) else {
+                continue;
+            };
+            for piece in &items[..size.min(items.len())] {
+                if let Some(g) = clothing_guid(piece)
+                    && self.seen.insert(g)
+                {
+                    self.clothing.push((list, piece.clone()));
+                }
+            }
+        }
+        if let Ok(vehicles) = array_at(player, VEHICLES) {
+            for v in vehicles {
+                if let Some(g) = vehicle_guid(v)
+                    && self.seen.insert(g)
+                {
+                    self.vehicles.push(v.clone());
+                }
+            }
+        }
+        if let Ok(presents) = array_at(player, PRESENTS) {
+            for g in presents.iter().filter_map(Guid::from_json) {
+                if !g.is_nil() && self.seen.insert(g) {
+                    self.items.push(g);
+                }
+            }
+        }
+    }
+}
+
+// Wardrobe lists are HawkMessageLists: items past `List`1+_size` are capacity
+pub(crate) fn wardrobe_list<'a>(player: &'a J, list: &str) -> Result<(&'a [J], usize), String> {
+    let base = format!("{}/{}", WARDROBE, list);
+    let items = array_at(player, &format!("{}/$value/List`1+_items", base))?;
+    let size = player
+        .pointer(&format!("{}/$value/List`1+_size", base))
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| format!("player data has no size for {}", list))?;
+    Ok((items, size as usize))
+}
+
+// Put new entries into free capacity slots first, then append
+pub(crate) fn fill_slots(ptr: &str, free: &[usize], values: Vec<(String, J)>) -> Vec<Change> {
+    let mut free = free.iter();
+    values
+        .into_iter()
+        .map(|(summary, value)| {
+            let op = match free.next() {
+                Some(i) => PatchOp::replace(format!("{}/{}", ptr, i), value),
+                None => PatchOp::Add {
+                    path: format!("{}/-", ptr),
+                    value,
+                },
+            };
+            Change { summary, op }
+        })
+        .collect()
+}
+
+/// Plan adding every catalog clothing piece the player lacks to their wardrobe.
+pub fn unlock_all_clothing(player: &J, catalog: &UnlockCatalog) -> Result<Vec<Change>, String> {
+    let mut changes = Vec::new();
+    for (list, label) in WARDROBE_LISTS {
+        let (items, size) = wardrobe_list(player, list)?;
+        let owned: HashSet<Guid> = items[..size.min(items.len())]
+            .iter()
+            .filter_map(clothing_guid)
+            .collect();
+        let missing: Vec<(String, J)> = catalog
+            .clothing
+            .iter()
+            .filter(|(l, _)| *l == list)
+            .filter_map(|(_, piece)| {
+                let g = clothing_guid(piece)?;
+                (!owned.contains(&g)).then(|| (format!("add {} {}", label, g), piece.clone()))
+            })
+            .collect();
+        changes.extend(add_to_wardrobe(player, list, label, missing)?);
+    }
+    Ok(changes)
+}
+
+// Put pieces into a wardrobe list's spare capacity (then past it) and raise
+// its size to match
+pub(crate) fn add_to_wardrobe(
+    player: &J,
+    list: &str,
+    label: &str,
+    pieces: Vec<(String, J)>,
+) -> Result<Vec<Change>, String> {
+    if pieces.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (items, size) = wardrobe_list(player, list)?;
+    let base = format!("{}/{}/$value", WARDROBE, list);
+    let new_size = size + pieces.len();
+    let free: Vec<usize> = (size..items.len()).collect();
+    let mut changes = fill_slots(&format!("{}/List`1+_items/$value", base), &free, pieces);
+    changes.push(Change {
+        summary: format!("{} count {} -> {}", label, size, new_size),
+        op: PatchOp::replace(format!("{}/List`1+_size", base), new_size.into()),
+    });
+    Ok(changes)
+}
+
+/// Plan adding every catalog vehicle the player does not own.
+pub fn unlock_all_vehicles(player: &J, catalog: &UnlockCatalog) -> Result<Vec<Change>, String> {
+    let vehicles = array_at(player, VEHICLES)?;
+    let owned: HashSet<Guid> = vehicles.iter().filter_map(vehicle_guid).collect();
+    let free: Vec<usize> = (0..vehicles.len())
+        .filter(|&i| vehicles[i].is_null())
+        .collect();
+    let missing = catalog
+        .vehicles
+        .iter()
+        .filter_map(|v| {
+            let g = vehicle_guid(v)?;
+            (!owned.contains(&g)).then(|| (format!("add vehicle {}", g), v.clone()))
+        })
+        .collect();
+    Ok(fill_slots(VEHICLES, &free, missing))
+}
+
+/// Plan marking every catalog present as unlocked.
+pub fn unlock_all_items(player: &J, catalog: &UnlockCatalog) -> Result<Vec<Change>, String> {
+    let presents = array_at(player, PRESENTS)?;
+    let owned: HashSet<Guid> = presents.iter().filter_map(Guid::from_json).collect();
+    let free: Vec<usize> = (0..presents.len())
+        .filter(|&i| Guid::from_json(&presents[i]).is_some_and(|g| g.is_nil()))
+        .collect();
+    let missing = catalog
+        .items
+        .iter()
+        .filter(|g| !owned.contains(g))
+        .map(|g| (format!("unlock present {}", g), g.to_json()))
+        .collect();
+    Ok(fill_slots(PRESENTS, &free, missing))
+}
+
+/// Build the change list for one preset, with the catalog taken from every
+/// slot next to `slot`. Nothing is written; pass the result to `apply_changes`.
+pub fn plan_unlock(slot: &Path, player: u32, what: Unlock) -> Result<Vec<Change>, String> {
+    let root = slot.parent().unwrap_or(Path::new("."));
+    let catalog = UnlockCatalog::scan(root);
+    let json = load_player_json(slot, player)?;
+    match what {
+        Unlock::Clothing => unlock_all_clothing(&json, &catalog),
+        Unlock::Vehicles => unlock_all_vehicles(&json, &catalog),
+        Unlock::Items => unlock_all_items(&json, &catalog),
+    }
+}
+
+/// Apply a reviewed change list to a player file (backed up first). Returns the backup.
+pub fn apply_changes(slot: &Path, player: u32, changes: &[Change]) -> Result<PathBuf, String> {
+    unlock_edit(slot, player, changes).apply()
+}
+
+pub fn unlock_edit(slot: &Path, player: u32, changes: &[Change]) -> PendingEdit {
+    PendingEdit {
+        path: player_file(slot, player),
+        ops: changes.iter().map(|c| c.op.clone()).collect(),
+    }
+}
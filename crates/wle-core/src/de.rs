@@ -0,0 +1,290 @@
+//! serde over a parsed document, so programs can read a .sav into their own
+//! types without going through JSON and pointers:
+//!
+//! ```no_run
+//! #[derive(serde::Deserialize)]
+//! struct MiscData { money: i64 }
+//! #[derive(serde::Deserialize)]
+//! struct PlayerData {
+//!     #[serde(rename = "<MiscData>k__BackingField")]
+//!     misc: MiscData,
+//! }
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let data = std::fs::read("PlayerData.sav")?;
+//! let player: PlayerData = wle_core::de::from_bytes(&data)?;
+//! println!("{}", player.misc.money);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Objects read as maps of their members (structs, `HashMap`s), arrays and
+//! byte arrays as sequences, and references as the object they point to, with
+//! a `List<T>` read as its first `_size` items. Strings are borrowed from the
+//! file. Enums read from a variant name or index, including a .NET enum
+//! object's `value__`; other values read as their own type, with the usual
+//! serde conversions between number types.
+
+use crate::binfmt::{Document, DynObject, Value};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+use std::fmt;
+
+// Deeper than any real save; stops reference cycles read into self-describing types
+const MAX_DEPTH: usize = 256;
+
+/// Why a document did not fit the requested type (or, from `ser`, a value
+/// could not be written).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(pub(crate) String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl From<Error> for String {
+    fn from(e: Error) -> Self {
+        e.0
+    }
+}
+
+/// Read the root object of `doc` as a `T`.
+pub fn from_document<'de, T: de::Deserialize<'de>>(doc: &'de Document<'de>) -> Result<T, Error> {
+    let root = doc
+        .root_value()
+        .ok_or_else(|| Error("document has no root object".into()))?;
+    T::deserialize(ValueDe::new(doc, root, 0))
+}
+
+/// Parse a .sav and read its root object as a `T`.
+pub fn from_bytes<T: DeserializeOwned>(data: &[u8]) -> Result<T, String> {
+    let doc = crate::binfmt::Parser::new(data).parse_stream()?;
+    Ok(from_document(&doc)?)
+}
+
+struct ValueDe<'de> {
+    doc: &'de Document<'de>,
+    v: &'de Value<'de>,
+    depth: usize,
+}
+
+impl<'de> ValueDe<'de> {
+    // Follows references, so `v` is never a `Ref` that resolves
+    fn new(doc: &'de Document<'de>, v: &'de Value<'de>, depth: usize) -> Self {
+        let mut v = v;
+        let mut hops = 0;
+        while let Value::Ref(id) = v
+            && let Some(target) = doc.get_object(*id)
+            && hops < MAX_DEPTH
+        {
+            v = target;
+            hops += 1;
+        }
+        ValueDe { doc, v, depth }
+    }
+
+    fn child(&self, v: &'de Value<'de>) -> Result<ValueDe<'de>, Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error(
+                "document nested too deeply (cyclic references?)".into(),
+            ));
+        }
+        Ok(ValueDe::new(self.doc, v, self.depth + 1))
+    }
+
+    // A `List<T>` object as its live items
+    fn list_items(&self, obj: &'de DynObject<'de>) -> Option<&'de [Value<'de>]> {
+        if !obj
+            .class_name
+            .starts_with("System.Collections.Generic.List`1")
+        {
+            return None;
+        }
+        let member = |name: &str| obj.members.iter().find(|(n, _)| *n == name).map(|(_, v)| v);
+        let items = match member("_items")? {
+            Value::Ref(id) => self.doc.get_object(*id)?,
+            v => v,
+        };
+        let Value::Array(items) = items else {
+            return None;
+        };
+        let size = match member("_size") {
+            Some(Value::I32(n)) => (*n).max(0) as usize,
+            _ => items.len(),
+        };
+        Some(&items[..size.min(items.len())])
+    }
+}
+
+impl<'de> Deserializer<'de> for ValueDe<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.v {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::I32(x) => visitor.visit_i32(*x),
+            Value::I64(x) => visitor.visit_i64(*x),
+            Value::U32(x) => visitor.visit_u32(*x),
+            Value::U64(x) => visitor.visit_u64(*x),
+            Value::F32(x) => visitor.visit_f32(*x),
+            Value::F64(x) => visitor.visit_f64(*x),
+            Value::U8(x) => visitor.visit_u8(*x),
+            Value::Str(s) => visitor.visit_borrowed_str(s),
+            Value::Bytes(b) => visitor.visit_seq(de::value::SeqDeserializer::<_, Error>::new(
+                b.iter().copied(),
+            )),
+            Value::Array(items) => visitor.visit_seq(Seq {
+                parent: self,
+                items: items.iter(),
+            }),
+            Value::Object(obj) => match self.list_items(obj) {
+                Some(items) => visitor.visit_seq(Seq {
+                    parent: self,
+                    items: items.iter(),
+                }),
+                None => visitor.visit_map(Map {
+                    parent: self,
+                    members: obj.members.iter(),
+                    value: None,
+                }),
+            },
+            Value::Ref(id) => Err(Error(format!("reference to missing object {}", id))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.v {
+            Value::Null | Value::Ref(_) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.v {
+            Value::Bytes(b) => visitor.visit_borrowed_bytes(b),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let index = |v: &Value<'_>| match v {
+            Value::I32(x) => u32::try_from(*x).ok(),
+            Value::I64(x) => u32::try_from(*x).ok(),
+            Value::U32(x) => Some(*x),
+            Value::U64(x) => u32::try_from(*x).ok(),
+            Value::U8(x) => Some(u32::from(*x)),
+            _ => None,
+        };
+        let n = match self.v {
+            Value::Str(s) => {
+                let s: de::value::BorrowedStrDeserializer<'de, Error> =
+                    de::value::BorrowedStrDeserializer::new(s);
+                return visitor.visit_enum(s);
+            }
+            Value::Object(obj) => obj
+                .members
+                .iter()
+                .find(|(n, _)| *n == "value__")
+                .and_then(|(_, v)| index(v)),
+            v => index(v),
+        };
+        match n {
+            Some(n) => visitor.visit_enum(n.into_deserializer()),
+            None => Err(Error("expected an enum name or number".into())),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+struct Seq<'de, I> {
+    parent: ValueDe<'de>,
+    items: I,
+}
+
+impl<'de, I: Iterator<Item = &'de Value<'de>>> SeqAccess<'de> for Seq<'de, I> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.items.next() {
+            Some(v) => seed.deserialize(self.parent.child(v)?).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.items.size_hint().1
+    }
+}
+
+struct Map<'de, I> {
+    parent: ValueDe<'de>,
+    members: I,
+    value: Option<&'de Value<'de>>,
+}
+
+impl<'de, I: Iterator<Item = &'de (&'de str, Value<'de>)>> MapAccess<'de> for Map<'de, I> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.members.next() {
+            Some((name, v)) => {
+                self.value = Some(v);
+                let name: de::value::BorrowedStrDeserializer<'de, Error> =
+                    de::value::BorrowedStrDeserializer::new(name);
+                seed.deserialize(name).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, Error> {
+        let v = self
+            .value
+            .take()
+            .ok_or_else(|| Error("map value requested before its key".into()))?;
+        seed.deserialize(self.parent.child(v)?)
+    }
+}
@@ -1,5 +1,6 @@
-use crate::binfmt::{Document, Value};
+use crate::binfmt::{CancellationToken, Document, Value};
 use crate::json::JsonOpts;
+use crate::model::Guid;
 use serde_json::json;
 use std::fs;
 use std::path::Path;
@@ -27,111 +28,414 @@ impl From<&JsonEditValue> for serde_json::Value {
     }
 }
 
+#[tracing::instrument(level = "debug", skip_all, fields(path = %path.display()))]
 pub fn parse_file_to_json_value(path: &Path, opts: JsonOpts) -> Result<serde_json::Value, String> {
     let data = fs::read(path).map_err(|e| e.to_string())?;
-    match data.iter().copied().find(|b| !b.is_ascii_whitespace()) {
-        Some(b'{') => serde_json::from_slice::<serde_json::Value>(&data).map_err(|e| e.to_string()),
+    parse_bytes_to_json_value(data, opts)
+}
+
+/// Edit JSON of a .sav for changing and writing back. Always
+/// `JsonOpts::full()`: no array is cut short behind a `$truncated` marker and
+/// no node is nulled out, so the result encodes back without loss. Smaller
+/// limits are for display only (`parse_file_to_json_value`, `json::dump_*`).
+pub fn parse_file_for_edit(path: &Path) -> Result<serde_json::Value, String> {
+    parse_file_to_json_value(path, JsonOpts::full())
+}
+
+/// `parse_file_for_edit` for contents already in memory
+pub fn parse_bytes_for_edit(data: Vec<u8>) -> Result<serde_json::Value, String> {
+    parse_bytes_to_json_value(data, JsonOpts::full())
+}
+
+/// `parse_bytes_for_edit` with progress and cancellation as in
+/// `parse_file_to_json_value_with_progress`
+pub fn parse_bytes_for_edit_with_progress(
+    data: Vec<u8>,
+    progress: std::sync::Arc<crate::binfmt::ParseProgress>,
+) -> Result<serde_json::Value, String> {
+    parse_bytes_to_json_value_with_progress(data, JsonOpts::full(), progress)
+}
+
+/// Edit JSON of recently parsed files, keyed by the SHA-256 of their bytes:
+/// opening a file again that has not changed since is a copy instead of a
+/// parse. Keeps the `capacity` most recently used documents and can be
+/// shared between threads (e.g. in an `Arc` with a background loader).
+#[derive(Debug)]
+pub struct ParseCache {
+    capacity: usize,
+    // Least recently used first
+    entries: std::sync::Mutex<Vec<([u8; 32], std::sync::Arc<serde_json::Value>)>>,
+    hits: std::sync::atomic::AtomicUsize,
+    misses: std::sync::atomic::AtomicUsize,
+}
+
+impl Default for ParseCache {
+    /// Room for every document of a slot
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+impl ParseCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Default::default(),
+            hits: Default::default(),
+            misses: Default::default(),
+        }
+    }
+
+    /// `parse_bytes_for_edit` through the cache.
+    pub fn parse(&self, data: Vec<u8>) -> Result<serde_json::Value, String> {
+        self.parse_with(data, parse_bytes_for_edit)
+    }
+
+    /// `parse_bytes_for_edit_with_progress` through the cache; a hit leaves
+    /// `progress` untouched.
+    pub fn parse_with_progress(
+        &self,
+        data: Vec<u8>,
+        progress: std::sync::Arc<crate::binfmt::ParseProgress>,
+    ) -> Result<serde_json::Value, String> {
+        self.parse_with(data, |d| parse_bytes_for_edit_with_progress(d, progress))
+    }
+
+    fn parse_with(
+        &self,
+        data: Vec<u8>,
+        parse: impl FnOnce(Vec<u8>) -> Result<serde_json::Value, String>,
+    ) -> Result<serde_json::Value, String> {
+        use sha2::{Digest, Sha256};
+        use std::sync::atomic::Ordering::Relaxed;
+        let key: [u8; 32] = Sha256::digest(&data).into();
+        let found = {
+            let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            let at = entries.iter().position(|(k, _)| *k == key);
+            at.map(|i| {
+                let entry = entries.remove(i);
+                let v = entry.1.clone();
+                entries.push(entry);
+                v
+            })
+        };
+        if let Some(v) = found {
+            self.hits.fetch_add(1, Relaxed);
+            tracing::debug!(bytes = data.len(), "parse cache hit");
+            return Ok((*v).clone());
+        }
+        self.misses.fetch_add(1, Relaxed);
+        let v = parse(data)?;
+        if self.capacity > 0 {
+            let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            entries.retain(|(k, _)| *k != key);
+            if entries.len() >= self.capacity {
+                entries.remove(0);
+            }
+            entries.push((key, std::sync::Arc::new(v.clone())));
+        }
+        Ok(v)
+    }
+
+    /// Documents held
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Parses answered from the cache
+    pub fn hits(&self) -> usize {
+        self.hits.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Parses that had to be done
+    pub fn misses(&self) -> usize {
+        self.misses.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+// Same as `parse_file_to_json_value` for contents already in memory (e.g. stdin)
+pub fn parse_bytes_to_json_value(
+    data: Vec<u8>,
+    opts: JsonOpts,
+) -> Result<serde_json::Value, String> {
+    parse_bytes_with(data, opts, None, None)
+}
+
+/// `parse_bytes_to_json_value` that gives up with a "cancelled" error once
+/// `cancel` is cancelled, during the parse or the conversion to JSON
+pub fn parse_bytes_to_json_value_with_cancel(
+    data: Vec<u8>,
+    opts: JsonOpts,
+    cancel: &CancellationToken,
+) -> Result<serde_json::Value, String> {
+    parse_bytes_with(data, opts, None, Some(cancel.clone()))
+}
+
+// `parse_file_to_json_value` for a caller on another thread that shows how far
+// the parse got and may cancel it (see `ParseProgress`)
+pub fn parse_file_to_json_value_with_progress(
+    path: &Path,
+    opts: JsonOpts,
+    progress: std::sync::Arc<crate::binfmt::ParseProgress>,
+) -> Result<serde_json::Value, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    parse_bytes_to_json_value_with_progress(data, opts, progress)
+}
+
+// `parse_bytes_to_json_value` with progress and cancellation as in
+// `parse_file_to_json_value_with_progress`
+pub fn parse_bytes_to_json_value_with_progress(
+    data: Vec<u8>,
+    opts: JsonOpts,
+    progress: std::sync::Arc<crate::binfmt::ParseProgress>,
+) -> Result<serde_json::Value, String> {
+    let cancel = progress.token();
+    parse_bytes_with(data, opts, Some(progress), Some(cancel))
+}
+
+fn parse_bytes_with(
+    data: Vec<u8>,
+    opts: JsonOpts,
+    progress: Option<std::sync::Arc<crate::binfmt::ParseProgress>>,
+    cancel: Option<CancellationToken>,
+) -> Result<serde_json::Value, String> {
+    match strip_bom(&data)
+        .iter()
+        .copied()
+        .find(|b| !b.is_ascii_whitespace())
+    {
+        Some(b'{') => json_from_bytes(&data),
+        // A .sav starts with its header record, type 0
+        #[cfg(feature = "yaml")]
+        Some(b) if b.is_ascii_graphic() => json_from_yaml(&data),
         Some(_) => {
-            let leaked: &'static [u8] = Box::leak(data.into_boxed_slice());
-            let mut parser = crate::binfmt::Parser::new(leaked);
-            match parser.parse_stream() {
-                Ok(doc) => Ok(document_to_json_value(&doc, opts)),
-                Err(e) => Err(e),
+            let mut parser = crate::binfmt::Parser::new(&data);
+            if let Some(p) = progress {
+                parser = parser.with_progress(p);
+            }
+            if let Some(c) = &cancel {
+                parser = parser.with_cancel(c.clone());
+            }
+            let doc = parser.parse_stream()?;
+            match &cancel {
+                Some(c) => document_to_json_value_with_cancel(&doc, opts, c),
+                None => Ok(document_to_json_value(&doc, opts)),
             }
         }
         None => Err("empty file".to_string()),
     }
 }
 
-pub fn document_to_json_value(doc: &Document<'_>, opts: JsonOpts) -> serde_json::Value {
-    fn write_value(
-        doc: &Document<'_>,
-        v: &Value<'_>,
-        depth: usize,
-        opts: &JsonOpts,
-    ) -> serde_json::Value {
-        match v {
-            Value::Null => serde_json::Value::Null,
-            Value::Bool(b) => serde_json::Value::Bool(*b),
-            Value::I32(x) => json!(*x),
-            Value::I64(x) => json!(*x),
-            Value::U32(x) => json!(*x),
-            Value::U64(x) => json!(*x),
-            Value::F32(x) => json!(*x),
-            Value::F64(x) => json!(*x),
-            Value::U8(x) => json!(*x),
-            Value::Str(s) => json!(*s),
-            Value::Bytes(b) => {
-                if opts.bytes_summary {
-                    json!({"$type":"bytes","len": b.len()})
+// One node of the edit JSON, `depth` levels below the document wrapper; null
+// once `cancel` is cancelled, for the caller to throw away
+fn write_value(
+    doc: &Document<'_>,
+    v: &Value<'_>,
+    depth: usize,
+    opts: &JsonOpts,
+    cancel: Option<&CancellationToken>,
+) -> serde_json::Value {
+    if cancel.is_some_and(CancellationToken::is_cancelled) {
+        return serde_json::Value::Null;
+    }
+    match v {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::I32(x) => json!(*x),
+        Value::I64(x) => json!(*x),
+        Value::U32(x) => json!(*x),
+        Value::U64(x) => json!(*x),
+        Value::F32(x) => json!(*x),
+        Value::F64(x) => json!(*x),
+        Value::U8(x) => json!(*x),
+        Value::Str(s) => json!(*s),
+        Value::Bytes(b) => {
+            if opts.bytes_summary {
+                json!({"$type":"bytes","len": b.len()})
+            } else {
+                json!(b)
+            }
+        }
+        Value::Array(items) => {
+            let max = opts.max_array_elems.min(items.len());
+            let mut arr = Vec::with_capacity(max + 1);
+            for it in items.iter().take(max) {
+                if depth >= opts.max_depth {
+                    arr.push(serde_json::Value::Null);
                 } else {
-                    serde_json::Value::Null
+                    arr.push(write_value(doc, it, depth + 1, opts, cancel));
                 }
             }
-            Value::Array(items) => {
-                let max = opts.max_array_elems.min(items.len());
-                let mut arr = Vec::with_capacity(max + 1);
-                for it in items.iter().take(max) {
-                    if depth >= opts.max_depth {
-                        arr.push(serde_json::Value::Null);
-                    } else {
-                        arr.push(write_value(doc, it, depth + 1, opts));
-                    }
-                }
-                if items.len() > max {
-                    arr.push(json!({"$truncated": true, "$omitted": items.len() - max }));
-                }
-                serde_json::Value::Array(arr)
-            }
-            Value::Object(obj) => {
-                let mut map = serde_json::Map::with_capacity(obj.members.len() + 1);
-                map.insert("$class".to_string(), json!(obj.class_name));
-                for (name, val) in obj.members.iter() {
-                    let vv = if depth >= opts.max_depth {
-                        serde_json::Value::Null
-                    } else {
-                        write_value(doc, val, depth + 1, opts)
-                    };
-                    map.insert((*name).to_string(), vv);
-                }
-                serde_json::Value::Object(map)
-            }
-            Value::Ref(id) => {
-                if let Some(Value::Object(obj)) = doc.get_object(*id)
-                    && obj
-                        .class_name
-                        .starts_with("System.Collections.Generic.List`1")
-                    && let Some((_, Value::Ref(items_id))) =
-                        obj.members.iter().find(|(name, _)| *name == "_items")
-                    && let Some(items) = doc.get_object(*items_id)
-                {
-                    return write_value(doc, items, depth, opts);
-                }
-                let mut map = serde_json::Map::new();
-                map.insert("$ref".to_string(), json!(*id));
-                if depth < opts.max_depth
-                    && let Some(v2) = doc.get_object(*id)
-                {
-                    map.insert("$value".to_string(), write_value(doc, v2, depth + 1, opts));
-                }
-                serde_json::Value::Object(map)
+            if items.len() > max {
+                arr.push(json!({"$truncated": true, "$omitted": items.len() - max }));
+            }
+            serde_json::Value::Array(arr)
+        }
+        Value::Object(obj) => {
+            let mut map = serde_json::Map::with_capacity(obj.members.len() + 1);
+            map.insert("$class".to_string(), json!(obj.class_name));
+            for (name, val) in obj.members.iter() {
+                let vv = if depth >= opts.max_depth {
+                    serde_json::Value::Null
+                } else {
+                    write_value(doc, val, depth + 1, opts, cancel)
+                };
+                map.insert((*name).to_string(), vv);
+            }
+            serde_json::Value::Object(map)
+        }
+        Value::Ref(id) => {
+            if let Some(items) = list_items(doc, *id) {
+                return write_value(doc, items, depth, opts, cancel);
+            }
+            let mut map = serde_json::Map::new();
+            map.insert("$ref".to_string(), json!(*id));
+            if depth < opts.max_depth
+                && let Some(v2) = doc.get_object(*id)
+            {
+                map.insert(
+                    "$value".to_string(),
+                    write_value(doc, v2, depth + 1, opts, cancel),
+                );
             }
+            serde_json::Value::Object(map)
         }
     }
+}
+
+pub fn document_to_json_value(doc: &Document<'_>, opts: JsonOpts) -> serde_json::Value {
+    to_json(doc, opts, None)
+}
+
+/// `document_to_json_value` that stops with a "cancelled" error once
+/// `cancel` is cancelled.
+pub fn document_to_json_value_with_cancel(
+    doc: &Document<'_>,
+    opts: JsonOpts,
+    cancel: &CancellationToken,
+) -> Result<serde_json::Value, String> {
+    let v = to_json(doc, opts, Some(cancel));
+    cancel.check()?;
+    Ok(v)
+}
 
+#[tracing::instrument(level = "debug", skip_all)]
+fn to_json(
+    doc: &Document<'_>,
+    opts: JsonOpts,
+    cancel: Option<&CancellationToken>,
+) -> serde_json::Value {
     let mut root = serde_json::Map::new();
     root.insert(
         "$rootClass".to_string(),
         json!(doc.root_class_name().unwrap_or("<unknown>")),
     );
     if let Some(v) = doc.root_value() {
-        root.insert("root".to_string(), write_value(doc, v, 1, &opts));
+        root.insert("root".to_string(), write_value(doc, v, 1, &opts, cancel));
     } else {
         root.insert("root".to_string(), serde_json::Value::Null);
     }
     serde_json::Value::Object(root)
 }
 
+// A referenced `List<T>` is dumped as its `_items` array
+fn list_items<'d, 'a>(doc: &'d Document<'a>, id: i32) -> Option<&'d Value<'a>> {
+    if let Some(Value::Object(obj)) = doc.get_object(id)
+        && obj
+            .class_name
+            .starts_with("System.Collections.Generic.List`1")
+        && let Some((_, Value::Ref(items_id))) =
+            obj.members.iter().find(|(name, _)| *name == "_items")
+    {
+        return doc.get_object(*items_id);
+    }
+    None
+}
+
+/// The node at `pointer` in the edit JSON of `doc`, with only that subtree
+/// converted; `opts` limits depth and array length below the node rather
+/// than below the root. None when there is no such node.
+pub fn document_value_at(
+    doc: &Document<'_>,
+    pointer: &str,
+    opts: JsonOpts,
+) -> Option<serde_json::Value> {
+    if pointer.is_empty() {
+        return Some(document_to_json_value(doc, opts));
+    }
+    // Anything else starts with '/' (RFC 6901)
+    let tokens: Vec<String> = pointer
+        .strip_prefix('/')?
+        .split('/')
+        .map(unescape_token)
+        .collect();
+    let mut v = match (tokens[0].as_str(), tokens.len()) {
+        ("$rootClass", 1) => return Some(json!(doc.root_class_name().unwrap_or("<unknown>"))),
+        ("root", _) => doc.root_value()?,
+        _ => return None,
+    };
+    for (i, token) in tokens.iter().enumerate().skip(1) {
+        // `$class` and `$ref` are leaves
+        let last = i + 1 == tokens.len();
+        if let Value::Ref(id) = v
+            && let Some(items) = list_items(doc, *id)
+        {
+            v = items;
+        }
+        v = match v {
+            Value::Object(obj) if token == "$class" => {
+                return last.then(|| json!(obj.class_name));
+            }
+            Value::Object(obj) => &obj.members.iter().find(|(n, _)| n == token)?.1,
+            Value::Array(items) => items.get(token.parse::<usize>().ok()?)?,
+            Value::Ref(id) if token == "$ref" => return last.then(|| json!(*id)),
+            Value::Ref(id) if token == "$value" => doc.get_object(*id)?,
+            _ => return None,
+        };
+    }
+    Some(write_value(doc, v, 1, &opts, None))
+}
+
+/// The node at each of `pointers` in a .sav (or a JSON or YAML dump), each
+/// found as `document_value_at` does so big documents are not converted whole.
+pub fn parse_bytes_at_pointers(
+    data: Vec<u8>,
+    pointers: &[String],
+    opts: JsonOpts,
+) -> Result<Vec<Option<serde_json::Value>>, String> {
+    let text = match strip_bom(&data)
+        .iter()
+        .copied()
+        .find(|b| !b.is_ascii_whitespace())
+    {
+        Some(b'{') => true,
+        #[cfg(feature = "yaml")]
+        Some(b) if b.is_ascii_graphic() => true,
+        Some(_) => false,
+        None => return Err("empty file".to_string()),
+    };
+    if text {
+        let v = parse_bytes_to_json_value(data, opts)?;
+        return Ok(pointers.iter().map(|p| v.pointer(p).cloned()).collect());
+    }
+    let doc = crate::binfmt::Parser::new(&data).parse_stream()?;
+    Ok(pointers
+        .iter()
+        .map(|p| document_value_at(&doc, p, opts))
+        .collect())
+}
+
 pub fn set_by_pointer(
     value: &mut serde_json::Value,
     pointer: &str,
@@ -146,6 +450,68 @@ pub fn set_by_pointer(
     }
 }
 
+/// What `set_typed_by_pointer` does with a number outside the catalog
+/// limits of its field (`catalog::FieldInfo::allows`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Limits {
+    /// Write it anyway
+    #[default]
+    Off,
+    /// Write the nearest value the field allows instead
+    Clamp,
+    /// Refuse the write
+    Reject,
+}
+
+impl std::str::FromStr for Limits {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "off" => Ok(Limits::Off),
+            "clamp" => Ok(Limits::Clamp),
+            "reject" => Ok(Limits::Reject),
+            _ => Err(format!("unknown limits '{}' (off, clamp or reject)", s)),
+        }
+    }
+}
+
+/// `set_by_pointer` that holds numbers to the catalog limits of the field
+/// at `pointer` (see `Limits`). Returns the value written, which differs
+/// from `new_value` when it was clamped.
+pub fn set_typed_by_pointer(
+    value: &mut serde_json::Value,
+    pointer: &str,
+    new_value: JsonEditValue,
+    limits: Limits,
+) -> Result<JsonEditValue, String> {
+    let num = match new_value {
+        JsonEditValue::Int(n) => Some(n as f64),
+        JsonEditValue::Float(f) => Some(f),
+        _ => None,
+    };
+    let field = crate::catalog::field_at(value, pointer);
+    let new_value = match (num, field, limits) {
+        (Some(x), Some(info), Limits::Clamp | Limits::Reject) if !info.allows(x) => {
+            if limits == Limits::Reject {
+                return Err(format!(
+                    "{} = {} is refused: {} expects {}",
+                    pointer,
+                    x,
+                    info.label,
+                    info.limits().unwrap_or_default()
+                ));
+            }
+            match new_value {
+                JsonEditValue::Int(_) => JsonEditValue::Int(info.clamp(x).round() as i64),
+                _ => JsonEditValue::Float(info.clamp(x)),
+            }
+        }
+        _ => new_value,
+    };
+    set_by_pointer(value, pointer, new_value.clone())?;
+    Ok(new_value)
+}
+
 pub fn get_by_pointer(value: &serde_json::Value, pointer: &str) -> Option<serde_json::Value> {
     value.pointer(pointer).cloned()
 }
@@ -200,11 +566,58 @@ pub fn apply_object_primitive_updates(
     Ok(())
 }
 
+/// Pretty JSON, or YAML for a `.yaml`/`.yml` path when the `yaml` feature is on.
 pub fn write_json_to_file(path: &Path, value: &serde_json::Value) -> Result<(), String> {
+    #[cfg(feature = "yaml")]
+    if is_yaml_path(path) {
+        return fs::write(path, yaml_from_json(value)?).map_err(|e| e.to_string());
+    }
     let s = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
     fs::write(path, s).map_err(|e| e.to_string())
 }
 
+/// Any JSON value, such as a subtree written by `write_json_to_file`. A UTF-8
+/// byte order mark, which some Windows editors add on save, is ignored.
+pub fn json_from_bytes(data: &[u8]) -> Result<serde_json::Value, String> {
+    serde_json::from_slice(strip_bom(data)).map_err(|e| e.to_string())
+}
+
+/// `json_from_bytes` on the contents of `path` (`json_from_yaml` for a YAML
+/// path when the `yaml` feature is on)
+pub fn read_json_file(path: &Path) -> Result<serde_json::Value, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    #[cfg(feature = "yaml")]
+    if is_yaml_path(path) {
+        return json_from_yaml(&data);
+    }
+    json_from_bytes(&data)
+}
+
+/// A `.yaml` or `.yml` file name.
+pub fn is_yaml_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("yaml") || e.eq_ignore_ascii_case("yml"))
+}
+
+/// A document or subtree as YAML: one line per scalar, so diffs of two dumps
+/// show each changed value. Keys keep their order and strings that would
+/// read back as another type are quoted.
+#[cfg(feature = "yaml")]
+pub fn yaml_from_json(value: &serde_json::Value) -> Result<String, String> {
+    serde_yaml::to_string(value).map_err(|e| e.to_string())
+}
+
+/// The JSON value of YAML such as `yaml_from_json` writes; a byte order mark
+/// is ignored as in `json_from_bytes`.
+#[cfg(feature = "yaml")]
+pub fn json_from_yaml(data: &[u8]) -> Result<serde_json::Value, String> {
+    serde_yaml::from_slice(strip_bom(data)).map_err(|e| e.to_string())
+}
+
+fn strip_bom(data: &[u8]) -> &[u8] {
+    data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data)
+}
+
 // -------- Extra generic helpers for tree browsing and editing --------
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -274,12 +687,303 @@ pub fn list_children(value: &serde_json::Value, pointer: &str) -> Result<Vec<Chi
     Ok(out)
 }
 
+// -------- Search --------
+
+/// What `search` matches the query against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchScope {
+    /// Member names (case-insensitive substring)
+    Keys,
+    /// Scalars: `true`/`false` and numbers compare equal, text matches substrings
+    Values,
+    /// `$class` names of objects (case-insensitive substring)
+    Class,
+    /// Keys, then values
+    All,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// Pointer to the matching node: the member for key hits, the scalar for
+    /// value hits, the object for class hits
+    pub pointer: String,
+    pub preview: String,
+}
+
+enum ValueQuery {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl ValueQuery {
+    fn parse(q: &str) -> Self {
+        if q.eq_ignore_ascii_case("true") {
+            ValueQuery::Bool(true)
+        } else if q.eq_ignore_ascii_case("false") {
+            ValueQuery::Bool(false)
+        } else if let Ok(i) = q.parse::<i64>() {
+            ValueQuery::Int(i)
+        } else if let Ok(f) = q.parse::<f64>() {
+            ValueQuery::Float(f)
+        } else {
+            ValueQuery::Text(q.to_lowercase())
+        }
+    }
+
+    fn matches(&self, v: &serde_json::Value) -> bool {
+        match self {
+            ValueQuery::Bool(b) => v.as_bool() == Some(*b),
+            ValueQuery::Int(i) => v.as_i64() == Some(*i),
+            ValueQuery::Float(f) => v.as_f64() == Some(*f),
+            ValueQuery::Text(s) => v.as_str().is_some_and(|x| x.to_lowercase().contains(s)),
+        }
+    }
+}
+
+/// Short one-line description of a node for search results and listings.
+pub fn value_preview(v: &serde_json::Value) -> String {
+    const MAX: usize = 60;
+    match v {
+        serde_json::Value::String(s) if s.chars().count() > MAX => {
+            format!("\"{}…\"", s.chars().take(MAX).collect::<String>())
+        }
+        serde_json::Value::Object(map) => {
+            if let Some(inner) = map.get("$value") {
+                value_preview(inner)
+            } else if let Some(class) = map.get("$class").and_then(|c| c.as_str()) {
+                format!("{} {{{} fields}}", class, map.len() - 1)
+            } else {
+                format!("{{{} keys}}", map.len())
+            }
+        }
+        serde_json::Value::Array(arr) => format!("[{} items]", arr.len()),
+        _ => v.to_string(),
+    }
+}
+
+/// Find nodes matching `query` under the document's `/root` (or the whole value
+/// when there is no `/root`), in document order, stopping after `limit` hits.
+pub fn search(
+    root: &serde_json::Value,
+    query: &str,
+    scope: SearchScope,
+    limit: usize,
+) -> Vec<SearchHit> {
+    let q = query.trim();
+    let mut out = Vec::new();
+    if q.is_empty() {
+        return out;
+    }
+    if scope == SearchScope::All {
+        out = search(root, q, SearchScope::Keys, limit);
+        for hit in search(root, q, SearchScope::Values, limit) {
+            if out.len() >= limit {
+                break;
+            }
+            if !out.iter().any(|h| h.pointer == hit.pointer) {
+                out.push(hit);
+            }
+        }
+        return out;
+    }
+
+    fn walk(
+        ptr: &str,
+        v: &serde_json::Value,
+        scope: SearchScope,
+        key_q: &str,
+        value_q: &ValueQuery,
+        out: &mut Vec<SearchHit>,
+        limit: usize,
+    ) {
+        if out.len() >= limit {
+            return;
+        }
+        let hit = match scope {
+            SearchScope::Values => value_q.matches(v),
+            SearchScope::Class => v
+                .get("$class")
+                .and_then(|c| c.as_str())
+                .is_some_and(|c| c.to_lowercase().contains(key_q)),
+            _ => false,
+        };
+        if hit {
+            out.push(SearchHit {
+                pointer: ptr.to_string(),
+                preview: value_preview(v),
+            });
+        }
+        match v {
+            serde_json::Value::Object(map) => {
+                for (k, c) in map {
+                    if out.len() >= limit {
+                        return;
+                    }
+                    let next = format!("{}/{}", ptr, escape_token(k));
+                    if scope == SearchScope::Keys && k.to_lowercase().contains(key_q) {
+                        out.push(SearchHit {
+                            pointer: next.clone(),
+                            preview: value_preview(c),
+                        });
+                    }
+                    walk(&next, c, scope, key_q, value_q, out, limit);
+                }
+            }
+            serde_json::Value::Array(arr) => {
+                for (i, c) in arr.iter().enumerate() {
+                    walk(
+                        &format!("{}/{}", ptr, i),
+                        c,
+                        scope,
+                        key_q,
+                        value_q,
+                        out,
+                        limit,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let key_q = q.to_lowercase();
+    let value_q = ValueQuery::parse(q);
+    let (base, start) = match root.get("root") {
+        Some(sub) => ("/root", sub),
+        None => ("", root),
+    };
+    // The root object's own `$class` is reported by the walk
+    if scope == SearchScope::Class
+        && start.get("$class").is_none()
+        && let Some(c) = root.get("$rootClass").and_then(|c| c.as_str())
+        && c.to_lowercase().contains(&key_q)
+    {
+        out.push(SearchHit {
+            pointer: base.to_string(),
+            preview: c.to_string(),
+        });
+    }
+    walk(base, start, scope, &key_q, &value_q, &mut out, limit);
+    out
+}
+
+/// `text` read as a value of the same JSON type as `current`, so a bulk edit
+/// cannot turn a bool field into a string: `false` for a bool, `12` for an
+/// integer, `1.5` for a float, anything for a string (a quoted JSON string is
+/// unquoted). Objects and arrays are not replaceable this way.
+pub fn coerce_like(current: &serde_json::Value, text: &str) -> Result<serde_json::Value, String> {
+    use serde_json::Value as J;
+    let t = text.trim();
+    match current {
+        J::Bool(_) => match t.to_ascii_lowercase().as_str() {
+            "true" => Ok(J::Bool(true)),
+            "false" => Ok(J::Bool(false)),
+            _ => Err(format!("expected true or false, got '{}'", t)),
+        },
+        J::Number(n) if n.is_i64() || n.is_u64() => t
+            .parse::<i64>()
+            .map(|i| J::Number(i.into()))
+            .map_err(|_| format!("expected an integer, got '{}'", t)),
+        J::Number(_) => t
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(J::Number)
+            .ok_or_else(|| format!("expected a number, got '{}'", t)),
+        J::String(_) => Ok(match serde_json::from_str::<J>(t) {
+            Ok(J::String(s)) => J::String(s),
+            _ => J::String(text.to_string()),
+        }),
+        J::Null if t == "null" => Ok(J::Null),
+        J::Null => Err("null fields have no type to keep".into()),
+        J::Object(m) if m.contains_key("$value") => coerce_like(&m["$value"], text),
+        _ => Err("objects and arrays cannot be replaced with a value".into()),
+    }
+}
+
+/// Replace ops setting every node at `pointers` to `text` via `coerce_like`;
+/// a `{"$ref", "$value"}` wrapper has its `$value` replaced. Nodes already
+/// holding the value get no op; nodes that are missing or do not take the
+/// value are returned with the reason instead.
+pub fn coerced_replace_ops(
+    root: &serde_json::Value,
+    pointers: &[String],
+    text: &str,
+) -> (Vec<PatchOp>, Vec<(String, String)>) {
+    let mut ops = Vec::new();
+    let mut skipped = Vec::new();
+    for ptr in pointers {
+        let Some(node) = root.pointer(ptr) else {
+            skipped.push((ptr.clone(), format!("json pointer not found: {}", ptr)));
+            continue;
+        };
+        let (path, current) = match node.get("$value") {
+            Some(inner) => (format!("{}/$value", ptr), inner),
+            None => (ptr.clone(), node),
+        };
+        match coerce_like(current, text) {
+            Ok(v) if &v == current => {}
+            Ok(v) => ops.push(PatchOp::replace(path, v)),
+            Err(e) => skipped.push((ptr.clone(), e)),
+        }
+    }
+    (ops, skipped)
+}
+
+/// Ops pasting `value` at `pointer`: over the node (a replace), or `into` it,
+/// appending to an array or setting each key of a pasted object on an
+/// object. An appended element gets fresh GUIDs like a duplicate, so pasting
+/// the same item twice does not clash.
+pub fn paste_ops(
+    root: &serde_json::Value,
+    pointer: &str,
+    value: serde_json::Value,
+    into: bool,
+) -> Result<Vec<PatchOp>, String> {
+    let node = root
+        .pointer(pointer)
+        .ok_or_else(|| format!("json pointer not found: {}", pointer))?;
+    if !into {
+        if pointer.is_empty() {
+            return Err("cannot paste over the whole document".into());
+        }
+        return Ok(vec![PatchOp::replace(pointer, value)]);
+    }
+    match (node, value) {
+        (serde_json::Value::Array(_), value) => {
+            let mut copy = serde_json::json!({ "item": value });
+            regenerate_guids(&mut copy, "/item")?;
+            Ok(vec![PatchOp::Add {
+                path: format!("{}/-", pointer),
+                value: copy["item"].take(),
+            }])
+        }
+        (serde_json::Value::Object(_), serde_json::Value::Object(map)) => Ok(map
+            .into_iter()
+            .map(|(k, v)| PatchOp::Add {
+                path: format!("{}/{}", pointer, escape_token(&k)),
+                value: v,
+            })
+            .collect()),
+        (serde_json::Value::Object(_), _) => {
+            Err("only an object can be pasted into an object".into())
+        }
+        _ => Err("can only paste into an object or array".into()),
+    }
+}
+
+pub(crate) fn escape_token(s: &str) -> String {
+    s.replace('~', "~0").replace('/', "~1")
+}
+
 fn unescape_token(tok: &str) -> String {
     let s = tok.replace("~1", "/");
     s.replace("~0", "~")
 }
 
-fn parent_pointer(ptr: &str) -> Option<(&str, &str)> {
+pub(crate) fn parent_pointer(ptr: &str) -> Option<(&str, &str)> {
     if ptr.is_empty() || ptr == "/" {
         return None;
     }
@@ -293,6 +997,11 @@ fn parent_pointer(ptr: &str) -> Option<(&str, &str)> {
     None
 }
 
+// Edit JSON wraps referenced objects as {"$ref", "$value"}; this is the value
+pub(crate) fn unwrap_ref(v: &serde_json::Value) -> &serde_json::Value {
+    v.get("$value").unwrap_or(v)
+}
+
 pub fn set_raw_by_pointer(
     root: &mut serde_json::Value,
     pointer: &str,
@@ -388,11 +1097,905 @@ pub fn array_remove(
     arr.remove(index);
     Ok(())
 }
+
+// One RFC 6902 (JSON Patch) operation. Typed models in `model` return these so
+// frontends can preview, apply, or export edits without knowing pointers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOp {
+    Add {
+        path: String,
+        value: serde_json::Value,
+    },
+    Remove {
+        path: String,
+    },
+    Replace {
+        path: String,
+        value: serde_json::Value,
+    },
+    Copy {
+        from: String,
+        path: String,
+    },
+}
+
+impl PatchOp {
+    pub fn replace(path: impl Into<String>, value: serde_json::Value) -> Self {
+        PatchOp::Replace {
+            path: path.into(),
+            value,
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            PatchOp::Add { path, .. }
+            | PatchOp::Remove { path }
+            | PatchOp::Replace { path, .. }
+            | PatchOp::Copy { path, .. } => path,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            PatchOp::Add { path, value } => json!({"op": "add", "path": path, "value": value}),
+            PatchOp::Remove { path } => json!({"op": "remove", "path": path}),
+            PatchOp::Replace { path, value } => {
+                json!({"op": "replace", "path": path, "value": value})
+            }
+            PatchOp::Copy { from, path } => json!({"op": "copy", "from": from, "path": path}),
+        }
+    }
+
+    /// Read one JSON Patch object; `move` and `test` are not supported.
+    pub fn from_json(v: &serde_json::Value) -> Result<Self, String> {
+        let field = |name: &str| {
+            v.get(name)
+                .and_then(|x| x.as_str())
+                .map(String::from)
+                .ok_or_else(|| format!("patch op is missing \"{}\"", name))
+        };
+        let value = || {
+            v.get("value")
+                .cloned()
+                .ok_or_else(|| "patch op is missing \"value\"".to_string())
+        };
+        match field("op")?.as_str() {
+            "add" => Ok(PatchOp::Add {
+                path: field("path")?,
+                value: value()?,
+            }),
+            "remove" => Ok(PatchOp::Remove {
+                path: field("path")?,
+            }),
+            "replace" => Ok(PatchOp::replace(field("path")?, value()?)),
+            "copy" => Ok(PatchOp::Copy {
+                from: field("from")?,
+                path: field("path")?,
+            }),
+            other => Err(format!("unsupported patch op '{}'", other)),
+        }
+    }
+}
+
+/// Read a JSON Patch document (an array of ops).
+pub fn parse_patch(v: &serde_json::Value) -> Result<Vec<PatchOp>, String> {
+    let arr = v
+        .as_array()
+        .ok_or_else(|| "a JSON patch must be an array of ops".to_string())?;
+    arr.iter()
+        .enumerate()
+        .map(|(i, op)| PatchOp::from_json(op).map_err(|e| format!("op {}: {}", i + 1, e)))
+        .collect()
+}
+
+/// Read a line-based edit script, one op per line; `#` starts a comment.
+///
+/// ```text
+/// set    /root/<MiscData>k__BackingField/$value/money 50000
+/// remove /root/list/3
+/// insert /root/list/- {"a": 1}
+/// copy   /root/list/0 /root/list/-
+/// ```
+///
+/// `set` replaces an existing value, `insert` is JSON Patch `add`. Values are
+/// raw JSON; a pointer containing spaces can be written as a JSON string.
+pub fn parse_edit_script(text: &str) -> Result<Vec<PatchOp>, String> {
+    // Next pointer token: a JSON string literal or everything up to whitespace
+    fn pointer(rest: &str) -> Result<(String, &str), String> {
+        let rest = rest.trim_start();
+        if rest.starts_with('"') {
+            let mut it = serde_json::Deserializer::from_str(rest).into_iter::<String>();
+            let p = it
+                .next()
+                .ok_or("missing pointer")?
+                .map_err(|e| format!("bad pointer: {}", e))?;
+            Ok((p, &rest[it.byte_offset()..]))
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            if end == 0 {
+                return Err("missing pointer".into());
+            }
+            Ok((rest[..end].to_string(), &rest[end..]))
+        }
+    }
+    fn value(rest: &str) -> Result<serde_json::Value, String> {
+        let rest = rest.trim();
+        if rest.is_empty() {
+            return Err("missing value".into());
+        }
+        serde_json::from_str(rest).map_err(|e| format!("bad value: {}", e))
+    }
+    fn no_more(rest: &str) -> Result<(), String> {
+        match rest.trim() {
+            "" => Ok(()),
+            extra => Err(format!("unexpected '{}'", extra)),
+        }
+    }
+
+    let mut ops = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (verb, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let op = (|| match verb {
+            "set" => {
+                let (path, rest) = pointer(rest)?;
+                Ok(PatchOp::replace(path, value(rest)?))
+            }
+            "insert" => {
+                let (path, rest) = pointer(rest)?;
+                Ok(PatchOp::Add {
+                    path,
+                    value: value(rest)?,
+                })
+            }
+            "remove" => {
+                let (path, rest) = pointer(rest)?;
+                no_more(rest)?;
+                Ok(PatchOp::Remove { path })
+            }
+            "copy" => {
+                let (from, rest) = pointer(rest)?;
+                let (path, rest) = pointer(rest)?;
+                no_more(rest)?;
+                Ok(PatchOp::Copy { from, path })
+            }
+            _ => Err(format!(
+                "unknown command '{}' (expected set, insert, remove or copy)",
+                verb
+            )),
+        })()
+        .map_err(|e: String| format!("line {}: {}", n + 1, e))?;
+        ops.push(op);
+    }
+    Ok(ops)
+}
+
+// JSON Patch `add`: inserts on an array index ("-" appends), sets an object key
+fn add_at(
+    root: &mut serde_json::Value,
+    path: &str,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    let (parent_ptr, last) =
+        parent_pointer(path).ok_or_else(|| "cannot add at root".to_string())?;
+    let last = unescape_token(last);
+    let parent = root
+        .pointer_mut(parent_ptr)
+        .ok_or_else(|| format!("json pointer not found: {}", parent_ptr))?;
+    match parent {
+        serde_json::Value::Object(map) => {
+            map.insert(last, value);
+        }
+        serde_json::Value::Array(arr) => {
+            let idx = if last == "-" {
+                arr.len()
+            } else {
+                last.parse()
+                    .map_err(|_| "array index invalid".to_string())?
+            };
+            if idx > arr.len() {
+                return Err("array index out of bounds".into());
+            }
+            arr.insert(idx, value);
+        }
+        _ => return Err("parent is neither object nor array".into()),
+    }
+    Ok(())
+}
+
+// Apply ops in order. `add` on an array index inserts ("-" appends); on an
+// object it sets the key. Stops at the first failing op.
+#[tracing::instrument(level = "debug", name = "patch", skip_all, fields(ops = ops.len()), err(level = "debug"))]
+pub fn apply_patch(root: &mut serde_json::Value, ops: &[PatchOp]) -> Result<(), String> {
+    for op in ops {
+        match op {
+            PatchOp::Replace { path, value } => set_raw_by_pointer(root, path, value.clone())?,
+            PatchOp::Remove { path } => remove_at_pointer(root, path)?,
+            PatchOp::Add { path, value } => add_at(root, path, value.clone())?,
+            PatchOp::Copy { from, path } => {
+                let value = root
+                    .pointer(from)
+                    .cloned()
+                    .ok_or_else(|| format!("json pointer not found: {}", from))?;
+                add_at(root, path, value)?
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One pointer-level difference found by `diff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub pointer: String,
+    /// None when the node only exists in the second value
+    pub old: Option<serde_json::Value>,
+    /// None when the node only exists in the first value
+    pub new: Option<serde_json::Value>,
+}
+
+impl Change {
+    /// The JSON Patch op performing this change.
+    pub fn to_patch_op(&self) -> PatchOp {
+        match (&self.old, &self.new) {
+            (_, None) => PatchOp::Remove {
+                path: self.pointer.clone(),
+            },
+            (None, Some(v)) => PatchOp::Add {
+                path: self.pointer.clone(),
+                value: v.clone(),
+            },
+            (Some(_), Some(v)) => PatchOp::replace(self.pointer.clone(), v.clone()),
+        }
+    }
+}
+
+/// Changes turning `a` into `b`, both taken to live at `base` (e.g. `/root`).
+/// Objects and arrays are compared member by member; array length changes are
+/// trailing adds / removes, listed so that applying them in order is valid.
+/// Object ids (`$ref`) are skipped, since every write renumbers them.
+pub fn diff(a: &serde_json::Value, b: &serde_json::Value, base: &str) -> Vec<Change> {
+    fn walk(a: &serde_json::Value, b: &serde_json::Value, ptr: &str, out: &mut Vec<Change>) {
+        use serde_json::Value as J;
+        match (a, b) {
+            (J::Object(ma), J::Object(mb)) => {
+                for (k, va) in ma.iter().filter(|(k, _)| *k != "$ref") {
+                    let p = format!("{}/{}", ptr, escape_token(k));
+                    match mb.get(k) {
+                        Some(vb) => walk(va, vb, &p, out),
+                        None => out.push(Change {
+                            pointer: p,
+                            old: Some(va.clone()),
+                            new: None,
+                        }),
+                    }
+                }
+                for (k, vb) in mb
+                    .iter()
+                    .filter(|(k, _)| *k != "$ref" && !ma.contains_key(*k))
+                {
+                    out.push(Change {
+                        pointer: format!("{}/{}", ptr, escape_token(k)),
+                        old: None,
+                        new: Some(vb.clone()),
+                    });
+                }
+            }
+            (J::Array(xa), J::Array(xb)) => {
+                for (i, (va, vb)) in xa.iter().zip(xb).enumerate() {
+                    walk(va, vb, &format!("{}/{}", ptr, i), out);
+                }
+                for (i, vb) in xb.iter().enumerate().skip(xa.len()) {
+                    out.push(Change {
+                        pointer: format!("{}/{}", ptr, i),
+                        old: None,
+                        new: Some(vb.clone()),
+                    });
+                }
+                for (i, va) in xa.iter().enumerate().skip(xb.len()).rev() {
+                    out.push(Change {
+                        pointer: format!("{}/{}", ptr, i),
+                        old: Some(va.clone()),
+                        new: None,
+                    });
+                }
+            }
+            _ if a != b => out.push(Change {
+                pointer: ptr.to_string(),
+                old: Some(a.clone()),
+                new: Some(b.clone()),
+            }),
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    walk(a, b, base, &mut out);
+    out
+}
+
+/// What writing `edited` (a document loaded as `original`) would do that the
+/// user may not expect: arrays cut short by a truncated dump (error), byte
+/// blobs only known by length and written as zeros (warning), and values
+/// whose JSON type changed, e.g. a number edited into a string (warning).
+pub fn validate_for_write(
+    original: &serde_json::Value,
+    edited: &serde_json::Value,
+) -> Vec<crate::validate::Issue> {
+    use crate::validate::{Issue, Severity};
+    use serde_json::Value as J;
+    fn type_name(v: &J) -> &'static str {
+        match v {
+            J::Null => "null",
+            J::Bool(_) => "bool",
+            J::Number(n) if n.is_f64() => "float",
+            J::Number(_) => "integer",
+            J::String(_) => "string",
+            J::Array(_) => "array",
+            J::Object(_) => "object",
+        }
+    }
+    fn walk(v: &J, ptr: String, out: &mut Vec<Issue>) {
+        match v {
+            J::Object(map) if map.get("$truncated") == Some(&J::Bool(true)) => out.push(Issue {
+                severity: Severity::Error,
+                pointer: ptr,
+                message: format!(
+                    "truncated dump: {} items would be lost",
+                    map.get("$omitted").and_then(|n| n.as_u64()).unwrap_or(0)
+                ),
+            }),
+            J::Object(map) if map.get("$type").and_then(|t| t.as_str()) == Some("bytes") => out
+                .push(Issue {
+                    severity: Severity::Warning,
+                    pointer: ptr,
+                    message: format!(
+                        "{} bytes are written as zeros",
+                        map.get("len").and_then(|n| n.as_u64()).unwrap_or(0)
+                    ),
+                }),
+            J::Object(map) => {
+                for (k, c) in map {
+                    walk(c, format!("{}/{}", ptr, escape_token(k)), out);
+                }
+            }
+            J::Array(items) => {
+                for (i, c) in items.iter().enumerate() {
+                    walk(c, format!("{}/{}", ptr, i), out);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    walk(edited, String::new(), &mut out);
+    // Filling in or clearing a null is not a type change worth flagging
+    for c in diff(original, edited, "") {
+        if let (Some(old), Some(new)) = (&c.old, &c.new)
+            && !old.is_null()
+            && !new.is_null()
+            && type_name(old) != type_name(new)
+        {
+            out.push(Issue {
+                severity: Severity::Warning,
+                pointer: c.pointer,
+                message: format!("type changes from {} to {}", type_name(old), type_name(new)),
+            });
+        }
+    }
+    out
+}
+
+/// A JSON array of integers 0..=255 as bytes; the edit JSON shows byte
+/// arrays (textures, photos) that way and the writer stores them so.
+pub fn byte_array(v: &serde_json::Value) -> Option<Vec<u8>> {
+    v.as_array()?
+        .iter()
+        .map(|x| x.as_u64().and_then(|n| u8::try_from(n).ok()))
+        .collect()
+}
+
+// The byte array at `pointer` (behind its `$ref` wrapper, if any): its own
+// pointer, and its bytes unless it is a summary that only knows its length
+fn bytes_node(
+    root: &serde_json::Value,
+    pointer: &str,
+) -> Result<(String, Option<Vec<u8>>), String> {
+    let node = root
+        .pointer(pointer)
+        .ok_or_else(|| format!("json pointer not found: {}", pointer))?;
+    if let Some(inner) = node.get("$value") {
+        let (_, bytes) = bytes_node(inner, "")?;
+        return Ok((format!("{}/$value", pointer), bytes));
+    }
+    if node.get("$type").and_then(|t| t.as_str()) == Some("bytes") {
+        return Ok((pointer.to_string(), None));
+    }
+    match byte_array(node) {
+        Some(bytes) => Ok((pointer.to_string(), Some(bytes))),
+        None => Err(format!("{} is not a byte array", pointer)),
+    }
+}
+
+/// The bytes of the byte array at `pointer`, in either form the dumps use:
+/// an array of integers, or `{"$type": "bytes", "len": N}`. The second only
+/// records a length (a summary dump), so reading it is an error.
+pub fn get_bytes_at(root: &serde_json::Value, pointer: &str) -> Result<Vec<u8>, String> {
+    let (_, bytes) = bytes_node(root, pointer)?;
+    bytes.ok_or_else(|| {
+        format!(
+            "{} holds only the length of its bytes; dump the file in full to read them",
+            pointer
+        )
+    })
+}
+
+/// The patch `set_bytes_at` applies. An empty array is written as a summary
+/// of length 0, as `[]` alone would not be written as bytes.
+pub fn set_bytes_op(
+    root: &serde_json::Value,
+    pointer: &str,
+    bytes: &[u8],
+) -> Result<PatchOp, String> {
+    let (at, _) = bytes_node(root, pointer)?;
+    let value = if bytes.is_empty() {
+        json!({"$type": "bytes", "len": 0})
+    } else {
+        json!(bytes)
+    };
+    Ok(PatchOp::replace(at, value))
+}
+
+/// Replace the byte array at `pointer` (either form, see `get_bytes_at`)
+/// with `bytes`, of any length.
+pub fn set_bytes_at(
+    root: &mut serde_json::Value,
+    pointer: &str,
+    bytes: &[u8],
+) -> Result<(), String> {
+    let op = set_bytes_op(root, pointer, bytes)?;
+    apply_patch(root, &[op])
+}
+
+/// SHA-256 (hex) of a document's logical content: object ids and
+/// `{"$ref", "$value"}` wrappers are dropped and keys sorted, so two saves
+/// that differ only in how they were encoded hash the same. Numbers compare
+/// by value at f32 precision (1 and 1.0 hash alike, as do a Single and the
+/// Double written for it); byte blobs count only by length.
+pub fn content_hash(v: &serde_json::Value) -> String {
+    fn canon(v: &serde_json::Value, out: &mut String) {
+        use serde_json::Value as J;
+        match v {
+            J::Object(m) if m.contains_key("$value") => canon(&m["$value"], out),
+            J::Object(m) => {
+                let mut keys: Vec<&String> = m.keys().filter(|k| *k != "$ref").collect();
+                keys.sort();
+                out.push('{');
+                for (i, k) in keys.into_iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&J::String(k.clone()).to_string());
+                    out.push(':');
+                    canon(&m[k], out);
+                }
+                out.push('}');
+            }
+            J::Array(a) => {
+                out.push('[');
+                for (i, x) in a.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    canon(x, out);
+                }
+                out.push(']');
+            }
+            J::Number(n) => match n.as_f64() {
+                Some(_) if n.is_i64() || n.is_u64() => out.push_str(&n.to_string()),
+                Some(x) if x.fract() == 0.0 && x.abs() < 1e15 => {
+                    out.push_str(&(x as i64).to_string())
+                }
+                // A Single may come back as a Double after a JSON round trip
+                Some(x) => out.push_str(&(x as f32).to_string()),
+                None => out.push_str(&n.to_string()),
+            },
+            other => out.push_str(&other.to_string()),
+        }
+    }
+    let mut text = String::new();
+    canon(v, &mut text);
+    sha256_hex(text.as_bytes())
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write as _;
+    let digest = Sha256::digest(data);
+    let mut out = String::with_capacity(64);
+    for b in digest {
+        write!(&mut out, "{:02x}", b).ok();
+    }
+    out
+}
+
 // Generic JSON-pointer editing utilities over serde_json::Value.
 // Highlights:
 // - RFC 6901 JSON Pointer addressing (`/root/a/b/0`).
 // - Inspect: `get_by_pointer`, `list_children`, `list_object_primitives_at`.
-// - Modify: `set_by_pointer`, `set_raw_by_pointer`, `add_key`, `remove_at_pointer`,
-//   `array_insert`, `array_remove`.
+// - Modify: `set_by_pointer` (`set_typed_by_pointer` to hold numbers to the
+//   catalog limits), `set_raw_by_pointer`, `add_key`, `remove_at_pointer`,
+//   `array_insert`, `array_remove`, and RFC 6902 `PatchOp`s via `apply_patch`
+//   (read from JSON with `parse_patch` or from a text script with `parse_edit_script`).
+// - Compare: `diff` lists pointer-level `Change`s (convertible to `PatchOp`s);
+//   `content_hash` fingerprints a whole document.
+// - `EditSession` wraps one file with apply/undo/redo/save for interactive frontends.
+// - `ParseCache` skips parsing a file again whose bytes did not change.
+// - `PointerBook` keeps pinned pointers per document kind for quick navigation.
 // - `JsonEditValue` covers common scalars; use `set_raw_by_pointer` for full JSON.
 // Intended to be UI-friendly and generic — no domain-specific keys.
+
+// Replace every System.Guid under `pointer` with a fresh random one, so a
+// duplicated subtree (player, world object) does not clash with the original.
+// Nil GUIDs mean "none" and are kept; repeated GUIDs map to the same new value
+// so references inside the subtree stay consistent. Returns (old, new) pairs.
+pub fn regenerate_guids(
+    root: &mut serde_json::Value,
+    pointer: &str,
+) -> Result<Vec<(Guid, Guid)>, String> {
+    type Seen = (std::collections::HashMap<Guid, Guid>, Vec<(Guid, Guid)>);
+    fn walk(v: &mut serde_json::Value, seen: &mut Seen) {
+        let is_guid = v
+            .get("$class")
+            .is_none_or(|c| c.as_str() == Some("System.Guid"));
+        if is_guid && let Some(old) = Guid::from_json(v) {
+            if old.is_nil() {
+                return;
+            }
+            let (map, order) = seen;
+            let new = *map.entry(old).or_insert_with(|| {
+                let n = Guid::new_random();
+                order.push((old, n));
+                n
+            });
+            *v = new.to_json();
+            return;
+        }
+        match v {
+            serde_json::Value::Object(obj) => obj.values_mut().for_each(|c| walk(c, seen)),
+            serde_json::Value::Array(arr) => arr.iter_mut().for_each(|c| walk(c, seen)),
+            _ => {}
+        }
+    }
+    let target = root
+        .pointer_mut(pointer)
+        .ok_or_else(|| format!("json pointer not found: {}", pointer))?;
+    let mut seen = Seen::default();
+    walk(target, &mut seen);
+    Ok(seen.1)
+}
+
+// One open file being edited: the full JSON document plus undo/redo history.
+// Each `apply` is one undo step; failed applies leave the document untouched.
+pub struct EditSession {
+    path: std::path::PathBuf,
+    value: serde_json::Value,
+    undo: Vec<EditStep>,
+    // Undone steps, most recently undone last; any new `apply` clears them
+    redo: Vec<EditStep>,
+    dirty: bool,
+}
+
+/// One `EditSession::apply` call as kept in the history.
+#[derive(Debug, Clone)]
+pub struct EditStep {
+    ops: Vec<PatchOp>,
+    // (pointer, previous value) pairs to put back in reverse order
+    before: Vec<(String, serde_json::Value)>,
+    changes: Vec<Change>,
+}
+
+impl EditStep {
+    /// What the step changed, pointer by pointer
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn ops(&self) -> &[PatchOp] {
+        &self.ops
+    }
+}
+
+impl EditSession {
+    // Load `path` (.sav or .json) without truncation so it can be written back
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let value = parse_file_for_edit(path)?;
+        Ok(Self::from_value(path, value))
+    }
+
+    // Edit a document the caller loaded itself; `path` is where `save` writes
+    pub fn from_value(path: &Path, value: serde_json::Value) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            value,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            dirty: false,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn value(&self) -> &serde_json::Value {
+        &self.value
+    }
+
+    // Unsaved changes since open or the last save
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // Applied steps, oldest first
+    pub fn history(&self) -> &[EditStep] {
+        &self.undo
+    }
+
+    // Undone steps, the next one to redo last
+    pub fn redo_history(&self) -> &[EditStep] {
+        &self.redo
+    }
+
+    pub fn apply(&mut self, ops: &[PatchOp]) -> Result<(), String> {
+        let step = Self::run(&mut self.value, ops.to_vec())?;
+        self.undo.push(step);
+        self.redo.clear();
+        self.dirty = true;
+        Ok(())
+    }
+
+    // Revert the last `apply`; false when there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(step) => {
+                Self::revert(&mut self.value, step.before.clone());
+                self.redo.push(step);
+                self.dirty = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Re-apply the last undone step; false when there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        let Some(step) = self.redo.pop() else {
+            return false;
+        };
+        // The document is back where the step first ran, so it applies again
+        match Self::run(&mut self.value, step.ops) {
+            Ok(step) => {
+                self.undo.push(step);
+                self.dirty = true;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    // The caller wrote `value()` to disk by other means (e.g. its own encoder)
+    pub fn mark_saved(&mut self) {
+        self.dirty = false;
+    }
+
+    fn run(root: &mut serde_json::Value, ops: Vec<PatchOp>) -> Result<EditStep, String> {
+        let mut before = Vec::with_capacity(ops.len());
+        for op in &ops {
+            // Replace only touches its target; add/remove/copy reshape the parent
+            let saved = match op {
+                PatchOp::Replace { path, .. } => path.as_str(),
+                _ => parent_pointer(op.path()).map_or("", |(p, _)| p),
+            };
+            let prev = root.pointer(saved).cloned();
+            let res = apply_patch(root, std::slice::from_ref(op));
+            if let Some(prev) = prev {
+                before.push((saved.to_string(), prev));
+            }
+            if let Err(e) = res {
+                Self::revert(root, before);
+                return Err(e);
+            }
+        }
+        // Diff each touched subtree once, from its state before the step
+        let mut changes = Vec::new();
+        let mut seen: Vec<&str> = Vec::new();
+        for (ptr, prev) in &before {
+            let covered =
+                |s: &&str| s.is_empty() || *s == ptr || ptr.starts_with(&format!("{}/", s));
+            if seen.iter().any(covered) {
+                continue;
+            }
+            seen.push(ptr);
+            match root.pointer(ptr) {
+                Some(now) => changes.extend(diff(prev, now, ptr)),
+                None => changes.push(Change {
+                    pointer: ptr.clone(),
+                    old: Some(prev.clone()),
+                    new: None,
+                }),
+            }
+        }
+        Ok(EditStep {
+            ops,
+            before,
+            changes,
+        })
+    }
+
+    fn revert(root: &mut serde_json::Value, step: Vec<(String, serde_json::Value)>) {
+        for (ptr, before) in step.into_iter().rev() {
+            if let Some(slot) = root.pointer_mut(&ptr) {
+                *slot = before;
+            }
+        }
+    }
+
+    // Write back to the source path: .json stays JSON, anything else is
+    // re-encoded as BinaryFormatter. Undo history is kept.
+    pub fn save(&mut self) -> Result<(), String> {
+        crate::binfmt_write::check_read_only()?;
+        let is_json = self
+            .path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+        if is_json {
+            write_json_to_file(&self.path, &self.value)?;
+        } else {
+            crate::binfmt_write::write_binfmt_file_from_json(&self.path, &self.value)
+                .map_err(|e| e.to_string())?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Pointers pinned by the user for quick access, grouped by a document kind
+/// chosen by the caller (e.g. "player", "world"). Stored as JSON:
+/// `{"player": [{"pointer": "/root/money", "label": "money"}]}`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PointerBook {
+    kinds: std::collections::BTreeMap<String, Vec<Bookmark>>,
+}
+
+/// One pinned pointer and the name shown for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub pointer: String,
+    pub label: String,
+}
+
+impl PointerBook {
+    /// Pins of `kind` in the order they were added
+    pub fn get(&self, kind: &str) -> &[Bookmark] {
+        self.kinds.get(kind).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn contains(&self, kind: &str, pointer: &str) -> bool {
+        self.get(kind).iter().any(|b| b.pointer == pointer)
+    }
+
+    /// Pin `pointer`, labelled with its field name when `label` is empty.
+    /// Returns false if it was already pinned.
+    pub fn add(&mut self, kind: &str, pointer: &str, label: &str) -> bool {
+        if self.contains(kind, pointer) {
+            return false;
+        }
+        self.kinds
+            .entry(kind.to_string())
+            .or_default()
+            .push(Bookmark {
+                pointer: pointer.to_string(),
+                label: Self::label_or_default(pointer, label),
+            });
+        true
+    }
+
+    // The name of the field, not the "$value" of its wrapper
+    fn label_or_default(pointer: &str, label: &str) -> String {
+        if !label.trim().is_empty() {
+            return label.trim().to_string();
+        }
+        let mut p = pointer;
+        loop {
+            match parent_pointer(p) {
+                Some((parent, "$value")) => p = parent,
+                Some((_, last)) => return unescape_token(last),
+                None => return pointer.to_string(),
+            }
+        }
+    }
+
+    pub fn remove(&mut self, kind: &str, pointer: &str) -> bool {
+        let Some(list) = self.kinds.get_mut(kind) else {
+            return false;
+        };
+        let before = list.len();
+        list.retain(|b| b.pointer != pointer);
+        let removed = list.len() != before;
+        if list.is_empty() {
+            self.kinds.remove(kind);
+        }
+        removed
+    }
+
+    /// Relabel a pin; an empty label goes back to the field name
+    pub fn rename(&mut self, kind: &str, pointer: &str, label: &str) -> bool {
+        match self
+            .kinds
+            .get_mut(kind)
+            .and_then(|l| l.iter_mut().find(|b| b.pointer == pointer))
+        {
+            Some(b) => {
+                b.label = Self::label_or_default(pointer, label);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut map = serde_json::Map::new();
+        for (kind, list) in &self.kinds {
+            let items = list
+                .iter()
+                .map(|b| json!({"pointer": b.pointer, "label": b.label}))
+                .collect();
+            map.insert(kind.clone(), serde_json::Value::Array(items));
+        }
+        serde_json::Value::Object(map)
+    }
+
+    // Entries without a string pointer are dropped rather than failing the book
+    pub fn from_json(v: &serde_json::Value) -> Self {
+        let mut book = Self::default();
+        for (kind, list) in v.as_object().into_iter().flatten() {
+            for item in list.as_array().into_iter().flatten() {
+                if let Some(ptr) = item.get("pointer").and_then(|p| p.as_str()) {
+                    let label = item.get("label").and_then(|l| l.as_str()).unwrap_or("");
+                    book.add(kind, ptr, label);
+                }
+            }
+        }
+        book
+    }
+
+    /// Read a book saved with `save`; a missing or unreadable file gives an
+    /// empty book
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|t| serde_json::from_str(&t).ok())
+            .map(|v| Self::from_json(&v))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(&self.to_json()).map_err(std::io::Error::other)?;
+        fs::write(path, text)
+    }
+}
@@ -9,17 +9,22 @@ use zip::write::FileOptions;
 
 // Zip backup of a slot directory (non-destructive)
 pub fn zip_backup_slot(dir: &Path) -> io::Result<PathBuf> {
+    zip_backup_slot_to(dir, dir.parent().unwrap_or(Path::new(".")))
+}
+
+// Same as zip_backup_slot, with the zip written into `parent` (created if missing)
+pub fn zip_backup_slot_to(dir: &Path, parent: &Path) -> io::Result<PathBuf> {
+    crate::binfmt_write::check_read_only_io()?;
     if !dir.is_dir() {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
             "not a directory",
         ));
     }
-    let parent = dir.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+    crate::saves::check_writable(parent)?;
     let name = dir.file_name().and_then(|s| s.to_str()).unwrap_or("slot");
-    let ts = chrono::Local::now().format("%Y%m%d-%H%M%S");
-    let zip_name = format!("{}_{}.zip", name, ts);
-    let dest = parent.join(zip_name);
+    let dest = free_backup_path(parent, name, chrono::Local::now().naive_local());
 
     let file = fs::File::create(&dest)?;
     let mut zip = zip::ZipWriter::new(file);
@@ -27,26 +32,627 @@ pub fn zip_backup_slot(dir: &Path) -> io::Result<PathBuf> {
         .compression_method(CompressionMethod::Deflated)
         .unix_permissions(0o644);
     let base = dir;
-    for entry in WalkDir::new(base) {
+    let mut manifest_files = Vec::new();
+    for entry in WalkDir::new(base).sort_by_file_name() {
         let entry = entry.map_err(|e| io::Error::other(e.to_string()))?;
         let path = entry.path();
         let rel = path.strip_prefix(base).unwrap();
-        if rel.as_os_str().is_empty() {
+        if rel.as_os_str().is_empty() || is_file_backup(path) {
             continue;
         }
         let name = rel.to_string_lossy().replace('\\', "/");
         if path.is_dir() {
             zip.add_directory(name, options)?;
         } else {
-            zip.start_file(name, options)?;
+            zip.start_file(name.as_str(), options)?;
             let data = fs::read(path)?;
             zip.write_all(&data)?;
+            let mtime = fs::metadata(path)?
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs());
+            manifest_files.push(serde_json::json!({
+                "name": name,
+                "size": data.len(),
+                "sha256": sha256_hex(&data),
+                "mtime": mtime,
+            }));
         }
     }
+    let manifest = serde_json::json!({
+        "version": 1,
+        "created": chrono::Local::now().to_rfc3339(),
+        "files": manifest_files,
+    });
+    zip.start_file(BACKUP_MANIFEST, options)?;
+    zip.write_all(
+        serde_json::to_string_pretty(&manifest)
+            .map_err(io::Error::other)?
+            .as_bytes(),
+    )?;
     zip.finish()?;
     Ok(dest)
 }
 
+// Integrity manifest written into every backup zip (not restored into slots)
+pub const BACKUP_MANIFEST: &str = "manifest.json";
+
+pub use crate::edit::sha256_hex;
+
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    /// Files whose size and checksum match the manifest
+    pub verified: Vec<String>,
+    /// Human-readable problems; empty means the backup is intact
+    pub problems: Vec<String>,
+    /// Backups made before manifests existed can only be CRC-checked
+    pub has_manifest: bool,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+// Check every entry of a backup zip against its manifest (sizes and SHA-256).
+// Without a manifest the zip entries are still read fully, which validates CRCs.
+pub fn verify_backup(zip_path: &Path) -> io::Result<VerifyReport> {
+    use std::collections::BTreeMap;
+    use std::io::Read as _;
+    let mut archive = zip::ZipArchive::new(fs::File::open(zip_path)?).map_err(io::Error::other)?;
+    let mut report = VerifyReport::default();
+    let mut expected: BTreeMap<String, (u64, String)> = BTreeMap::new();
+    if let Ok(mut entry) = archive.by_name(BACKUP_MANIFEST) {
+        let mut text = String::new();
+        entry.read_to_string(&mut text)?;
+        let manifest: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        report.has_manifest = true;
+        for f in manifest["files"].as_array().into_iter().flatten() {
+            if let (Some(name), Some(size), Some(sha)) =
+                (f["name"].as_str(), f["size"].as_u64(), f["sha256"].as_str())
+            {
+                expected.insert(name.to_string(), (size, sha.to_string()));
+            }
+        }
+    }
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(io::Error::other)?;
+        let name = entry.name().to_string();
+        if entry.is_dir() || name == BACKUP_MANIFEST {
+            continue;
+        }
+        let mut data = Vec::new();
+        if let Err(e) = entry.read_to_end(&mut data) {
+            report
+                .problems
+                .push(format!("{}: unreadable ({})", name, e));
+            expected.remove(&name);
+            continue;
+        }
+        if !report.has_manifest {
+            report.verified.push(name);
+            continue;
+        }
+        match expected.remove(&name) {
+            None => report.problems.push(format!("{}: not in manifest", name)),
+            Some((size, _)) if size != data.len() as u64 => report.problems.push(format!(
+                "{}: size {} != manifest {}",
+                name,
+                data.len(),
+                size
+            )),
+            Some((_, sha)) if sha != sha256_hex(&data) => {
+                report.problems.push(format!("{}: checksum mismatch", name))
+            }
+            Some(_) => report.verified.push(name),
+        }
+    }
+    for name in expected.keys() {
+        report.problems.push(format!("{}: missing from zip", name));
+    }
+    Ok(report)
+}
+
+// Snapshot a single file next to itself as `<name>.<timestamp>.bak`, or as a
+// one-entry `<name>.<timestamp>.zip` when `zipped` is set.
+pub fn backup_file(path: &Path, zipped: bool) -> io::Result<PathBuf> {
+    crate::binfmt_write::check_read_only_io()?;
+    if !path.is_file() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "not a file"));
+    }
+    let parent = path.parent().unwrap_or(Path::new("."));
+    let name = path.file_name().and_then(|s| s.to_str()).unwrap_or("file");
+    let ts = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let ext = if zipped { "zip" } else { "bak" };
+    let mut dest = parent.join(format!("{}.{}.{}", name, ts, ext));
+    let mut n = 1;
+    while dest.exists() {
+        dest = parent.join(format!("{}.{}_{}.{}", name, ts, n, ext));
+        n += 1;
+    }
+    if zipped {
+        let data = fs::read(path)?;
+        let mut zip = zip::ZipWriter::new(fs::File::create(&dest)?);
+        let options = FileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .unix_permissions(0o644);
+        zip.start_file(name, options)?;
+        zip.write_all(&data)?;
+        zip.finish()?;
+    } else {
+        fs::copy(path, &dest)?;
+    }
+    Ok(dest)
+}
+
+// A `backup_file` snapshot (`<name>.<timestamp>.bak` or `.zip`). These sit in
+// the slot folder, so slot zips, clones and restores leave them out rather
+// than nesting every earlier snapshot inside each new backup.
+pub fn is_file_backup(path: &Path) -> bool {
+    let name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+    let Some(stem) = name
+        .strip_suffix(".bak")
+        .or_else(|| name.strip_suffix(".zip"))
+    else {
+        return false;
+    };
+    let Some((base, ts)) = stem.rsplit_once('.') else {
+        return false;
+    };
+    let ts = ts.split('_').next().unwrap_or("");
+    !base.is_empty() && chrono::NaiveDateTime::parse_from_str(ts, "%Y%m%d-%H%M%S").is_ok()
+}
+
+// Single-file backups of `path` made by `backup_file`, oldest first
+pub fn find_file_backups(path: &Path) -> Vec<PathBuf> {
+    let parent = path.parent().unwrap_or(Path::new("."));
+    let name = path.file_name().and_then(|s| s.to_str()).unwrap_or("file");
+    let prefix = format!("{}.", name);
+    let mut out = Vec::new();
+    if let Ok(rd) = fs::read_dir(parent) {
+        for entry in rd.flatten() {
+            let p = entry.path();
+            let file_name = p.file_name().and_then(|s| s.to_str()).unwrap_or("");
+            if p.is_file()
+                && file_name.starts_with(&prefix)
+                && (file_name.ends_with(".bak") || file_name.ends_with(".zip"))
+            {
+                out.push(p);
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+// Contents of `name` as saved in a backup: the entry of that name inside a
+// slot backup zip (or a one-file `backup_file` zip), or a whole `.bak` file.
+pub fn read_backup_file(backup: &Path, name: &str) -> io::Result<Vec<u8>> {
+    if backup.extension().and_then(|s| s.to_str()) != Some("zip") {
+        // A `.bak` holds exactly one file; make sure it is this one
+        let file = backup.file_name().and_then(|s| s.to_str()).unwrap_or("");
+        if !file.starts_with(&format!("{}.", name)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a backup of {}", backup.display(), name),
+            ));
+        }
+        return fs::read(backup);
+    }
+    use std::io::Read as _;
+    let mut archive = zip::ZipArchive::new(fs::File::open(backup)?).map_err(io::Error::other)?;
+    let mut entry = archive.by_name(name).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} not in {}", name, backup.display()),
+        )
+    })?;
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+// Put a `backup_file` snapshot (or a file inside a slot backup zip with the
+// same name) back at `dest`. The current `dest` is snapshotted first; that
+// snapshot path is returned.
+pub fn restore_file(backup: &Path, dest: &Path) -> io::Result<Option<PathBuf>> {
+    crate::binfmt_write::check_read_only_io()?;
+    let name = dest.file_name().and_then(|s| s.to_str()).unwrap_or("");
+    let data = read_backup_file(backup, name)?;
+    let pre = if dest.is_file() {
+        Some(backup_file(dest, false)?)
+    } else {
+        None
+    };
+    crate::binfmt_write::write_file_atomic(dest, &data).map_err(io::Error::other)?;
+    Ok(pre)
+}
+
+// `<name>_<ts>.zip` in `parent`, numbered so it never overwrites an existing
+// backup taken within the same second
+fn free_backup_path(parent: &Path, name: &str, ts: chrono::NaiveDateTime) -> PathBuf {
+    let ts = ts.format("%Y%m%d-%H%M%S");
+    let mut dest = parent.join(format!("{}_{}.zip", name, ts));
+    let mut n = 1;
+    while dest.exists() {
+        dest = parent.join(format!("{}_{}_{}.zip", name, ts, n));
+        n += 1;
+    }
+    dest
+}
+
+// Move the backups of `slot_dir` kept in `parent` over to the name of
+// `new_slot_dir`, keeping their timestamps. Returns the new paths.
+pub fn rename_backups_in(
+    slot_dir: &Path,
+    new_slot_dir: &Path,
+    parent: &Path,
+) -> io::Result<Vec<PathBuf>> {
+    crate::binfmt_write::check_read_only_io()?;
+    let new_name = new_slot_dir
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("slot");
+    let mut out = Vec::new();
+    for zip in find_backup_zips_in(slot_dir, parent) {
+        let file_name = zip.file_name().and_then(|s| s.to_str()).unwrap_or("");
+        let Some((_, ts)) = parse_backup_name(file_name) else {
+            continue;
+        };
+        let dest = free_backup_path(parent, new_name, ts);
+        fs::rename(&zip, &dest)?;
+        out.push(dest);
+    }
+    Ok(out)
+}
+
+// Backup zips for a slot live next to it as `<SlotName>_<timestamp>.zip`
+pub fn find_backup_zips(slot_dir: &Path) -> Vec<PathBuf> {
+    find_backup_zips_in(slot_dir, slot_dir.parent().unwrap_or(Path::new(".")))
+}
+
+// Backup zips for a slot kept in another folder (see zip_backup_slot_to)
+pub fn find_backup_zips_in(slot_dir: &Path, parent: &Path) -> Vec<PathBuf> {
+    let name = slot_dir
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("slot");
+    let prefix = format!("{}_", name);
+    let mut out = Vec::new();
+    if let Ok(rd) = fs::read_dir(parent) {
+        for entry in rd.flatten() {
+            let p = entry.path();
+            let file_name = p.file_name().and_then(|s| s.to_str()).unwrap_or("");
+            if p.is_file() && file_name.starts_with(&prefix) && file_name.ends_with(".zip") {
+                out.push(p);
+            }
+        }
+    }
+    // Timestamps sort lexically; newest last
+    out.sort();
+    out
+}
+
+#[derive(Debug, Clone)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    /// Backup time taken from the file name
+    pub timestamp: Option<chrono::NaiveDateTime>,
+    /// Zip size on disk
+    pub size: u64,
+    /// File entries inside the zip
+    pub files: Vec<String>,
+    /// In-game `dateTime` of the SlotInfo.sav captured in the backup
+    pub slot_date_time: Option<String>,
+}
+
+// Backups of a slot with metadata, oldest first
+pub fn list_backups(slot_dir: &Path) -> io::Result<Vec<BackupInfo>> {
+    list_backups_in(slot_dir, slot_dir.parent().unwrap_or(Path::new(".")))
+}
+
+pub fn list_backups_in(slot_dir: &Path, backup_dir: &Path) -> io::Result<Vec<BackupInfo>> {
+    let mut out = Vec::new();
+    for path in find_backup_zips_in(slot_dir, backup_dir) {
+        out.push(read_backup_info(&path)?);
+    }
+    Ok(out)
+}
+
+pub fn read_backup_info(zip_path: &Path) -> io::Result<BackupInfo> {
+    use std::io::Read as _;
+    let size = fs::metadata(zip_path)?.len();
+    let timestamp = zip_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .and_then(parse_backup_name)
+        .map(|(_, ts)| ts);
+    let mut archive = zip::ZipArchive::new(fs::File::open(zip_path)?).map_err(io::Error::other)?;
+    let mut files: Vec<String> = archive
+        .file_names()
+        .filter(|n| !n.ends_with('/') && *n != BACKUP_MANIFEST)
+        .map(|n| n.to_string())
+        .collect();
+    files.sort();
+    let slot_date_time = match archive.by_name("SlotInfo.sav") {
+        Ok(mut entry) => {
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            let mut parser = crate::binfmt::Parser::new(&data);
+            parser
+                .parse_stream()
+                .ok()
+                .and_then(|doc| doc.as_save_slot_info().map(|i| i.date_time.to_string()))
+        }
+        Err(_) => None,
+    };
+    Ok(BackupInfo {
+        path: zip_path.to_path_buf(),
+        timestamp,
+        size,
+        files,
+        slot_date_time,
+    })
+}
+
+// Thumbnail of the SlotInfo.sav captured in a backup zip; None when the zip
+// has no SlotInfo.sav or its image does not decode
+pub fn read_backup_thumbnail(zip_path: &Path) -> io::Result<Option<crate::model::SlotImage>> {
+    let data = match read_backup_file(zip_path, "SlotInfo.sav") {
+        Ok(data) => data,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut parser = crate::binfmt::Parser::new(&data);
+    Ok(parser
+        .parse_stream()
+        .ok()
+        .and_then(|doc| doc.as_save_slot_info().and_then(|i| i.decode_image())))
+}
+
+// Split `<SlotName>_<YYYYmmdd-HHMMSS>[_n].zip` into slot name and timestamp
+pub fn parse_backup_name(file_name: &str) -> Option<(&str, chrono::NaiveDateTime)> {
+    let stem = file_name.strip_suffix(".zip")?;
+    for (pos, _) in stem.match_indices('_').rev() {
+        let token = stem[pos + 1..].split('_').next().unwrap_or("");
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(token, "%Y%m%d-%H%M%S") {
+            return Some((&stem[..pos], dt));
+        }
+    }
+    None
+}
+
+// The slot a backup zip was taken from: `<SlotName>` next to the zip
+pub fn backup_slot_dir(zip_path: &Path) -> Option<PathBuf> {
+    let file_name = zip_path.file_name()?.to_str()?;
+    let (slot, _) = parse_backup_name(file_name)?;
+    Some(zip_path.parent().unwrap_or(Path::new(".")).join(slot))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Always keep at most this many newest backups per slot
+    pub keep_last: Option<usize>,
+    /// Drop backups older than this many days
+    pub keep_days: Option<u32>,
+    /// Drop oldest backups until a slot's backups fit in this many bytes
+    pub max_total_bytes: Option<u64>,
+}
+
+impl RetentionPolicy {
+    pub fn is_unbounded(&self) -> bool {
+        self.keep_last.is_none() && self.keep_days.is_none() && self.max_total_bytes.is_none()
+    }
+}
+
+// Apply a retention policy to every slot's backups under `slot_parent`
+// (the GameSaves folder). The newest backup of each slot is never removed.
+// Returns the deleted zip paths.
+pub fn prune_backups(slot_parent: &Path, policy: RetentionPolicy) -> io::Result<Vec<PathBuf>> {
+    prune_matching(slot_parent, policy, None)
+}
+
+// Same for the backups of `slot_dir` alone, kept in `parent`; other slots'
+// zips in the folder are left alone
+pub fn prune_slot_backups(
+    slot_dir: &Path,
+    parent: &Path,
+    policy: RetentionPolicy,
+) -> io::Result<Vec<PathBuf>> {
+    let name = slot_dir
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("slot");
+    prune_matching(parent, policy, Some(name))
+}
+
+fn prune_matching(
+    slot_parent: &Path,
+    policy: RetentionPolicy,
+    only: Option<&str>,
+) -> io::Result<Vec<PathBuf>> {
+    crate::binfmt_write::check_read_only_io()?;
+    use std::collections::BTreeMap;
+    let mut groups: BTreeMap<String, Vec<(chrono::NaiveDateTime, PathBuf, u64)>> = BTreeMap::new();
+    for entry in fs::read_dir(slot_parent)?.flatten() {
+        let p = entry.path();
+        let Some(file_name) = p.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if !p.is_file() {
+            continue;
+        }
+        if let Some((slot, ts)) = parse_backup_name(file_name)
+            && only.is_none_or(|o| o == slot)
+        {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            groups
+                .entry(slot.to_string())
+                .or_default()
+                .push((ts, p.clone(), size));
+        }
+    }
+    let now = chrono::Local::now().naive_local();
+    let mut removed = Vec::new();
+    for (_, mut backups) in groups {
+        // Newest first
+        backups.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+        let mut keep = vec![true; backups.len()];
+        for (i, (ts, _, _)) in backups.iter().enumerate().skip(1) {
+            if policy.keep_last.is_some_and(|n| i >= n.max(1)) {
+                keep[i] = false;
+            }
+            if policy
+                .keep_days
+                .is_some_and(|d| now - *ts > chrono::Duration::days(d as i64))
+            {
+                keep[i] = false;
+            }
+        }
+        if let Some(max) = policy.max_total_bytes {
+            let mut total: u64 = backups
+                .iter()
+                .zip(&keep)
+                .filter(|(_, k)| **k)
+                .map(|(b, _)| b.2)
+                .sum();
+            for i in (1..backups.len()).rev() {
+                if total <= max {
+                    break;
+                }
+                if keep[i] {
+                    keep[i] = false;
+                    total -= backups[i].2;
+                }
+            }
+        }
+        for ((_, path, _), k) in backups.into_iter().zip(keep) {
+            if !k {
+                fs::remove_file(&path)?;
+                removed.push(path);
+            }
+        }
+    }
+    removed.sort();
+    Ok(removed)
+}
+
+// Zip backup followed by pruning per `policy` (skipped when unbounded).
+// Returns the new zip and any backups that were pruned.
+pub fn zip_backup_slot_pruned(
+    dir: &Path,
+    policy: RetentionPolicy,
+) -> io::Result<(PathBuf, Vec<PathBuf>)> {
+    zip_backup_slot_pruned_to(dir, dir.parent().unwrap_or(Path::new(".")), policy)
+}
+
+// Same with the zip written into `parent`, pruning only this slot's backups there
+pub fn zip_backup_slot_pruned_to(
+    dir: &Path,
+    parent: &Path,
+    policy: RetentionPolicy,
+) -> io::Result<(PathBuf, Vec<PathBuf>)> {
+    let zip = zip_backup_slot_to(dir, parent)?;
+    if policy.is_unbounded() {
+        return Ok((zip, Vec::new()));
+    }
+    let removed = prune_slot_backups(dir, parent, policy)?;
+    Ok((zip, removed))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RestoreOpts {
+    /// Only report what would be restored; touch nothing on disk
+    pub dry_run: bool,
+    /// Restrict the restore to these zip entry names (None = everything)
+    pub files: Option<Vec<String>>,
+    /// Folder for the pre-restore backup (None = next to the slot)
+    pub backup_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RestoreReport {
+    /// Zip entry names written (or that would be written in dry-run)
+    pub restored: Vec<String>,
+    /// Backup of the slot taken right before it was overwritten
+    pub pre_restore_backup: Option<PathBuf>,
+}
+
+// Unpack a backup zip over a slot directory. The current slot contents are
+// zipped first so a restore can itself be undone.
+pub fn restore_backup(
+    zip_path: &Path,
+    slot_dir: &Path,
+    opts: RestoreOpts,
+) -> io::Result<RestoreReport> {
+    let file = fs::File::open(zip_path)?;
+    let mut archive = zip::ZipArchive::new(file).map_err(io::Error::other)?;
+    let mut entries: Vec<(usize, PathBuf, String)> = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(io::Error::other)?;
+        if entry.is_dir() || entry.name() == BACKUP_MANIFEST {
+            continue;
+        }
+        // Zips taken before snapshots were left out may still hold some
+        if is_file_backup(Path::new(entry.name())) {
+            continue;
+        }
+        // Reject absolute paths and `..` components
+        let Some(rel) = entry.enclosed_name().map(|p| p.to_path_buf()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsafe path in backup: {}", entry.name()),
+            ));
+        };
+        let name = entry.name().to_string();
+        if let Some(wanted) = &opts.files
+            && !wanted.contains(&name)
+        {
+            continue;
+        }
+        entries.push((i, rel, name));
+    }
+    if let Some(wanted) = &opts.files
+        && let Some(missing) = wanted
+            .iter()
+            .find(|w| !entries.iter().any(|(_, _, n)| n == *w))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("not in backup: {}", missing),
+        ));
+    }
+
+    let mut report = RestoreReport {
+        restored: entries.iter().map(|(_, _, n)| n.clone()).collect(),
+        pre_restore_backup: None,
+    };
+    if opts.dry_run {
+        return Ok(report);
+    }
+    crate::binfmt_write::check_read_only_io()?;
+    if slot_dir.is_dir() {
+        report.pre_restore_backup = Some(match &opts.backup_dir {
+            Some(parent) => zip_backup_slot_to(slot_dir, parent)?,
+            None => zip_backup_slot(slot_dir)?,
+        });
+    }
+    fs::create_dir_all(slot_dir)?;
+    for (i, rel, _) in &entries {
+        let mut entry = archive.by_index(*i).map_err(io::Error::other)?;
+        let dest = slot_dir.join(rel);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = fs::File::create(&dest)?;
+        io::copy(&mut entry, &mut out)?;
+    }
+    Ok(report)
+}
+
 // TODO: Generic BinaryFormatter write-back (edit → binary) is out of scope for now.
 //       Only JSON-value edits and JSON file writes are supported in core.
 
@@ -91,7 +697,7 @@ pub fn parse_guid_hyphen(s: &str) -> Result<Guid, String> {
     Ok(Guid { a, b, c, d_to_k })
 }
 
-// Test helper: build a minimal SlotInfo.sav payload (BinaryFormatter)
+// Build a SlotInfo.sav payload (BinaryFormatter) in the layout the game writes
 pub fn build_slot_info_bytes(
     last_selected_player_slot: i32,
     date_time: &str,
@@ -140,7 +746,10 @@ pub fn build_slot_info_bytes(
     w.push(6);
     write_i32(&mut w, 3); // string object id
     write_lp_string(&mut w, date_time);
-    // smallImageData as ArraySinglePrimitive (15)
+    // smallImageData as MemberReference (9) to the array record that follows
+    w.push(9);
+    write_i32(&mut w, 4);
+    // ArraySinglePrimitive (15)
     w.push(15);
     write_i32(&mut w, 4); // array object id
     write_i32(&mut w, small_image_data.len() as i32); // length
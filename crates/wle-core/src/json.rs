@@ -2,7 +2,7 @@ use std::fmt::Write as _;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::binfmt::{Document, Parser, Value};
+use crate::binfmt::{CancellationToken, Document, Parser, Value};
 
 #[derive(Clone, Copy)]
 pub struct JsonOpts {
@@ -21,6 +21,29 @@ impl Default for JsonOpts {
     }
 }
 
+impl JsonOpts {
+    // Deeper than any save class nests; still bounds `$ref` cycles
+    const FULL_DEPTH: usize = 64;
+
+    // No array truncation, raw bytes and enough depth for every save class;
+    // use this when the JSON is going to be written back to a .sav.
+    pub fn full() -> Self {
+        Self {
+            max_array_elems: usize::MAX,
+            max_depth: Self::FULL_DEPTH,
+            bytes_summary: false,
+        }
+    }
+
+    // Whether JSON made with these options encodes back to the same .sav:
+    // nothing cut short, nothing nulled out, no byte data summarised
+    pub fn is_full(&self) -> bool {
+        self.max_array_elems == usize::MAX
+            && self.max_depth >= Self::FULL_DEPTH
+            && !self.bytes_summary
+    }
+}
+
 pub fn parse_binary(path: &Path) -> Result<Document<'static>, String> {
     let data = fs::read(path).map_err(|e| e.to_string())?;
     let leaked: &'static [u8] = Box::leak(data.into_boxed_slice());
@@ -30,23 +53,81 @@ pub fn parse_binary(path: &Path) -> Result<Document<'static>, String> {
 
 pub fn dump_file_json(path: &Path, opts: JsonOpts) -> Result<String, String> {
     let data = fs::read(path).map_err(|e| e.to_string())?;
+    dump_bytes_json(data, opts)
+}
+
+// Dump a .sav (or pass through a JSON document) already read into memory
+pub fn dump_bytes_json(data: Vec<u8>, opts: JsonOpts) -> Result<String, String> {
+    dump_bytes(data, opts, None, None)
+}
+
+/// `dump_bytes_json` that gives up with a "cancelled" error once `cancel` is
+/// cancelled, during the parse or the dump.
+pub fn dump_bytes_json_with_cancel(
+    data: Vec<u8>,
+    opts: JsonOpts,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    dump_bytes(data, opts, Some(cancel), None)
+}
+
+/// `dump_bytes_json` reporting the parse to (and honouring cancellation
+/// from) `progress`.
+pub fn dump_bytes_json_with_progress(
+    data: Vec<u8>,
+    opts: JsonOpts,
+    progress: std::sync::Arc<crate::binfmt::ParseProgress>,
+) -> Result<String, String> {
+    let cancel = progress.token();
+    dump_bytes(data, opts, Some(&cancel), Some(progress))
+}
+
+fn dump_bytes(
+    data: Vec<u8>,
+    opts: JsonOpts,
+    cancel: Option<&CancellationToken>,
+    progress: Option<std::sync::Arc<crate::binfmt::ParseProgress>>,
+) -> Result<String, String> {
     match data.iter().copied().find(|b| !b.is_ascii_whitespace()) {
         Some(b'{') => std::str::from_utf8(&data)
             .map(|s| s.to_string())
             .map_err(|_| "non-utf8 text".to_string()),
         Some(_) => {
-            let leaked: &'static [u8] = Box::leak(data.into_boxed_slice());
-            let mut parser = Parser::new(leaked);
-            match parser.parse_stream() {
-                Ok(doc) => Ok(dump_dynamic_json(&doc, opts)),
-                Err(e) => Err(e),
+            let mut parser = Parser::new(&data);
+            if let Some(p) = progress {
+                parser = parser.with_progress(p);
             }
+            if let Some(c) = cancel {
+                parser = parser.with_cancel(c.clone());
+            }
+            let doc = parser.parse_stream()?;
+            dump_document(&doc, opts, cancel)
         }
         None => Err("empty file".to_string()),
     }
 }
 
+// The node at `pointer` in the dump of a .sav, pretty-printed, without
+// converting the rest of the document (see `edit::document_value_at`)
+pub fn dump_pointer_json(path: &Path, pointer: &str, opts: JsonOpts) -> Result<String, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let [value] = crate::edit::parse_bytes_at_pointers(data, &[pointer.to_string()], opts)?
+        .try_into()
+        .expect("one value per pointer");
+    let value = value.ok_or_else(|| format!("json pointer not found: {}", pointer))?;
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
 pub fn dump_dynamic_json(doc: &Document<'_>, opts: JsonOpts) -> String {
+    dump_document(doc, opts, None).unwrap_or_default()
+}
+
+#[tracing::instrument(level = "debug", name = "dump", skip_all)]
+fn dump_document(
+    doc: &Document<'_>,
+    opts: JsonOpts,
+    cancel: Option<&CancellationToken>,
+) -> Result<String, String> {
     let mut out = String::new();
     write!(
         &mut out,
@@ -55,12 +136,16 @@ pub fn dump_dynamic_json(doc: &Document<'_>, opts: JsonOpts) -> String {
     )
     .ok();
     if let Some(v) = doc.root_value() {
-        write_value_json(doc, v, 1, &mut out, &opts).ok();
+        // Only a cancel stops the writes into a String
+        if write_value_json(doc, v, 1, &mut out, &opts, cancel).is_err() {
+            return Err("cancelled".into());
+        }
     } else {
         out.push_str("null");
     }
     out.push_str("\n}\n");
-    out
+    tracing::debug!(bytes = out.len(), "dumped");
+    Ok(out)
 }
 
 fn write_value_json(
@@ -69,7 +154,11 @@ fn write_value_json(
     depth: usize,
     out: &mut String,
     opts: &JsonOpts,
+    cancel: Option<&CancellationToken>,
 ) -> std::fmt::Result {
+    if cancel.is_some_and(CancellationToken::is_cancelled) {
+        return Err(std::fmt::Error);
+    }
     match v {
         Value::Null => out.push_str("null"),
         Value::Bool(b) => write!(out, "{}", if *b { "true" } else { "false" })?,
@@ -105,7 +194,7 @@ fn write_value_json(
                 if depth >= opts.max_depth {
                     out.push_str("null");
                 } else {
-                    write_value_json(wctx, it, depth + 1, out, opts)?;
+                    write_value_json(wctx, it, depth + 1, out, opts, cancel)?;
                 }
             }
             if items.len() > max {
@@ -126,7 +215,7 @@ fn write_value_json(
                 if depth >= opts.max_depth {
                     out.push_str("null");
                 } else {
-                    write_value_json(wctx, val, depth + 1, out, opts)?;
+                    write_value_json(wctx, val, depth + 1, out, opts, cancel)?;
                 }
             }
             out.push('}');
@@ -138,7 +227,7 @@ fn write_value_json(
                 && let Some(v2) = wctx.get_object(*id)
             {
                 out.push_str(",\"$value\":");
-                write_value_json(wctx, v2, depth + 1, out, opts)?;
+                write_value_json(wctx, v2, depth + 1, out, opts, cancel)?;
             }
             out.push('}');
         }
@@ -200,3 +289,108 @@ pub fn dump_dir_map_json(dir: &Path, opts: JsonOpts) -> Result<String, String> {
     out.push_str("\n}\n");
     Ok(out)
 }
+
+// Dump every .sav in `dir` to `out_dir/<stem>.json` (creating `out_dir`).
+// Returns each source with the written path or its error, so one unreadable
+// file does not stop the rest.
+pub fn dump_dir_to_files(
+    dir: &Path,
+    out_dir: &Path,
+    opts: JsonOpts,
+) -> std::io::Result<Vec<(PathBuf, Result<PathBuf, String>)>> {
+    dump_files_to_dir(&find_sav_files(dir), out_dir, opts)
+}
+
+// Same as `dump_dir_to_files` for a chosen set of files.
+pub fn dump_files_to_dir(
+    files: &[PathBuf],
+    out_dir: &Path,
+    opts: JsonOpts,
+) -> std::io::Result<Vec<(PathBuf, Result<PathBuf, String>)>> {
+    fs::create_dir_all(out_dir)?;
+    let mut out = Vec::new();
+    for f in files {
+        let dest = out_dir
+            .join(f.file_stem().unwrap_or_default())
+            .with_extension("json");
+        let res = dump_file_json(f, opts)
+            .and_then(|s| fs::write(&dest, s).map_err(|e| e.to_string()))
+            .map(|_| dest);
+        out.push((f.clone(), res));
+    }
+    Ok(out)
+}
+
+/// One kind of data the reader did not fully understand, over many files.
+#[derive(Debug, Clone)]
+pub struct UnknownEntry {
+    pub what: String,
+    pub class: Option<String>,
+    pub count: usize,
+    /// Files it appeared in, in the order they were read
+    pub files: Vec<PathBuf>,
+    /// Where it was first seen
+    pub first: (PathBuf, usize),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UnknownReport {
+    /// BinaryFormatter files read (plain-JSON saves are skipped)
+    pub files: usize,
+    /// Most frequent first
+    pub entries: Vec<UnknownEntry>,
+    /// Files the reader gave up on; the error names the record and offset
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+// Collect what the reader skipped or kept raw in each file, grouped by kind
+// and class, so parser gaps can be ranked by how often real saves hit them.
+pub fn report_unknown(files: &[PathBuf]) -> UnknownReport {
+    let mut report = UnknownReport::default();
+    for f in files {
+        let data = match fs::read(f) {
+            Ok(d) => d,
+            Err(e) => {
+                report.failed.push((f.clone(), e.to_string()));
+                continue;
+            }
+        };
+        if data.iter().copied().find(|b| !b.is_ascii_whitespace()) == Some(b'{') {
+            continue;
+        }
+        report.files += 1;
+        let doc = match Parser::new(&data).parse_stream() {
+            Ok(doc) => doc,
+            Err(e) => {
+                report.failed.push((f.clone(), e));
+                continue;
+            }
+        };
+        for u in doc.unknowns() {
+            let i = match report
+                .entries
+                .iter()
+                .position(|e| e.what == u.what && e.class == u.class)
+            {
+                Some(i) => i,
+                None => {
+                    report.entries.push(UnknownEntry {
+                        what: u.what.clone(),
+                        class: u.class.clone(),
+                        count: 0,
+                        files: Vec::new(),
+                        first: (f.clone(), u.offset),
+                    });
+                    report.entries.len() - 1
+                }
+            };
+            let entry = &mut report.entries[i];
+            entry.count += 1;
+            if entry.files.last() != Some(f) {
+                entry.files.push(f.clone());
+            }
+        }
+    }
+    report.entries.sort_by_key(|e| std::cmp::Reverse(e.count));
+    report
+}
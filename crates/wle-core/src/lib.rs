@@ -5,20 +5,77 @@
 //! - Minimal typed helpers for data we care about (SlotInfo convenience)
 //! - JSON dump for any .sav for CLI use
 //! - Generic JSON edit API (JSON Pointer), and slot zip backup
+//! - `DocumentBuilder`: write a .sav from calls, without building JSON first
+//! - `de`, `ser`: serde between a .sav and your own types
+//! - `catalog`: friendly labels, units and ranges for raw member names
+//! - `cheats`: one-call versions of the most common edits (money, unlock presets)
+//! - `presets`: the cheats and user-defined patches as named, reviewable edits
+//! - `loadout`: a player's clothes, wardrobe and vehicles as a shareable file
+//! - `plugin`: document transforms, in code or loaded from JSON rule files
+//! - `migrate`: data-driven fixes for saves from older game versions
+//! - `templates`: documents stripped of ids and timestamps, to start new ones from
+//! - `profile`: which nodes and classes take the bytes of a .sav
+//! - `validate`: parse, write-back and sanity checks for save health reports,
+//!   and copies of objects a glitched save piles up
+//! - `table`: CSV/TSV export and import of arrays and flat objects
+//!
+//! Everything that works on save folders (`saves`, `editor`, `cheats`,
+//! `presets`, `loadout`, `plugin`, `migrate`, `templates`) needs the default
+//! `fs` feature; the rest only needs bytes and builds for
+//! wasm32-unknown-unknown. The `yaml` feature adds YAML as an alternative to
+//! JSON dumps (`yaml_from_json`, `json_from_yaml`), and `script` adds
+//! `script`: Rhai scripts that edit a document through JSON Pointers.
 //!
 pub mod binfmt;
 pub mod binfmt_write;
+pub mod catalog;
+#[cfg(feature = "fs")]
+pub mod cheats;
+pub mod de;
 pub mod edit;
+#[cfg(feature = "fs")]
 pub mod editor;
 pub mod json;
+#[cfg(feature = "fs")]
+pub mod loadout;
+#[cfg(feature = "fs")]
+pub mod migrate;
 pub mod model;
+#[cfg(feature = "fs")]
+pub mod plugin;
+#[cfg(feature = "fs")]
+pub mod presets;
+pub mod profile;
+#[cfg(feature = "fs")]
 pub mod saves;
+#[cfg(feature = "script")]
+pub mod script;
+pub mod ser;
+pub mod table;
+#[cfg(feature = "fs")]
+pub mod templates;
+pub mod validate;
 
 // Re-export generic JSON edit API
-pub use binfmt_write::{write_binfmt_file_from_json, write_binfmt_from_json};
+pub use binfmt_write::{
+    DocumentBuilder, WriteError, WriteProgress, is_read_only, set_read_only,
+    write_binfmt_file_from_json, write_binfmt_from_json, write_binfmt_from_json_with_cancel,
+    write_binfmt_from_json_with_progress, write_file_atomic,
+};
 pub use edit::{
-    ChildInfo, JsonEditValue, JsonKind, add_key, apply_object_primitive_updates, array_insert,
-    array_remove, document_to_json_value, get_by_pointer, list_children, list_object_primitives_at,
-    parse_file_to_json_value, remove_at_pointer, set_by_pointer, set_raw_by_pointer,
+    Bookmark, Change, ChildInfo, EditSession, EditStep, JsonEditValue, JsonKind, Limits,
+    ParseCache, PatchOp, PointerBook, SearchHit, SearchScope, add_key,
+    apply_object_primitive_updates, apply_patch, array_insert, array_remove, byte_array,
+    coerce_like, coerced_replace_ops, content_hash, diff, document_to_json_value,
+    document_to_json_value_with_cancel, document_value_at, get_by_pointer, get_bytes_at,
+    is_yaml_path, json_from_bytes, list_children, list_object_primitives_at,
+    parse_bytes_at_pointers, parse_bytes_for_edit, parse_bytes_for_edit_with_progress,
+    parse_bytes_to_json_value, parse_bytes_to_json_value_with_cancel,
+    parse_bytes_to_json_value_with_progress, parse_edit_script, parse_file_for_edit,
+    parse_file_to_json_value, parse_file_to_json_value_with_progress, parse_patch, paste_ops,
+    read_json_file, regenerate_guids, remove_at_pointer, search, set_by_pointer, set_bytes_at,
+    set_bytes_op, set_raw_by_pointer, set_typed_by_pointer, validate_for_write, value_preview,
     write_json_to_file,
 };
+#[cfg(feature = "yaml")]
+pub use edit::{json_from_yaml, yaml_from_json};
@@ -0,0 +1,370 @@
+//! Shareable loadouts: what a player wears, the wardrobe they own and their
+//! vehicles, in a small file that can be put on another player in any save.
+//! Only game content ids and colours are kept, no classes or save-specific
+//! references, so a loadout stays valid across saves:
+//!
+//! ```json
+//! {
+//!   "format": "wle-loadout",
+//!   "version": 1,
+//!   "name": "Pirate",
+//!   "worn": {"hat": {"prefab": "261c6a70-b1cd-432e-af5b-a894757519c1", "color": [1, 0.8, 0.4, 1]}, "outfit": null},
+//!   "wardrobe": {"hat": [{"prefab": "261c6a70-b1cd-432e-af5b-a894757519c1", "color": [1, 0.8, 0.4, 1]}]},
+//!   "vehicles": [{"id": "0037b77c-871e-4c4c-8c0f-451bb24805ac", "color": [0, 0, 0, 1]}]
+//! }
+//! ```
+//!
+//! `worn` sets each slot it names (`null` takes the piece off), `wardrobe`
+//! adds the pieces the player does not own yet and `vehicles` the vehicles
+//! they lack; anything left out is not touched.
+
+use crate::cheats::{self, Change, PendingEdit};
+use crate::edit::unwrap_ref;
+use crate::model::{ClothingPiece, ClothingSlot, DocKind, Guid, PlayerData};
+use serde_json::{Value as J, json};
+use std::collections::HashSet;
+use std::path::Path;
+
+pub const FORMAT: &str = "wle-loadout";
+/// The newest loadout version this build reads and the one it writes.
+pub const VERSION: u64 = 1;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Loadout {
+    pub name: String,
+    /// Slots to dress; a nil prefab takes the piece off
+    pub worn: Vec<(ClothingSlot, ClothingPiece)>,
+    /// Owned pieces in `ClothingSlot::ALL` order
+    pub wardrobe: [Vec<ClothingPiece>; 4],
+    /// Vehicle id and primary colour
+    pub vehicles: Vec<(Guid, [f32; 4])>,
+}
+
+fn color_from_json(v: &J) -> Option<[f32; 4]> {
+    let v = unwrap_ref(v);
+    let c = |k: &str| v.get(k).and_then(|x| x.as_f64()).map(|x| x as f32);
+    Some([c("r")?, c("g")?, c("b")?, c("a")?])
+}
+
+fn color_to_json([r, g, b, a]: [f32; 4]) -> J {
+    json!({"$class": "SerializableColor", "r": r, "g": g, "b": b, "a": a})
+}
+
+fn piece_from_json(piece: &J) -> Option<ClothingPiece> {
+    let piece = unwrap_ref(piece);
+    Some(ClothingPiece {
+        prefab: Guid::from_json(piece.get("clothingPrefabGUID")?)?,
+        color: piece
+            .get("clothingPrimaryColor")
+            .and_then(color_from_json)
+            .unwrap_or([1.0; 4]),
+    })
+}
+
+fn piece_to_json(piece: &ClothingPiece) -> J {
+    json!({
+        "$class": "ClothingPieceData",
+        "clothingPrefabGUID": piece.prefab.to_json(),
+        "clothingPrimaryColor": color_to_json(piece.color),
+    })
+}
+
+fn vehicle_to_json(id: Guid, color: [f32; 4]) -> J {
+    json!({"$ref": 0, "$value": {
+        "$class": "VehicleData",
+        "VehicleID": id.to_json(),
+        "CustomizeData": {"$ref": 0, "$value": {
+            "$class": "VehicleCustomizeColourData",
+            "primaryColor": color_to_json(color),
+        }},
+    }})
+}
+
+fn is_player(player: &J) -> Result<(), String> {
+    match crate::model::detect_kind_json(player) {
+        DocKind::PlayerData => Ok(()),
+        kind => Err(format!(
+            "a loadout needs PlayerData, not a {} document",
+            kind
+        )),
+    }
+}
+
+impl Loadout {
+    /// What the player in `player` (its edit JSON) wears and owns.
+    pub fn from_player(player: &J, name: &str) -> Result<Loadout, String> {
+        is_player(player)?;
+        let mut loadout = Loadout {
+            name: name.to_string(),
+            ..Loadout::default()
+        };
+        let clothes = player
+            .pointer("/root/<CurrentClothes>k__BackingField")
+            .map(unwrap_ref);
+        for slot in ClothingSlot::ALL {
+            if let Some(piece) = clothes
+                .and_then(|c| c.get(slot.member()))
+                .and_then(piece_from_json)
+            {
+                loadout.worn.push((slot, piece));
+            }
+        }
+        for (i, (list, _)) in cheats::WARDROBE_LISTS.iter().enumerate() {
+            let (items, size) = cheats::wardrobe_list(player, list)?;
+            loadout.wardrobe[i] = items[..size.min(items.len())]
+                .iter()
+                .filter_map(piece_from_json)
+                .filter(|p| !p.prefab.is_nil())
+                .collect();
+        }
+        for v in cheats::array_at(player, cheats::VEHICLES)? {
+            if let Some(id) = cheats::vehicle_guid(v) {
+                let color = unwrap_ref(v)
+                    .get("CustomizeData")
+                    .and_then(|c| unwrap_ref(c).get("primaryColor"))
+                    .and_then(color_from_json)
+                    .unwrap_or([1.0; 4]);
+                loadout.vehicles.push((id, color));
+            }
+        }
+        Ok(loadout)
+    }
+
+    /// The loadout file (see the module docs).
+    pub fn to_json(&self) -> J {
+        let piece = |p: &ClothingPiece| match p.prefab.is_nil() {
+            true => J::Null,
+            false => json!({"prefab": p.prefab.to_string(), "color": p.color}),
+        };
+        let worn: serde_json::Map<String, J> = self
+            .worn
+            .iter()
+            .map(|(s, p)| (s.name().to_string(), piece(p)))
+            .collect();
+        let wardrobe: serde_json::Map<String, J> = ClothingSlot::ALL
+            .iter()
+            .zip(&self.wardrobe)
+            .filter(|(_, list)| !list.is_empty())
+            .map(|(s, list)| (s.name().to_string(), list.iter().map(piece).collect()))
+            .collect();
+        let vehicles: Vec<J> = self
+            .vehicles
+            .iter()
+            .map(|(id, color)| json!({"id": id.to_string(), "color": color}))
+            .collect();
+        json!({
+            "format": FORMAT,
+            "version": VERSION,
+            "name": self.name,
+            "worn": worn,
+            "wardrobe": wardrobe,
+            "vehicles": vehicles,
+        })
+    }
+
+    /// Read and check a loadout file: the header, every id and every colour.
+    pub fn from_json(v: &J) -> Result<Loadout, String> {
+        if v.get("format").and_then(|f| f.as_str()) != Some(FORMAT) {
+            return Err(format!("not a loadout (\"format\" must be \"{}\")", FORMAT));
+        }
+        let Some(version) = v.get("version") else {
+            return Err("missing \"version\"".into());
+        };
+        match version.as_u64() {
+            Some(n) if (1..=VERSION).contains(&n) => {}
+            Some(n) if n > VERSION => {
+                return Err(format!(
+                    "loadout version {} is newer than this editor reads ({})",
+                    n, VERSION
+                ));
+            }
+            _ => return Err(format!("invalid loadout version: {}", version)),
+        }
+        let guid = |v: &J, what: &str| -> Result<Guid, String> {
+            v.as_str()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| format!("{} is not a GUID: {}", what, v))
+        };
+        let color = |v: Option<&J>, what: &str| -> Result<[f32; 4], String> {
+            let Some(v) = v else {
+                return Ok([1.0; 4]);
+            };
+            let parts: Option<Vec<f64>> = v
+                .as_array()
+                .filter(|a| a.len() == 4)
+                .map(|a| a.iter().filter_map(|x| x.as_f64()).collect());
+            match parts {
+                Some(p) if p.len() == 4 && p.iter().all(|x| (0.0..=1.0).contains(x)) => {
+                    Ok([p[0] as f32, p[1] as f32, p[2] as f32, p[3] as f32])
+                }
+                _ => Err(format!(
+                    "{} colour must be 4 numbers from 0 to 1: {}",
+                    what, v
+                )),
+            }
+        };
+        let piece = |v: &J, what: &str| -> Result<ClothingPiece, String> {
+            if v.is_null() {
+                return Ok(ClothingPiece {
+                    prefab: Guid::NIL,
+                    color: [0.0; 4],
+                });
+            }
+            let prefab = guid(v.get("prefab").unwrap_or(&J::Null), what)?;
+            Ok(ClothingPiece {
+                prefab,
+                color: color(v.get("color"), what)?,
+            })
+        };
+        let slot_named = |name: &str| {
+            ClothingSlot::ALL
+                .into_iter()
+                .find(|s| s.name() == name)
+                .ok_or_else(|| {
+                    format!(
+                        "unknown clothing slot '{}' (hat, top, bottom, outfit)",
+                        name
+                    )
+                })
+        };
+        let object = |k: &str| match v.get(k) {
+            None => Ok(serde_json::Map::new()),
+            Some(J::Object(m)) => Ok(m.clone()),
+            Some(_) => Err(format!("\"{}\" must be an object", k)),
+        };
+
+        let mut loadout = Loadout {
+            name: v
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            ..Loadout::default()
+        };
+        for (name, p) in object("worn")? {
+            let slot = slot_named(&name)?;
+            loadout
+                .worn
+                .push((slot, piece(&p, &format!("worn {}", name))?));
+        }
+        for (name, list) in object("wardrobe")? {
+            let slot = slot_named(&name)?;
+            let i = ClothingSlot::ALL
+                .iter()
+                .position(|s| *s == slot)
+                .unwrap_or(0);
+            let list = list
+                .as_array()
+                .ok_or_else(|| format!("wardrobe {} must be an array", name))?;
+            for (n, p) in list.iter().enumerate() {
+                let p = piece(p, &format!("wardrobe {} #{}", name, n + 1))?;
+                if p.prefab.is_nil() {
+                    return Err(format!("wardrobe {} #{} has no prefab", name, n + 1));
+                }
+                loadout.wardrobe[i].push(p);
+            }
+        }
+        let vehicles = match v.get("vehicles") {
+            None => Vec::new(),
+            Some(J::Array(a)) => a.clone(),
+            Some(_) => return Err("\"vehicles\" must be an array".into()),
+        };
+        for (n, vehicle) in vehicles.iter().enumerate() {
+            let what = format!("vehicle #{}", n + 1);
+            let id = guid(vehicle.get("id").unwrap_or(&J::Null), &what)?;
+            loadout
+                .vehicles
+                .push((id, color(vehicle.get("color"), &what)?));
+        }
+        Ok(loadout)
+    }
+
+    /// Plan putting this loadout on `player` (its edit JSON): dress each
+    /// `worn` slot, then add the wardrobe pieces and vehicles the player
+    /// lacks. A piece worn but not owned is added to the wardrobe too, so
+    /// the game can show it.
+    pub fn changes(&self, player: &J) -> Result<Vec<Change>, String> {
+        is_player(player)?;
+        let current = Loadout::from_player(player, "")?;
+        let mut changes = Vec::new();
+        for (slot, piece) in &self.worn {
+            let same = |(s, p): &&(ClothingSlot, ClothingPiece)| {
+                s == slot
+                    && p.prefab == piece.prefab
+                    && (p.prefab.is_nil() || p.color == piece.color)
+            };
+            if current.worn.iter().any(|w| same(&w)) {
+                continue;
+            }
+            let [wear, colour] = <[_; 2]>::try_from(PlayerData::set_clothing(*slot, piece))
+                .map_err(|_| "unexpected clothing patch".to_string())?;
+            let what = match piece.prefab.is_nil() {
+                true => "nothing".to_string(),
+                false => piece.prefab.to_string(),
+            };
+            changes.push(Change {
+                summary: format!("wear {} {}", slot.name(), what),
+                op: wear,
+            });
+            changes.push(Change {
+                summary: format!("{} colour {:?}", slot.name(), piece.color),
+                op: colour,
+            });
+        }
+        for (i, (list, label)) in cheats::WARDROBE_LISTS.iter().enumerate() {
+            let (items, size) = cheats::wardrobe_list(player, list)?;
+            let mut owned: HashSet<Guid> = items[..size.min(items.len())]
+                .iter()
+                .filter_map(cheats::clothing_guid)
+                .collect();
+            let worn = self
+                .worn
+                .iter()
+                .filter(|(s, p)| *s == ClothingSlot::ALL[i] && !p.prefab.is_nil())
+                .map(|(_, p)| p);
+            let missing: Vec<(String, J)> = self.wardrobe[i]
+                .iter()
+                .chain(worn)
+                .filter(|p| owned.insert(p.prefab))
+                .map(|p| (format!("add {} {}", label, p.prefab), piece_to_json(p)))
+                .collect();
+            changes.extend(cheats::add_to_wardrobe(player, list, label, missing)?);
+        }
+        let vehicles = cheats::array_at(player, cheats::VEHICLES)?;
+        let mut owned: HashSet<Guid> = vehicles.iter().filter_map(cheats::vehicle_guid).collect();
+        let free: Vec<usize> = (0..vehicles.len())
+            .filter(|&i| vehicles[i].is_null())
+            .collect();
+        let missing = self
+            .vehicles
+            .iter()
+            .filter(|(id, _)| owned.insert(*id))
+            .map(|(id, color)| (format!("add vehicle {}", id), vehicle_to_json(*id, *color)))
+            .collect();
+        changes.extend(cheats::fill_slots(cheats::VEHICLES, &free, missing));
+        Ok(changes)
+    }
+}
+
+/// The loadout of player `player` of `slot`.
+pub fn export(slot: &Path, player: u32, name: &str) -> Result<Loadout, String> {
+    Loadout::from_player(&cheats::load_player_json(slot, player)?, name)
+}
+
+/// `Loadout::from_json` on a loadout file.
+pub fn load(path: &Path) -> Result<Loadout, String> {
+    Loadout::from_json(&crate::edit::read_json_file(path)?)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// The edit putting `loadout` on player `player` of `slot`, with the
+/// summary of each change; nothing is written.
+pub fn import_edit(
+    slot: &Path,
+    player: u32,
+    loadout: &Loadout,
+) -> Result<(Vec<Change>, PendingEdit), String> {
+    let changes = loadout.changes(&cheats::load_player_json(slot, player)?)?;
+    let edit = cheats::unlock_edit(slot, player, &changes);
+    Ok((changes, edit))
+}
@@ -0,0 +1,366 @@
+//! Migrations for saves written by an older version of the game: ordered,
+//! data-driven rules that recognise an old layout and rename, add or remove
+//! what a game update changed, so the save loads again. A rule file holds a
+//! version and the migrations in the order they apply:
+//!
+//! ```json
+//! {
+//!   "version": 2,
+//!   "migrations": [{
+//!     "id": "stats-barrels-rename",
+//!     "description": "1.2 renamed barrelsFed to toxicWasteBarrelsFed",
+//!     "documents": ["stats"],
+//!     "detect": [{"exists": "/root/barrelsFed"}],
+//!     "steps": [
+//!       {"rename": "/root/barrelsFed", "to": "toxicWasteBarrelsFed"},
+//!       {"default": "/root/onStatChanged", "value": null},
+//!       {"remove": "/root/legacyCounter"},
+//!       {"class": "SaveStatsDataOld", "to": "SaveStatsData"}
+//!     ]
+//!   }]
+//! }
+//! ```
+//!
+//! A migration runs when every `detect` check holds: `exists` and `missing`
+//! take a pointer pattern, `class` a class name some object must have. Its
+//! steps then see the document as the steps before them left it. Patterns
+//! are JSON Pointers into the edit JSON where `*` stands for every item or
+//! member (see `plugin::expand_pointer`). `rename` keeps the member where it
+//! was, `default` adds a member only to objects that lack it, and `class`
+//! renames a class everywhere, `$rootClass` included. `documents` uses
+//! the names a plugin transform's does (see `plugin`), where a bare "player"
+//! or "settings" matches every player; without it a migration applies to any
+//! document.
+//!
+//! `builtin()` is the rule set shipped with the editor; rule files loaded at
+//! run time run after it, so a fix for a new game patch does not need a new
+//! editor build.
+
+use crate::cheats::PendingEdit;
+use crate::edit::{PatchOp, escape_token};
+use crate::model::DocKind;
+use crate::plugin::expand_pointer;
+use serde_json::Value as J;
+use std::path::{Path, PathBuf};
+
+/// Migrations in the order they apply, and the version of the file they
+/// came from.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    pub version: u64,
+    pub migrations: Vec<Migration>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Migration {
+    pub id: String,
+    pub description: String,
+    /// As `plugin::Transform::doc_kinds`: a player's number is not checked
+    pub kinds: Vec<crate::saves::DocKind>,
+    pub detect: Vec<Check>,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Check {
+    Exists(String),
+    Missing(String),
+    Class(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Step {
+    Rename { path: String, to: String },
+    Default { path: String, value: J },
+    Remove(String),
+    Class { from: String, to: String },
+}
+
+impl Migration {
+    /// Whether the document has the layout this migration fixes.
+    pub fn matches(&self, kind: DocKind, doc: &J) -> bool {
+        (self.kinds.is_empty() || self.kinds.iter().any(|k| DocKind::from(*k) == kind))
+            && self.detect.iter().all(|c| match c {
+                Check::Exists(p) => !expand_pointer(doc, p).is_empty(),
+                Check::Missing(p) => expand_pointer(doc, p).is_empty(),
+                Check::Class(name) => has_class(doc, name),
+            })
+    }
+
+    /// The ops that migrate `doc`, step by step; empty when nothing is left
+    /// to change.
+    pub fn ops(&self, doc: &J) -> Result<Vec<PatchOp>, String> {
+        let mut cur = doc.clone();
+        let mut all = Vec::new();
+        for step in &self.steps {
+            let ops = step_ops(step, &cur);
+            crate::edit::apply_patch(&mut cur, &ops)?;
+            all.extend(ops);
+        }
+        Ok(all)
+    }
+}
+
+fn step_ops(step: &Step, doc: &J) -> Vec<PatchOp> {
+    match step {
+        Step::Rename { path, to } => expand_pointer(doc, path)
+            .into_iter()
+            .filter_map(|ptr| {
+                let (parent, key) = split_last(&ptr)?;
+                let J::Object(map) = doc.pointer(parent)? else {
+                    return None;
+                };
+                if map.contains_key(to) {
+                    return None;
+                }
+                // Rebuilt in place: the writer keeps members in key order
+                let renamed = map
+                    .iter()
+                    .map(|(k, v)| (if *k == key { to.clone() } else { k.clone() }, v.clone()))
+                    .collect();
+                Some(PatchOp::replace(parent, J::Object(renamed)))
+            })
+            .collect(),
+        Step::Default { path, value } => {
+            let Some((parents, key)) = split_last(path) else {
+                return Vec::new();
+            };
+            expand_pointer(doc, parents)
+                .into_iter()
+                .filter(|p| {
+                    doc.pointer(p)
+                        .is_some_and(|o| o.is_object() && o.get(&key).is_none())
+                })
+                .map(|p| PatchOp::Add {
+                    path: format!("{}/{}", p, escape_token(&key)),
+                    value: value.clone(),
+                })
+                .collect()
+        }
+        // Last first, so removing an array item leaves the other pointers valid
+        Step::Remove(path) => expand_pointer(doc, path)
+            .into_iter()
+            .rev()
+            .map(|path| PatchOp::Remove { path })
+            .collect(),
+        Step::Class { from, to } => {
+            let mut ops = Vec::new();
+            if doc.get("$rootClass").and_then(|c| c.as_str()) == Some(from) {
+                ops.push(PatchOp::replace("/$rootClass", J::from(to.as_str())));
+            }
+            class_pointers(doc, String::new(), from, &mut |ptr| {
+                ops.push(PatchOp::replace(
+                    format!("{}/$class", ptr),
+                    J::from(to.as_str()),
+                ))
+            });
+            ops
+        }
+    }
+}
+
+// Parent pointer and unescaped last token
+fn split_last(ptr: &str) -> Option<(&str, String)> {
+    let (parent, tok) = ptr.rsplit_once('/')?;
+    Some((parent, tok.replace("~1", "/").replace("~0", "~")))
+}
+
+fn has_class(doc: &J, name: &str) -> bool {
+    let mut found = false;
+    class_pointers(doc, String::new(), name, &mut |_| found = true);
+    found || doc.get("$rootClass").and_then(|c| c.as_str()) == Some(name)
+}
+
+// Pointer of every object whose `$class` is `name`
+fn class_pointers(v: &J, ptr: String, name: &str, f: &mut impl FnMut(&str)) {
+    match v {
+        J::Object(map) => {
+            if map.get("$class").and_then(|c| c.as_str()) == Some(name) {
+                f(&ptr);
+            }
+            for (k, c) in map {
+                class_pointers(c, format!("{}/{}", ptr, escape_token(k)), name, f);
+            }
+        }
+        J::Array(items) => {
+            for (i, c) in items.iter().enumerate() {
+                class_pointers(c, format!("{}/{}", ptr, i), name, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Migrations of `rules` that apply to `doc` and the ops they make, worked out
+/// in order so each sees the document the ones before it left. `kind` is the
+/// document's kind as `model::detect_kind_json` or its file name tells it.
+pub fn plan_json(
+    rules: &[Migration],
+    kind: DocKind,
+    doc: &J,
+) -> Result<(Vec<String>, Vec<PatchOp>), String> {
+    let mut cur = doc.clone();
+    let (mut applied, mut all) = (Vec::new(), Vec::new());
+    for m in rules {
+        if !m.matches(kind, &cur) {
+            continue;
+        }
+        let ops = m.ops(&cur).map_err(|e| format!("{}: {}", m.id, e))?;
+        if ops.is_empty() {
+            continue;
+        }
+        crate::edit::apply_patch(&mut cur, &ops).map_err(|e| format!("{}: {}", m.id, e))?;
+        applied.push(m.id.clone());
+        all.extend(ops);
+    }
+    Ok((applied, all))
+}
+
+/// `plan_json` on the .sav at `path`: the ids of the migrations that apply
+/// and the edit that makes them. Nothing is written.
+pub fn plan(rules: &[Migration], path: &Path) -> Result<(Vec<String>, PendingEdit), String> {
+    let doc = crate::edit::parse_file_for_edit(path)?;
+    // An old layout may no longer look like its kind; the file name still tells
+    let kind = match crate::model::detect_kind_json(&doc) {
+        DocKind::Unknown => DocKind::expected_for(path),
+        k => k,
+    };
+    let (applied, ops) = plan_json(rules, kind, &doc)?;
+    Ok((
+        applied,
+        PendingEdit {
+            path: path.to_path_buf(),
+            ops,
+        },
+    ))
+}
+
+/// The rule set shipped with the editor (`migrations.json` next to this file).
+pub fn builtin() -> RuleSet {
+    let v: J = serde_json::from_str(include_str!("migrations.json"))
+        .expect("built-in migrations are valid JSON");
+    parse_rules(&v).expect("built-in migrations are valid rules")
+}
+
+/// The rule set in the JSON of a rule file.
+pub fn parse_rules(v: &J) -> Result<RuleSet, String> {
+    let version = match v.get("version") {
+        None => 0,
+        Some(n) => n.as_u64().ok_or("\"version\" must be a whole number")?,
+    };
+    let migrations = v
+        .get("migrations")
+        .and_then(|m| m.as_array())
+        .ok_or("missing \"migrations\" array")?
+        .iter()
+        .enumerate()
+        .map(|(i, m)| parse_migration(m).map_err(|e| format!("migration {}: {}", i + 1, e)))
+        .collect::<Result<_, _>>()?;
+    Ok(RuleSet {
+        version,
+        migrations,
+    })
+}
+
+fn parse_migration(v: &J) -> Result<Migration, String> {
+    let text = |k: &str| v.get(k).and_then(|x| x.as_str());
+    let id = text("id").ok_or("missing \"id\"")?;
+    let list = |k: &str| match v.get(k) {
+        None => Ok(Vec::new()),
+        Some(J::Array(items)) => Ok(items.iter().collect::<Vec<_>>()),
+        Some(_) => Err(format!("\"{}\" must be an array", k)),
+    };
+    let kinds = list("documents")?
+        .into_iter()
+        .map(|n| {
+            n.as_str()
+                .ok_or("document names are strings".to_string())
+                .and_then(crate::plugin::doc_kind)
+        })
+        .collect::<Result<_, _>>()?;
+    let detect = list("detect")?
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| parse_check(c).map_err(|e| format!("check {}: {}", i + 1, e)))
+        .collect::<Result<_, _>>()?;
+    let steps = list("steps")?
+        .into_iter()
+        .enumerate()
+        .map(|(i, s)| parse_step(s).map_err(|e| format!("step {}: {}", i + 1, e)))
+        .collect::<Result<_, _>>()?;
+    Ok(Migration {
+        id: id.to_string(),
+        description: text("description").unwrap_or_default().to_string(),
+        kinds,
+        detect,
+        steps,
+    })
+}
+
+fn pointer(v: &J, key: &str) -> Option<Result<String, String>> {
+    let p = v.get(key)?;
+    Some(match p.as_str() {
+        Some(p) if p.starts_with('/') => Ok(p.to_string()),
+        _ => Err(format!("\"{}\" must be a JSON Pointer", key)),
+    })
+}
+
+fn parse_check(v: &J) -> Result<Check, String> {
+    if let Some(p) = pointer(v, "exists") {
+        return p.map(Check::Exists);
+    }
+    if let Some(p) = pointer(v, "missing") {
+        return p.map(Check::Missing);
+    }
+    match v.get("class").and_then(|c| c.as_str()) {
+        Some(c) => Ok(Check::Class(c.to_string())),
+        None => Err("needs one of \"exists\", \"missing\" or \"class\"".into()),
+    }
+}
+
+fn parse_step(v: &J) -> Result<Step, String> {
+    let to = || {
+        v.get("to")
+            .and_then(|t| t.as_str())
+            .map(String::from)
+            .ok_or("missing \"to\"")
+    };
+    if let Some(p) = pointer(v, "rename") {
+        return Ok(Step::Rename {
+            path: p?,
+            to: to()?,
+        });
+    }
+    if let Some(p) = pointer(v, "default") {
+        let value = v.get("value").ok_or("missing \"value\"")?.clone();
+        return Ok(Step::Default { path: p?, value });
+    }
+    if let Some(p) = pointer(v, "remove") {
+        return p.map(Step::Remove);
+    }
+    match v.get("class").and_then(|c| c.as_str()) {
+        Some(from) => Ok(Step::Class {
+            from: from.to_string(),
+            to: to()?,
+        }),
+        None => Err("needs one of \"rename\", \"default\", \"remove\" or \"class\"".into()),
+    }
+}
+
+/// `parse_rules` on a rule file (JSON, or YAML with the `yaml` feature).
+pub fn load_rules(path: &Path) -> Result<RuleSet, String> {
+    parse_rules(&crate::edit::read_json_file(path)?)
+}
+
+/// Every rule file in `dir` (*.json, and *.yaml with the `yaml` feature) in
+/// name order, each with its rules or why it could not be read. A missing
+/// folder has none.
+pub fn load_rule_dir(dir: &Path) -> Vec<(PathBuf, Result<RuleSet, String>)> {
+    crate::plugin::rule_files(dir)
+        .into_iter()
+        .map(|p| {
+            let r = load_rules(&p);
+            (p, r)
+        })
+        .collect()
+}
@@ -1,4 +1,5 @@
 use crate::binfmt::{Document, DynObject, Value};
+use crate::edit::PatchOp;
 use std::borrow::Cow;
 
 #[derive(Debug, Clone)]
@@ -8,7 +9,131 @@ pub struct SaveSlotInfoData<'a> {
     pub small_image_data: Cow<'a, [u8]>,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Slot thumbnail as RGBA pixels, top row first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotImage {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+impl SlotImage {
+    // `smallImageData` is a raw Unity texture dump with no header: a square
+    // RGB24 (or RGBA32) image stored bottom row first. The size is inferred.
+    pub fn decode(bytes: &[u8]) -> Option<SlotImage> {
+        let (side, ch) = [3usize, 4].into_iter().find_map(|ch| {
+            if bytes.is_empty() || !bytes.len().is_multiple_of(ch) {
+                return None;
+            }
+            let px = bytes.len() / ch;
+            let side = (px as f64).sqrt().round() as usize;
+            (side * side == px).then_some((side, ch))
+        })?;
+        Some(Self::from_raw(bytes, side, side, ch))
+    }
+
+    /// Guess whether a byte array is a raw texture like `decode` reads: a
+    /// square (preferred when the size fits several shapes), or a 16:9, 4:3
+    /// or 3:2 landscape, at least 8 pixels wide.
+    /// Returns the image and its bytes per pixel (3 or 4) for `encode`.
+    pub fn guess(bytes: &[u8]) -> Option<(SlotImage, usize)> {
+        [3usize, 4].into_iter().find_map(|ch| {
+            if bytes.is_empty() || !bytes.len().is_multiple_of(ch) {
+                return None;
+            }
+            let px = bytes.len() / ch;
+            let (w, h) = [(1, 1), (16, 9), (4, 3), (3, 2)]
+                .into_iter()
+                .find_map(|(rw, rh)| {
+                    let k = ((px / (rw * rh)) as f64).sqrt().round() as usize;
+                    (k > 0 && rw * k * rh * k == px).then_some((rw * k, rh * k))
+                })
+                .filter(|(w, _)| *w >= 8)?;
+            Some((Self::from_raw(bytes, w, h, ch), ch))
+        })
+    }
+
+    // Raw pixels with `ch` bytes each, bottom row first
+    fn from_raw(bytes: &[u8], width: usize, height: usize, ch: usize) -> SlotImage {
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for row in bytes.chunks_exact(width * ch).rev() {
+            for p in row.chunks_exact(ch) {
+                rgba.extend_from_slice(&p[..3]);
+                rgba.push(if ch == 4 { p[3] } else { 255 });
+            }
+        }
+        SlotImage {
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    // Inverse of `decode`: raw bytes with `channels` (3 or 4) per pixel, bottom row first
+    pub fn encode(&self, channels: usize) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.width * self.height * channels);
+        for row in self.rgba.chunks_exact(self.width * 4).rev() {
+            for p in row.chunks_exact(4) {
+                out.extend_from_slice(&p[..channels.min(4)]);
+            }
+        }
+        out
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        let mut out = std::io::Cursor::new(Vec::new());
+        image::write_buffer_with_format(
+            &mut out,
+            &self.rgba,
+            self.width as u32,
+            self.height as u32,
+            image::ExtendedColorType::Rgba8,
+            image::ImageFormat::Png,
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(out.into_inner())
+    }
+
+    pub fn from_png(data: &[u8]) -> Result<SlotImage, String> {
+        let img = image::load_from_memory_with_format(data, image::ImageFormat::Png)
+            .map_err(|e| e.to_string())?
+            .to_rgba8();
+        Ok(SlotImage {
+            width: img.width() as usize,
+            height: img.height() as usize,
+            rgba: img.into_raw(),
+        })
+    }
+
+    // Scale to `width` x `height`; the aspect ratio is not kept
+    pub fn resized(&self, width: usize, height: usize) -> SlotImage {
+        if (self.width, self.height) == (width, height) {
+            return self.clone();
+        }
+        let src =
+            image::RgbaImage::from_raw(self.width as u32, self.height as u32, self.rgba.clone())
+                .expect("rgba length matches dimensions");
+        let out = image::imageops::resize(
+            &src,
+            width as u32,
+            height as u32,
+            image::imageops::FilterType::Triangle,
+        );
+        SlotImage {
+            width,
+            height,
+            rgba: out.into_raw(),
+        }
+    }
+}
+
+impl SaveSlotInfoData<'_> {
+    pub fn decode_image(&self) -> Option<SlotImage> {
+        SlotImage::decode(&self.small_image_data)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Guid {
     pub a: i32,
     pub b: i32,
@@ -30,6 +155,84 @@ impl core::fmt::Display for Guid {
     }
 }
 
+impl Guid {
+    pub const NIL: Guid = Guid {
+        a: 0,
+        b: 0,
+        c: 0,
+        d_to_k: [0; 8],
+    };
+
+    pub fn is_nil(&self) -> bool {
+        self.a == 0 && self.b == 0 && self.c == 0 && self.d_to_k == [0; 8]
+    }
+
+    /// A fresh version-4 GUID from the OS random source.
+    pub fn new_random() -> Guid {
+        let mut bytes = [0u8; 16];
+        getrandom::getrandom(&mut bytes).expect("OS random source unavailable");
+        let mut d_to_k = [0u8; 8];
+        d_to_k.copy_from_slice(&bytes[8..]);
+        d_to_k[0] = (d_to_k[0] & 0x3F) | 0x80; // RFC 4122 variant
+        let c = u16::from_le_bytes([bytes[6], bytes[7]]) & 0x0FFF | 0x4000;
+        Guid {
+            a: i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            b: i16::from_le_bytes([bytes[4], bytes[5]]) as i32,
+            c: c as i16 as i32,
+            d_to_k,
+        }
+    }
+
+    // System.Guid as it appears in the edit JSON (`_a`.. `_k` members)
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut map = serde_json::Map::new();
+        map.insert("$class".into(), "System.Guid".into());
+        map.insert("_a".into(), self.a.into());
+        map.insert("_b".into(), (self.b as i16).into());
+        map.insert("_c".into(), (self.c as i16).into());
+        for (i, b) in self.d_to_k.iter().enumerate() {
+            map.insert(format!("_{}", (b'd' + i as u8) as char), (*b).into());
+        }
+        serde_json::Value::Object(map)
+    }
+
+    pub fn from_json(v: &serde_json::Value) -> Option<Guid> {
+        let field = |k: &str| v.get(k).and_then(|x| x.as_i64());
+        let mut d_to_k = [0u8; 8];
+        for (i, b) in d_to_k.iter_mut().enumerate() {
+            *b = field(&format!("_{}", (b'd' + i as u8) as char))? as u8;
+        }
+        Some(Guid {
+            a: field("_a")? as i32,
+            b: field("_b")? as i32,
+            c: field("_c")? as i32,
+            d_to_k,
+        })
+    }
+}
+
+// Parses the Display form, e.g. 261c6a70-b1cd-432e-af5b-a894757519c1
+impl std::str::FromStr for Guid {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        let hex: String = s.trim().chars().filter(|c| *c != '-').collect();
+        if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("invalid GUID: {}", s));
+        }
+        let num = |r: std::ops::Range<usize>| u32::from_str_radix(&hex[r], 16).unwrap_or(0);
+        let mut d_to_k = [0u8; 8];
+        for (i, b) in d_to_k.iter_mut().enumerate() {
+            *b = num(16 + i * 2..18 + i * 2) as u8;
+        }
+        Ok(Guid {
+            a: num(0..8) as i32,
+            b: num(8..12) as u16 as i16 as i32,
+            c: num(12..16) as u16 as i16 as i32,
+            d_to_k,
+        })
+    }
+}
+
 impl<'a> Document<'a> {
     pub fn as_save_slot_info(&'a self) -> Option<SaveSlotInfoData<'a>> {
         let Value::Object(obj) = self.root_value()? else {
@@ -124,5 +327,993 @@ impl<'a> Document<'a> {
             _ => None,
         }
     }
+    fn get_num(&'a self, v: &'a Value<'a>) -> Option<i64> {
+        match self.resolve_value(v) {
+            Value::I32(x) => Some(*x as i64),
+            Value::I64(x) => Some(*x),
+            Value::U32(x) => Some(*x as i64),
+            Value::U64(x) => Some(*x as i64),
+            Value::U8(x) => Some(*x as i64),
+            _ => None,
+        }
+    }
+    fn get_f32(&'a self, v: &'a Value<'a>) -> Option<f32> {
+        match self.resolve_value(v) {
+            Value::F32(x) => Some(*x),
+            Value::F64(x) => Some(*x as f32),
+            _ => self.get_num(v).map(|n| n as f32),
+        }
+    }
+    pub fn get_guid(&'a self, v: &'a Value<'a>) -> Option<Guid> {
+        let obj = self.as_object_value(v)?;
+        let field = |k: &str| self.member_value(obj, k).and_then(|x| self.get_num(x));
+        let mut d_to_k = [0u8; 8];
+        for (i, b) in d_to_k.iter_mut().enumerate() {
+            *b = field(&format!("_{}", (b'd' + i as u8) as char))? as u8;
+        }
+        Some(Guid {
+            a: field("_a")? as i32,
+            b: field("_b")? as i32,
+            c: field("_c")? as i32,
+            d_to_k,
+        })
+    }
+    // Follow a chain of member names through refs
+    pub fn member_path(&'a self, obj: &'a DynObject<'a>, path: &[&str]) -> Option<&'a Value<'a>> {
+        let (first, rest) = path.split_first()?;
+        let v = self.member_value(obj, first)?;
+        if rest.is_empty() {
+            return Some(v);
+        }
+        self.member_path(self.as_object_value(v)?, rest)
+    }
+    // Elements of a T[], List<T> or HawkMessageList<T>, without the unused
+    // capacity slots at the end of the backing array.
+    pub fn list_items(&'a self, v: &'a Value<'a>) -> Vec<&'a Value<'a>> {
+        match self.resolve_value(v) {
+            Value::Array(items) => items.iter().collect(),
+            Value::Object(obj) => {
+                for (items_key, size_key) in
+                    [("_items", "_size"), ("List`1+_items", "List`1+_size")]
+                {
+                    if let Some(items) = self.member_value(obj, items_key) {
+                        let size = self
+                            .member_value(obj, size_key)
+                            .and_then(|s| self.get_num(s))
+                            .unwrap_or(i64::MAX) as usize;
+                        let mut out = self.list_items(items);
+                        out.truncate(size);
+                        return out;
+                    }
+                }
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
     // Note: domain-specific helpers removed to keep core generic.
 }
+
+// GameSaves/SaveInfo.sav: global settings shared by all slots. Unlike the slot
+// files it is plain JSON (Unity JsonUtility). Unknown keys are kept as-is so
+// saving never drops settings added by newer game versions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SaveInfo {
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SaveInfo {
+    pub const FILE_NAME: &'static str = "SaveInfo.sav";
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let v: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        Self::from_value(v)
+    }
+
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&text)
+    }
+
+    pub fn from_value(v: serde_json::Value) -> Result<Self, String> {
+        match v {
+            serde_json::Value::Object(fields) => Ok(Self { fields }),
+            _ => Err("SaveInfo must be a JSON object".into()),
+        }
+    }
+
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::Value::Object(self.fields.clone())
+    }
+
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string_pretty(&self.fields).unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &std::path::Path) -> Result<(), crate::WriteError> {
+        crate::binfmt_write::write_file_atomic(path, self.to_json_string().as_bytes())
+    }
+
+    pub fn fields(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.fields
+    }
+
+    // Slot number (SaveSlot_N) the game opens by default
+    pub fn last_loaded_slot(&self) -> Option<i64> {
+        self.fields.get("lastLoadedSlot")?.as_i64()
+    }
+
+    pub fn set_last_loaded_slot(&mut self, slot: i64) {
+        self.fields.insert("lastLoadedSlot".into(), slot.into());
+    }
+
+    pub fn mod_safety_tip(&self) -> Option<bool> {
+        self.fields.get("bModSafetyTip")?.as_bool()
+    }
+
+    pub fn set_mod_safety_tip(&mut self, shown: bool) {
+        self.fields.insert("bModSafetyTip".into(), shown.into());
+    }
+}
+
+// What a document holds, told from its content rather than its file name.
+// Unlike `saves::DocKind` it cannot know a player file's number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocKind {
+    SlotInfo,
+    PlayerData,
+    PlayerSettings,
+    MissionData,
+    StatsData,
+    WorldData,
+    SaveInfo,
+    Unknown,
+}
+
+impl DocKind {
+    // Root class of each slot file, and a member only that class has, for
+    // a class a game update renamed
+    const SIGNS: [(DocKind, &'static str, &'static str); 6] = [
+        (DocKind::SlotInfo, "SaveSlotInfoData", "smallImageData"),
+        (
+            DocKind::PlayerData,
+            "SavePlayerPersistentData",
+            "<CurrentClothes>k__BackingField",
+        ),
+        (
+            DocKind::PlayerSettings,
+            "SavePlayerSettingsData",
+            "bUseEasyGrab",
+        ),
+        (
+            DocKind::MissionData,
+            "SaveMissionData",
+            "missions_Completed",
+        ),
+        (DocKind::StatsData, "SaveStatsData", "toxicWasteBarrelsFed"),
+        (DocKind::WorldData, "SaveWorldData", "dataScenes"),
+    ];
+
+    fn from_root(class: &str, has_member: impl Fn(&str) -> bool) -> DocKind {
+        Self::SIGNS
+            .iter()
+            .find(|(_, c, _)| *c == class)
+            .or_else(|| Self::SIGNS.iter().find(|(_, _, m)| has_member(m)))
+            .map_or(DocKind::Unknown, |(k, _, _)| *k)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DocKind::SlotInfo => "SlotInfo",
+            DocKind::PlayerData => "PlayerData",
+            DocKind::PlayerSettings => "PlayerSettingsData",
+            DocKind::MissionData => "MissionData",
+            DocKind::StatsData => "StatsData",
+            DocKind::WorldData => "WorldData",
+            DocKind::SaveInfo => "SaveInfo",
+            DocKind::Unknown => "unknown",
+        }
+    }
+
+    // What `file` should hold, from its name (SaveInfo.sav included)
+    #[cfg(feature = "fs")]
+    pub fn expected_for(file: &std::path::Path) -> DocKind {
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+        if name == SaveInfo::FILE_NAME {
+            return DocKind::SaveInfo;
+        }
+        crate::saves::DocKind::from_file_name(&name).map_or(DocKind::Unknown, DocKind::from)
+    }
+}
+
+impl std::fmt::Display for DocKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The kind of a parsed slot file, from its root class and members.
+pub fn detect_kind(doc: &Document<'_>) -> DocKind {
+    match doc.root_value() {
+        Some(Value::Object(obj)) => DocKind::from_root(obj.class_name, |m| {
+            obj.members.iter().any(|(name, _)| *name == m)
+        }),
+        _ => DocKind::Unknown,
+    }
+}
+
+/// `detect_kind` for edit or dump JSON (`{"$rootClass", "root"}`); the
+/// SaveInfo document the editors build is `{"$rootClass": "SaveInfo"}`.
+pub fn detect_kind_json(v: &serde_json::Value) -> DocKind {
+    let class = v.get("$rootClass").and_then(|c| c.as_str()).unwrap_or("");
+    if class == "SaveInfo" {
+        return DocKind::SaveInfo;
+    }
+    let root = v.get("root");
+    let root = root
+        .and_then(|r| r.get("$value"))
+        .or(root)
+        .and_then(|r| r.as_object());
+    match root {
+        Some(map) => DocKind::from_root(class, |m| map.contains_key(m)),
+        None => DocKind::Unknown,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClothingSlot {
+    Hat,
+    Top,
+    Bottom,
+    Outfit,
+}
+
+impl ClothingSlot {
+    pub const ALL: [ClothingSlot; 4] = [
+        ClothingSlot::Hat,
+        ClothingSlot::Top,
+        ClothingSlot::Bottom,
+        ClothingSlot::Outfit,
+    ];
+
+    pub(crate) fn member(&self) -> &'static str {
+        match self {
+            ClothingSlot::Hat => "<ClothingHat>k__BackingField",
+            ClothingSlot::Top => "<ClothingTop>k__BackingField",
+            ClothingSlot::Bottom => "<ClothingBottom>k__BackingField",
+            ClothingSlot::Outfit => "<ClothingOutfit>k__BackingField",
+        }
+    }
+
+    // List of owned pieces in PlayerWardrobeData
+    fn wardrobe_member(&self) -> &'static str {
+        match self {
+            ClothingSlot::Hat => "WardrobeHats",
+            ClothingSlot::Top => "WardrobeTops",
+            ClothingSlot::Bottom => "WardrobeBottoms",
+            ClothingSlot::Outfit => "WardrobeOutfits",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClothingSlot::Hat => "hat",
+            ClothingSlot::Top => "top",
+            ClothingSlot::Bottom => "bottom",
+            ClothingSlot::Outfit => "outfit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClothingPiece {
+    /// Nil when nothing is worn in this slot
+    pub prefab: Guid,
+    /// RGBA, 0.0..=1.0
+    pub color: [f32; 4],
+}
+
+#[derive(Debug, Clone)]
+pub struct PetInfo<'a> {
+    pub guid: Guid,
+    pub name: &'a str,
+}
+
+// Typed view of SavePlayerPersistentData (PlayerData_N.sav). The file holds no
+// player name or world position; those live in Steam and WorldData.
+#[derive(Debug, Clone)]
+pub struct PlayerData<'a> {
+    pub money: Option<i64>,
+    pub clothes: Vec<(ClothingSlot, ClothingPiece)>,
+    /// Number of owned pieces per wardrobe category (hats, tops, bottoms, outfits)
+    pub wardrobe_counts: [usize; 4],
+    pub vehicles: Vec<Guid>,
+    pub presents_unlocked: Vec<Guid>,
+    pub pets: Vec<PetInfo<'a>>,
+    pub selected_pet_index: Option<i64>,
+    pub unlocked_pets: Vec<Guid>,
+    /// `stat_*` counters kept in PlayerMiscData, e.g. stat_candycanes
+    pub misc_stats: Vec<(&'a str, i64)>,
+}
+
+const PLAYER_CLOTHES: &str = "<CurrentClothes>k__BackingField";
+const PLAYER_WARDROBE: &str = "<WardrobeData>k__BackingField";
+const PLAYER_VEHICLES: &str = "<VehiclesData>k__BackingField";
+const PLAYER_MISC: &str = "<MiscData>k__BackingField";
+const PLAYER_PETS: &str = "<PetData>k__BackingField";
+
+impl<'a> Document<'a> {
+    pub fn as_player_data(&'a self) -> Option<PlayerData<'a>> {
+        if self.root_class_name()? != "SavePlayerPersistentData" {
+            return None;
+        }
+        let root = self.as_object_value(self.root_value()?)?;
+        let misc = self
+            .member_value(root, PLAYER_MISC)
+            .and_then(|v| self.as_object_value(v));
+        let pets = self
+            .member_value(root, PLAYER_PETS)
+            .and_then(|v| self.as_object_value(v));
+        let guids = |v: Option<&'a Value<'a>>| -> Vec<Guid> {
+            v.map(|v| {
+                self.list_items(v)
+                    .into_iter()
+                    .filter_map(|g| self.get_guid(g))
+                    .collect()
+            })
+            .unwrap_or_default()
+        };
+
+        let mut clothes = Vec::new();
+        for slot in ClothingSlot::ALL {
+            let Some(piece) = self
+                .member_path(root, &[PLAYER_CLOTHES, slot.member()])
+                .and_then(|v| self.as_object_value(v))
+            else {
+                continue;
+            };
+            clothes.push((slot, self.clothing_piece(piece)));
+        }
+
+        let mut wardrobe_counts = [0; 4];
+        for (i, slot) in ClothingSlot::ALL.iter().enumerate() {
+            wardrobe_counts[i] = self
+                .member_path(root, &[PLAYER_WARDROBE, slot.wardrobe_member()])
+                .map(|v| self.list_items(v).len())
+                .unwrap_or(0);
+        }
+
+        let vehicles = self
+            .member_path(root, &[PLAYER_VEHICLES, "Vehicles"])
+            .map(|v| {
+                self.list_items(v)
+                    .into_iter()
+                    .filter_map(|veh| {
+                        let obj = self.as_object_value(veh)?;
+                        self.get_guid(self.member_value(obj, "VehicleID")?)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let pet_list = pets
+            .and_then(|p| self.member_value(p, "petDatas"))
+            .map(|v| {
+                self.list_items(v)
+                    .into_iter()
+                    .filter_map(|pet| {
+                        let obj = self.as_object_value(pet)?;
+                        Some(PetInfo {
+                            guid: self
+                                .member_value(obj, "guid")
+                                .and_then(|g| self.get_guid(g))
+                                .unwrap_or(Guid::NIL),
+                            name: self
+                                .member_value(obj, "petName")
+                                .and_then(|n| self.get_str(n))
+                                .unwrap_or(""),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let misc_stats = misc
+            .map(|m| {
+                m.members
+                    .iter()
+                    .filter(|(n, _)| n.starts_with("stat_"))
+                    .filter_map(|(n, v)| Some((*n, self.get_num(v)?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(PlayerData {
+            money: misc
+                .and_then(|m| self.member_value(m, "money"))
+                .and_then(|v| self.get_num(v)),
+            clothes,
+            wardrobe_counts,
+            vehicles,
+            presents_unlocked: guids(misc.and_then(|m| self.member_value(m, "presentsUnlocked"))),
+            pets: pet_list,
+            selected_pet_index: pets
+                .and_then(|p| self.member_value(p, "selectedPetIndex"))
+                .and_then(|v| self.get_num(v)),
+            unlocked_pets: guids(pets.and_then(|p| self.member_value(p, "unlockedPets"))),
+            misc_stats,
+        })
+    }
+}
+
+impl<'a> Document<'a> {
+    // ClothingPieceData: prefab GUID plus primary colour
+    fn clothing_piece(&'a self, piece: &'a DynObject<'a>) -> ClothingPiece {
+        let prefab = self
+            .member_value(piece, "clothingPrefabGUID")
+            .and_then(|v| self.get_guid(v))
+            .unwrap_or(Guid::NIL);
+        let mut color = [0.0; 4];
+        if let Some(c) = self
+            .member_value(piece, "clothingPrimaryColor")
+            .and_then(|v| self.as_object_value(v))
+        {
+            for (i, k) in ["r", "g", "b", "a"].iter().enumerate() {
+                color[i] = self
+                    .member_value(c, k)
+                    .and_then(|v| self.get_f32(v))
+                    .unwrap_or(0.0);
+            }
+        }
+        ClothingPiece { prefab, color }
+    }
+
+    pub fn as_character_appearance(&'a self) -> Option<CharacterAppearance> {
+        let player = self.as_player_data()?;
+        let root = self.as_object_value(self.root_value()?)?;
+        let wardrobe = ClothingSlot::ALL.map(|slot| {
+            self.member_path(root, &[PLAYER_WARDROBE, slot.wardrobe_member()])
+                .map(|v| {
+                    self.list_items(v)
+                        .into_iter()
+                        .filter_map(|p| self.as_object_value(p))
+                        .map(|p| self.clothing_piece(p))
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+        Some(CharacterAppearance {
+            worn: player.clothes,
+            wardrobe,
+        })
+    }
+}
+
+// What the character wears and the pieces the wardrobe offers for each slot.
+// The save has no skin colour or accessory fields and names no items: pieces
+// are prefab GUIDs, chosen by their index in the player's wardrobe.
+#[derive(Debug, Clone)]
+pub struct CharacterAppearance {
+    pub worn: Vec<(ClothingSlot, ClothingPiece)>,
+    /// Owned pieces in `ClothingSlot::ALL` order
+    pub wardrobe: [Vec<ClothingPiece>; 4],
+}
+
+impl CharacterAppearance {
+    pub fn worn(&self, slot: ClothingSlot) -> Option<&ClothingPiece> {
+        self.worn.iter().find(|(s, _)| *s == slot).map(|(_, p)| p)
+    }
+
+    pub fn options(&self, slot: ClothingSlot) -> &[ClothingPiece] {
+        let i = ClothingSlot::ALL
+            .iter()
+            .position(|s| *s == slot)
+            .unwrap_or(0);
+        &self.wardrobe[i]
+    }
+
+    // Wardrobe index of the worn piece, if it is one of the owned pieces
+    pub fn worn_index(&self, slot: ClothingSlot) -> Option<usize> {
+        let worn = self.worn(slot)?;
+        self.options(slot)
+            .iter()
+            .position(|p| p.prefab == worn.prefab && !p.prefab.is_nil())
+    }
+
+    /// Patch to wear wardrobe piece `index` in `slot`, or nothing with None.
+    pub fn wear(&self, slot: ClothingSlot, index: Option<usize>) -> Result<Vec<PatchOp>, String> {
+        let piece = match index {
+            Some(i) => *self.options(slot).get(i).ok_or_else(|| {
+                format!(
+                    "no {} #{} (wardrobe has {})",
+                    slot.name(),
+                    i,
+                    self.options(slot).len()
+                )
+            })?,
+            None => ClothingPiece {
+                prefab: Guid::NIL,
+                color: [0.0; 4],
+            },
+        };
+        Ok(PlayerData::set_clothing(slot, &piece))
+    }
+}
+
+// Setters: each returns the patch to apply to the document's edit JSON
+// (`parse_file_to_json_value`), where class members sit under `$value`.
+impl PlayerData<'_> {
+    pub fn set_money(money: i64) -> PatchOp {
+        PatchOp::replace(
+            format!("/root/{}/$value/money", esc(PLAYER_MISC)),
+            money.into(),
+        )
+    }
+
+    pub fn set_misc_stat(name: &str, value: i64) -> PatchOp {
+        PatchOp::replace(
+            format!("/root/{}/$value/{}", esc(PLAYER_MISC), esc(name)),
+            value.into(),
+        )
+    }
+
+    pub fn set_clothing(slot: ClothingSlot, piece: &ClothingPiece) -> Vec<PatchOp> {
+        let base = format!("/root/{}/{}", esc(PLAYER_CLOTHES), esc(slot.member()));
+        let [r, g, b, a] = piece.color;
+        vec![
+            PatchOp::replace(
+                format!("{}/clothingPrefabGUID", base),
+                piece.prefab.to_json(),
+            ),
+            PatchOp::replace(
+                format!("{}/clothingPrimaryColor", base),
+                serde_json::json!({"$class": "SerializableColor", "r": r, "g": g, "b": b, "a": a}),
+            ),
+        ]
+    }
+
+    pub fn set_pet_name(index: usize, name: &str) -> PatchOp {
+        PatchOp::replace(
+            format!(
+                "/root/{}/$value/petDatas/$value/{}/petName",
+                esc(PLAYER_PETS),
+                index
+            ),
+            name.into(),
+        )
+    }
+
+    pub fn set_selected_pet(index: i64) -> PatchOp {
+        PatchOp::replace(
+            format!("/root/{}/$value/selectedPetIndex", esc(PLAYER_PETS)),
+            index.into(),
+        )
+    }
+}
+
+// RFC 6901 escaping for a single pointer token
+fn esc(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissionState {
+    Completed,
+    /// Has progress data and `bMissionStarted` is true
+    Started,
+    NotStarted,
+}
+
+#[derive(Debug, Clone)]
+pub struct MissionEntry<'a> {
+    pub id: Guid,
+    pub state: MissionState,
+    /// Per-mission progress: the JSON string kept in SaveActiveMissionData.data
+    pub progress: Option<&'a str>,
+    /// Index into missions_Completed / missions_Data, when present there
+    completed_index: Option<usize>,
+    data_index: Option<usize>,
+}
+
+// Typed view of SaveMissionData (MissionData.sav). Only missions the save
+// already mentions are known; the game's full mission list is not stored.
+#[derive(Debug, Clone)]
+pub struct MissionData<'a> {
+    pub missions: Vec<MissionEntry<'a>>,
+    completed_len: usize,
+}
+
+impl<'a> Document<'a> {
+    pub fn as_mission_data(&'a self) -> Option<MissionData<'a>> {
+        if self.root_class_name()? != "SaveMissionData" {
+            return None;
+        }
+        let root = self.as_object_value(self.root_value()?)?;
+        let completed: Vec<Guid> = self
+            .member_value(root, "missions_Completed")
+            .map(|v| {
+                self.list_items(v)
+                    .into_iter()
+                    .filter_map(|g| self.get_guid(g))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut missions: Vec<MissionEntry<'a>> = Vec::new();
+        let data_items = self
+            .member_value(root, "missions_Data")
+            .map(|v| self.list_items(v))
+            .unwrap_or_default();
+        for (i, item) in data_items.into_iter().enumerate() {
+            let Some(obj) = self.as_object_value(item) else {
+                continue;
+            };
+            let Some(id) = self
+                .member_value(obj, "missionGuid")
+                .and_then(|g| self.get_guid(g))
+            else {
+                continue;
+            };
+            let progress = self.member_value(obj, "data").and_then(|d| self.get_str(d));
+            let started = progress
+                .and_then(|p| serde_json::from_str::<serde_json::Value>(p).ok())
+                .and_then(|v| v.get("bMissionStarted")?.as_bool())
+                .unwrap_or(false);
+            missions.push(MissionEntry {
+                id,
+                state: if started {
+                    MissionState::Started
+                } else {
+                    MissionState::NotStarted
+                },
+                progress,
+                completed_index: None,
+                data_index: Some(i),
+            });
+        }
+        for (i, id) in completed.iter().enumerate() {
+            match missions.iter_mut().find(|m| m.id == *id) {
+                Some(m) => {
+                    m.state = MissionState::Completed;
+                    m.completed_index = Some(i);
+                }
+                None => missions.push(MissionEntry {
+                    id: *id,
+                    state: MissionState::Completed,
+                    progress: None,
+                    completed_index: Some(i),
+                    data_index: None,
+                }),
+            }
+        }
+        Some(MissionData {
+            missions,
+            completed_len: completed.len(),
+        })
+    }
+}
+
+// Patches target the edit JSON, where both lists are flattened to arrays.
+impl MissionData<'_> {
+    pub fn get(&self, id: Guid) -> Option<&MissionEntry<'_>> {
+        self.missions.iter().find(|m| m.id == id)
+    }
+
+    // Mark completed; progress data is kept, as the game keeps it too
+    pub fn complete_mission(&self, id: Guid) -> Result<Vec<PatchOp>, String> {
+        let m = self
+            .get(id)
+            .ok_or_else(|| format!("mission {} not found in this save", id))?;
+        Ok(self.complete_ops(m, self.completed_len))
+    }
+
+    pub fn complete_all(&self) -> Vec<PatchOp> {
+        let mut ops = Vec::new();
+        let mut next = self.completed_len;
+        for m in &self.missions {
+            if m.state != MissionState::Completed {
+                ops.extend(self.complete_ops(m, next));
+                next += 1;
+            }
+        }
+        ops
+    }
+
+    // Forget completion and progress so the mission starts over
+    pub fn reset_mission(&self, id: Guid) -> Result<Vec<PatchOp>, String> {
+        let m = self
+            .get(id)
+            .ok_or_else(|| format!("mission {} not found in this save", id))?;
+        let mut ops = Vec::new();
+        if let Some(i) = m.completed_index {
+            ops.push(PatchOp::Remove {
+                path: format!("/root/missions_Completed/{}", i),
+            });
+        }
+        if let Some(i) = m.data_index {
+            ops.push(PatchOp::replace(
+                format!("/root/missions_Data/{}/$value/data", i),
+                serde_json::Value::Null,
+            ));
+        }
+        Ok(ops)
+    }
+
+    fn complete_ops(&self, m: &MissionEntry<'_>, at: usize) -> Vec<PatchOp> {
+        if m.state == MissionState::Completed {
+            return Vec::new();
+        }
+        vec![PatchOp::Add {
+            path: format!("/root/missions_Completed/{}", at),
+            value: m.id.to_json(),
+        }]
+    }
+}
+
+// A stat keeps the numeric kind it was saved with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatValue {
+    Int(i64),
+    Float(f64),
+}
+
+impl core::fmt::Display for StatValue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            StatValue::Int(n) => write!(f, "{}", n),
+            StatValue::Float(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+impl From<StatValue> for serde_json::Value {
+    fn from(v: StatValue) -> Self {
+        match v {
+            StatValue::Int(n) => n.into(),
+            StatValue::Float(x) => x.into(),
+        }
+    }
+}
+
+// Typed view of SaveStatsData (StatsData.sav): every numeric member, keyed by
+// its field name. Game versions differ in which stats exist, so none are
+// hard-coded; per-player `stat_*` counters live in PlayerData instead.
+#[derive(Debug, Clone)]
+pub struct StatsData<'a> {
+    pub stats: Vec<(&'a str, StatValue)>,
+}
+
+impl<'a> Document<'a> {
+    pub fn as_stats_data(&'a self) -> Option<StatsData<'a>> {
+        if self.root_class_name()? != "SaveStatsData" {
+            return None;
+        }
+        let root = self.as_object_value(self.root_value()?)?;
+        let stats = root
+            .members
+            .iter()
+            .filter_map(|(name, v)| {
+                let value = match self.resolve_value(v) {
+                    Value::F32(x) => StatValue::Float(*x as f64),
+                    Value::F64(x) => StatValue::Float(*x),
+                    _ => StatValue::Int(self.get_num(v)?),
+                };
+                Some((*name, value))
+            })
+            .collect();
+        Some(StatsData { stats })
+    }
+}
+
+impl StatsData<'_> {
+    pub fn get(&self, name: &str) -> Option<StatValue> {
+        self.stats.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)
+    }
+
+    // `value` is parsed as the stat's own kind, so "1.5" is rejected for an
+    // integer counter instead of silently changing its type.
+    pub fn set(&self, name: &str, value: &str) -> Result<PatchOp, String> {
+        let current = self
+            .get(name)
+            .ok_or_else(|| format!("unknown stat '{}'", name))?;
+        let new = match current {
+            StatValue::Int(_) => value
+                .trim()
+                .parse::<i64>()
+                .map(StatValue::Int)
+                .map_err(|_| format!("stat '{}' needs an integer", name))?,
+            StatValue::Float(_) => value
+                .trim()
+                .parse::<f64>()
+                .map(StatValue::Float)
+                .map_err(|_| format!("stat '{}' needs a number", name))?,
+        };
+        Ok(PatchOp::replace(format!("/root/{}", esc(name)), new.into()))
+    }
+}
+
+// Members holding where an object is in the world. Matched at the end of the
+// name, so spawnPosition counts too.
+pub const POSITION_MEMBERS: &[&str] = &["position", "Position"];
+
+// An object found while indexing a document
+#[derive(Debug, Clone)]
+pub struct IndexedObject<'a> {
+    pub class_name: &'a str,
+    /// First `System.Guid` member of the object, if any
+    pub guid: Option<Guid>,
+    /// JSON pointer of the object in the edit JSON (`parse_file_to_json_value`)
+    pub pointer: String,
+    pub object: &'a DynObject<'a>,
+}
+
+// Objects of a document grouped by class and looked up by GUID. Plumbing
+// classes (System.*, lists, colors) are skipped; shared references are
+// indexed once, at the first place the edit JSON shows them.
+#[derive(Debug, Clone, Default)]
+pub struct ObjectIndex<'a> {
+    objects: Vec<IndexedObject<'a>>,
+}
+
+impl<'a> ObjectIndex<'a> {
+    pub fn build(doc: &'a Document<'a>) -> Self {
+        let mut idx = ObjectIndex::default();
+        let mut seen = std::collections::HashSet::new();
+        if let Some(root) = doc.root_value() {
+            idx.walk(doc, root, "/root".to_string(), &mut seen, 0);
+        }
+        idx
+    }
+
+    fn walk(
+        &mut self,
+        doc: &'a Document<'a>,
+        v: &'a Value<'a>,
+        ptr: String,
+        seen: &mut std::collections::HashSet<i32>,
+        depth: usize,
+    ) {
+        if depth > 64 {
+            return;
+        }
+        match v {
+            Value::Ref(id) => {
+                if !seen.insert(*id) {
+                    return;
+                }
+                let Some(target) = doc.get_object(*id) else {
+                    return;
+                };
+                // Same List`1 flattening as document_to_json_value
+                if let Value::Object(obj) = target
+                    && obj
+                        .class_name
+                        .starts_with("System.Collections.Generic.List`1")
+                    && let Some(Value::Ref(items_id)) = doc.member_value(obj, "_items")
+                    && let Some(items) = doc.get_object(*items_id)
+                {
+                    self.walk(doc, items, ptr, seen, depth + 1);
+                    return;
+                }
+                self.walk(doc, target, format!("{}/$value", ptr), seen, depth + 1);
+            }
+            Value::Array(items) => {
+                for (i, it) in items.iter().enumerate() {
+                    self.walk(doc, it, format!("{}/{}", ptr, i), seen, depth + 1);
+                }
+            }
+            Value::Object(obj) => {
+                if !is_plumbing_class(obj.class_name) {
+                    let guid = obj
+                        .members
+                        .iter()
+                        .find_map(|(_, m)| match doc.as_object_value(m) {
+                            Some(o) if o.class_name == "System.Guid" => doc.get_guid(m),
+                            _ => None,
+                        });
+                    self.objects.push(IndexedObject {
+                        class_name: obj.class_name,
+                        guid,
+                        pointer: ptr.clone(),
+                        object: obj,
+                    });
+                }
+                for (name, m) in &obj.members {
+                    self.walk(doc, m, format!("{}/{}", ptr, esc(name)), seen, depth + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &IndexedObject<'a>> {
+        self.objects.iter()
+    }
+
+    // (class name, count), sorted by class name
+    pub fn classes(&self) -> Vec<(&'a str, usize)> {
+        let mut counts: std::collections::BTreeMap<&'a str, usize> = Default::default();
+        for o in &self.objects {
+            *counts.entry(o.class_name).or_default() += 1;
+        }
+        counts.into_iter().collect()
+    }
+
+    pub fn by_class(&self, class_name: &str) -> Vec<&IndexedObject<'a>> {
+        self.objects
+            .iter()
+            .filter(|o| o.class_name == class_name)
+            .collect()
+    }
+
+    pub fn by_guid(&self, guid: Guid) -> Option<&IndexedObject<'a>> {
+        self.objects.iter().find(|o| o.guid == Some(guid))
+    }
+}
+
+pub(crate) fn is_plumbing_class(name: &str) -> bool {
+    name.starts_with("System.")
+        || name.starts_with("HawkNetworking.HawkMessageList`1")
+        || name == "SerializableColor"
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldScene {
+    /// LoadScene enum value
+    pub scene: i64,
+    pub time_of_day: f32,
+    pub weather_index: i64,
+}
+
+// Typed view of SaveWorldData (WorldData.sav): per-scene time and weather plus
+// an index of every object stored in the file.
+#[derive(Debug, Clone)]
+pub struct WorldData<'a> {
+    pub scenes: Vec<WorldScene>,
+    pub index: ObjectIndex<'a>,
+}
+
+impl<'a> Document<'a> {
+    pub fn as_world_data(&'a self) -> Option<WorldData<'a>> {
+        if self.root_class_name()? != "SaveWorldData" {
+            return None;
+        }
+        let root = self.as_object_value(self.root_value()?)?;
+        let scenes = self
+            .member_value(root, "dataScenes")
+            .map(|v| {
+                self.list_items(v)
+                    .into_iter()
+                    .filter_map(|s| {
+                        let obj = self.as_object_value(s)?;
+                        let scene = self
+                            .member_path(obj, &["loadScene", "value__"])
+                            .and_then(|v| self.get_num(v))?;
+                        Some(WorldScene {
+                            scene,
+                            time_of_day: self
+                                .member_value(obj, "timeOfDay")
+                                .and_then(|v| self.get_f32(v))
+                                .unwrap_or(0.0),
+                            weather_index: self
+                                .member_value(obj, "weatherIndex")
+                                .and_then(|v| self.get_num(v))
+                                .unwrap_or(0),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(WorldData {
+            scenes,
+            index: ObjectIndex::build(self),
+        })
+    }
+}
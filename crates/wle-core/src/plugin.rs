@@ -0,0 +1,435 @@
+//! Document transforms: a name, the documents they apply to and a function
+//! from a document to the patch that changes it. Code implements `Transform`;
+//! everyone else writes `JsonTransform`s as JSON files, loaded at run time so
+//! new fixes and cheats ship without rebuilding the editor:
+//!
+//! ```json
+//! {
+//!   "name": "Wide view",
+//!   "description": "Field of view at least 90 and easy grab on",
+//!   "documents": ["settings"],
+//!   "rules": [
+//!     {"path": "/root/fov", "min": 90},
+//!     {"path": "/root/bUseEasyGrab", "set": true}
+//!   ]
+//! }
+//! ```
+//!
+//! A rule's `path` is a JSON Pointer into the edit JSON in which `*` stands
+//! for every item of an array or member of an object. Only nodes that exist
+//! are touched: `set` replaces them, `min` raises numbers below it and `max`
+//! lowers numbers above it, and `if` limits the rule to nodes equal to its
+//! value. Nodes already as wanted give no op. `documents` uses the names
+//! `saves::DocKind` accepts, where a bare "player" or "settings" matches every
+//! player; without it the transform applies to any document. A file holds one
+//! transform or an array of them.
+
+use crate::cheats::PendingEdit;
+use crate::edit::PatchOp;
+use crate::saves::DocKind;
+use serde_json::Value as J;
+use std::path::{Path, PathBuf};
+
+/// A named change to one kind of document, worked out from its edit JSON.
+pub trait Transform {
+    fn name(&self) -> &str;
+    fn description(&self) -> &str {
+        ""
+    }
+    /// Documents the transform understands (player numbers are ignored);
+    /// empty for any document
+    fn doc_kinds(&self) -> &[DocKind];
+    /// The ops that make the change; empty when there is nothing to do
+    fn transform(&self, doc: &J) -> Result<Vec<PatchOp>, String>;
+
+    fn applies_to(&self, kind: DocKind) -> bool {
+        let kinds = self.doc_kinds();
+        kinds.is_empty()
+            || kinds
+                .iter()
+                .any(|k| std::mem::discriminant(k) == std::mem::discriminant(&kind))
+    }
+}
+
+/// Work out `transform` on the .sav at `path`; nothing is written.
+pub fn plan(transform: &dyn Transform, path: &Path) -> Result<PendingEdit, String> {
+    let (_, ops) = load_and_transform(transform, path)?;
+    Ok(PendingEdit {
+        path: path.to_path_buf(),
+        ops,
+    })
+}
+
+fn load_and_transform(transform: &dyn Transform, path: &Path) -> Result<(J, Vec<PatchOp>), String> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if let Some(kind) = DocKind::from_file_name(&name)
+        && !transform.applies_to(kind)
+    {
+        return Err(format!("{} does not apply to {}", transform.name(), name));
+    }
+    let doc = crate::edit::parse_file_for_edit(path)?;
+    let ops = transform.transform(&doc)?;
+    Ok((doc, ops))
+}
+
+/// Run `transform` on the .sav at `src` and write the result to `dest`,
+/// leaving `src` as it was (e.g. an anonymized copy for a bug report).
+/// Returns how many ops it took.
+pub fn write_transformed(
+    transform: &dyn Transform,
+    src: &Path,
+    dest: &Path,
+) -> Result<usize, String> {
+    let (mut doc, ops) = load_and_transform(transform, src)?;
+    crate::edit::apply_patch(&mut doc, &ops)?;
+    crate::binfmt_write::write_binfmt_file_from_json(dest, &doc)?;
+    Ok(ops.len())
+}
+
+/// A transform defined in JSON (see the module docs).
+#[derive(Debug, Clone)]
+pub struct JsonTransform {
+    pub name: String,
+    pub description: String,
+    pub kinds: Vec<DocKind>,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub path: String,
+    pub only_if: Option<J>,
+    pub action: RuleAction,
+}
+
+#[derive(Debug, Clone)]
+pub enum RuleAction {
+    Set(J),
+    Min(f64),
+    Max(f64),
+}
+
+impl Transform for JsonTransform {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn description(&self) -> &str {
+        &self.description
+    }
+    fn doc_kinds(&self) -> &[DocKind] {
+        &self.kinds
+    }
+    fn transform(&self, doc: &J) -> Result<Vec<PatchOp>, String> {
+        let mut ops = Vec::new();
+        for rule in &self.rules {
+            for ptr in expand_pointer(doc, &rule.path) {
+                let Some(old) = doc.pointer(&ptr) else {
+                    continue;
+                };
+                if rule.only_if.as_ref().is_some_and(|want| want != old) {
+                    continue;
+                }
+                let new = match &rule.action {
+                    RuleAction::Set(v) => (v != old).then(|| v.clone()),
+                    RuleAction::Min(m) => old.as_f64().filter(|x| x < m).map(|_| number(*m, old)),
+                    RuleAction::Max(m) => old.as_f64().filter(|x| x > m).map(|_| number(*m, old)),
+                };
+                if let Some(value) = new {
+                    ops.push(PatchOp::replace(ptr, value));
+                }
+            }
+        }
+        Ok(ops)
+    }
+}
+
+/// Replaces what could identify a player with placeholders, so a save can be
+/// attached to a bug report: user-chosen names (pets, players), Steam IDs and
+/// the slot thumbnail. Types and sizes stay as they were, so the save still
+/// loads and shows the same problem. The same name or ID gets the same
+/// placeholder throughout a document, and an anonymized document gives no ops.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Anonymize;
+
+// Members holding a name the player typed
+const NAME_MEMBERS: &[&str] = &[
+    "petName",
+    "playerName",
+    "userName",
+    "displayName",
+    "nickName",
+    "ownerName",
+    "characterName",
+];
+// Byte arrays holding a picture of the game
+const IMAGE_MEMBERS: &[&str] = &["smallImageData"];
+// SteamID64s of individual accounts start here
+const STEAM_ID_BASE: u64 = 76561197960265728;
+const STEAM_ID_RANGE: u64 = 1 << 32;
+
+fn is_steam_id(n: u64) -> bool {
+    (STEAM_ID_BASE..STEAM_ID_BASE + STEAM_ID_RANGE).contains(&n)
+}
+
+impl Transform for Anonymize {
+    fn name(&self) -> &str {
+        "anonymize"
+    }
+    fn description(&self) -> &str {
+        "Replace names, Steam IDs and the thumbnail with placeholders"
+    }
+    fn doc_kinds(&self) -> &[DocKind] {
+        &[]
+    }
+    fn transform(&self, doc: &J) -> Result<Vec<PatchOp>, String> {
+        #[derive(Default)]
+        struct Seen {
+            names: Vec<String>,
+            ids: Vec<u64>,
+            ops: Vec<PatchOp>,
+        }
+        // Placeholder N of a kind: "Pet 2" for the second petName
+        fn name_for(seen: &mut Seen, member: &str, old: &str) -> String {
+            let prefix = member.strip_suffix("Name").unwrap_or(member);
+            let mut prefix = prefix.to_string();
+            if let Some(first) = prefix.get_mut(..1) {
+                first.make_ascii_uppercase();
+            }
+            let n = match seen.names.iter().position(|x| x == old) {
+                Some(i) => i + 1,
+                None => {
+                    seen.names.push(old.to_string());
+                    seen.names.len()
+                }
+            };
+            format!("{} {}", prefix, n)
+        }
+        // The Nth Steam ID becomes the Nth account id
+        fn id_for(seen: &mut Seen, old: u64) -> u64 {
+            let n = match seen.ids.iter().position(|x| *x == old) {
+                Some(i) => i + 1,
+                None => {
+                    seen.ids.push(old);
+                    seen.ids.len()
+                }
+            };
+            STEAM_ID_BASE + n as u64
+        }
+        fn walk(v: &J, ptr: &str, member: &str, seen: &mut Seen) {
+            let steam = member.to_ascii_lowercase().contains("steam");
+            let new = match v {
+                J::String(s) if NAME_MEMBERS.contains(&member) => {
+                    Some(J::from(name_for(seen, member, s)))
+                }
+                J::String(s) => match s.parse::<u64>() {
+                    Ok(n) if is_steam_id(n) || (steam && n != 0) => {
+                        Some(J::from(id_for(seen, n).to_string()))
+                    }
+                    _ => None,
+                },
+                J::Number(n) => match n.as_u64() {
+                    Some(n) if is_steam_id(n) || (steam && n != 0) => {
+                        Some(J::from(id_for(seen, n)))
+                    }
+                    _ => None,
+                },
+                J::Array(items) if IMAGE_MEMBERS.contains(&member) => {
+                    Some(J::from(vec![J::from(128); items.len()]))
+                }
+                _ => None,
+            };
+            // Placeholders are numbered in document order, so an anonymized
+            // document maps onto itself
+            if let Some(value) = new {
+                if *v != value {
+                    seen.ops.push(PatchOp::replace(ptr, value));
+                }
+                return;
+            }
+            match v {
+                J::Object(map) => {
+                    for (k, c) in map {
+                        // A boxed value belongs to the member around it
+                        let name = if k == "$value" { member } else { k.as_str() };
+                        let child = format!("{}/{}", ptr, crate::edit::escape_token(k));
+                        walk(c, &child, name, seen);
+                    }
+                }
+                J::Array(items) => {
+                    for (i, c) in items.iter().enumerate() {
+                        walk(c, &format!("{}/{}", ptr, i), "", seen);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut seen = Seen::default();
+        walk(doc, "", "", &mut seen);
+        Ok(seen.ops)
+    }
+}
+
+/// The transforms built into the editor, offered next to the ones loaded
+/// from files.
+pub fn builtin() -> Vec<Box<dyn Transform>> {
+    vec![Box::new(Anonymize)]
+}
+
+// `x` as an integer when the value it replaces is one
+fn number(x: f64, like: &J) -> J {
+    if like.is_i64() || like.is_u64() {
+        J::from(x as i64)
+    } else {
+        J::from(x)
+    }
+}
+
+/// Every existing pointer `pattern` matches, `*` segments standing for each
+/// array item or object member.
+pub fn expand_pointer(doc: &J, pattern: &str) -> Vec<String> {
+    let mut found = vec![(String::new(), doc)];
+    for seg in pattern.split('/').skip(1) {
+        let mut next = Vec::new();
+        for (ptr, node) in found {
+            if seg == "*" {
+                match node {
+                    J::Array(items) => next.extend(
+                        items
+                            .iter()
+                            .enumerate()
+                            .map(|(i, c)| (format!("{}/{}", ptr, i), c)),
+                    ),
+                    J::Object(map) => next
+                        .extend(map.iter().map(|(k, c)| {
+                            (format!("{}/{}", ptr, crate::edit::escape_token(k)), c)
+                        })),
+                    _ => {}
+                }
+            } else {
+                let child = format!("{}/{}", ptr, seg);
+                if let Some(c) = doc.pointer(&child) {
+                    next.push((child, c));
+                }
+            }
+        }
+        found = next;
+    }
+    found.into_iter().map(|(p, _)| p).collect()
+}
+
+/// The transforms in the JSON of a transform file.
+pub fn parse_transforms(v: &J) -> Result<Vec<JsonTransform>, String> {
+    match v {
+        J::Array(list) => list
+            .iter()
+            .enumerate()
+            .map(|(i, t)| parse_transform(t).map_err(|e| format!("transform {}: {}", i + 1, e)))
+            .collect(),
+        t => parse_transform(t).map(|t| vec![t]),
+    }
+}
+
+fn parse_transform(v: &J) -> Result<JsonTransform, String> {
+    let text = |k: &str| v.get(k).and_then(|x| x.as_str());
+    let name = text("name").ok_or("missing \"name\"")?;
+    let kinds = match v.get("documents") {
+        None => Vec::new(),
+        Some(J::Array(names)) => names
+            .iter()
+            .map(|n| {
+                n.as_str()
+                    .ok_or_else(|| "document names are strings".to_string())
+                    .and_then(doc_kind)
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err("\"documents\" must be an array".into()),
+    };
+    let rules = v
+        .get("rules")
+        .and_then(|r| r.as_array())
+        .ok_or("missing \"rules\" array")?
+        .iter()
+        .enumerate()
+        .map(|(i, r)| parse_rule(r).map_err(|e| format!("rule {}: {}", i + 1, e)))
+        .collect::<Result<_, _>>()?;
+    Ok(JsonTransform {
+        name: name.to_string(),
+        description: text("description").unwrap_or_default().to_string(),
+        kinds,
+        rules,
+    })
+}
+
+// "player" and "settings" without a number match every player
+pub(crate) fn doc_kind(name: &str) -> Result<DocKind, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "player" => Ok(DocKind::Player(0)),
+        "settings" => Ok(DocKind::PlayerSettings(0)),
+        _ => name.parse(),
+    }
+}
+
+fn parse_rule(v: &J) -> Result<Rule, String> {
+    let path = v
+        .get("path")
+        .and_then(|p| p.as_str())
+        .ok_or("missing \"path\"")?;
+    if !path.starts_with('/') {
+        return Err(format!("\"{}\" is not a JSON Pointer", path));
+    }
+    let bound = |k: &str| {
+        v.get(k)
+            .map(|x| x.as_f64().ok_or(format!("\"{}\" must be a number", k)))
+            .transpose()
+    };
+    let action = match (v.get("set"), bound("min")?, bound("max")?) {
+        (Some(value), None, None) => RuleAction::Set(value.clone()),
+        (None, Some(m), None) => RuleAction::Min(m),
+        (None, None, Some(m)) => RuleAction::Max(m),
+        _ => return Err("needs exactly one of \"set\", \"min\" or \"max\"".into()),
+    };
+    Ok(Rule {
+        path: path.to_string(),
+        only_if: v.get("if").cloned(),
+        action,
+    })
+}
+
+/// `parse_transforms` on a transform file (JSON, or YAML with the `yaml` feature).
+pub fn load_transforms(path: &Path) -> Result<Vec<JsonTransform>, String> {
+    parse_transforms(&crate::edit::read_json_file(path)?)
+}
+
+/// Every transform file in `dir` (*.json, and *.yaml with the `yaml` feature)
+/// in name order, each with its transforms or why it could not be read. A
+/// missing folder has none.
+pub fn load_transform_dir(dir: &Path) -> Vec<(PathBuf, Result<Vec<JsonTransform>, String>)> {
+    rule_files(dir)
+        .into_iter()
+        .map(|p| {
+            let t = load_transforms(&p);
+            (p, t)
+        })
+        .collect()
+}
+
+// The JSON (and YAML) files of `dir` in name order
+pub(crate) fn rule_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            let json = p
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+            p.is_file() && (json || (cfg!(feature = "yaml") && crate::edit::is_yaml_path(p)))
+        })
+        .collect();
+    files.sort();
+    files
+}
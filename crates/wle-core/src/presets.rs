@@ -0,0 +1,140 @@
+//! Named batches of edits offered as one-click presets: the built-in cheats
+//! plus user presets read from a JSON file. A preset only plans; the
+//! `PendingEdit` it returns is previewed and applied like any typed edit.
+//!
+//! A user preset file holds an array of presets (or `{"presets": [...]}`):
+//!
+//! ```json
+//! [{
+//!   "name": "Rich",
+//!   "description": "Money to 500000",
+//!   "file": "player",
+//!   "ops": [{"op": "replace", "path": "/root/<MiscData>k__BackingField/$value/money", "value": 500000}]
+//! }]
+//! ```
+//!
+//! `file` is "player" for the player the preset is applied to, or any name
+//! `saves::DocKind` accepts ("mission", "stats", "world", "player:2", ...).
+//! `ops` is a JSON Patch in the edit JSON of that file.
+
+use crate::cheats::{PendingEdit, Unlock};
+use crate::edit::PatchOp;
+use crate::saves::DocKind;
+use serde_json::Value as J;
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub struct Preset {
+    pub name: String,
+    pub description: String,
+    pub action: PresetAction,
+}
+
+#[derive(Debug, Clone)]
+pub enum PresetAction {
+    /// The player's money to the most the game stores (an Int32)
+    MaxMoney,
+    Unlock(Unlock),
+    CompleteMissions,
+    /// Ops for one file of the slot; None is the player's PlayerData
+    Patch {
+        file: Option<DocKind>,
+        ops: Vec<PatchOp>,
+    },
+}
+
+/// The cheats every slot can use.
+pub fn builtin() -> Vec<Preset> {
+    let preset = |name: &str, description: &str, action| Preset {
+        name: name.into(),
+        description: description.into(),
+        action,
+    };
+    vec![
+        preset(
+            "Max money",
+            "Set the player's money to 2147483647",
+            PresetAction::MaxMoney,
+        ),
+        preset(
+            "Unlock all clothing",
+            "Add every hat, top, bottom and outfit any player in these saves owns",
+            PresetAction::Unlock(Unlock::Clothing),
+        ),
+        preset(
+            "Unlock all vehicles",
+            "Add every vehicle any player in these saves owns",
+            PresetAction::Unlock(Unlock::Vehicles),
+        ),
+        preset(
+            "Unlock all presents",
+            "Mark every present any player in these saves unlocked as unlocked",
+            PresetAction::Unlock(Unlock::Items),
+        ),
+        preset(
+            "Complete all missions",
+            "Complete every mission of the slot not yet completed",
+            PresetAction::CompleteMissions,
+        ),
+    ]
+}
+
+/// User presets from the JSON of a preset file (see the module docs).
+pub fn parse_presets(v: &J) -> Result<Vec<Preset>, String> {
+    let list = v
+        .get("presets")
+        .unwrap_or(v)
+        .as_array()
+        .ok_or_else(|| "a preset file must hold an array of presets".to_string())?;
+    list.iter()
+        .enumerate()
+        .map(|(i, p)| parse_preset(p).map_err(|e| format!("preset {}: {}", i + 1, e)))
+        .collect()
+}
+
+fn parse_preset(v: &J) -> Result<Preset, String> {
+    let text = |k: &str| v.get(k).and_then(|x| x.as_str());
+    let name = text("name").ok_or("missing \"name\"")?;
+    let file = match text("file").unwrap_or("player") {
+        "player" => None,
+        f => Some(f.parse::<DocKind>()?),
+    };
+    let ops = crate::edit::parse_patch(v.get("ops").ok_or("missing \"ops\"")?)?;
+    Ok(Preset {
+        name: name.to_string(),
+        description: text("description").unwrap_or_default().to_string(),
+        action: PresetAction::Patch { file, ops },
+    })
+}
+
+/// `parse_presets` on a preset file.
+pub fn load_presets(path: &Path) -> Result<Vec<Preset>, String> {
+    parse_presets(&crate::edit::read_json_file(path)?)
+}
+
+impl Preset {
+    /// Work out the edit for `player` of `slot`; nothing is written.
+    pub fn plan(&self, slot: &Path, player: u32) -> Result<PendingEdit, String> {
+        use crate::cheats;
+        match &self.action {
+            PresetAction::MaxMoney => cheats::money_edit(slot, player, i32::MAX as i64),
+            PresetAction::Unlock(what) => {
+                let changes = cheats::plan_unlock(slot, player, *what)?;
+                Ok(cheats::unlock_edit(slot, player, &changes))
+            }
+            PresetAction::CompleteMissions => {
+                cheats::complete_all_missions_edit(slot).map(|(_, e)| e)
+            }
+            PresetAction::Patch { file, ops } => {
+                let path = slot.join(file.unwrap_or(DocKind::Player(player)).file_name());
+                if !path.is_file() {
+                    return Err(format!("{} not found", path.display()));
+                }
+                Ok(PendingEdit {
+                    path,
+                    ops: ops.clone(),
+                })
+            }
+        }
+    }
+}
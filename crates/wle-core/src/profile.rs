@@ -0,0 +1,203 @@
+//! Where the bytes of a .sav go. Every byte of the file is given to the
+//! deepest node of the edit JSON whose span (`Document::byte_spans`) covers
+//! it, then added up per subtree and per class, so a WorldData that grew to
+//! megabytes can be traced to the list or objects that grew with it.
+//!
+//! A member holding a reference and the record it points at are one node,
+//! named by the member's pointer. A record referenced from several places is
+//! counted once, at the first of them.
+
+use crate::binfmt::{ByteSpan, Document, Parser};
+use crate::json::JsonOpts;
+use serde_json::Value as J;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeNode {
+    /// JSON pointer in the edit JSON
+    pub pointer: String,
+    pub class: Option<String>,
+    /// Bytes of the node outside all its children
+    pub own: usize,
+    /// Bytes of the node and everything under it
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassSize {
+    pub class: String,
+    /// Nodes of the class that hold bytes of their own
+    pub count: usize,
+    /// Own bytes of those nodes and of the members without a class under them
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct SizeProfile {
+    pub file_size: usize,
+    /// Largest `total` first; a parent comes before a child of the same size,
+    /// so any prefix of the list holds the parents of its nodes
+    pub nodes: Vec<SizeNode>,
+}
+
+impl SizeProfile {
+    pub fn get(&self, pointer: &str) -> Option<&SizeNode> {
+        self.nodes.iter().find(|n| n.pointer == pointer)
+    }
+
+    /// The `n` largest subtrees, with their parents.
+    pub fn top(&self, n: usize) -> &[SizeNode] {
+        &self.nodes[..n.min(self.nodes.len())]
+    }
+
+    /// Bytes outside the tree: the stream header, library records, list
+    /// wrappers and records nothing refers to.
+    pub fn other(&self) -> usize {
+        let tree = self.get("/root").map_or(0, |n| n.total);
+        self.file_size.saturating_sub(tree)
+    }
+
+    /// Own bytes per class, largest first. Members that are not objects
+    /// (numbers, strings, arrays) count for the object around them.
+    pub fn by_class(&self) -> Vec<ClassSize> {
+        let by_ptr: HashMap<&str, &SizeNode> =
+            self.nodes.iter().map(|n| (n.pointer.as_str(), n)).collect();
+        let mut out: HashMap<&str, ClassSize> = HashMap::new();
+        for n in self.nodes.iter().filter(|n| n.own > 0) {
+            let mut at = Some(n);
+            while let Some(a) = at.filter(|a| a.class.is_none()) {
+                at = parent(&a.pointer).and_then(|p| by_ptr.get(p).copied());
+            }
+            let class = at.and_then(|a| a.class.as_deref()).unwrap_or("(none)");
+            let entry = out.entry(class).or_insert_with(|| ClassSize {
+                class: class.to_string(),
+                count: 0,
+                bytes: 0,
+            });
+            entry.count += usize::from(n.class.is_some());
+            entry.bytes += n.own;
+        }
+        let mut out: Vec<ClassSize> = out.into_values().collect();
+        out.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.class.cmp(&b.class)));
+        out
+    }
+}
+
+/// The pointer of the node holding `pointer`; None for "/root".
+pub fn parent(pointer: &str) -> Option<&str> {
+    let (up, _) = pointer.rsplit_once('/').filter(|(up, _)| !up.is_empty())?;
+    Some(up.strip_suffix("/$value").unwrap_or(up))
+}
+
+// The node a span pointer belongs to: a reference and its record are the
+// member holding them
+fn node_of(pointer: &str) -> &str {
+    pointer
+        .strip_suffix("/$ref")
+        .or_else(|| pointer.strip_suffix("/$value"))
+        .unwrap_or(pointer)
+}
+
+// Class of every object of the edit JSON, by pointer
+fn classes(v: &J, ptr: String, out: &mut HashMap<String, String>) {
+    match v {
+        J::Object(m) => {
+            if let Some(c) = m.get("$class").and_then(|c| c.as_str()) {
+                out.insert(node_of(&ptr).to_string(), c.to_string());
+            }
+            for (k, c) in m.iter().filter(|(k, _)| *k != "$class") {
+                classes(c, format!("{}/{}", ptr, crate::edit::escape_token(k)), out);
+            }
+        }
+        J::Array(a) => {
+            for (i, c) in a.iter().enumerate() {
+                classes(c, format!("{}/{}", ptr, i), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Profile a parsed document; `file_size` is the length of the bytes it was
+/// parsed from.
+pub fn profile_document(doc: &Document<'_>, file_size: usize) -> SizeProfile {
+    let mut spans: Vec<(String, ByteSpan)> = doc
+        .byte_spans()
+        .into_iter()
+        .filter(|(_, s)| !s.is_empty())
+        .collect();
+    spans.sort_by(|(pa, a), (pb, b)| {
+        (a.start, b.end, pa.len())
+            .cmp(&(b.start, a.end, pb.len()))
+            .then_with(|| pa.cmp(pb))
+    });
+
+    // Spans nest like the records they cover, so one pass with a stack of
+    // open spans gives each byte to the innermost one
+    let mut own: HashMap<String, usize> = HashMap::new();
+    let mut open: Vec<(String, ByteSpan)> = Vec::new();
+    let mut claimed: Option<(String, ByteSpan)> = None;
+    for (ptr, span) in spans {
+        // The same record again under another pointer: a shared reference
+        if let Some((first, s)) = &claimed
+            && *s == span
+            && !ptr.starts_with(&format!("{}/", first))
+        {
+            continue;
+        }
+        claimed = Some((ptr.clone(), span.clone()));
+        while open.last().is_some_and(|(_, s)| s.end <= span.start) {
+            open.pop();
+        }
+        if let Some((up, s)) = open.last() {
+            let inside = span.end.min(s.end) - span.start;
+            let n = own.get_mut(node_of(up)).expect("open spans are counted");
+            *n = n.saturating_sub(inside);
+        }
+        *own.entry(node_of(&ptr).to_string()).or_default() += span.len();
+        open.push((ptr, span));
+    }
+
+    let mut class = HashMap::new();
+    let json = crate::edit::document_to_json_value(doc, JsonOpts::full());
+    if let Some(root) = json.get("root") {
+        classes(root, "/root".to_string(), &mut class);
+    }
+    if let Some(c) = json.get("$rootClass").and_then(|c| c.as_str()) {
+        class
+            .entry("/root".to_string())
+            .or_insert_with(|| c.to_string());
+    }
+
+    // Children before parents: a child's pointer is longer
+    let mut order: Vec<String> = own.keys().cloned().collect();
+    order.sort_by_key(|p| std::cmp::Reverse(p.len()));
+    let mut total: HashMap<String, usize> = own.clone();
+    for p in &order {
+        let t = total[p];
+        if let Some(up) = parent(p) {
+            *total.entry(up.to_string()).or_default() += t;
+        }
+    }
+    let mut nodes: Vec<SizeNode> = total
+        .into_iter()
+        .map(|(pointer, total)| SizeNode {
+            own: own.get(&pointer).copied().unwrap_or(0),
+            class: class.get(&pointer).cloned(),
+            pointer,
+            total,
+        })
+        .collect();
+    nodes.sort_by(|a, b| {
+        (b.total, a.pointer.len())
+            .cmp(&(a.total, b.pointer.len()))
+            .then_with(|| a.pointer.cmp(&b.pointer))
+    });
+    SizeProfile { file_size, nodes }
+}
+
+/// Parse `data` as BinaryFormatter and profile it.
+pub fn profile_bytes(data: &[u8]) -> Result<SizeProfile, String> {
+    let doc = Parser::new(data).parse_stream()?;
+    Ok(profile_document(&doc, data.len()))
+}
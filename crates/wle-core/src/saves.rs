@@ -1,4 +1,6 @@
+use crate::model::SaveInfo;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 
 pub fn is_save_root(p: &Path) -> bool {
@@ -23,3 +25,1153 @@ pub fn list_slots(root: &Path) -> Vec<PathBuf> {
     out.sort();
     out
 }
+
+// Fail early, with the folder named, when files cannot be created in `dir`
+// (a save folder on read-only media, or owned by another user). Checked by
+// creating and removing a probe file, since permission bits miss ACLs and
+// read-only mounts. In read-only mode this always fails.
+pub fn check_writable(dir: &Path) -> io::Result<()> {
+    crate::binfmt_write::check_read_only_io()?;
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let probe = dir.join(format!(".wle-write-check.{}", std::process::id()));
+    let res = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&probe);
+    match res {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            Ok(())
+        }
+        Err(e) => {
+            let hint = match e.kind() {
+                io::ErrorKind::PermissionDenied => {
+                    "; check the folder's permissions, or open it read-only to only inspect it"
+                }
+                _ => "",
+            };
+            Err(io::Error::new(
+                e.kind(),
+                format!("cannot write to {}: {}{}", dir.display(), e, hint),
+            ))
+        }
+    }
+}
+
+// Numeric suffix of a `SaveSlot_N` directory
+pub fn slot_index(slot_dir: &Path) -> Option<u32> {
+    slot_dir
+        .file_name()
+        .and_then(|s| s.to_str())
+        .and_then(|n| n.strip_prefix("SaveSlot_"))
+        .and_then(|n| n.parse().ok())
+}
+
+pub fn slot_dir_for(root: &Path, index: u32) -> PathBuf {
+    root.join(format!("SaveSlot_{}", index))
+}
+
+// Lowest N >= 1 with no existing SaveSlot_N under `root`
+pub fn next_free_slot_index(root: &Path) -> u32 {
+    let mut n = 1;
+    while slot_dir_for(root, n).exists() {
+        n += 1;
+    }
+    n
+}
+
+// Copy a slot to `SaveSlot_<new_index>` (or the next free index) under
+// `dest_root`. The slot number only lives in the folder name; the files in a
+// slot (SlotInfo included) carry no slot id, so they are copied verbatim and
+// the clone shows the same thumbnail and last-played time as the original.
+pub fn clone_slot(
+    src_slot: &Path,
+    dest_root: &Path,
+    new_index: Option<u32>,
+) -> io::Result<PathBuf> {
+    crate::binfmt_write::check_read_only_io()?;
+    if !src_slot.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("not a slot directory: {}", src_slot.display()),
+        ));
+    }
+    let index = new_index.unwrap_or_else(|| next_free_slot_index(dest_root));
+    let dest = slot_dir_for(dest_root, index);
+    if dest.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", dest.display()),
+        ));
+    }
+    copy_dir(src_slot, &dest)?;
+    Ok(dest)
+}
+
+fn copy_dir(src: &Path, dest: &Path) -> io::Result<()> {
+    for entry in walkdir::WalkDir::new(src) {
+        let entry = entry.map_err(|e| io::Error::other(e.to_string()))?;
+        if crate::editor::is_file_backup(entry.path()) {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(src).unwrap();
+        let target = dest.join(rel);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+// Remove a slot folder. A zip backup is always taken first (next to the
+// slot, like every other slot backup); its path is returned.
+pub fn delete_slot(slot_dir: &Path) -> io::Result<PathBuf> {
+    delete_slot_to(slot_dir, slot_dir.parent().unwrap_or(Path::new(".")))
+}
+
+// Same as delete_slot, with the backup written into `backup_dir`
+pub fn delete_slot_to(slot_dir: &Path, backup_dir: &Path) -> io::Result<PathBuf> {
+    crate::binfmt_write::check_read_only_io()?;
+    if slot_index(slot_dir).is_none() || !slot_dir.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("not a SaveSlot_N directory: {}", slot_dir.display()),
+        ));
+    }
+    let backup = crate::editor::zip_backup_slot_to(slot_dir, backup_dir)?;
+    fs::remove_dir_all(slot_dir)?;
+    Ok(backup)
+}
+
+// Compact slot numbering to SaveSlot_1..N, keeping relative order. Every slot
+// that moves is backed up first, and SaveInfo.sav's `lastLoadedSlot` follows
+// its slot. The slot's backup zips are renamed along with it.
+// Returns (old, new) paths for the slots that moved.
+pub fn renumber_slots(root: &Path) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    renumber_slots_to(root, root)
+}
+
+// Same as renumber_slots, with the backups written into `backup_dir`
+pub fn renumber_slots_to(root: &Path, backup_dir: &Path) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    crate::binfmt_write::check_read_only_io()?;
+    let mut slots: Vec<(u32, PathBuf)> = list_slots(root)
+        .into_iter()
+        .filter_map(|p| slot_index(&p).map(|i| (i, p)))
+        .collect();
+    // list_slots sorts lexically (SaveSlot_10 < SaveSlot_2); order numerically
+    slots.sort_by_key(|(i, _)| *i);
+    let moves: Vec<(u32, u32, PathBuf)> = slots
+        .into_iter()
+        .enumerate()
+        .map(|(pos, (old, p))| (old, pos as u32 + 1, p))
+        .filter(|(old, new, _)| old != new)
+        .collect();
+    for (_, _, p) in &moves {
+        crate::editor::zip_backup_slot_to(p, backup_dir)?;
+    }
+    // Targets are always below their source and ascending, so no collisions
+    let mut out = Vec::new();
+    for (_, new, p) in &moves {
+        let dest = slot_dir_for(root, *new);
+        fs::rename(p, &dest)?;
+        crate::editor::rename_backups_in(p, &dest, backup_dir)?;
+        out.push((p.clone(), dest));
+    }
+    if let Some(last) = read_last_loaded_slot(root)
+        && let Some((_, new, _)) = moves.iter().find(|(old, _, _)| *old as i64 == last)
+    {
+        write_last_loaded_slot(root, *new as i64)?;
+    }
+    Ok(out)
+}
+
+fn read_last_loaded_slot(root: &Path) -> Option<i64> {
+    SaveInfo::load(&root.join(SaveInfo::FILE_NAME))
+        .ok()?
+        .last_loaded_slot()
+}
+
+fn write_last_loaded_slot(root: &Path, slot: i64) -> io::Result<()> {
+    let path = root.join(SaveInfo::FILE_NAME);
+    let mut info =
+        SaveInfo::load(&path).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    info.set_last_loaded_slot(slot);
+    info.save(&path).map_err(io::Error::other)
+}
+
+/// Writes and removals across several files of a save that land together.
+/// `commit` first writes every new file to a temp file next to its target,
+/// then moves the originals aside and the new files into place; if any step
+/// fails, the originals are put back, so a slot never ends up half edited.
+#[derive(Debug, Default)]
+pub struct Transaction {
+    steps: Vec<(PathBuf, Option<Vec<u8>>)>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write `bytes` to `path`, replacing an earlier step for the same path.
+    pub fn write(&mut self, path: impl Into<PathBuf>, bytes: impl Into<Vec<u8>>) -> &mut Self {
+        self.step(path.into(), Some(bytes.into()))
+    }
+
+    /// Delete `path` if it exists.
+    pub fn remove(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.step(path.into(), None)
+    }
+
+    fn step(&mut self, path: PathBuf, bytes: Option<Vec<u8>>) -> &mut Self {
+        self.steps.retain(|(p, _)| *p != path);
+        self.steps.push((path, bytes));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The files the transaction writes or removes, in order.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.steps.iter().map(|(p, _)| p.as_path())
+    }
+
+    pub fn commit(self) -> io::Result<()> {
+        let mut dirs: Vec<&Path> = self
+            .paths()
+            .map(|p| p.parent().unwrap_or(Path::new("")))
+            .collect();
+        dirs.dedup();
+        for dir in dirs {
+            check_writable(dir)?;
+        }
+        let aside = |p: &Path, ext: &str| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy();
+            p.with_file_name(format!(".{}.{}.{}", name, std::process::id(), ext))
+        };
+        // Probe first: on Windows the game keeps saves open without write sharing
+        for (path, _) in &self.steps {
+            if path.is_file()
+                && let Err(e) = fs::OpenOptions::new().append(true).open(path)
+            {
+                return Err(if crate::binfmt_write::is_lock_error(&e) {
+                    io::Error::other(crate::binfmt_write::WriteError::Locked(path.clone()))
+                } else {
+                    e
+                });
+            }
+        }
+
+        let mut staged: Vec<PathBuf> = Vec::new();
+        let stage = |staged: &mut Vec<PathBuf>| -> io::Result<()> {
+            use std::io::Write as _;
+            for (path, bytes) in &self.steps {
+                let Some(bytes) = bytes else { continue };
+                let tmp = aside(path, "tmp");
+                staged.push(tmp.clone());
+                let mut f = fs::File::create(&tmp)?;
+                f.write_all(bytes)?;
+                f.sync_all()?;
+            }
+            Ok(())
+        };
+        if let Err(e) = stage(&mut staged) {
+            for tmp in &staged {
+                let _ = fs::remove_file(tmp);
+            }
+            return Err(e);
+        }
+
+        // (original, where it was moved) and the new files already in place
+        let mut moved: Vec<(&Path, PathBuf)> = Vec::new();
+        let mut placed: Vec<&Path> = Vec::new();
+        let mut swap = || -> io::Result<()> {
+            for (path, _) in &self.steps {
+                if path.is_file() {
+                    let orig = aside(path, "orig");
+                    fs::rename(path, &orig)?;
+                    moved.push((path, orig));
+                }
+            }
+            for (path, bytes) in &self.steps {
+                if bytes.is_some() {
+                    fs::rename(aside(path, "tmp"), path)?;
+                    placed.push(path);
+                }
+            }
+            Ok(())
+        };
+        if let Err(e) = swap() {
+            let mut failed = Vec::new();
+            for path in placed {
+                let _ = fs::remove_file(path);
+            }
+            for (path, orig) in &moved {
+                if let Err(r) = fs::rename(orig, path) {
+                    failed.push(format!(
+                        "{} ({}, kept as {})",
+                        path.display(),
+                        r,
+                        orig.display()
+                    ));
+                }
+            }
+            for tmp in &staged {
+                let _ = fs::remove_file(tmp);
+            }
+            if !failed.is_empty() {
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!("{}; restoring failed for {}", e, failed.join(", ")),
+                ));
+            }
+            return Err(e);
+        }
+        for (_, orig) in &moved {
+            let _ = fs::remove_file(orig);
+        }
+        Ok(())
+    }
+}
+
+// A player of a slot is PlayerData_N.sav plus PlayerSettingsData_N.sav; the
+// game supports up to four local players.
+pub const MAX_PLAYERS: u32 = 4;
+
+// A never-played player as the game writes it on slot creation
+const BLANK_PLAYER: &[u8] =
+    include_bytes!("../../../reference-data/GameSaves/SaveSlot_1/PlayerData_2.sav");
+const BLANK_PLAYER_SETTINGS: &[u8] =
+    include_bytes!("../../../reference-data/GameSaves/SaveSlot_1/PlayerSettingsData_2.sav");
+
+/// A document `new` can create without another save to copy from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateKind {
+    PlayerData,
+    PlayerSettings,
+}
+
+impl std::str::FromStr for TemplateKind {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "player-data" => Ok(TemplateKind::PlayerData),
+            "player-settings" => Ok(TemplateKind::PlayerSettings),
+            _ => Err(format!(
+                "no template for '{}' (player-data, player-settings)",
+                s
+            )),
+        }
+    }
+}
+
+impl TemplateKind {
+    /// Template names for this kind; the first is the default.
+    pub fn names(self) -> &'static [&'static str] {
+        &["default"]
+    }
+
+    /// The embedded .sav bytes of a named template.
+    pub fn template(self, name: &str) -> Result<&'static [u8], String> {
+        match (self, name) {
+            (TemplateKind::PlayerData, "default") => Ok(BLANK_PLAYER),
+            (TemplateKind::PlayerSettings, "default") => Ok(BLANK_PLAYER_SETTINGS),
+            _ => Err(format!(
+                "unknown template '{}' (available: {})",
+                name,
+                self.names().join(", ")
+            )),
+        }
+    }
+}
+
+fn player_files(slot_dir: &Path, n: u32) -> [PathBuf; 2] {
+    [
+        slot_dir.join(DocKind::Player(n).file_name()),
+        slot_dir.join(DocKind::PlayerSettings(n).file_name()),
+    ]
+}
+
+fn check_player(n: u32) -> io::Result<()> {
+    if (1..=MAX_PLAYERS).contains(&n) {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("player must be 1..={}, got {}", MAX_PLAYERS, n),
+        ))
+    }
+}
+
+// Write PlayerData_N.sav (and its settings file) for a new player, copied from
+// player `template` of the same slot or, when None, a blank new player. A copy
+// gets fresh object ids (pets and the like, see `templates::fresh_ids`) so it
+// does not clash with its template.
+pub fn create_player(slot_dir: &Path, n: u32, template: Option<u32>) -> io::Result<PathBuf> {
+    check_player(n)?;
+    let (data_bytes, settings_bytes) = match template {
+        Some(t) => {
+            check_player(t)?;
+            let [src_data, src_settings] = player_files(slot_dir, t);
+            let settings = fs::read(&src_settings).unwrap_or_else(|_| BLANK_PLAYER_SETTINGS.into());
+            let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+            let doc = crate::edit::parse_bytes_for_edit(fs::read(&src_data)?).map_err(invalid)?;
+            let doc = crate::templates::fresh_ids(&doc);
+            let data = crate::binfmt_write::write_binfmt_from_json(&doc).map_err(invalid)?;
+            (data, settings)
+        }
+        None => (BLANK_PLAYER.to_vec(), BLANK_PLAYER_SETTINGS.to_vec()),
+    };
+    create_player_from(slot_dir, n, &data_bytes, Some(&settings_bytes))
+}
+
+// Write PlayerData_N.sav from `data` (a template's bytes, say). The settings
+// file is `settings`, or a blank player's when None; one already there is kept.
+// Both files are written in one transaction.
+pub fn create_player_from(
+    slot_dir: &Path,
+    n: u32,
+    data_bytes: &[u8],
+    settings_bytes: Option<&[u8]>,
+) -> io::Result<PathBuf> {
+    check_player(n)?;
+    let [data, settings] = player_files(slot_dir, n);
+    if data.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", data.display()),
+        ));
+    }
+    let mut tx = Transaction::new();
+    tx.write(&data, data_bytes);
+    if !settings.exists() {
+        tx.write(&settings, settings_bytes.unwrap_or(BLANK_PLAYER_SETTINGS));
+    }
+    tx.commit()?;
+    Ok(data)
+}
+
+// Delete a player's files. The slot is zip-backed up first (its path is
+// returned); if the game would open the removed player next, it falls back
+// to the first remaining one. The files and SlotInfo change in one transaction.
+pub fn remove_player(slot_dir: &Path, n: u32) -> io::Result<PathBuf> {
+    remove_player_to(slot_dir, n, slot_dir.parent().unwrap_or(Path::new(".")))
+}
+
+// Same as remove_player, with the backup written into `backup_dir`
+pub fn remove_player_to(slot_dir: &Path, n: u32, backup_dir: &Path) -> io::Result<PathBuf> {
+    check_player(n)?;
+    let [data, settings] = player_files(slot_dir, n);
+    if !data.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} does not exist", data.display()),
+        ));
+    }
+    let backup = crate::editor::zip_backup_slot_to(slot_dir, backup_dir)?;
+    let mut tx = Transaction::new();
+    tx.remove(&data).remove(&settings);
+    if read_last_selected_player(slot_dir) == Some(n) {
+        let first = (1..=MAX_PLAYERS)
+            .filter(|i| *i != n)
+            .find(|i| player_files(slot_dir, *i)[0].exists())
+            .unwrap_or(1);
+        select_player(&mut tx, slot_dir, first)?;
+    }
+    tx.commit()?;
+    Ok(backup)
+}
+
+// Exchange players `a` and `b` (either may be missing, which moves the other).
+// The slot is zip-backed up first and SlotInfo's selected player follows its
+// files, all in one transaction. Returns the backup.
+pub fn swap_players(slot_dir: &Path, a: u32, b: u32) -> io::Result<PathBuf> {
+    swap_players_to(slot_dir, a, b, slot_dir.parent().unwrap_or(Path::new(".")))
+}
+
+// Same as swap_players, with the backup written into `backup_dir`
+pub fn swap_players_to(slot_dir: &Path, a: u32, b: u32, backup_dir: &Path) -> io::Result<PathBuf> {
+    check_player(a)?;
+    check_player(b)?;
+    if a == b {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot swap a player with itself",
+        ));
+    }
+    let backup = crate::editor::zip_backup_slot_to(slot_dir, backup_dir)?;
+    let read = |p: &Path| -> io::Result<Option<Vec<u8>>> {
+        if p.exists() {
+            fs::read(p).map(Some)
+        } else {
+            Ok(None)
+        }
+    };
+    let mut tx = Transaction::new();
+    for (fa, fb) in player_files(slot_dir, a)
+        .into_iter()
+        .zip(player_files(slot_dir, b))
+    {
+        for (from, to) in [(&fa, &fb), (&fb, &fa)] {
+            match read(from)? {
+                Some(bytes) => tx.write(to, bytes),
+                None => tx.remove(to),
+            };
+        }
+    }
+    match read_last_selected_player(slot_dir) {
+        Some(n) if n == a => select_player(&mut tx, slot_dir, b)?,
+        Some(n) if n == b => select_player(&mut tx, slot_dir, a)?,
+        _ => {}
+    }
+    tx.commit()?;
+    Ok(backup)
+}
+
+// SlotInfo.sav stores the selected player 0-based; these take player numbers
+fn read_last_selected_player(slot_dir: &Path) -> Option<u32> {
+    let doc = crate::json::parse_binary(&slot_dir.join(DocKind::SlotInfo.file_name())).ok()?;
+    let last = doc.as_save_slot_info()?.last_selected_player_slot;
+    u32::try_from(last).ok().map(|i| i + 1)
+}
+
+// Add the SlotInfo.sav write selecting player `n` to `tx`
+fn select_player(tx: &mut Transaction, slot_dir: &Path, n: u32) -> io::Result<()> {
+    let bytes = slot_info_bytes(slot_dir, |info| {
+        info.last_selected_player_slot = n as i32 - 1
+    })?;
+    tx.write(slot_dir.join(DocKind::SlotInfo.file_name()), bytes);
+    Ok(())
+}
+
+// Load SlotInfo.sav, let `edit` change it and write it back atomically
+fn rewrite_slot_info(
+    slot_dir: &Path,
+    edit: impl FnOnce(&mut crate::model::SaveSlotInfoData<'_>),
+) -> io::Result<()> {
+    let path = slot_dir.join(DocKind::SlotInfo.file_name());
+    let bytes = slot_info_bytes(slot_dir, edit)?;
+    crate::binfmt_write::write_file_atomic(&path, &bytes).map_err(io::Error::other)
+}
+
+// SlotInfo.sav's bytes after `edit` changed it
+fn slot_info_bytes(
+    slot_dir: &Path,
+    edit: impl FnOnce(&mut crate::model::SaveSlotInfoData<'_>),
+) -> io::Result<Vec<u8>> {
+    let path = slot_dir.join(DocKind::SlotInfo.file_name());
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+    let doc = crate::json::parse_binary(&path).map_err(invalid)?;
+    let mut info = doc
+        .as_save_slot_info()
+        .ok_or_else(|| invalid("not a SlotInfo file".into()))?;
+    edit(&mut info);
+    Ok(crate::editor::build_slot_info_bytes(
+        info.last_selected_player_slot,
+        info.date_time,
+        &info.small_image_data,
+    ))
+}
+
+// Thumbnail used when a slot's current image is missing or undecodable
+const THUMBNAIL_SIDE: usize = 256;
+
+// Save the slot's SlotInfo thumbnail as a PNG
+pub fn export_thumbnail(slot_dir: &Path, out: &Path) -> io::Result<()> {
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+    let doc = crate::json::parse_binary(&slot_dir.join(DocKind::SlotInfo.file_name()))
+        .map_err(invalid)?;
+    let img = doc
+        .as_save_slot_info()
+        .and_then(|i| i.decode_image())
+        .ok_or_else(|| invalid("SlotInfo has no decodable thumbnail".into()))?;
+    fs::write(out, img.to_png().map_err(invalid)?)
+}
+
+// Replace the slot's thumbnail with a PNG, scaled to the current image size
+// and stored in the current pixel format. SlotInfo.sav is backed up first.
+pub fn import_thumbnail(slot_dir: &Path, png: &Path) -> io::Result<PathBuf> {
+    let src = crate::model::SlotImage::from_png(&fs::read(png)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let backup = crate::editor::backup_file(&slot_dir.join(DocKind::SlotInfo.file_name()), false)?;
+    rewrite_slot_info(slot_dir, |info| {
+        let (side, channels) = match info.decode_image() {
+            Some(cur) => (
+                cur.width,
+                info.small_image_data.len() / (cur.width * cur.height),
+            ),
+            None => (THUMBNAIL_SIDE, 3),
+        };
+        info.small_image_data = src.resized(side, side).encode(channels).into();
+    })?;
+    Ok(backup)
+}
+
+// A GameSaves folder found by detect_save_roots, with a human-readable origin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveRootCandidate {
+    pub label: String,
+    pub path: PathBuf,
+}
+
+// Wobbly Life's Steam app id (Proton prefixes and Steam Cloud folders)
+const STEAM_APP_ID: &str = "1211020";
+// Unity persistentDataPath parts: <base>/RubberBandGames/Wobbly Life/Save/<id>/GameSaves
+const UNITY_SUBDIR: [&str; 3] = ["RubberBandGames", "Wobbly Life", "Save"];
+
+// Probe the standard save locations of the current user on every platform.
+pub fn detect_save_roots() -> Vec<SaveRootCandidate> {
+    let home = std::env::var_os("USERPROFILE")
+        .or_else(|| std::env::var_os("HOME"))
+        .map(PathBuf::from);
+    let mut out = home
+        .as_deref()
+        .map(detect_save_roots_in)
+        .unwrap_or_default();
+    // Steam's default install keeps cloud files outside the user profile
+    for steam in [
+        PathBuf::from("C:/Program Files (x86)/Steam"),
+        PathBuf::from("C:/Program Files/Steam"),
+    ] {
+        probe_steam_userdata(&steam, &mut out);
+    }
+    out
+}
+
+// Same as detect_save_roots, with every location resolved against `home`.
+pub fn detect_save_roots_in(home: &Path) -> Vec<SaveRootCandidate> {
+    let mut out = Vec::new();
+    let unity = |base: PathBuf| UNITY_SUBDIR.iter().fold(base, |p, s| p.join(s));
+
+    probe_save_dir(
+        "Steam (Windows)",
+        &unity(home.join("AppData/LocalLow")),
+        &mut out,
+    );
+    probe_save_dir(
+        "Steam (Linux)",
+        &unity(home.join(".config/unity3d")),
+        &mut out,
+    );
+    probe_save_dir(
+        "macOS",
+        &unity(home.join("Library/Application Support")),
+        &mut out,
+    );
+
+    let steam_dirs = [
+        home.join(".steam/steam"),
+        home.join(".local/share/Steam"),
+        home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+        home.join("Library/Application Support/Steam"),
+    ];
+    for steam in &steam_dirs {
+        let pfx = steam
+            .join("steamapps/compatdata")
+            .join(STEAM_APP_ID)
+            .join("pfx/drive_c/users/steamuser/AppData/LocalLow");
+        probe_save_dir("Steam (Proton)", &unity(pfx), &mut out);
+        probe_steam_userdata(steam, &mut out);
+    }
+
+    // Xbox app / Game Pass installs live under a package folder
+    for pkg in subdirs(&home.join("AppData/Local/Packages")) {
+        let name = pkg.file_name().unwrap_or_default().to_string_lossy();
+        if name.contains("WobblyLife") || name.contains("RubberBandGames") {
+            probe_tree("Xbox Game Pass", &pkg, &mut out);
+        }
+    }
+    out
+}
+
+// <Save>/<id>/GameSaves, or a GameSaves folder passed directly
+fn probe_save_dir(label: &str, save_dir: &Path, out: &mut Vec<SaveRootCandidate>) {
+    for id_dir in subdirs(save_dir) {
+        let id = id_dir.file_name().unwrap_or_default().to_string_lossy();
+        let label = format!("{} {}", label, id);
+        push_candidate(&label, &id_dir.join("GameSaves"), out);
+        push_candidate(&label, &id_dir, out);
+    }
+    push_candidate(label, save_dir, out);
+}
+
+// Steam Cloud copies: <steam>/userdata/<account id>/<app id>/remote/...
+fn probe_steam_userdata(steam: &Path, out: &mut Vec<SaveRootCandidate>) {
+    for user in subdirs(&steam.join("userdata")) {
+        let id = user.file_name().unwrap_or_default().to_string_lossy();
+        let label = format!("Steam userdata {}", id);
+        probe_tree(&label, &user.join(STEAM_APP_ID), out);
+    }
+}
+
+fn probe_tree(label: &str, dir: &Path, out: &mut Vec<SaveRootCandidate>) {
+    if !dir.is_dir() {
+        return;
+    }
+    for e in walkdir::WalkDir::new(dir)
+        .max_depth(8)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if e.file_type().is_dir() {
+            push_candidate(label, e.path(), out);
+        }
+    }
+}
+
+fn push_candidate(label: &str, path: &Path, out: &mut Vec<SaveRootCandidate>) {
+    if is_save_root(path) && !out.iter().any(|c| c.path == path) {
+        out.push(SaveRootCandidate {
+            label: label.to_string(),
+            path: path.to_path_buf(),
+        });
+    }
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut out: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|rd| {
+            rd.flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    out.sort();
+    out
+}
+
+// What a dropped or typed path points at in a save tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveLocation {
+    /// GameSaves folder: SaveInfo.sav plus the SaveSlot_N folders
+    pub root: PathBuf,
+    pub slot: Option<PathBuf>,
+    /// A single .sav inside the slot or the root
+    pub file: Option<PathBuf>,
+}
+
+// Resolve a GameSaves folder, a SaveSlot_N folder, a .sav in either, or a
+// folder a few levels above a GameSaves folder (Save/<id>/GameSaves)
+pub fn locate_save_path(p: &Path) -> Option<SaveLocation> {
+    if p.is_file() {
+        if p.extension().and_then(|e| e.to_str()) != Some("sav") {
+            return None;
+        }
+        let mut loc = locate_save_path(p.parent()?)?;
+        if loc.root == p.parent()? || loc.slot.as_deref() == p.parent() {
+            loc.file = Some(p.to_path_buf());
+            return Some(loc);
+        }
+        return None;
+    }
+    if !p.is_dir() {
+        return None;
+    }
+    if slot_index(p).is_some() {
+        return Some(SaveLocation {
+            root: p.parent()?.to_path_buf(),
+            slot: Some(p.to_path_buf()),
+            file: None,
+        });
+    }
+    let root = if is_save_root(p) || !list_slots(p).is_empty() {
+        p.to_path_buf()
+    } else {
+        walkdir::WalkDir::new(p)
+            .max_depth(3)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .find(|e| e.file_type().is_dir() && is_save_root(e.path()))?
+            .into_path()
+    };
+    Some(SaveLocation {
+        root,
+        slot: None,
+        file: None,
+    })
+}
+
+// Per-user settings folder: %APPDATA% on Windows, ~/Library/Application
+// Support on macOS, $XDG_CONFIG_HOME or ~/.config elsewhere
+pub fn user_config_dir() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home.map(|h| h.join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|h| h.join(".config")))
+    }
+}
+
+// Size and parse health of one .sav file in a slot
+#[derive(Debug, Clone)]
+pub struct SaveFileStatus {
+    pub name: String,
+    pub size: u64,
+    /// Root class name for BinaryFormatter files, "JSON" for plain-text ones
+    pub kind: Option<String>,
+    pub error: Option<String>,
+}
+
+// Key facts about a slot, for slot lists and the CLI `slots` table
+#[derive(Debug, Clone)]
+pub struct SlotSummary {
+    pub slot_dir: PathBuf,
+    pub index: Option<u32>,
+    pub date_time: Option<String>,
+    pub last_selected_player: Option<i32>,
+    /// SlotInfo's thumbnail, decoded
+    pub thumbnail: Option<crate::model::SlotImage>,
+    /// N of every PlayerData_N.sav present, ascending
+    pub players: Vec<u32>,
+    pub files: Vec<SaveFileStatus>,
+}
+
+impl SlotSummary {
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+    pub fn is_healthy(&self) -> bool {
+        self.files.iter().all(|f| f.error.is_none())
+    }
+}
+
+// Parse every .sav in the slot (without keeping the documents) and collect
+// SlotInfo metadata plus per-file health.
+pub fn slot_summary(slot_dir: &Path) -> io::Result<SlotSummary> {
+    if !slot_dir.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("not a directory: {}", slot_dir.display()),
+        ));
+    }
+    let mut sum = SlotSummary {
+        slot_dir: slot_dir.to_path_buf(),
+        index: slot_index(slot_dir),
+        date_time: None,
+        last_selected_player: None,
+        thumbnail: None,
+        players: Vec::new(),
+        files: Vec::new(),
+    };
+    for p in crate::json::find_sav_files(slot_dir) {
+        let name = p
+            .file_name()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if let Some(n) = name
+            .strip_prefix("PlayerData_")
+            .and_then(|s| s.strip_suffix(".sav"))
+            .and_then(|s| s.parse::<u32>().ok())
+        {
+            sum.players.push(n);
+        }
+        let data = fs::read(&p)?;
+        let mut st = SaveFileStatus {
+            name,
+            size: data.len() as u64,
+            kind: None,
+            error: None,
+        };
+        if data.iter().copied().find(|b| !b.is_ascii_whitespace()) == Some(b'{') {
+            match serde_json::from_slice::<serde_json::Value>(&data) {
+                Ok(_) => st.kind = Some("JSON".into()),
+                Err(e) => st.error = Some(e.to_string()),
+            }
+        } else {
+            match crate::binfmt::Parser::new(&data).parse_stream() {
+                Ok(doc) => {
+                    st.kind = doc.root_class_name().map(|s| s.to_string());
+                    if st.name == "SlotInfo.sav"
+                        && let Some(info) = doc.as_save_slot_info()
+                    {
+                        sum.date_time = Some(info.date_time.to_string());
+                        sum.last_selected_player = Some(info.last_selected_player_slot);
+                        sum.thumbnail = info.decode_image();
+                    }
+                }
+                Err(e) => st.error = Some(e),
+            }
+        }
+        sum.files.push(st);
+    }
+    sum.players.sort_unstable();
+    Ok(sum)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+// A .sav file in the watched slot whose size or mtime changed on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotChange {
+    pub path: PathBuf,
+    pub kind: SlotChangeKind,
+}
+
+type FileStamp = (u64, Option<std::time::SystemTime>);
+
+// Watches one slot folder for .sav files changed by someone else (usually the
+// game). Raw notify events are checked against a size/mtime snapshot, so
+// writes the editor reports via `resync` do not show up as changes.
+pub struct SlotWatcher {
+    slot_dir: PathBuf,
+    _watcher: notify::RecommendedWatcher,
+    rx: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+    known: std::collections::HashMap<PathBuf, FileStamp>,
+}
+
+impl SlotWatcher {
+    pub fn new(slot_dir: &Path) -> io::Result<Self> {
+        use notify::Watcher;
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx).map_err(io::Error::other)?;
+        watcher
+            .watch(slot_dir, notify::RecursiveMode::NonRecursive)
+            .map_err(io::Error::other)?;
+        let mut w = SlotWatcher {
+            slot_dir: slot_dir.to_path_buf(),
+            _watcher: watcher,
+            rx,
+            known: Default::default(),
+        };
+        w.resync();
+        Ok(w)
+    }
+
+    pub fn slot_dir(&self) -> &Path {
+        &self.slot_dir
+    }
+
+    // Accept the current state of every .sav as known; call after the editor
+    // itself writes into the slot.
+    pub fn resync(&mut self) {
+        self.known = crate::json::find_sav_files(&self.slot_dir)
+            .into_iter()
+            .filter_map(|p| file_stamp(&p).map(|s| (p, s)))
+            .collect();
+    }
+
+    // Drain pending events without blocking; one entry per changed file.
+    pub fn try_changes(&mut self) -> Vec<SlotChange> {
+        let mut paths = Vec::new();
+        while let Ok(ev) = self.rx.try_recv() {
+            collect_event_paths(ev, &mut paths);
+        }
+        self.diff_paths(paths)
+    }
+
+    // Block until at least one change is seen (None once the watcher is gone).
+    pub fn wait_changes(&mut self) -> Option<Vec<SlotChange>> {
+        loop {
+            let mut paths = Vec::new();
+            collect_event_paths(self.rx.recv().ok()?, &mut paths);
+            // Editors and the game often emit several events per save
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            while let Ok(ev) = self.rx.try_recv() {
+                collect_event_paths(ev, &mut paths);
+            }
+            let changes = self.diff_paths(paths);
+            if !changes.is_empty() {
+                return Some(changes);
+            }
+        }
+    }
+
+    fn diff_paths(&mut self, mut paths: Vec<PathBuf>) -> Vec<SlotChange> {
+        paths.sort();
+        paths.dedup();
+        let mut out = Vec::new();
+        for path in paths {
+            if path.extension().and_then(|s| s.to_str()) != Some("sav") {
+                continue;
+            }
+            let kind = match (self.known.get(&path), file_stamp(&path)) {
+                (Some(old), Some(new)) if *old == new => continue,
+                (Some(_), Some(new)) => {
+                    self.known.insert(path.clone(), new);
+                    SlotChangeKind::Modified
+                }
+                (None, Some(new)) => {
+                    self.known.insert(path.clone(), new);
+                    SlotChangeKind::Created
+                }
+                (Some(_), None) => {
+                    self.known.remove(&path);
+                    SlotChangeKind::Removed
+                }
+                (None, None) => continue,
+            };
+            out.push(SlotChange { path, kind });
+        }
+        out
+    }
+}
+
+fn collect_event_paths(ev: notify::Result<notify::Event>, out: &mut Vec<PathBuf>) {
+    if let Ok(ev) = ev
+        && !ev.kind.is_access()
+    {
+        out.extend(ev.paths);
+    }
+}
+
+fn file_stamp(p: &Path) -> Option<FileStamp> {
+    let md = fs::metadata(p).ok()?;
+    md.is_file().then(|| (md.len(), md.modified().ok()))
+}
+
+// The .sav documents a slot can contain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocKind {
+    SlotInfo,
+    Player(u32),
+    PlayerSettings(u32),
+    Mission,
+    Stats,
+    World,
+}
+
+impl DocKind {
+    pub fn file_name(&self) -> String {
+        match self {
+            DocKind::SlotInfo => "SlotInfo.sav".into(),
+            DocKind::Player(n) => format!("PlayerData_{}.sav", n),
+            DocKind::PlayerSettings(n) => format!("PlayerSettingsData_{}.sav", n),
+            DocKind::Mission => "MissionData.sav".into(),
+            DocKind::Stats => "StatsData.sav".into(),
+            DocKind::World => "WorldData.sav".into(),
+        }
+    }
+
+    /// The kind of a slot file name, the inverse of `file_name`.
+    pub fn from_file_name(name: &str) -> Option<DocKind> {
+        let numbered = |prefix: &str| {
+            name.strip_prefix(prefix)?
+                .strip_suffix(".sav")?
+                .parse::<u32>()
+                .ok()
+        };
+        match name {
+            "SlotInfo.sav" => Some(DocKind::SlotInfo),
+            "MissionData.sav" => Some(DocKind::Mission),
+            "StatsData.sav" => Some(DocKind::Stats),
+            "WorldData.sav" => Some(DocKind::World),
+            _ => numbered("PlayerData_")
+                .map(DocKind::Player)
+                .or_else(|| numbered("PlayerSettingsData_").map(DocKind::PlayerSettings)),
+        }
+    }
+}
+
+// The content kind of a slot file, without its player number
+impl From<DocKind> for crate::model::DocKind {
+    fn from(kind: DocKind) -> Self {
+        use crate::model::DocKind as K;
+        match kind {
+            DocKind::SlotInfo => K::SlotInfo,
+            DocKind::Player(_) => K::PlayerData,
+            DocKind::PlayerSettings(_) => K::PlayerSettings,
+            DocKind::Mission => K::MissionData,
+            DocKind::Stats => K::StatsData,
+            DocKind::World => K::WorldData,
+        }
+    }
+}
+
+// Accepts "slotinfo", "player:N", "settings:N", "mission", "stats", "world"
+impl std::str::FromStr for DocKind {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        let lower = s.to_ascii_lowercase();
+        let (name, num) = match lower.split_once(':') {
+            Some((n, i)) => (
+                n,
+                Some(
+                    i.parse::<u32>()
+                        .map_err(|_| format!("bad player number in '{}'", s))?,
+                ),
+            ),
+            None => (lower.as_str(), None),
+        };
+        match (name, num) {
+            ("slotinfo", None) => Ok(DocKind::SlotInfo),
+            ("player", Some(n)) => Ok(DocKind::Player(n)),
+            ("settings", Some(n)) => Ok(DocKind::PlayerSettings(n)),
+            ("mission", None) => Ok(DocKind::Mission),
+            ("stats", None) => Ok(DocKind::Stats),
+            ("world", None) => Ok(DocKind::World),
+            _ => Err(format!(
+                "unknown document '{}' (slotinfo, player:N, settings:N, mission, stats, world)",
+                s
+            )),
+        }
+    }
+}
+
+// Copy one whole document between slots. The destination file, if any, is
+// backed up next to itself first; that backup path is returned.
+pub fn copy_document(
+    src_slot: &Path,
+    dest_slot: &Path,
+    kind: DocKind,
+) -> io::Result<Option<PathBuf>> {
+    crate::binfmt_write::check_read_only_io()?;
+    let src = src_slot.join(kind.file_name());
+    let dest = dest_slot.join(kind.file_name());
+    if !src.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("missing {}", src.display()),
+        ));
+    }
+    let data = fs::read(&src)?;
+    let backup = if dest.exists() {
+        Some(crate::editor::backup_file(&dest, false)?)
+    } else {
+        None
+    };
+    crate::binfmt_write::write_file_atomic(&dest, &data).map_err(io::Error::other)?;
+    Ok(backup)
+}
+
+// Copy the value at `pointer` (e.g. a player's money or clothes) from one
+// slot's document into the same place of another slot's document. The
+// pointer must already exist in the destination, which is backed up first.
+pub fn copy_pointer(
+    src_slot: &Path,
+    dest_slot: &Path,
+    kind: DocKind,
+    pointer: &str,
+) -> Result<PathBuf, String> {
+    let opts = crate::json::JsonOpts::full();
+    let src = crate::edit::parse_file_to_json_value(&src_slot.join(kind.file_name()), opts)?;
+    let dest_path = dest_slot.join(kind.file_name());
+    let mut dest = crate::edit::parse_file_to_json_value(&dest_path, opts)?;
+    let value = src
+        .pointer(pointer)
+        .cloned()
+        .ok_or_else(|| format!("json pointer not found in source: {}", pointer))?;
+    crate::edit::set_raw_by_pointer(&mut dest, pointer, value)?;
+    let backup = crate::editor::backup_file(&dest_path, false).map_err(|e| e.to_string())?;
+    crate::binfmt_write::write_binfmt_file_from_json(&dest_path, &dest)?;
+    Ok(backup)
+}
@@ -0,0 +1,124 @@
+//! Rhai scripts over one document, for edits a patch or transform cannot
+//! express because they depend on what the document holds:
+//!
+//! ```rhai
+//! // give every locked vehicle to player 1
+//! for p in search("Vehicle", "class") {
+//!     if get(p + "/bLocked") == true {
+//!         set(p + "/bLocked", false);
+//!         set(p + "/ownerPlayerId", 1);
+//!     }
+//! }
+//! ```
+//!
+//! Scripts see the edit JSON (`JsonOpts::full`) through JSON Pointers:
+//! - `get(ptr)`: the value there as a Rhai value, or `()` when missing
+//! - `exists(ptr)`: whether there is a node at `ptr`
+//! - `set(ptr, value)`: replace an existing node
+//! - `remove(ptr)`: remove a member or array item
+//! - `list(ptr)`: pointers to the children of an object or array
+//! - `search(text)`, `search(text, scope)`: pointers to nodes matching `text`,
+//!   scope being "keys", "values", "class" or "all" (the default)
+//!
+//! Edits are seen by later calls in the same script, and come back as the
+//! patch that makes them so they can be previewed, saved or undone like any
+//! other edit. `print` output is collected rather than written anywhere.
+
+use crate::edit::{PatchOp, SearchScope};
+use rhai::{Array, Dynamic, Engine, EvalAltResult};
+use serde_json::Value as J;
+use std::sync::{Arc, Mutex};
+
+// Stops a runaway loop long before it looks like a hang
+const MAX_OPERATIONS: u64 = 200_000_000;
+
+/// What a script did: the ops for its edits, in order, and what it printed.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptRun {
+    pub ops: Vec<PatchOp>,
+    pub output: Vec<String>,
+}
+
+struct Work {
+    doc: J,
+    run: ScriptRun,
+}
+
+type Shared = Arc<Mutex<Work>>;
+type Res<T> = Result<T, Box<EvalAltResult>>;
+
+/// Run `script` against `doc`, which is left as it is.
+pub fn run_script(script: &str, doc: &J) -> Result<ScriptRun, String> {
+    let work: Shared = Arc::new(Mutex::new(Work {
+        doc: doc.clone(),
+        run: ScriptRun::default(),
+    }));
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    let w = work.clone();
+    engine.on_print(move |s| w.lock().unwrap().run.output.push(s.to_string()));
+    let w = work.clone();
+    engine.on_debug(move |s, _, _| w.lock().unwrap().run.output.push(s.to_string()));
+    let w = work.clone();
+    engine.register_fn("get", move |ptr: &str| -> Res<Dynamic> {
+        match w.lock().unwrap().doc.pointer(ptr) {
+            Some(v) => rhai::serde::to_dynamic(v),
+            None => Ok(Dynamic::UNIT),
+        }
+    });
+    let w = work.clone();
+    engine.register_fn("exists", move |ptr: &str| {
+        w.lock().unwrap().doc.pointer(ptr).is_some()
+    });
+    let w = work.clone();
+    engine.register_fn("set", move |ptr: &str, value: Dynamic| -> Res<()> {
+        let value: J = rhai::serde::from_dynamic(&value)?;
+        let mut w = w.lock().unwrap();
+        crate::edit::set_raw_by_pointer(&mut w.doc, ptr, value.clone())?;
+        w.run.ops.push(PatchOp::replace(ptr, value));
+        Ok(())
+    });
+    let w = work.clone();
+    engine.register_fn("remove", move |ptr: &str| -> Res<()> {
+        let mut w = w.lock().unwrap();
+        crate::edit::remove_at_pointer(&mut w.doc, ptr)?;
+        w.run.ops.push(PatchOp::Remove {
+            path: ptr.to_string(),
+        });
+        Ok(())
+    });
+    let w = work.clone();
+    engine.register_fn("list", move |ptr: &str| -> Res<Array> {
+        let w = w.lock().unwrap();
+        let children = crate::edit::list_children(&w.doc, ptr)?;
+        Ok(children
+            .into_iter()
+            .map(|c| format!("{}/{}", ptr, crate::edit::escape_token(&c.key_or_index)).into())
+            .collect())
+    });
+    let w = work.clone();
+    engine.register_fn("search", move |text: &str| {
+        search(&w.lock().unwrap().doc, text, SearchScope::All)
+    });
+    let w = work.clone();
+    engine.register_fn("search", move |text: &str, scope: &str| -> Res<Array> {
+        let scope = match scope.to_ascii_lowercase().as_str() {
+            "keys" => SearchScope::Keys,
+            "values" => SearchScope::Values,
+            "class" => SearchScope::Class,
+            "all" => SearchScope::All,
+            _ => return Err(format!("unknown search scope \"{}\"", scope).into()),
+        };
+        Ok(search(&w.lock().unwrap().doc, text, scope))
+    });
+    engine.run(script).map_err(|e| e.to_string())?;
+    let run = std::mem::take(&mut work.lock().unwrap().run);
+    Ok(run)
+}
+
+fn search(doc: &J, text: &str, scope: SearchScope) -> Array {
+    crate::edit::search(doc, text, scope, usize::MAX)
+        .into_iter()
+        .map(|h| h.pointer.into())
+        .collect()
+}
@@ -0,0 +1,397 @@
+//! serde into BinaryFormatter, the other half of `de`: typed structs written
+//! straight to .sav bytes through `DocumentBuilder`.
+//!
+//! ```
+//! #[derive(serde::Serialize)]
+//! #[serde(rename = "PlayerMiscData")]
+//! struct MiscData { money: i32 }
+//! #[derive(serde::Serialize)]
+//! #[serde(rename = "PlayerData")]
+//! struct PlayerData {
+//!     #[serde(rename = "<MiscData>k__BackingField")]
+//!     misc: MiscData,
+//! }
+//! let bytes = wle_core::ser::to_bytes(&PlayerData { misc: MiscData { money: 500 } }).unwrap();
+//! ```
+//!
+//! A struct is written as an object whose class is the struct's serde name
+//! (rename the container to the .NET class), maps as objects of class
+//! `Object`, and unit enum variants as a .NET enum: an object of the enum's
+//! class holding the variant index as `value__`. Numbers keep their width
+//! (`i8`, `i16` and `u16` become Int32), `None` and `()` are null members and
+//! sequences become byte, number and string arrays, or object arrays when
+//! their items are structs or `None`. A game `List<T>` is a struct of its own
+//! with `_items` and `_size`. The root must be a struct.
+
+use crate::binfmt_write::DocumentBuilder;
+use crate::de::Error;
+use serde::ser::{self, Impossible, Serialize};
+use std::fmt;
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+/// The .sav bytes for `value`, which must serialize as a struct.
+pub fn to_bytes<T: ?Sized + Serialize>(value: &T) -> Result<Vec<u8>, Error> {
+    let Node::Object { class, members } = value.serialize(NodeSer)? else {
+        return Err(Error("the root of a .sav must be a struct".into()));
+    };
+    let mut b = DocumentBuilder::new();
+    b.begin_object(&class);
+    for (name, node) in &members {
+        member(&mut b, name, node)?;
+    }
+    b.end_object();
+    b.finish().map_err(Error)
+}
+
+// A serialized value before it is laid out as records; sequences need all of
+// their items to choose an array type
+enum Node {
+    Null,
+    Bool(bool),
+    I32(i32),
+    I64(i64),
+    U8(u8),
+    U32(u32),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    Str(String),
+    Object {
+        class: String,
+        members: Vec<(String, Node)>,
+    },
+    Array(Vec<Node>),
+}
+
+fn member(b: &mut DocumentBuilder, name: &str, node: &Node) -> Result<(), Error> {
+    match node {
+        Node::Null => b.member_null(name),
+        Node::Bool(v) => b.member_bool(name, *v),
+        Node::I32(v) => b.member_i32(name, *v),
+        Node::I64(v) => b.member_i64(name, *v),
+        Node::U8(v) => b.member_u8(name, *v),
+        Node::U32(v) => b.member_u32(name, *v),
+        Node::U64(v) => b.member_u64(name, *v),
+        Node::F32(v) => b.member_f32(name, *v),
+        Node::F64(v) => b.member_f64(name, *v),
+        Node::Str(v) => b.member_str(name, v),
+        Node::Object { class, members } => {
+            b.member_object(name, class);
+            for (k, v) in members {
+                member(b, k, v)?;
+            }
+            b.end_object()
+        }
+        Node::Array(items) => return array(b, name, items),
+    };
+    Ok(())
+}
+
+fn array(b: &mut DocumentBuilder, name: &str, items: &[Node]) -> Result<(), Error> {
+    // Every item as `f`'s kind, or None when one is not
+    fn all<'a, T>(items: &'a [Node], f: impl Fn(&'a Node) -> Option<T>) -> Option<Vec<T>> {
+        items.iter().map(f).collect()
+    }
+    let objects = items
+        .iter()
+        .all(|n| matches!(n, Node::Object { .. } | Node::Null));
+    if objects {
+        b.begin_array(name);
+        for item in items {
+            match item {
+                Node::Object { class, members } => {
+                    b.begin_object(class);
+                    for (k, v) in members {
+                        member(b, k, v)?;
+                    }
+                    b.end_object();
+                }
+                _ => {
+                    b.null_element();
+                }
+            }
+        }
+        b.end_array();
+    } else if let Some(v) = all(items, |n| match n {
+        Node::U8(x) => Some(*x),
+        _ => None,
+    }) {
+        b.member_bytes(name, &v);
+    } else if let Some(v) = all(items, |n| match n {
+        Node::I32(x) => Some(*x),
+        _ => None,
+    }) {
+        b.member_i32_array(name, &v);
+    } else if let Some(v) = all(items, |n| match n {
+        Node::I64(x) => Some(*x),
+        _ => None,
+    }) {
+        b.member_i64_array(name, &v);
+    } else if let Some(v) = all(items, |n| match n {
+        Node::F32(x) => Some(*x),
+        _ => None,
+    }) {
+        b.member_f32_array(name, &v);
+    } else if let Some(v) = all(items, |n| match n {
+        Node::F64(x) => Some(*x),
+        _ => None,
+    }) {
+        b.member_f64_array(name, &v);
+    } else if let Some(v) = all(items, |n| match n {
+        Node::Str(x) => Some(x.as_str()),
+        _ => None,
+    }) {
+        b.member_str_array(name, &v);
+    } else {
+        return Err(Error(format!(
+            "{}: arrays hold structs, or numbers or strings of one type",
+            name
+        )));
+    }
+    Ok(())
+}
+
+struct NodeSer;
+
+impl ser::Serializer for NodeSer {
+    type Ok = Node;
+    type Error = Error;
+    type SerializeSeq = SeqSer;
+    type SerializeTuple = SeqSer;
+    type SerializeTupleStruct = SeqSer;
+    type SerializeTupleVariant = Impossible<Node, Error>;
+    type SerializeMap = ObjectSer;
+    type SerializeStruct = ObjectSer;
+    type SerializeStructVariant = Impossible<Node, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Node, Error> {
+        Ok(Node::Bool(v))
+    }
+    fn serialize_i8(self, v: i8) -> Result<Node, Error> {
+        Ok(Node::I32(v.into()))
+    }
+    fn serialize_i16(self, v: i16) -> Result<Node, Error> {
+        Ok(Node::I32(v.into()))
+    }
+    fn serialize_i32(self, v: i32) -> Result<Node, Error> {
+        Ok(Node::I32(v))
+    }
+    fn serialize_i64(self, v: i64) -> Result<Node, Error> {
+        Ok(Node::I64(v))
+    }
+    fn serialize_u8(self, v: u8) -> Result<Node, Error> {
+        Ok(Node::U8(v))
+    }
+    fn serialize_u16(self, v: u16) -> Result<Node, Error> {
+        Ok(Node::I32(v.into()))
+    }
+    fn serialize_u32(self, v: u32) -> Result<Node, Error> {
+        Ok(Node::U32(v))
+    }
+    fn serialize_u64(self, v: u64) -> Result<Node, Error> {
+        Ok(Node::U64(v))
+    }
+    fn serialize_f32(self, v: f32) -> Result<Node, Error> {
+        Ok(Node::F32(v))
+    }
+    fn serialize_f64(self, v: f64) -> Result<Node, Error> {
+        Ok(Node::F64(v))
+    }
+    fn serialize_char(self, v: char) -> Result<Node, Error> {
+        Ok(Node::Str(v.to_string()))
+    }
+    fn serialize_str(self, v: &str) -> Result<Node, Error> {
+        Ok(Node::Str(v.to_string()))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Node, Error> {
+        Ok(Node::Array(v.iter().map(|b| Node::U8(*b)).collect()))
+    }
+    fn serialize_none(self) -> Result<Node, Error> {
+        Ok(Node::Null)
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Node, Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Node, Error> {
+        Ok(Node::Null)
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Node, Error> {
+        Ok(Node::Null)
+    }
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        _variant: &'static str,
+    ) -> Result<Node, Error> {
+        Ok(Node::Object {
+            class: name.to_string(),
+            members: vec![("value__".to_string(), Node::I32(index as i32))],
+        })
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Node, Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<Node, Error> {
+        Err(variant_error(name, variant))
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSer, Error> {
+        Ok(SeqSer(Vec::with_capacity(len.unwrap_or(0))))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SeqSer, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSer, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(variant_error(name, variant))
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<ObjectSer, Error> {
+        Ok(ObjectSer::new("Object"))
+    }
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<ObjectSer, Error> {
+        Ok(ObjectSer::new(name))
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(variant_error(name, variant))
+    }
+}
+
+fn variant_error(name: &str, variant: &str) -> Error {
+    Error(format!(
+        "{}::{}: only enum variants without data can be written",
+        name, variant
+    ))
+}
+
+struct SeqSer(Vec<Node>);
+
+impl SeqSer {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.0.push(value.serialize(NodeSer)?);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for SeqSer {
+    type Ok = Node;
+    type Error = Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Node, Error> {
+        Ok(Node::Array(self.0))
+    }
+}
+
+impl ser::SerializeTuple for SeqSer {
+    type Ok = Node;
+    type Error = Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Node, Error> {
+        Ok(Node::Array(self.0))
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSer {
+    type Ok = Node;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Node, Error> {
+        Ok(Node::Array(self.0))
+    }
+}
+
+struct ObjectSer {
+    class: String,
+    members: Vec<(String, Node)>,
+    key: Option<String>,
+}
+
+impl ObjectSer {
+    fn new(class: &str) -> Self {
+        ObjectSer {
+            class: class.to_string(),
+            members: Vec::new(),
+            key: None,
+        }
+    }
+    fn end(self) -> Node {
+        Node::Object {
+            class: self.class,
+            members: self.members,
+        }
+    }
+}
+
+impl ser::SerializeStruct for ObjectSer {
+    type Ok = Node;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.members
+            .push((key.to_string(), value.serialize(NodeSer)?));
+        Ok(())
+    }
+    fn end(self) -> Result<Node, Error> {
+        Ok(ObjectSer::end(self))
+    }
+}
+
+impl ser::SerializeMap for ObjectSer {
+    type Ok = Node;
+    type Error = Error;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        match key.serialize(NodeSer)? {
+            Node::Str(k) => self.key = Some(k),
+            _ => return Err(Error("map keys must be strings".into())),
+        }
+        Ok(())
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error("map value without a key".into()))?;
+        self.members.push((key, value.serialize(NodeSer)?));
+        Ok(())
+    }
+    fn end(self) -> Result<Node, Error> {
+        Ok(ObjectSer::end(self))
+    }
+}
@@ -0,0 +1,289 @@
+// Tabular export/import of a JSON subtree for editing in a spreadsheet.
+//
+// An array (or HawkMessageList) becomes one row per item with an `index`
+// column and one column per leaf value, named by its pointer relative to the
+// item. Any other object becomes `key,value` rows, one per leaf. `$class` and
+// `$ref` are left out and `{"$ref", "$value"}` wrappers are hidden from the
+// names; importing maps the names back onto the document. Import only
+// changes existing values: rows cannot add or remove items.
+use serde_json::Value as J;
+
+use crate::edit::{PatchOp, escape_token, unwrap_ref};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Render as CSV (`sep` = ',') or TSV (`sep` = '\t').
+    pub fn to_text(&self, sep: char) -> String {
+        let mut out = String::new();
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            let cells: Vec<String> = row.iter().map(|c| quote(c, sep)).collect();
+            out.push_str(&cells.join(&sep.to_string()));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse CSV/TSV text; the first record is the header. Quoted fields may
+    /// contain separators, newlines and doubled quotes.
+    pub fn parse(text: &str, sep: char) -> Result<Table, String> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let mut records = Vec::new();
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if quoted {
+                match c {
+                    '"' if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    '"' => quoted = false,
+                    _ => field.push(c),
+                }
+            } else if c == '"' && field.is_empty() {
+                quoted = true;
+            } else if c == sep {
+                record.push(std::mem::take(&mut field));
+            } else if c == '\n' || c == '\r' {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            } else {
+                field.push(c);
+            }
+        }
+        if quoted {
+            return Err(format!("record {}: unterminated quote", records.len() + 1));
+        }
+        if !field.is_empty() || !record.is_empty() {
+            record.push(field);
+            records.push(record);
+        }
+        // Blank lines carry nothing
+        records.retain(|r| !(r.len() == 1 && r[0].is_empty()));
+        let mut records = records.into_iter();
+        let headers = records.next().ok_or("empty table")?;
+        Ok(Table {
+            headers,
+            rows: records.collect(),
+        })
+    }
+}
+
+fn quote(cell: &str, sep: char) -> String {
+    if cell.contains([sep, '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+// The rows of the table at `ptr`: (real pointer of the container, entries)
+fn rows_at<'a>(doc: &'a J, ptr: &str) -> Result<(String, &'a J), String> {
+    let mut real = ptr.to_string();
+    let mut v = doc
+        .pointer(ptr)
+        .ok_or_else(|| format!("not found: {}", ptr))?;
+    if v.get("$value").is_some() {
+        real.push_str("/$value");
+        v = unwrap_ref(v);
+    }
+    if let Some(items) = v.get("List`1+_items") {
+        real.push_str("/List`1+_items");
+        if items.get("$value").is_some() {
+            real.push_str("/$value");
+        }
+        return Ok((real, unwrap_ref(items)));
+    }
+    Ok((real, v))
+}
+
+fn cell(v: &J) -> Option<String> {
+    match v {
+        J::Null => Some(String::new()),
+        J::Bool(b) => Some(b.to_string()),
+        J::Number(n) => Some(n.to_string()),
+        J::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+// Leaves under `v` as (relative name, cell), wrappers hidden
+fn leaves(v: &J, name: &str, out: &mut Vec<(String, String)>) {
+    match unwrap_ref(v) {
+        J::Object(m) => {
+            for (k, c) in m.iter().filter(|(k, _)| !k.starts_with('$')) {
+                let child = if name.is_empty() {
+                    escape_token(k)
+                } else {
+                    format!("{}/{}", name, escape_token(k))
+                };
+                leaves(c, &child, out);
+            }
+        }
+        J::Array(a) => {
+            for (i, c) in a.iter().enumerate() {
+                let child = if name.is_empty() {
+                    i.to_string()
+                } else {
+                    format!("{}/{}", name, i)
+                };
+                leaves(c, &child, out);
+            }
+        }
+        leaf => out.extend(cell(leaf).map(|c| (name.to_string(), c))),
+    }
+}
+
+/// Flatten the array, list or object at `ptr` into a table.
+pub fn export_table(doc: &J, ptr: &str) -> Result<Table, String> {
+    let (_, v) = rows_at(doc, ptr)?;
+    let mut table = Table::default();
+    match v {
+        J::Array(items) => {
+            // HawkMessageList keeps spare capacity as trailing nulls
+            let size = doc
+                .pointer(ptr)
+                .map(unwrap_ref)
+                .and_then(|l| l.get("List`1+_size"))
+                .and_then(|s| s.as_u64())
+                .map_or(items.len(), |s| s as usize);
+            table.headers.push("index".into());
+            let mut rows = Vec::new();
+            for (i, item) in items.iter().enumerate().take(size) {
+                let mut cells = Vec::new();
+                leaves(item, "", &mut cells);
+                for (name, _) in &cells {
+                    if !table.headers.contains(name) {
+                        table.headers.push(name.clone());
+                    }
+                }
+                rows.push((i, cells));
+            }
+            for (i, cells) in rows {
+                let mut row = vec![String::new(); table.headers.len()];
+                row[0] = i.to_string();
+                for (name, c) in cells {
+                    let col = table.headers.iter().position(|h| *h == name).unwrap();
+                    row[col] = c;
+                }
+                table.rows.push(row);
+            }
+        }
+        J::Object(_) => {
+            table.headers = vec!["key".into(), "value".into()];
+            let mut cells = Vec::new();
+            leaves(v, "", &mut cells);
+            table.rows = cells.into_iter().map(|(k, c)| vec![k, c]).collect();
+        }
+        _ => return Err(format!("{} is not an array or object", ptr)),
+    }
+    Ok(table)
+}
+
+// Resolve a relative column name under `base`, stepping into hidden wrappers
+fn resolve(doc: &J, base: &str, name: &str) -> Option<String> {
+    let mut real = base.to_string();
+    let mut v = doc.pointer(base)?;
+    for tok in name.split('/') {
+        if v.get("$value").is_some() {
+            real.push_str("/$value");
+        }
+        real.push('/');
+        real.push_str(tok);
+        v = doc.pointer(&real)?;
+    }
+    if v.get("$value").is_some() {
+        real.push_str("/$value");
+    }
+    Some(real)
+}
+
+// Parse a cell as the same kind of value it replaces
+fn typed(old: &J, text: &str) -> Result<J, String> {
+    match old {
+        J::Number(n) if n.is_i64() || n.is_u64() => text
+            .trim()
+            .parse::<i64>()
+            .map(J::from)
+            .map_err(|_| format!("expected an integer, got '{}'", text)),
+        J::Number(_) => text
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(|x| serde_json::Number::from_f64(x).map(J::Number))
+            .ok_or_else(|| format!("expected a number, got '{}'", text)),
+        J::Bool(_) => match text.trim().to_ascii_lowercase().as_str() {
+            "true" => Ok(J::Bool(true)),
+            "false" => Ok(J::Bool(false)),
+            _ => Err(format!("expected true or false, got '{}'", text)),
+        },
+        J::Null if text.is_empty() => Ok(J::Null),
+        J::Null | J::String(_) => Ok(J::String(text.to_string())),
+        _ => Err("not a plain value".to_string()),
+    }
+}
+
+/// Turn an edited table back into replace operations for the subtree at
+/// `ptr`; cells equal to the current value produce nothing.
+pub fn import_table(doc: &J, ptr: &str, table: &Table) -> Result<Vec<PatchOp>, String> {
+    let (base, v) = rows_at(doc, ptr)?;
+    let mut targets = Vec::new();
+    match (v, table.headers.first().map(|h| h.as_str())) {
+        (J::Array(items), Some("index")) => {
+            for (r, row) in table.rows.iter().enumerate() {
+                let line = r + 1;
+                let index: usize = row
+                    .first()
+                    .and_then(|i| i.trim().parse().ok())
+                    .ok_or_else(|| format!("row {}: bad index", line))?;
+                if index >= items.len() {
+                    return Err(format!("row {}: no item {}", line, index));
+                }
+                let item = format!("{}/{}", base, index);
+                for (name, text) in table.headers.iter().zip(row).skip(1) {
+                    targets.push((line, name, resolve(doc, &item, name), text));
+                }
+            }
+        }
+        (J::Object(_), Some("key")) => {
+            if table.headers.get(1).map(|h| h.as_str()) != Some("value") {
+                return Err("expected columns key,value".to_string());
+            }
+            for (r, row) in table.rows.iter().enumerate() {
+                let (Some(name), Some(text)) = (row.first(), row.get(1)) else {
+                    return Err(format!("row {}: expected key,value", r + 1));
+                };
+                targets.push((r + 1, name, resolve(doc, &base, name), text));
+            }
+        }
+        (J::Array(_), _) => return Err("the first column must be index".to_string()),
+        (J::Object(_), _) => return Err("the first column must be key".to_string()),
+        _ => return Err(format!("{} is not an array or object", ptr)),
+    }
+    let mut ops = Vec::new();
+    for (line, name, real, text) in targets {
+        let old = real.as_deref().and_then(|p| doc.pointer(p));
+        match (real, old) {
+            (Some(real), Some(old)) => {
+                let new = typed(old, text).map_err(|e| format!("row {}: {}: {}", line, name, e))?;
+                if new != *old {
+                    ops.push(PatchOp::replace(real, new));
+                }
+            }
+            // Items that lack a column leave the cell blank
+            _ if text.is_empty() => {}
+            _ => return Err(format!("row {}: {} not found", line, name)),
+        }
+    }
+    Ok(ops)
+}
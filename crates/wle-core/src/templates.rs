@@ -0,0 +1,277 @@
+//! Reusable documents made from existing saves. `extract` takes the edit JSON
+//! of a document and strips what belongs to that one save: the ids objects
+//! carry for themselves, timestamps and positions. `instantiate` turns the
+//! result back into a document with fresh ids, so a player built from a
+//! template never shares a pet or a house id with the save it came from.
+//!
+//! A template is stored as `<name>.json` in a templates folder:
+//!
+//! ```json
+//! {"name": "starter", "description": "Three pets, no money", "document": {"$rootClass": "SavePlayerPersistentData", "root": {}}}
+//! ```
+//!
+//! What it is for (a PlayerData, a settings file, ...) is read from the
+//! document, as `model::detect_kind_json` does for any save.
+//!
+//! Only ids of the object itself are replaced: GUIDs naming game content
+//! (clothing prefabs, vehicles, missions) stay as they are. A stripped id
+//! becomes a placeholder, the same one wherever the id appeared, so a
+//! dictionary keyed by it still points at its entry after `instantiate`.
+
+use crate::model::{DocKind, Guid};
+use serde_json::Value as J;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Members holding the id of the object they belong to
+const OWN_IDS: &[&str] = &["guid"];
+// Members holding when the save was made; reset to "" or 0
+const TIMESTAMPS: &[&str] = &["dateTime", "timeStamp", "lastPlayed"];
+
+// Placeholder N is 0000000N-0000-0000-776c-652d746d706c ("wle-tmpl")
+const MARK: [u8; 8] = *b"wle-tmpl";
+
+fn placeholder(n: usize) -> Guid {
+    Guid {
+        a: n as i32,
+        b: 0,
+        c: 0,
+        d_to_k: MARK,
+    }
+}
+
+fn is_placeholder(g: &Guid) -> bool {
+    g.d_to_k == MARK && g.b == 0 && g.c == 0
+}
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub name: String,
+    pub description: String,
+    /// The stripped edit JSON
+    pub document: J,
+}
+
+impl Template {
+    pub fn kind(&self) -> DocKind {
+        crate::model::detect_kind_json(&self.document)
+    }
+
+    /// A new document from this template, with fresh ids.
+    pub fn instantiate(&self) -> J {
+        instantiate(&self.document)
+    }
+
+    /// `instantiate`, encoded as .sav bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        crate::binfmt_write::write_binfmt_from_json(&self.instantiate())
+    }
+}
+
+// A System.Guid value, as `regenerate_guids` recognises one
+fn as_guid(v: &J) -> Option<Guid> {
+    let is_guid = v
+        .get("$class")
+        .is_none_or(|c| c.as_str() == Some("System.Guid"));
+    is_guid.then(|| Guid::from_json(v)).flatten()
+}
+
+// Visit every value with the name of the member holding it (None for array
+// items and the root); `f` returns true when it replaced the value.
+fn walk(v: &mut J, member: Option<&str>, f: &mut impl FnMut(&mut J, Option<&str>) -> bool) {
+    if f(v, member) {
+        return;
+    }
+    match v {
+        J::Object(obj) => obj.iter_mut().for_each(|(k, c)| {
+            // A boxed value belongs to the member around it
+            let name = if k == "$value" {
+                member
+            } else {
+                Some(k.as_str())
+            };
+            walk(c, name, f)
+        }),
+        J::Array(arr) => arr.iter_mut().for_each(|c| walk(c, None, f)),
+        _ => {}
+    }
+}
+
+/// Strip instance data from the edit JSON of a document (see the module
+/// docs). Returns the template document and how many values were changed.
+pub fn extract(doc: &J) -> (J, usize) {
+    let mut out = doc.clone();
+    // Number the object ids first, so references to them elsewhere in the
+    // document get the same placeholder
+    let ids = map_own_ids(&mut out, placeholder);
+    let mut changed = 0;
+    walk(&mut out, None, &mut |v, member| {
+        if let Some(new) = as_guid(v).and_then(|g| ids.get(&g)) {
+            *v = new.to_json();
+            changed += 1;
+            return true;
+        }
+        let Some(m) = member else {
+            return false;
+        };
+        if TIMESTAMPS.contains(&m) {
+            let blank = match v {
+                J::String(_) => J::from(""),
+                J::Number(_) => J::from(0),
+                _ => return false,
+            };
+            changed += usize::from(*v != blank);
+            *v = blank;
+            return true;
+        }
+        // Positions have their numbers reset to 0
+        if crate::model::POSITION_MEMBERS
+            .iter()
+            .any(|p| m.ends_with(p))
+            && let J::Object(obj) = v
+        {
+            for n in obj.values_mut().filter(|n| n.is_number()) {
+                changed += usize::from(n.as_f64() != Some(0.0));
+                *n = J::from(0.0);
+            }
+            return true;
+        }
+        false
+    });
+    (out, changed)
+}
+
+// Map each distinct object id in `doc` to `new(n)`, n counting from 1
+fn map_own_ids(doc: &mut J, mut new: impl FnMut(usize) -> Guid) -> HashMap<Guid, Guid> {
+    let mut ids: HashMap<Guid, Guid> = HashMap::new();
+    walk(doc, None, &mut |v, member| {
+        let own = member.is_some_and(|m| OWN_IDS.contains(&m));
+        match as_guid(v).filter(|g| own && !g.is_nil()) {
+            Some(g) => {
+                let n = ids.len() + 1;
+                ids.entry(g).or_insert_with(|| new(n));
+                true
+            }
+            None => false,
+        }
+    });
+    ids
+}
+
+/// A copy of `doc` where the ids objects carry for themselves are fresh
+/// random GUIDs, references to them included, so it can sit next to the
+/// original. GUIDs naming game content and everything else stay as they are.
+pub fn fresh_ids(doc: &J) -> J {
+    let mut out = doc.clone();
+    let ids = map_own_ids(&mut out, |_| Guid::new_random());
+    walk(
+        &mut out,
+        None,
+        &mut |v, _| match as_guid(v).and_then(|g| ids.get(&g)) {
+            Some(new) => {
+                *v = new.to_json();
+                true
+            }
+            None => false,
+        },
+    );
+    out
+}
+
+/// A copy of a template document where every placeholder id is a fresh
+/// random GUID; a placeholder used twice gets the same new id both times.
+pub fn instantiate(template: &J) -> J {
+    let mut out = template.clone();
+    let mut fresh: HashMap<Guid, Guid> = HashMap::new();
+    walk(&mut out, None, &mut |v, _| match as_guid(v) {
+        Some(g) if is_placeholder(&g) => {
+            *v = fresh.entry(g).or_insert_with(Guid::new_random).to_json();
+            true
+        }
+        _ => false,
+    });
+    out
+}
+
+fn check_name(name: &str) -> io::Result<()> {
+    let bad = name.is_empty()
+        || name.starts_with('.')
+        || name.contains(['/', '\\', ':'])
+        || name.trim() != name;
+    if bad {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("'{}' cannot be a template name", name),
+        ));
+    }
+    Ok(())
+}
+
+/// The templates folder the CLI and GUI share, wle-templates in the user
+/// config folder.
+pub fn default_dir() -> Option<PathBuf> {
+    crate::saves::user_config_dir().map(|d| d.join("wle-templates"))
+}
+
+/// Write `template` as `<name>.json` in `dir`, creating the folder. An
+/// existing template of that name is only replaced with `overwrite`.
+pub fn save_template(dir: &Path, template: &Template, overwrite: bool) -> io::Result<PathBuf> {
+    check_name(&template.name)?;
+    let path = dir.join(format!("{}.json", template.name));
+    if path.exists() && !overwrite {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("template '{}' already exists", template.name),
+        ));
+    }
+    std::fs::create_dir_all(dir)?;
+    let file = serde_json::json!({
+        "name": template.name,
+        "description": template.description,
+        "document": template.document,
+    });
+    let text = serde_json::to_string_pretty(&file).map_err(io::Error::other)?;
+    crate::write_file_atomic(&path, text.as_bytes()).map_err(io::Error::other)?;
+    Ok(path)
+}
+
+/// A template file; the name defaults to the file name.
+pub fn load_template(path: &Path) -> Result<Template, String> {
+    let v = crate::edit::read_json_file(path)?;
+    let document = v
+        .get("document")
+        .filter(|d| d.get("root").is_some())
+        .ok_or("a template file needs a \"document\" with a \"root\"")?
+        .clone();
+    let text = |k: &str| v.get(k).and_then(|x| x.as_str()).map(str::to_string);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    Ok(Template {
+        name: text("name").unwrap_or_else(|| stem.into_owned()),
+        description: text("description").unwrap_or_default(),
+        document,
+    })
+}
+
+/// Every template of `dir` by name, with the error of each file that does
+/// not load.
+pub fn list_templates(dir: &Path) -> Vec<(PathBuf, Result<Template, String>)> {
+    crate::plugin::rule_files(dir)
+        .into_iter()
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .map(|p| {
+            let t = load_template(&p);
+            (p, t)
+        })
+        .collect()
+}
+
+/// The template called `name` in `dir`.
+pub fn find_template(dir: &Path, name: &str) -> Result<Template, String> {
+    check_name(name).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.json", name));
+    if !path.is_file() {
+        return Err(format!("no template '{}' in {}", name, dir.display()));
+    }
+    load_template(&path)
+}
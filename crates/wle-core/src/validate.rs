@@ -0,0 +1,494 @@
+// Health checks for save files: does each file parse, would writing it back
+// reproduce the same data, and (optionally) do values look sane against the
+// field catalog and list bookkeeping. `find_duplicates` looks for objects a
+// glitched save copied.
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde_json::Value as J;
+
+use crate::edit::escape_token;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Worth knowing, nothing to fix
+    Info,
+    /// Suspicious value; the game may cope
+    Warning,
+    /// The file cannot be read or written back faithfully
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// JSON pointer into the file's dump ("" for the whole file)
+    pub pointer: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub issues: Vec<Issue>,
+}
+
+impl FileReport {
+    pub fn worst(&self) -> Option<Severity> {
+        self.issues.iter().map(|i| i.severity).max()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidateOpts {
+    /// Also check catalog ranges and list sizes
+    pub schema: bool,
+}
+
+// Stop reporting round-trip differences after this many per file
+const MAX_ROUNDTRIP_ISSUES: usize = 20;
+
+fn issue(severity: Severity, pointer: impl Into<String>, message: impl Into<String>) -> Issue {
+    Issue {
+        severity,
+        pointer: pointer.into(),
+        message: message.into(),
+    }
+}
+
+/// Check one .sav (BinaryFormatter or plain JSON).
+pub fn validate_file(path: &Path, opts: ValidateOpts) -> FileReport {
+    let mut report = FileReport {
+        path: path.to_path_buf(),
+        issues: Vec::new(),
+    };
+    let data = match std::fs::read(path) {
+        Ok(d) => d,
+        Err(e) => {
+            report
+                .issues
+                .push(issue(Severity::Error, "", e.to_string()));
+            return report;
+        }
+    };
+    let is_json = data.iter().copied().find(|b| !b.is_ascii_whitespace()) == Some(b'{');
+    match crate::edit::parse_bytes_for_edit(data) {
+        // Plain-JSON saves (SaveInfo.sav) are written as-is; only parsing matters
+        Ok(v) if is_json => {
+            if opts.schema {
+                report.issues.extend(schema_issues(&v));
+            }
+        }
+        Ok(v) => report.issues = validate_json(&v, opts),
+        Err(e) => report
+            .issues
+            .push(issue(Severity::Error, "", format!("cannot parse: {}", e))),
+    }
+    report
+}
+
+/// Check every .sav in a slot directory, in file name order.
+pub fn validate_dir(dir: &Path, opts: ValidateOpts) -> Vec<FileReport> {
+    crate::json::find_sav_files(dir)
+        .iter()
+        .map(|p| validate_file(p, opts))
+        .collect()
+}
+
+/// Checks for a BinaryFormatter document dumped with `JsonOpts::full()`:
+/// it must encode, and decoding the result must give the same values.
+/// Object ids (`$ref`) may be renumbered and floats narrowed to f32; neither
+/// is reported.
+pub fn validate_json(v: &J, opts: ValidateOpts) -> Vec<Issue> {
+    let mut out = Vec::new();
+    match crate::binfmt_write::write_binfmt_from_json(v) {
+        Err(e) => out.push(issue(
+            Severity::Error,
+            "",
+            format!("cannot be written: {}", e),
+        )),
+        Ok(bytes) => match crate::edit::parse_bytes_for_edit(bytes) {
+            Err(e) => out.push(issue(
+                Severity::Error,
+                "",
+                format!("written file does not parse: {}", e),
+            )),
+            Ok(back) => {
+                let mut diffs = Vec::new();
+                roundtrip_diffs(v, &back, String::new(), &mut diffs);
+                let extra = diffs.len().saturating_sub(MAX_ROUNDTRIP_ISSUES);
+                out.extend(diffs.into_iter().take(MAX_ROUNDTRIP_ISSUES));
+                if extra > 0 {
+                    out.push(issue(
+                        Severity::Error,
+                        "",
+                        format!("{} more round-trip differences", extra),
+                    ));
+                }
+            }
+        },
+    }
+    if opts.schema {
+        out.extend(schema_issues(v));
+    }
+    out
+}
+
+fn same_number(a: &serde_json::Number, b: &serde_json::Number) -> bool {
+    if a == b {
+        return true;
+    }
+    match (a.as_f64(), b.as_f64()) {
+        // The writer stores Single members as f32
+        (Some(x), Some(y)) => x == y || (x as f32) == (y as f32),
+        _ => false,
+    }
+}
+
+// Equal as far as a write-back can tell (see `validate_json`)
+#[cfg(feature = "fs")]
+pub(crate) fn same_value(a: &J, b: &J) -> bool {
+    let mut diffs = Vec::new();
+    roundtrip_diffs(a, b, String::new(), &mut diffs);
+    diffs.is_empty()
+}
+
+// Object ids are renumbered on write, and a `$ref` wrapper's `$class` (older
+// writers saved wrappers as class "Object") is not written back either
+fn is_bookkeeping(map: &serde_json::Map<String, J>, key: &str) -> bool {
+    key == "$ref" || (key == "$class" && map.contains_key("$ref"))
+}
+
+fn roundtrip_diffs(a: &J, b: &J, ptr: String, out: &mut Vec<Issue>) {
+    match (a, b) {
+        (J::Object(ma), J::Object(mb)) => {
+            for (k, va) in ma.iter().filter(|(k, _)| !is_bookkeeping(ma, k)) {
+                let p = format!("{}/{}", ptr, escape_token(k));
+                match mb.get(k) {
+                    Some(vb) => roundtrip_diffs(va, vb, p, out),
+                    None => out.push(issue(Severity::Error, p, "lost when written back")),
+                }
+            }
+            for k in mb
+                .keys()
+                .filter(|k| !is_bookkeeping(mb, k) && !ma.contains_key(*k))
+            {
+                out.push(issue(
+                    Severity::Error,
+                    format!("{}/{}", ptr, escape_token(k)),
+                    "appears when written back",
+                ));
+            }
+        }
+        (J::Array(xa), J::Array(xb)) if xa.len() == xb.len() => {
+            for (i, (va, vb)) in xa.iter().zip(xb).enumerate() {
+                roundtrip_diffs(va, vb, format!("{}/{}", ptr, i), out);
+            }
+        }
+        (J::Array(xa), J::Array(xb)) => out.push(issue(
+            Severity::Error,
+            ptr,
+            format!("{} items become {} when written back", xa.len(), xb.len()),
+        )),
+        (J::Number(x), J::Number(y)) if same_number(x, y) => {}
+        _ if a != b => out.push(issue(
+            Severity::Error,
+            ptr,
+            format!(
+                "{} becomes {} when written back",
+                crate::edit::value_preview(a),
+                crate::edit::value_preview(b)
+            ),
+        )),
+        _ => {}
+    }
+}
+
+// Catalog limits and HawkMessageList sizes
+fn schema_issues(v: &J) -> Vec<Issue> {
+    fn walk(v: &J, ptr: &str, out: &mut Vec<Issue>) {
+        match v {
+            J::Object(map) => {
+                if let Some(class) = map.get("$class").and_then(|c| c.as_str()) {
+                    for (member, value) in map {
+                        let Some(x) = value.as_f64() else { continue };
+                        if let Some(info) = crate::catalog::field_info(class, member)
+                            && !info.allows(x)
+                        {
+                            out.push(issue(
+                                Severity::Warning,
+                                format!("{}/{}", ptr, escape_token(member)),
+                                format!(
+                                    "{} = {}, expected {}",
+                                    info.label,
+                                    x,
+                                    info.limits().unwrap_or_default()
+                                ),
+                            ));
+                        }
+                    }
+                }
+                if let Some(size) = map.get("List`1+_size").and_then(|s| s.as_i64()) {
+                    let items = map
+                        .get("List`1+_items")
+                        .map(|i| i.get("$value").unwrap_or(i));
+                    let cap = items.and_then(|i| i.as_array()).map(|a| a.len());
+                    if size < 0 || cap.is_some_and(|c| size as usize > c) {
+                        out.push(issue(
+                            Severity::Error,
+                            format!("{}/List`1+_size", ptr),
+                            format!(
+                                "list size {} does not fit its {} items",
+                                size,
+                                cap.unwrap_or(0)
+                            ),
+                        ));
+                    }
+                }
+                for (k, c) in map {
+                    walk(c, &format!("{}/{}", ptr, escape_token(k)), out);
+                }
+            }
+            J::Array(arr) => {
+                for (i, c) in arr.iter().enumerate() {
+                    walk(c, &format!("{}/{}", ptr, i), out);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    walk(v, "", &mut out);
+    out
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DuplicateKind {
+    /// Objects whose own `guid` member holds the same id
+    SameGuid(crate::model::Guid),
+    /// Objects of one class at the same (non-zero) position
+    SamePlace { class: String, position: Vec<f64> },
+}
+
+/// Objects of a document that look like copies of each other, a symptom of
+/// a glitched save (often in WorldData).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicates {
+    pub kind: DuplicateKind,
+    /// Edit-JSON pointers in document order; the first is the one to keep
+    pub pointers: Vec<String>,
+}
+
+impl Duplicates {
+    /// Every copy but the first.
+    pub fn extra(&self) -> &[String] {
+        &self.pointers[1..]
+    }
+}
+
+impl std::fmt::Display for Duplicates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            DuplicateKind::SameGuid(g) => {
+                write!(f, "{} objects with GUID {}", self.pointers.len(), g)
+            }
+            DuplicateKind::SamePlace { class, position } => {
+                let at: Vec<String> = position.iter().map(|x| x.to_string()).collect();
+                write!(
+                    f,
+                    "{} {} at ({})",
+                    self.pointers.len(),
+                    class,
+                    at.join(", ")
+                )
+            }
+        }
+    }
+}
+
+// List items of the edit JSON that are objects, with their pointers, skipping the spare capacity
+// of HawkMessageLists (`limit` is the list size when `v` holds its items).
+// A shared reference is visited once, at the first place it shows.
+fn objects<'a>(
+    v: &'a J,
+    ptr: String,
+    limit: Option<usize>,
+    seen: &mut HashSet<i64>,
+    out: &mut Vec<(String, &'a serde_json::Map<String, J>)>,
+) {
+    match v {
+        J::Object(map) => {
+            if let Some(inner) = map.get("$value") {
+                if map
+                    .get("$ref")
+                    .and_then(|r| r.as_i64())
+                    .is_some_and(|r| !seen.insert(r))
+                {
+                    return;
+                }
+                return objects(inner, format!("{}/$value", ptr), limit, seen, out);
+            }
+            if map
+                .get("$class")
+                .and_then(|c| c.as_str())
+                .is_some_and(|c| !crate::model::is_plumbing_class(c))
+            {
+                // A referenced object is named by the item holding it
+                let node = ptr.strip_suffix("/$value").unwrap_or(&ptr);
+                if node.rsplit('/').next().is_some_and(is_index) {
+                    out.push((node.to_string(), map));
+                }
+            }
+            let size = map
+                .get("List`1+_size")
+                .and_then(|s| s.as_u64())
+                .map(|s| s as usize);
+            for (k, c) in map.iter().filter(|(k, _)| !k.starts_with('$')) {
+                let limit = if k == "List`1+_items" { size } else { None };
+                objects(c, format!("{}/{}", ptr, escape_token(k)), limit, seen, out);
+            }
+        }
+        J::Array(arr) => {
+            for (i, c) in arr.iter().enumerate().take(limit.unwrap_or(usize::MAX)) {
+                objects(c, format!("{}/{}", ptr, i), None, seen, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_index(token: &str) -> bool {
+    token.parse::<usize>().is_ok()
+}
+
+fn position(map: &serde_json::Map<String, J>) -> Option<Vec<f64>> {
+    map.iter()
+        .filter(|(k, _)| {
+            crate::model::POSITION_MEMBERS
+                .iter()
+                .any(|p| k.ends_with(p))
+        })
+        .find_map(|(_, v)| {
+            let v = v.get("$value").unwrap_or(v);
+            let xs: Vec<f64> = v.as_object()?.values().filter_map(|x| x.as_f64()).collect();
+            (xs.len() >= 2 && xs.iter().any(|x| *x != 0.0)).then_some(xs)
+        })
+}
+
+/// Groups of objects in the edit JSON (`JsonOpts::full()`) sharing their
+/// own GUID, or of one class at one position. Only list items are compared:
+/// a dictionary may hold the same entry as a list beside it. Positions left
+/// at zero are not compared either, many objects that were never placed
+/// sit there.
+pub fn find_duplicates(v: &J) -> Vec<Duplicates> {
+    let mut found = Vec::new();
+    objects(v, String::new(), None, &mut HashSet::new(), &mut found);
+    let mut groups: Vec<Duplicates> = Vec::new();
+    // Index of each group by its kind, with positions compared bit for bit
+    let mut index: HashMap<(Option<crate::model::Guid>, String, Vec<u64>), usize> = HashMap::new();
+    let mut add = |kind: DuplicateKind, ptr: &str| {
+        let key = match &kind {
+            DuplicateKind::SameGuid(g) => (Some(*g), String::new(), Vec::new()),
+            DuplicateKind::SamePlace { class, position } => (
+                None,
+                class.clone(),
+                position.iter().map(|x| x.to_bits()).collect(),
+            ),
+        };
+        let i = *index.entry(key).or_insert_with(|| {
+            groups.push(Duplicates {
+                kind,
+                pointers: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        groups[i].pointers.push(ptr.to_string());
+    };
+    for (ptr, map) in found {
+        if let Some(g) = map
+            .get("guid")
+            .and_then(crate::model::Guid::from_json)
+            .filter(|g| !g.is_nil())
+        {
+            add(DuplicateKind::SameGuid(g), &ptr);
+        }
+        if let Some(position) = position(map) {
+            let class = map
+                .get("$class")
+                .and_then(|c| c.as_str())
+                .unwrap_or_default()
+                .to_string();
+            add(DuplicateKind::SamePlace { class, position }, &ptr);
+        }
+    }
+    // Guid groups first, as they were found
+    groups.sort_by_key(|g| !matches!(g.kind, DuplicateKind::SameGuid(_)));
+    groups.retain(|g| g.pointers.len() > 1);
+    groups
+}
+
+/// Patch removing the extra copies of `groups` from `doc`; HawkMessageList
+/// sizes shrink to match. Items are removed last index first, so the
+/// pointers stay valid as it applies.
+pub fn remove_duplicates_ops(doc: &J, groups: &[Duplicates]) -> Vec<crate::PatchOp> {
+    let mut extra: Vec<&str> = groups
+        .iter()
+        .flat_map(|g| g.extra())
+        .map(String::as_str)
+        .filter(|p| {
+            p.rsplit('/')
+                .next()
+                .is_some_and(|t| t.parse::<usize>().is_ok())
+        })
+        .collect();
+    // A copy inside another copy goes with it
+    extra.sort_unstable();
+    extra.dedup();
+    let inside = |p: &str| {
+        extra
+            .iter()
+            .any(|o| p.len() > o.len() && p.starts_with(o) && p.as_bytes()[o.len()] == b'/')
+    };
+    let mut extra: Vec<&str> = extra.iter().copied().filter(|p| !inside(p)).collect();
+    let key = |p: &str| -> Vec<Result<usize, String>> {
+        p.split('/')
+            .map(|t| t.parse::<usize>().map_err(|_| t.to_string()))
+            .collect()
+    };
+    extra.sort_by_key(|p| std::cmp::Reverse(key(p)));
+
+    let mut shrink: Vec<(String, u64)> = Vec::new();
+    for p in &extra {
+        let (items, _) = p.rsplit_once('/').unwrap_or_default();
+        let items = items.strip_suffix("/$value").unwrap_or(items);
+        if let Some(list) = items.strip_suffix("/List`1+_items") {
+            match shrink.iter_mut().find(|(l, _)| l == list) {
+                Some((_, n)) => *n += 1,
+                None => shrink.push((list.to_string(), 1)),
+            }
+        }
+    }
+    let mut ops: Vec<crate::PatchOp> = extra
+        .iter()
+        .map(|p| crate::PatchOp::Remove {
+            path: p.to_string(),
+        })
+        .collect();
+    for (list, n) in shrink {
+        let at = format!("{}/List`1+_size", list);
+        if let Some(size) = doc.pointer(&at).and_then(|s| s.as_u64()) {
+            ops.push(crate::PatchOp::replace(at, size.saturating_sub(n).into()));
+        }
+    }
+    ops
+}
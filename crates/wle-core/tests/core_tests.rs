@@ -28,6 +28,33 @@ fn decode_slot_info_image_len() {
     assert!(info.last_selected_player_slot >= 0);
 }
 
+#[test]
+fn decode_slot_image_flips_and_expands() {
+    use wle_core::model::SlotImage;
+    // 2x2 RGB, bottom row first
+    let rgb = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+    let img = SlotImage::decode(&rgb).unwrap();
+    assert_eq!((img.width, img.height), (2, 2));
+    assert_eq!(
+        img.rgba,
+        [7, 8, 9, 255, 10, 11, 12, 255, 1, 2, 3, 255, 4, 5, 6, 255]
+    );
+    let rgba: Vec<u8> = (0..16).collect();
+    let img = SlotImage::decode(&rgba).unwrap();
+    assert_eq!(&img.rgba[..4], &[8, 9, 10, 11]);
+    assert!(SlotImage::decode(&[0; 10]).is_none());
+    assert!(SlotImage::decode(&[]).is_none());
+
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/SlotInfo.sav");
+    let doc = wle_core::json::parse_binary(&p).unwrap();
+    let img = doc.as_save_slot_info().unwrap().decode_image().unwrap();
+    assert_eq!(
+        (img.width, img.height, img.rgba.len()),
+        (256, 256, 256 * 256 * 4)
+    );
+}
+
 #[test]
 fn generic_json_pointer_edit_ops() {
     use tempfile::tempdir;
@@ -92,6 +119,64 @@ fn zip_backup_arbitrary_dir() {
     assert!(zip.exists());
 }
 
+#[test]
+fn restore_backup_over_slot() {
+    use std::fs;
+    use wle_core::editor::{
+        RestoreOpts, backup_slot_dir, find_backup_zips, restore_backup, zip_backup_slot,
+    };
+    let (_d, slot) = temp_slot(&[]);
+    fs::write(slot.join("A.sav"), b"original").unwrap();
+    fs::write(slot.join("B.sav"), b"original-b").unwrap();
+    let zip = zip_backup_slot(&slot).unwrap();
+    assert_eq!(find_backup_zips(&slot), vec![zip.clone()]);
+    assert_eq!(backup_slot_dir(&zip), Some(slot.clone()));
+
+    fs::write(slot.join("A.sav"), b"modified").unwrap();
+    fs::write(slot.join("B.sav"), b"modified-b").unwrap();
+    let dry = restore_backup(
+        &zip,
+        &slot,
+        RestoreOpts {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(dry.restored.len(), 2);
+    assert_eq!(fs::read(slot.join("A.sav")).unwrap(), b"modified");
+
+    let only_a = RestoreOpts {
+        dry_run: false,
+        files: Some(vec!["A.sav".into()]),
+        ..Default::default()
+    };
+    let rep = restore_backup(&zip, &slot, only_a).unwrap();
+    assert_eq!(rep.restored, vec!["A.sav".to_string()]);
+    assert!(rep.pre_restore_backup.is_some());
+    assert_eq!(fs::read(slot.join("A.sav")).unwrap(), b"original");
+    assert_eq!(fs::read(slot.join("B.sav")).unwrap(), b"modified-b");
+    // The integrity manifest stays inside the zip
+    assert!(!slot.join(wle_core::editor::BACKUP_MANIFEST).exists());
+}
+
+#[test]
+fn verify_backup_against_manifest() {
+    use std::fs;
+    let (_d, slot) = temp_slot(&[]);
+    fs::write(slot.join("A.sav"), b"alpha").unwrap();
+    fs::write(slot.join("B.sav"), b"beta").unwrap();
+    let zip = wle_core::editor::zip_backup_slot(&slot).unwrap();
+    let report = wle_core::editor::verify_backup(&zip).unwrap();
+    assert!(report.has_manifest);
+    assert!(report.is_ok(), "{:?}", report.problems);
+    assert_eq!(report.verified, vec!["A.sav", "B.sav"]);
+    assert_eq!(
+        wle_core::editor::sha256_hex(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
+
 #[test]
 fn write_generic_binfmt_roundtrip() {
     use tempfile::tempdir;
@@ -120,3 +205,2684 @@ fn write_generic_binfmt_roundtrip() {
     assert!(dumped.contains("\"d\":[1,2,3]"));
     assert!(dumped.contains("\"$class\":\"Child\""));
 }
+
+#[test]
+fn member_order_preserved_through_roundtrip() {
+    use tempfile::tempdir;
+    use wle_core::json::JsonOpts;
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("order.sav");
+    let root = serde_json::json!({
+        "$rootClass": "OrderRoot",
+        "root": { "$class": "OrderRoot", "zeta": 1, "alpha": 2, "mid": { "$class": "M", "y": 1, "x": 2 } }
+    });
+    wle_core::write_binfmt_file_from_json(&p, &root).expect("write");
+    let v = wle_core::parse_file_to_json_value(&p, JsonOpts::default()).expect("parse");
+    let keys: Vec<&str> = v["root"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(|k| k.as_str())
+        .collect();
+    assert_eq!(keys, ["$class", "zeta", "alpha", "mid"]);
+    let inner: Vec<&str> = v["root"]["mid"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(|k| k.as_str())
+        .collect();
+    assert_eq!(inner, ["$class", "y", "x"]);
+}
+
+#[test]
+fn referenced_objects_roundtrip_as_references() {
+    use wle_core::json::JsonOpts;
+    // Object ids are the writer's own; everything else must come back as is
+    fn strip_ids(v: &serde_json::Value) -> serde_json::Value {
+        match v {
+            serde_json::Value::Object(m) => m
+                .iter()
+                .filter(|(k, _)| *k != "$ref")
+                .map(|(k, c)| (k.clone(), strip_ids(c)))
+                .collect(),
+            serde_json::Value::Array(a) => a.iter().map(strip_ids).collect(),
+            _ => v.clone(),
+        }
+    }
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let orig = wle_core::parse_file_to_json_value(&p, JsonOpts::full()).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("PlayerData_1.sav");
+    wle_core::write_binfmt_file_from_json(&out, &orig).unwrap();
+    let back = wle_core::parse_file_to_json_value(&out, JsonOpts::full()).unwrap();
+    // `$ref` members stay references, so pointers through `$value` still work
+    let money = "/root/<MiscData>k__BackingField/$value/money";
+    assert!(orig.pointer(money).is_some());
+    assert_eq!(back.pointer(money), orig.pointer(money));
+    assert_eq!(strip_ids(&back), strip_ids(&orig));
+}
+
+#[test]
+fn prune_backups_keeps_newest() {
+    use std::fs;
+    use tempfile::tempdir;
+    use wle_core::editor::{RetentionPolicy, parse_backup_name, prune_backups};
+    let d = tempdir().unwrap();
+    fs::create_dir_all(d.path().join("SaveSlot_1")).unwrap();
+    for ts in ["20240101-100000", "20240102-100000", "20240103-100000"] {
+        fs::write(d.path().join(format!("SaveSlot_1_{}.zip", ts)), [0u8; 10]).unwrap();
+    }
+    fs::write(d.path().join("SaveSlot_2_20240101-100000.zip"), [0u8; 10]).unwrap();
+    let (slot, _) = parse_backup_name("SaveSlot_1_20240103-100000_2.zip").unwrap();
+    assert_eq!(slot, "SaveSlot_1");
+
+    let policy = RetentionPolicy {
+        keep_last: Some(2),
+        ..Default::default()
+    };
+    let removed = prune_backups(d.path(), policy).unwrap();
+    assert_eq!(
+        removed,
+        vec![d.path().join("SaveSlot_1_20240101-100000.zip")]
+    );
+
+    // Age-based pruning never removes the only/newest backup of a slot
+    let policy = RetentionPolicy {
+        keep_days: Some(1),
+        ..Default::default()
+    };
+    let removed = prune_backups(d.path(), policy).unwrap();
+    assert_eq!(
+        removed,
+        vec![d.path().join("SaveSlot_1_20240102-100000.zip")]
+    );
+    assert!(d.path().join("SaveSlot_2_20240101-100000.zip").exists());
+}
+
+#[test]
+fn list_backups_reports_slot_info() {
+    use std::fs;
+    use tempfile::tempdir;
+    let d = tempdir().unwrap();
+    let slot = d.path().join("SaveSlot_3");
+    fs::create_dir_all(&slot).unwrap();
+    let bytes = wle_core::editor::build_slot_info_bytes(1, "2025-01-02 03:04", &[0u8; 12]);
+    fs::write(slot.join("SlotInfo.sav"), bytes).unwrap();
+    fs::write(slot.join("PlayerData_1.sav"), b"x").unwrap();
+    wle_core::editor::zip_backup_slot(&slot).unwrap();
+    let list = wle_core::editor::list_backups(&slot).unwrap();
+    assert_eq!(list.len(), 1);
+    let b = &list[0];
+    assert!(b.timestamp.is_some());
+    assert!(b.size > 0);
+    assert_eq!(b.files, vec!["PlayerData_1.sav", "SlotInfo.sav"]);
+    assert_eq!(b.slot_date_time.as_deref(), Some("2025-01-02 03:04"));
+}
+
+#[test]
+fn backups_in_separate_folder() {
+    use std::fs;
+    use wle_core::editor::{
+        RetentionPolicy, find_backup_zips, find_backup_zips_in, list_backups_in,
+        zip_backup_slot_pruned_to,
+    };
+    let (d, slot) = temp_slot(&[]);
+    let dir = d.path().join("backups/wle");
+    fs::write(slot.join("A.sav"), b"a").unwrap();
+    // Another slot's backups sharing the folder
+    fs::create_dir_all(&dir).unwrap();
+    let other =
+        ["20240101-100000", "20240102-100000"].map(|ts| dir.join(format!("SaveSlot_2_{}.zip", ts)));
+    for p in &other {
+        fs::write(p, [0u8; 10]).unwrap();
+    }
+    let policy = RetentionPolicy {
+        keep_last: Some(1),
+        ..Default::default()
+    };
+    let (first, _) = zip_backup_slot_pruned_to(&slot, &dir, policy).unwrap();
+    assert_eq!(first.parent(), Some(dir.as_path()));
+    let (second, pruned) = zip_backup_slot_pruned_to(&slot, &dir, policy).unwrap();
+    assert_eq!(pruned, vec![first]);
+    assert_eq!(find_backup_zips_in(&slot, &dir), vec![second]);
+    assert!(other.iter().all(|p| p.exists()));
+    assert!(find_backup_zips(&slot).is_empty());
+    assert_eq!(
+        list_backups_in(&slot, &dir).unwrap()[0].files,
+        vec!["A.sav"]
+    );
+}
+
+#[test]
+fn single_file_backup_and_restore() {
+    use std::fs;
+    use tempfile::tempdir;
+    use wle_core::editor::{backup_file, find_file_backups, restore_file};
+    let d = tempdir().unwrap();
+    let f = d.path().join("PlayerData_1.sav");
+    fs::write(&f, b"v1").unwrap();
+    let bak = backup_file(&f, false).unwrap();
+    let zipped = backup_file(&f, true).unwrap();
+    assert_eq!(find_file_backups(&f).len(), 2);
+
+    fs::write(&f, b"v2").unwrap();
+    let pre = restore_file(&bak, &f)
+        .unwrap()
+        .expect("pre-restore snapshot");
+    assert_eq!(fs::read(&f).unwrap(), b"v1");
+    assert_eq!(fs::read(&pre).unwrap(), b"v2");
+
+    fs::write(&f, b"v3").unwrap();
+    restore_file(&zipped, &f).unwrap();
+    assert_eq!(fs::read(&f).unwrap(), b"v1");
+
+    // Another file's snapshot is refused and leaves the file alone
+    let other = d.path().join("PlayerData_2.sav");
+    fs::write(&other, b"v3").unwrap();
+    let err = restore_file(&bak, &other).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(fs::read(&other).unwrap(), b"v3");
+}
+
+#[test]
+fn read_file_from_backups() {
+    use std::fs;
+    use wle_core::editor::{backup_file, read_backup_file, zip_backup_slot};
+    let (_d, slot) = temp_slot(&[]);
+    fs::write(slot.join("A.sav"), b"alpha").unwrap();
+    let zip = zip_backup_slot(&slot).unwrap();
+    let bak = backup_file(&slot.join("A.sav"), false).unwrap();
+    fs::write(slot.join("A.sav"), b"changed").unwrap();
+    assert_eq!(read_backup_file(&zip, "A.sav").unwrap(), b"alpha");
+    assert_eq!(read_backup_file(&bak, "A.sav").unwrap(), b"alpha");
+    let missing = read_backup_file(&zip, "B.sav").unwrap_err();
+    assert_eq!(missing.kind(), std::io::ErrorKind::NotFound);
+
+    // Snapshots stay out of later slot zips and clones
+    let bak_name = bak.file_name().unwrap().to_str().unwrap();
+    assert!(wle_core::editor::is_file_backup(&bak));
+    assert!(!wle_core::editor::is_file_backup(&zip));
+    assert!(!wle_core::editor::is_file_backup(&slot.join("A.sav")));
+    let later = zip_backup_slot(&slot).unwrap();
+    assert!(read_backup_file(&later, bak_name).is_err());
+    let clone = wle_core::saves::clone_slot(&slot, slot.parent().unwrap(), None).unwrap();
+    assert!(clone.join("A.sav").exists());
+    assert!(!clone.join(bak_name).exists());
+}
+
+#[test]
+fn backup_thumbnail_preview() {
+    use std::fs;
+    use wle_core::editor::{build_slot_info_bytes, read_backup_thumbnail, zip_backup_slot};
+    let (_d, slot) = temp_slot(&[]);
+    fs::write(slot.join("A.sav"), b"a").unwrap();
+    let bare = zip_backup_slot(&slot).unwrap();
+    assert!(read_backup_thumbnail(&bare).unwrap().is_none());
+
+    let img = vec![200u8; 16 * 16 * 3];
+    fs::write(
+        slot.join("SlotInfo.sav"),
+        build_slot_info_bytes(1, "2025-01-02 03:04", &img),
+    )
+    .unwrap();
+    let zip = zip_backup_slot(&slot).unwrap();
+    let thumb = read_backup_thumbnail(&zip).unwrap().expect("thumbnail");
+    assert_eq!((thumb.width, thumb.height), (16, 16));
+    assert_eq!(&thumb.rgba[..4], &[200, 200, 200, 255]);
+}
+
+#[test]
+fn locate_dropped_save_paths() {
+    use std::fs;
+    use wle_core::saves::{SaveLocation, locate_save_path};
+    let d = tempfile::tempdir().unwrap();
+    let root = d.path().join("Save/76561198000000000/GameSaves");
+    let slot = root.join("SaveSlot_2");
+    fs::create_dir_all(&slot).unwrap();
+    fs::write(root.join("SaveInfo.sav"), b"{}").unwrap();
+    fs::write(slot.join("MissionData.sav"), b"").unwrap();
+    fs::write(d.path().join("notes.txt"), b"").unwrap();
+
+    let at =
+        |root: &std::path::Path, slot: Option<&std::path::Path>, file: Option<&std::path::Path>| {
+            Some(SaveLocation {
+                root: root.to_path_buf(),
+                slot: slot.map(|p| p.to_path_buf()),
+                file: file.map(|p| p.to_path_buf()),
+            })
+        };
+    assert_eq!(locate_save_path(&root), at(&root, None, None));
+    assert_eq!(
+        locate_save_path(&d.path().join("Save")),
+        at(&root, None, None)
+    );
+    assert_eq!(locate_save_path(&slot), at(&root, Some(&slot), None));
+    let mission = slot.join("MissionData.sav");
+    assert_eq!(
+        locate_save_path(&mission),
+        at(&root, Some(&slot), Some(&mission))
+    );
+    let info = root.join("SaveInfo.sav");
+    assert_eq!(locate_save_path(&info), at(&root, None, Some(&info)));
+    assert_eq!(locate_save_path(&d.path().join("notes.txt")), None);
+    assert_eq!(locate_save_path(&d.path().join("missing")), None);
+}
+
+#[test]
+fn clone_slot_to_next_free_index() {
+    use std::fs;
+    use tempfile::tempdir;
+    use wle_core::saves::{clone_slot, next_free_slot_index, slot_index};
+    let d = tempdir().unwrap();
+    for n in [1, 2] {
+        let slot = d.path().join(format!("SaveSlot_{}", n));
+        fs::create_dir_all(&slot).unwrap();
+        fs::write(slot.join("PlayerData_1.sav"), format!("p{}", n)).unwrap();
+    }
+    assert_eq!(next_free_slot_index(d.path()), 3);
+    let src = d.path().join("SaveSlot_1");
+    let dest = clone_slot(&src, d.path(), None).unwrap();
+    assert_eq!(slot_index(&dest), Some(3));
+    assert_eq!(fs::read(dest.join("PlayerData_1.sav")).unwrap(), b"p1");
+    // Refuses to overwrite an existing slot
+    assert!(clone_slot(&src, d.path(), Some(2)).is_err());
+}
+
+#[test]
+fn delete_and_renumber_slots_back_up_first() {
+    use std::fs;
+    use tempfile::tempdir;
+    use wle_core::saves::{delete_slot, delete_slot_to, list_slots, renumber_slots};
+    let d = tempdir().unwrap();
+    fs::write(d.path().join("SaveInfo.sav"), r#"{"lastLoadedSlot":3}"#).unwrap();
+    for n in [1, 2, 3] {
+        let slot = d.path().join(format!("SaveSlot_{}", n));
+        fs::create_dir_all(&slot).unwrap();
+        fs::write(slot.join("PlayerData_1.sav"), format!("p{}", n)).unwrap();
+    }
+    let backup = delete_slot(&d.path().join("SaveSlot_2")).unwrap();
+    assert!(backup.exists());
+    assert!(!d.path().join("SaveSlot_2").exists());
+    // A separate backup folder is honoured
+    let elsewhere = tempdir().unwrap();
+    fs::create_dir_all(d.path().join("SaveSlot_4")).unwrap();
+    let backup = delete_slot_to(&d.path().join("SaveSlot_4"), elsewhere.path()).unwrap();
+    assert_eq!(backup.parent(), Some(elsewhere.path()));
+
+    let moved = renumber_slots(d.path()).unwrap();
+    assert_eq!(moved.len(), 1);
+    assert_eq!(list_slots(d.path()).len(), 2);
+    let slot2 = d.path().join("SaveSlot_2");
+    assert_eq!(fs::read(slot2.join("PlayerData_1.sav")).unwrap(), b"p3");
+    // The moved slot was backed up, and its backups follow it: SaveSlot_2_*
+    // now holds the deleted slot's zip and the one taken before the move
+    let zips = |prefix: &str| {
+        fs::read_dir(d.path())
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with(prefix))
+            .count()
+    };
+    assert_eq!(zips("SaveSlot_3_"), 0);
+    assert_eq!(zips("SaveSlot_2_"), 2);
+    let info = fs::read_to_string(d.path().join("SaveInfo.sav")).unwrap();
+    let v: serde_json::Value = serde_json::from_str(&info).unwrap();
+    assert_eq!(v["lastLoadedSlot"], 2);
+}
+
+#[test]
+fn detect_save_roots_under_home() {
+    use std::fs;
+    use tempfile::tempdir;
+    let home = tempdir().unwrap();
+    let win = home
+        .path()
+        .join("AppData/LocalLow/RubberBandGames/Wobbly Life/Save/76561198000000000/GameSaves");
+    let proton = home.path().join(
+        ".steam/steam/steamapps/compatdata/1211020/pfx/drive_c/users/steamuser/AppData/LocalLow/RubberBandGames/Wobbly Life/Save/1/GameSaves",
+    );
+    for root in [&win, &proton] {
+        fs::create_dir_all(root.join("SaveSlot_1")).unwrap();
+        fs::write(root.join("SaveInfo.sav"), "{}").unwrap();
+    }
+    // A folder without SaveInfo.sav is not a candidate
+    fs::create_dir_all(
+        home.path()
+            .join(".config/unity3d/RubberBandGames/Wobbly Life/Save/2"),
+    )
+    .unwrap();
+    let found = wle_core::saves::detect_save_roots_in(home.path());
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].path, win);
+    assert!(found[0].label.contains("76561198000000000"));
+    assert_eq!(found[1].path, proton);
+}
+
+#[test]
+fn slot_summary_reports_info_and_health() {
+    use std::fs;
+    let d = tempfile::tempdir().unwrap();
+    let slot = d.path().join("SaveSlot_4");
+    fs::create_dir_all(&slot).unwrap();
+    let img = vec![0u8; 4 * 4 * 3];
+    let bytes = wle_core::editor::build_slot_info_bytes(2, "2025-09-22 12:00", &img);
+    fs::write(slot.join("SlotInfo.sav"), &bytes).unwrap();
+    fs::write(slot.join("PlayerData_3.sav"), b"\x00\x01garbage").unwrap();
+    fs::write(slot.join("PlayerData_1.sav"), &bytes).unwrap();
+    let sum = wle_core::saves::slot_summary(&slot).unwrap();
+    assert_eq!(sum.index, Some(4));
+    assert_eq!(sum.date_time.as_deref(), Some("2025-09-22 12:00"));
+    assert_eq!(sum.last_selected_player, Some(2));
+    let thumb = sum.thumbnail.as_ref().unwrap();
+    assert_eq!((thumb.width, thumb.height), (4, 4));
+    assert_eq!(sum.players, vec![1, 3]);
+    assert_eq!(sum.files.len(), 3);
+    assert!(!sum.is_healthy());
+    let bad = sum
+        .files
+        .iter()
+        .find(|f| f.name == "PlayerData_3.sav")
+        .unwrap();
+    assert!(bad.error.is_some());
+    assert_eq!(sum.total_size(), 2 * bytes.len() as u64 + 9);
+}
+
+#[test]
+fn slot_watcher_reports_external_writes_only() {
+    use std::fs;
+    use wle_core::saves::{SlotChangeKind, SlotWatcher};
+    let d = tempfile::tempdir().unwrap();
+    let file = d.path().join("PlayerData_1.sav");
+    fs::write(&file, b"one").unwrap();
+    let mut w = SlotWatcher::new(d.path()).unwrap();
+
+    // A write acknowledged through resync is not reported
+    fs::write(&file, b"editor").unwrap();
+    w.resync();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    assert!(w.try_changes().is_empty());
+
+    fs::write(&file, b"written by the game").unwrap();
+    let changes = w.wait_changes().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, SlotChangeKind::Modified);
+    assert!(changes[0].path.ends_with("PlayerData_1.sav"));
+}
+
+#[test]
+fn copy_pointer_between_slots() {
+    use std::fs;
+    use wle_core::json::JsonOpts;
+    use wle_core::saves::{DocKind, copy_document, copy_pointer};
+    let (d, a) = temp_slot(&["PlayerData_1.sav"]);
+    let reference = a.join("PlayerData_1.sav");
+    let b = d.path().join("SaveSlot_2");
+    fs::create_dir_all(&b).unwrap();
+    fs::copy(&reference, b.join("PlayerData_1.sav")).unwrap();
+    let kind: DocKind = "player:1".parse().unwrap();
+    assert_eq!(kind, DocKind::Player(1));
+    assert!("player".parse::<DocKind>().is_err());
+
+    let ptr = "/root/<MiscData>k__BackingField/$value/money";
+    let dest = b.join(kind.file_name());
+    let mut v = wle_core::parse_file_to_json_value(&dest, JsonOpts::full()).unwrap();
+    wle_core::set_raw_by_pointer(&mut v, ptr, serde_json::json!(5)).unwrap();
+    wle_core::write_binfmt_file_from_json(&dest, &v).unwrap();
+
+    let backup = copy_pointer(&a, &b, kind, ptr).unwrap();
+    assert!(backup.exists());
+    let v = wle_core::parse_file_to_json_value(&dest, JsonOpts::full()).unwrap();
+    assert_eq!(v.pointer(ptr), Some(&serde_json::json!(1560451)));
+
+    // Whole-document copy brings the bytes over verbatim
+    assert!(copy_document(&a, &b, kind).unwrap().is_some());
+    assert_eq!(fs::read(&dest).unwrap(), fs::read(&reference).unwrap());
+}
+
+#[test]
+fn atomic_write_leaves_no_temp_files() {
+    let d = tempfile::tempdir().unwrap();
+    let p = d.path().join("StatsData.sav");
+    std::fs::write(&p, b"old").unwrap();
+    wle_core::write_file_atomic(&p, b"new contents").unwrap();
+    assert_eq!(std::fs::read(&p).unwrap(), b"new contents");
+    let names: Vec<_> = std::fs::read_dir(d.path()).unwrap().flatten().collect();
+    assert_eq!(names.len(), 1);
+    // Missing directory is a plain I/O error, not a lock
+    let err = wle_core::write_file_atomic(&d.path().join("nope/x.sav"), b"x").unwrap_err();
+    assert!(matches!(err, wle_core::WriteError::Io(_)));
+}
+
+#[test]
+fn writable_check_names_the_folder() {
+    use wle_core::saves::check_writable;
+    let d = tempfile::tempdir().unwrap();
+    check_writable(d.path()).unwrap();
+    // The probe file is gone again
+    assert_eq!(std::fs::read_dir(d.path()).unwrap().count(), 0);
+    let missing = d.path().join("SaveSlot_9");
+    let err = check_writable(&missing).unwrap_err();
+    assert!(err.to_string().contains("SaveSlot_9"), "{}", err);
+}
+
+#[test]
+fn transaction_commits_all_files_or_none() {
+    use wle_core::saves::Transaction;
+    let d = tempfile::tempdir().unwrap();
+    let (a, b, gone) = (
+        d.path().join("PlayerData_1.sav"),
+        d.path().join("SlotInfo.sav"),
+        d.path().join("PlayerData_2.sav"),
+    );
+    for p in [&a, &b, &gone] {
+        std::fs::write(p, b"old").unwrap();
+    }
+    let mut tx = Transaction::new();
+    tx.write(&a, b"new a".to_vec())
+        .write(&b, b"new b".to_vec())
+        .remove(&gone);
+    tx.commit().unwrap();
+    assert_eq!(std::fs::read(&a).unwrap(), b"new a");
+    assert_eq!(std::fs::read(&b).unwrap(), b"new b");
+    assert!(!gone.exists());
+    let names: Vec<_> = std::fs::read_dir(d.path()).unwrap().flatten().collect();
+    assert_eq!(names.len(), 2);
+
+    // The second target cannot be replaced (a folder is in the way), so the
+    // first file, already swapped in, goes back to what it was
+    let blocked = d.path().join("PlayerData_3.sav");
+    std::fs::create_dir(&blocked).unwrap();
+    std::fs::write(blocked.join("keep"), b"x").unwrap();
+    let mut tx = Transaction::new();
+    tx.write(&a, b"newer a".to_vec())
+        .remove(&b)
+        .write(&blocked, b"x".to_vec());
+    assert!(tx.commit().is_err());
+    assert_eq!(std::fs::read(&a).unwrap(), b"new a");
+    assert_eq!(std::fs::read(&b).unwrap(), b"new b");
+    assert!(blocked.join("keep").exists());
+    let names: Vec<_> = std::fs::read_dir(d.path()).unwrap().flatten().collect();
+    assert_eq!(names.len(), 3);
+
+    // Edits to several saves land together, each with its own backup
+    let (_slot_dir, slot) = temp_slot(&["PlayerData_1.sav", "PlayerData_2.sav"]);
+    let money = "/root/<MiscData>k__BackingField/$value/money";
+    let edits: Vec<_> = [1, 2]
+        .map(|n| wle_core::cheats::PendingEdit {
+            path: slot.join(format!("PlayerData_{}.sav", n)),
+            ops: vec![wle_core::PatchOp::Replace {
+                path: money.into(),
+                value: serde_json::json!(500 + n),
+            }],
+        })
+        .into();
+    let backups = wle_core::cheats::apply_all(&edits).unwrap();
+    assert_eq!(backups.len(), 2);
+    for (n, e) in [501, 502].into_iter().zip(&edits) {
+        let v = wle_core::parse_file_for_edit(&e.path).unwrap();
+        assert_eq!(v.pointer(money).unwrap(), n);
+    }
+}
+
+#[test]
+fn save_info_accessors_keep_unknown_keys() {
+    use wle_core::model::SaveInfo;
+    let d = tempfile::tempdir().unwrap();
+    let p = d.path().join(SaveInfo::FILE_NAME);
+    std::fs::write(
+        &p,
+        r#"{"lastLoadedSlot": 1, "bModSafetyTip": false, "futureSetting": [1, 2]}"#,
+    )
+    .unwrap();
+    let mut info = SaveInfo::load(&p).unwrap();
+    assert_eq!(info.last_loaded_slot(), Some(1));
+    assert_eq!(info.mod_safety_tip(), Some(false));
+    info.set_last_loaded_slot(3);
+    info.save(&p).unwrap();
+    let again = SaveInfo::load(&p).unwrap();
+    assert_eq!(again.last_loaded_slot(), Some(3));
+    assert_eq!(again.fields()["futureSetting"], serde_json::json!([1, 2]));
+    assert!(SaveInfo::parse("[1]").is_err());
+}
+
+#[test]
+fn player_data_view_and_setters() {
+    use wle_core::model::{ClothingSlot, Guid, PlayerData};
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let doc = wle_core::json::parse_binary(&p).unwrap();
+    let player = doc.as_player_data().expect("player view");
+    assert_eq!(player.money, Some(1560451));
+    assert_eq!(player.clothes.len(), 4);
+    let (slot, outfit) = player.clothes[3];
+    assert_eq!(slot, ClothingSlot::Outfit);
+    assert!(outfit.prefab.is_nil());
+    assert_eq!(player.vehicles.len(), 43);
+    assert_eq!(player.presents_unlocked.len(), 120);
+    assert_eq!(player.pets[0].name, "Sludge");
+    assert!(player.misc_stats.contains(&("stat_candycanes", 2)));
+
+    // Guid text form round-trips
+    let g = player.clothes[0].1.prefab;
+    assert_eq!(g.to_string().parse::<Guid>().unwrap(), g);
+
+    let mut json =
+        wle_core::parse_file_to_json_value(&p, wle_core::json::JsonOpts::default()).unwrap();
+    let mut ops = vec![
+        PlayerData::set_money(42),
+        PlayerData::set_pet_name(0, "Rex"),
+    ];
+    ops.extend(PlayerData::set_clothing(
+        ClothingSlot::Hat,
+        &player.clothes[1].1,
+    ));
+    wle_core::apply_patch(&mut json, &ops).unwrap();
+    assert_eq!(json.pointer(ops[0].path()), Some(&serde_json::json!(42)));
+    assert_eq!(json.pointer(ops[1].path()), Some(&serde_json::json!("Rex")));
+    let hat = json.pointer(ops[2].path()).unwrap();
+    assert_eq!(Guid::from_json(hat), Some(player.clothes[1].1.prefab));
+}
+
+#[test]
+fn mission_data_complete_and_reset() {
+    use wle_core::model::MissionState;
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/MissionData.sav");
+    let doc = wle_core::json::parse_binary(&p).unwrap();
+    let missions = doc.as_mission_data().expect("mission view");
+    let done = missions
+        .missions
+        .iter()
+        .filter(|m| m.state == MissionState::Completed)
+        .count();
+    // 64-slot backing array, of which 48 are in use
+    assert_eq!(done, 48);
+    let open = missions
+        .missions
+        .iter()
+        .find(|m| m.state == MissionState::Started)
+        .expect("a started mission");
+    assert!(open.progress.unwrap().contains("bMissionStarted"));
+
+    let mut json =
+        wle_core::parse_file_to_json_value(&p, wle_core::json::JsonOpts::full()).unwrap();
+    let ops = missions.complete_mission(open.id).unwrap();
+    wle_core::apply_patch(&mut json, &ops).unwrap();
+    let added = json.pointer("/root/missions_Completed/48").unwrap();
+    assert_eq!(wle_core::model::Guid::from_json(added), Some(open.id));
+
+    let first = missions.missions[0].id;
+    let ops = missions.reset_mission(first).unwrap();
+    assert!(!ops.is_empty());
+    assert!(missions.complete_all().len() >= 2);
+}
+
+#[test]
+fn stats_data_get_and_set() {
+    use wle_core::model::StatValue;
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/StatsData.sav");
+    let doc = wle_core::json::parse_binary(&p).unwrap();
+    let stats = doc.as_stats_data().expect("stats view");
+    assert_eq!(stats.get("toxicWasteBarrelsFed"), Some(StatValue::Int(0)));
+    // The null event field is not a stat
+    assert!(stats.get("onStatChanged").is_none());
+    assert!(stats.set("toxicWasteBarrelsFed", "1.5").is_err());
+    assert!(stats.set("nope", "1").is_err());
+
+    let op = stats.set("toxicWasteBarrelsFed", "12").unwrap();
+    let mut json =
+        wle_core::parse_file_to_json_value(&p, wle_core::json::JsonOpts::default()).unwrap();
+    wle_core::apply_patch(&mut json, &[op]).unwrap();
+    assert_eq!(
+        json.pointer("/root/toxicWasteBarrelsFed"),
+        Some(&serde_json::json!(12))
+    );
+}
+
+#[test]
+fn parse_with_progress_and_cancel() {
+    use std::sync::Arc;
+    use wle_core::binfmt::ParseProgress;
+    use wle_core::json::JsonOpts;
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let progress = Arc::new(ParseProgress::default());
+    assert_eq!(progress.fraction(), 0.0);
+    let v =
+        wle_core::parse_file_to_json_value_with_progress(&p, JsonOpts::full(), progress.clone())
+            .unwrap();
+    assert_eq!(
+        v,
+        wle_core::parse_file_to_json_value(&p, JsonOpts::full()).unwrap()
+    );
+    assert!(progress.fraction() > 0.99);
+
+    let cancelled = Arc::new(ParseProgress::default());
+    cancelled.cancel();
+    let err = wle_core::parse_file_to_json_value_with_progress(&p, JsonOpts::full(), cancelled)
+        .unwrap_err();
+    assert_eq!(err, "cancelled");
+}
+
+#[test]
+fn cancel_tokens_stop_conversion_dump_and_write() {
+    use wle_core::binfmt::{CancellationToken, Parser};
+    use wle_core::json::JsonOpts;
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let data = std::fs::read(&p).unwrap();
+    let doc = Parser::new(&data).parse_stream().unwrap();
+    let v = wle_core::document_to_json_value(&doc, JsonOpts::full());
+
+    // Untouched tokens change nothing
+    let live = CancellationToken::default();
+    assert_eq!(
+        wle_core::document_to_json_value_with_cancel(&doc, JsonOpts::full(), &live).unwrap(),
+        v
+    );
+    assert_eq!(
+        wle_core::parse_bytes_to_json_value_with_cancel(data.clone(), JsonOpts::full(), &live)
+            .unwrap(),
+        v
+    );
+    assert_eq!(
+        wle_core::json::dump_bytes_json_with_cancel(data.clone(), JsonOpts::full(), &live).unwrap(),
+        wle_core::json::dump_dynamic_json(&doc, JsonOpts::full())
+    );
+    assert_eq!(
+        wle_core::write_binfmt_from_json_with_cancel(&v, &live).unwrap(),
+        wle_core::write_binfmt_from_json(&v).unwrap()
+    );
+
+    // Clones share the flag
+    let cancelled = CancellationToken::default();
+    cancelled.clone().cancel();
+    assert!(cancelled.is_cancelled());
+    let err = Parser::new(&data)
+        .with_cancel(cancelled.clone())
+        .parse_stream()
+        .unwrap_err();
+    assert_eq!(err, "cancelled");
+    for err in [
+        wle_core::document_to_json_value_with_cancel(&doc, JsonOpts::full(), &cancelled)
+            .map(|_| ())
+            .unwrap_err(),
+        wle_core::json::dump_bytes_json_with_cancel(data.clone(), JsonOpts::full(), &cancelled)
+            .map(|_| ())
+            .unwrap_err(),
+        wle_core::write_binfmt_from_json_with_cancel(&v, &cancelled)
+            .map(|_| ())
+            .unwrap_err(),
+    ] {
+        assert_eq!(err, "cancelled");
+    }
+}
+
+#[test]
+fn byte_spans_cover_edit_json() {
+    fn walk(v: &serde_json::Value, ptr: String, out: &mut Vec<(String, serde_json::Value)>) {
+        match v {
+            serde_json::Value::Object(m) => {
+                for (k, c) in m {
+                    if k != "$class" {
+                        walk(
+                            c,
+                            format!("{}/{}", ptr, k.replace('~', "~0").replace('/', "~1")),
+                            out,
+                        );
+                    }
+                }
+            }
+            serde_json::Value::Array(a) => {
+                for (i, c) in a.iter().enumerate() {
+                    walk(c, format!("{}/{}", ptr, i), out);
+                }
+            }
+            _ => {}
+        }
+        out.push((ptr, v.clone()));
+    }
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let data = std::fs::read(&p).unwrap();
+    let doc = wle_core::binfmt::Parser::new(&data).parse_stream().unwrap();
+    let spans = doc.byte_spans();
+    let json = wle_core::parse_file_to_json_value(&p, wle_core::json::JsonOpts::full()).unwrap();
+    let mut nodes = Vec::new();
+    walk(&json["root"], "/root".into(), &mut nodes);
+    assert!(nodes.len() > 1000);
+    for (ptr, v) in &nodes {
+        let span = spans
+            .get(ptr)
+            .unwrap_or_else(|| panic!("no span for {}", ptr));
+        assert!(span.start < span.end && span.end <= data.len(), "{}", ptr);
+        // Inline strings are stored as UTF-8 right in their span
+        if let Some(s) = v.as_str()
+            && !s.is_empty()
+            && !ptr.ends_with("/$ref")
+        {
+            let bytes = &data[span.clone()];
+            assert!(bytes.windows(s.len()).any(|w| w == s.as_bytes()) || bytes[0] == 9);
+        }
+    }
+    // Children sit inside their parent's record
+    let root = &spans["/root"];
+    assert!(nodes.iter().all(|(p, _)| {
+        let s = &spans[p];
+        p.contains("/$value") || (root.start <= s.start && s.end <= root.end)
+    }));
+}
+
+#[test]
+fn object_index_pointers_match_edit_json() {
+    use wle_core::model::ObjectIndex;
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1");
+    let world = wle_core::json::parse_binary(&dir.join("WorldData.sav")).unwrap();
+    let view = world.as_world_data().expect("world view");
+    assert_eq!(view.scenes.len(), 2);
+    assert_eq!(view.scenes[0].scene, 1);
+    assert_eq!(view.index.by_class("SaveWorldDataScene").len(), 2);
+
+    // On PlayerData, vehicles are found by GUID at their edit-JSON pointer
+    let p = dir.join("PlayerData_1.sav");
+    let doc = wle_core::json::parse_binary(&p).unwrap();
+    let index = ObjectIndex::build(&doc);
+    let vehicles = index.by_class("VehicleData");
+    assert_eq!(vehicles.len(), 43);
+    let first = index.by_guid(vehicles[0].guid.unwrap()).unwrap();
+    let json = wle_core::parse_file_to_json_value(&p, wle_core::json::JsonOpts::full()).unwrap();
+    let node = json.pointer(&first.pointer).expect("pointer resolves");
+    assert_eq!(node["$class"], "VehicleData");
+}
+
+#[test]
+fn set_money_writes_back_with_backup() {
+    let (_d, slot) = temp_slot(&["PlayerData_1.sav"]);
+    assert_eq!(wle_core::cheats::get_money(&slot, 1).unwrap(), 1560451);
+    assert!(wle_core::cheats::set_money(&slot, 1, -1).is_err());
+    assert!(wle_core::cheats::set_money(&slot, 2, 5).is_err());
+    // A pending edit can be previewed without touching the file
+    let edit = wle_core::cheats::money_edit(&slot, 1, 999_999).unwrap();
+    let changes = edit.preview().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].new, Some(serde_json::json!(999_999)));
+    assert_eq!(wle_core::cheats::get_money(&slot, 1).unwrap(), 1560451);
+    let backup = wle_core::cheats::set_money(&slot, 1, 999_999).unwrap();
+    assert!(backup.exists());
+    assert_eq!(wle_core::cheats::get_money(&slot, 1).unwrap(), 999_999);
+}
+
+#[test]
+fn complete_all_missions_writes_verified_with_backup() {
+    let (_d, slot) = temp_slot(&["MissionData.sav"]);
+    let (count, backup) = wle_core::cheats::complete_all_missions(&slot).unwrap();
+    assert!(count > 0);
+    assert!(backup.unwrap().exists());
+    let doc = wle_core::json::parse_binary(&slot.join("MissionData.sav")).unwrap();
+    let missions = doc.as_mission_data().unwrap();
+    assert!(
+        missions
+            .missions
+            .iter()
+            .all(|m| m.state == wle_core::model::MissionState::Completed)
+    );
+    // A second run has nothing to do and writes nothing
+    assert_eq!(
+        wle_core::cheats::complete_all_missions(&slot).unwrap(),
+        (0, None)
+    );
+}
+
+#[test]
+fn mission_and_stats_edits_write_back() {
+    use wle_core::cheats::{mission_edit, stats_edit};
+    use wle_core::model::{MissionState, StatValue};
+    let (_d, slot) = temp_slot(&["MissionData.sav", "StatsData.sav"]);
+    let state = |id| {
+        let doc = wle_core::json::parse_binary(&slot.join("MissionData.sav")).unwrap();
+        doc.as_mission_data().unwrap().get(id).map(|m| m.state)
+    };
+    let doc = wle_core::json::parse_binary(&slot.join("MissionData.sav")).unwrap();
+    let open = doc
+        .as_mission_data()
+        .unwrap()
+        .missions
+        .iter()
+        .find(|m| m.state == MissionState::Started)
+        .unwrap()
+        .id;
+    mission_edit(&slot, open, true).unwrap().apply().unwrap();
+    assert_eq!(state(open), Some(MissionState::Completed));
+    mission_edit(&slot, open, false).unwrap().apply().unwrap();
+    assert_eq!(state(open), Some(MissionState::NotStarted));
+
+    let values = [("toxicWasteBarrelsFed".to_string(), "7".to_string())];
+    stats_edit(&slot, &values).unwrap().apply().unwrap();
+    let doc = wle_core::json::parse_binary(&slot.join("StatsData.sav")).unwrap();
+    let stats = doc.as_stats_data().unwrap();
+    assert_eq!(stats.get("toxicWasteBarrelsFed"), Some(StatValue::Int(7)));
+    // One bad value rejects the whole edit
+    let bad = [
+        ("toxicWasteBarrelsFed".to_string(), "8".to_string()),
+        ("toxicWasteBarrelsFed".to_string(), "x".to_string()),
+    ];
+    assert!(stats_edit(&slot, &bad).is_err());
+}
+
+#[test]
+fn unlock_presets_plan_then_apply() {
+    use wle_core::cheats::{Unlock, apply_changes, plan_unlock};
+    let (_d, slot) = temp_slot(&["PlayerData_1.sav", "PlayerData_2.sav"]);
+    let owner = wle_core::json::parse_binary(&slot.join("PlayerData_1.sav")).unwrap();
+    let owner = owner.as_player_data().unwrap();
+
+    // Player 1 owns everything in the catalog, so there is nothing to add
+    assert!(plan_unlock(&slot, 1, Unlock::Items).unwrap().is_empty());
+
+    let before = std::fs::read(slot.join("PlayerData_2.sav")).unwrap();
+    let mut changes = Vec::new();
+    for what in [Unlock::Clothing, Unlock::Vehicles, Unlock::Items] {
+        changes.extend(plan_unlock(&slot, 2, what).unwrap());
+    }
+    // Planning alone does not touch the file
+    assert_eq!(
+        std::fs::read(slot.join("PlayerData_2.sav")).unwrap(),
+        before
+    );
+    assert!(changes.iter().any(|c| c.summary.starts_with("add hat ")));
+
+    let backup = apply_changes(&slot, 2, &changes).unwrap();
+    assert!(backup.exists());
+    let doc = wle_core::json::parse_binary(&slot.join("PlayerData_2.sav")).unwrap();
+    let p2 = doc.as_player_data().unwrap();
+    assert_eq!(p2.wardrobe_counts, owner.wardrobe_counts);
+    assert_eq!(p2.vehicles.len(), owner.vehicles.len());
+    assert_eq!(p2.presents_unlocked.len(), owner.presents_unlocked.len());
+    assert!(plan_unlock(&slot, 2, Unlock::Vehicles).unwrap().is_empty());
+}
+
+#[test]
+fn regenerate_guids_rekeys_subtree_consistently() {
+    use wle_core::model::Guid;
+    let g = Guid::new_random();
+    assert_ne!(g, Guid::new_random());
+    assert_eq!(g.to_string().as_bytes()[14], b'4');
+    assert_eq!(g.to_string().parse::<Guid>().unwrap(), g);
+
+    let old = Guid::new_random();
+    let mut v = serde_json::json!({"root": {
+        "a": {"id": old.to_json(), "none": Guid::NIL.to_json()},
+        "b": [old.to_json(), {"n": 5}],
+        "keep": old.to_json()
+    }});
+    let map = wle_core::regenerate_guids(&mut v, "/root/a").unwrap();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map[0].0, old);
+    let fresh = map[0].1;
+    assert_eq!(Guid::from_json(&v["root"]["a"]["id"]), Some(fresh));
+    assert_eq!(Guid::from_json(&v["root"]["a"]["none"]), Some(Guid::NIL));
+    assert_eq!(Guid::from_json(&v["root"]["keep"]), Some(old));
+
+    // Repeated GUIDs share one replacement
+    let map = wle_core::regenerate_guids(&mut v, "/root").unwrap();
+    assert_eq!(map.len(), 2);
+    assert_eq!(v["root"]["b"][0], v["root"]["keep"]);
+    assert!(wle_core::regenerate_guids(&mut v, "/nope").is_err());
+}
+
+#[test]
+fn add_remove_swap_players() {
+    use wle_core::saves::{create_player, remove_player, swap_players};
+    let (_d, slot) = temp_slot(&[
+        "SlotInfo.sav",
+        "PlayerData_1.sav",
+        "PlayerData_2.sav",
+        "PlayerData_3.sav",
+        "PlayerData_4.sav",
+        "PlayerSettingsData_1.sav",
+        "PlayerSettingsData_2.sav",
+        "PlayerSettingsData_3.sav",
+        "PlayerSettingsData_4.sav",
+    ]);
+    let read = |name: &str| std::fs::read(slot.join(name)).unwrap();
+    let selected = || {
+        let doc = wle_core::json::parse_binary(&slot.join("SlotInfo.sav")).unwrap();
+        doc.as_save_slot_info().unwrap().last_selected_player_slot
+    };
+    let (p1, slot_info) = (read("PlayerData_1.sav"), read("SlotInfo.sav"));
+
+    assert!(swap_players(&slot, 1, 2).unwrap().exists());
+    assert_eq!(read("PlayerData_2.sav"), p1);
+    assert_eq!(selected(), 1);
+    swap_players(&slot, 2, 1).unwrap();
+    assert_eq!(read("PlayerData_1.sav"), p1);
+    // SlotInfo is rebuilt byte-for-byte, thumbnail included
+    assert_eq!(read("SlotInfo.sav"), slot_info);
+
+    remove_player(&slot, 1).unwrap();
+    assert!(!slot.join("PlayerData_1.sav").exists());
+    assert!(!slot.join("PlayerSettingsData_1.sav").exists());
+    assert_eq!(selected(), 1);
+
+    create_player(&slot, 1, None).unwrap();
+    assert_eq!(read("PlayerData_1.sav"), read("PlayerData_4.sav"));
+    assert!(create_player(&slot, 1, None).is_err());
+    assert!(create_player(&slot, 5, None).is_err());
+    remove_player(&slot, 4).unwrap();
+    create_player(&slot, 4, Some(3)).unwrap();
+    assert!(slot.join("PlayerData_4.sav").exists());
+
+    // A copy gets fresh pet ids, references included; clothes are kept
+    std::fs::write(slot.join("PlayerData_1.sav"), &p1).unwrap();
+    remove_player(&slot, 4).unwrap();
+    create_player(&slot, 4, Some(1)).unwrap();
+    let doc = |name: &str| wle_core::parse_file_for_edit(&slot.join(name)).unwrap();
+    let (a, b) = (doc("PlayerData_1.sav"), doc("PlayerData_4.sav"));
+    let pet = "/root/<PetData>k__BackingField/$value/petDatas/$value/0/guid";
+    assert_ne!(a.pointer(pet), b.pointer(pet));
+    let ext = "/root/<ExternalData>k__BackingField/$value/datasDic/$value/KeyValuePairs/$value/0";
+    let b_ext = b.pointer(ext).unwrap();
+    assert_eq!(b_ext["key"], b_ext["value"]["$value"]["guid"]);
+    assert_ne!(a.pointer(ext).unwrap()["key"], b_ext["key"]);
+    let hat = "/root/<CurrentClothes>k__BackingField/<ClothingHat>k__BackingField";
+    assert!(a.pointer(hat).is_some());
+    assert_eq!(a.pointer(hat), b.pointer(hat));
+}
+
+#[test]
+fn thumbnail_export_import_roundtrip() {
+    use wle_core::model::SlotImage;
+    use wle_core::saves::{export_thumbnail, import_thumbnail};
+    let (d, slot) = temp_slot(&["SlotInfo.sav"]);
+    let original = std::fs::read(slot.join("SlotInfo.sav")).unwrap();
+
+    let png = d.path().join("thumb.png");
+    export_thumbnail(&slot, &png).unwrap();
+    assert!(import_thumbnail(&slot, &png).unwrap().exists());
+    assert_eq!(std::fs::read(slot.join("SlotInfo.sav")).unwrap(), original);
+
+    // Other sizes are scaled to the slot's format
+    let red = SlotImage {
+        width: 4,
+        height: 2,
+        rgba: [255, 0, 0, 255].repeat(8),
+    };
+    std::fs::write(&png, red.to_png().unwrap()).unwrap();
+    import_thumbnail(&slot, &png).unwrap();
+    let doc = wle_core::json::parse_binary(&slot.join("SlotInfo.sav")).unwrap();
+    let info = doc.as_save_slot_info().unwrap();
+    assert_eq!(info.small_image_data.len(), 256 * 256 * 3);
+    let img = info.decode_image().unwrap();
+    assert_eq!((img.width, &img.rgba[..4]), (256, &[255, 0, 0, 255][..]));
+
+    std::fs::write(&png, b"not a png").unwrap();
+    assert!(import_thumbnail(&slot, &png).is_err());
+}
+
+#[test]
+fn appearance_view_and_wear() {
+    use wle_core::model::ClothingSlot;
+    let (_d, slot) = temp_slot(&["PlayerData_1.sav"]);
+    let src = slot.join("PlayerData_1.sav");
+
+    let doc = wle_core::json::parse_binary(&src).unwrap();
+    let look = doc.as_character_appearance().unwrap();
+    let counts = doc.as_player_data().unwrap().wardrobe_counts;
+    for (i, s) in ClothingSlot::ALL.iter().enumerate() {
+        assert_eq!(look.options(*s).len(), counts[i]);
+    }
+    assert!(look.worn_index(ClothingSlot::Hat).is_some());
+    assert!(look.worn_index(ClothingSlot::Outfit).is_none());
+    assert!(look.wear(ClothingSlot::Outfit, Some(99)).is_err());
+    let hat = look.options(ClothingSlot::Hat)[3];
+
+    wle_core::cheats::set_appearance(
+        &slot,
+        1,
+        &[(ClothingSlot::Hat, Some(3)), (ClothingSlot::Top, None)],
+    )
+    .unwrap();
+    let doc = wle_core::json::parse_binary(&slot.join("PlayerData_1.sav")).unwrap();
+    let look = doc.as_character_appearance().unwrap();
+    assert_eq!(look.worn(ClothingSlot::Hat), Some(&hat));
+    assert!(look.worn(ClothingSlot::Top).unwrap().prefab.is_nil());
+}
+
+#[test]
+fn field_catalog_lookup() {
+    use wle_core::catalog::{field_info, label};
+    let info = field_info("SaveSlotInfoData", "lastSelectedPlayerSlot").unwrap();
+    assert_eq!(info.label, "Last selected player");
+    assert!(info.in_range(3.0) && !info.in_range(4.0));
+    assert!(info.tooltip().contains("0..=3"));
+    // Generic classes match without their type arguments
+    let list = "HawkNetworking.HawkMessageList`1[[ClothingPieceData, Game, Version=0.0.0.0, Culture=neutral, PublicKeyToken=null]]";
+    assert_eq!(label(list, "List`1+_size"), "Count");
+    assert_eq!(label("PlayerMiscData", "unknownMember"), "unknownMember");
+    assert!(field_info("NoSuchClass", "money").is_none());
+}
+
+#[test]
+fn search_keys_values_and_classes() {
+    use wle_core::{SearchScope, search};
+    let v = serde_json::json!({
+        "$rootClass": "PlayerData",
+        "root": {
+            "$class": "PlayerData",
+            "<MiscData>k__BackingField": {"$ref": 2, "$value": {"$class": "PlayerMiscData", "money": 1500, "name": "Wobbly"}},
+            "list": [1500, "x"]
+        }
+    });
+    let ptrs = |scope, q| -> Vec<String> {
+        search(&v, q, scope, 10)
+            .into_iter()
+            .map(|h| h.pointer)
+            .collect()
+    };
+    assert_eq!(
+        ptrs(SearchScope::Keys, "MONEY"),
+        ["/root/<MiscData>k__BackingField/$value/money"]
+    );
+    assert_eq!(
+        ptrs(SearchScope::Values, "1500"),
+        [
+            "/root/<MiscData>k__BackingField/$value/money",
+            "/root/list/0"
+        ]
+    );
+    assert_eq!(
+        ptrs(SearchScope::Class, "misc"),
+        ["/root/<MiscData>k__BackingField/$value"]
+    );
+    assert_eq!(ptrs(SearchScope::Class, "playerdata"), ["/root"]);
+    // Without its own `$class` the root is found by `$rootClass`
+    let bare = serde_json::json!({"$rootClass": "PlayerData", "root": {"money": 1}});
+    assert_eq!(search(&bare, "playerdata", SearchScope::Class, 10).len(), 1);
+    // Keys first, values after, no duplicates; the limit is honoured
+    let all = search(&v, "wobbly", SearchScope::All, 10);
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].preview, "\"Wobbly\"");
+    assert_eq!(search(&v, "1500", SearchScope::Values, 1).len(), 1);
+    assert!(search(&v, "  ", SearchScope::All, 10).is_empty());
+}
+
+#[test]
+fn replace_all_keeps_field_types() {
+    use wle_core::{PatchOp, apply_patch, coerce_like, coerced_replace_ops};
+    let mut v = serde_json::json!({
+        "root": {
+            "a": {"locked": true, "count": 3, "ratio": 0.5, "name": "x"},
+            "b": {"$ref": 4, "$value": {"locked": {"$ref": 5, "$value": true}}},
+            "c": {"locked": false}
+        }
+    });
+    assert_eq!(coerce_like(&v["root"]["a"]["count"], "12"), Ok(12.into()));
+    assert!(coerce_like(&v["root"]["a"]["count"], "1.5").is_err());
+    assert_eq!(coerce_like(&v["root"]["a"]["ratio"], "2"), Ok(2.0.into()));
+    assert_eq!(
+        coerce_like(&v["root"]["a"]["name"], "\"y\""),
+        Ok("y".into())
+    );
+    assert_eq!(
+        coerce_like(&v["root"]["a"]["name"], "y z"),
+        Ok("y z".into())
+    );
+
+    let ptrs: Vec<String> = [
+        "/root/a/locked",
+        "/root/b/$value/locked",
+        "/root/c/locked",
+        "/root/a/name",
+        "/root/missing",
+    ]
+    .map(String::from)
+    .to_vec();
+    let (ops, skipped) = coerced_replace_ops(&v, &ptrs, "FALSE");
+    assert_eq!(
+        ops,
+        [
+            PatchOp::replace("/root/a/locked", false.into()),
+            PatchOp::replace("/root/b/$value/locked/$value", false.into()),
+            PatchOp::replace("/root/a/name", "FALSE".into()),
+        ]
+    );
+    // /root/c/locked already holds false; the missing pointer is reported
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].0, "/root/missing");
+    apply_patch(&mut v, &ops).unwrap();
+    assert_eq!(v["root"]["b"]["$value"]["locked"]["$value"], false);
+
+    let (ops, skipped) = coerced_replace_ops(&v, &["/root/a/count".into(), "/root/a".into()], "x");
+    assert!(ops.is_empty());
+    assert_eq!(skipped.len(), 2);
+}
+
+#[test]
+fn paste_over_and_into_nodes() {
+    use wle_core::model::Guid;
+    use wle_core::{apply_patch, paste_ops};
+    let id = Guid::new_random();
+    let mut v = serde_json::json!({"root": {
+        "items": [{"id": id.to_json(), "n": 1}],
+        "stats": {"a": 1},
+        "money": 5
+    }});
+    let item = v["root"]["items"][0].clone();
+    let ops = paste_ops(&v, "/root/items", item.clone(), true).unwrap();
+    apply_patch(&mut v, &ops).unwrap();
+    // The pasted copy is appended with a GUID of its own
+    assert_eq!(v["root"]["items"][1]["n"], 1);
+    assert_ne!(Guid::from_json(&v["root"]["items"][1]["id"]), Some(id));
+
+    let ops = paste_ops(
+        &v,
+        "/root/stats",
+        serde_json::json!({"b": 2, "a/x": 3}),
+        true,
+    )
+    .unwrap();
+    apply_patch(&mut v, &ops).unwrap();
+    assert_eq!(
+        v["root"]["stats"],
+        serde_json::json!({"a": 1, "b": 2, "a/x": 3})
+    );
+
+    let ops = paste_ops(&v, "/root/money", 9.into(), false).unwrap();
+    apply_patch(&mut v, &ops).unwrap();
+    assert_eq!(v["root"]["money"], 9);
+
+    assert!(paste_ops(&v, "/root/money", 1.into(), true).is_err());
+    assert!(paste_ops(&v, "/root/stats", 1.into(), true).is_err());
+    assert!(paste_ops(&v, "", item.clone(), false).is_err());
+    assert!(paste_ops(&v, "/root/nope", item, false).is_err());
+}
+
+#[test]
+fn pointer_book_round_trips() {
+    use wle_core::PointerBook;
+    let mut book = PointerBook::default();
+    assert!(book.add("player", "/root/money/$value", ""));
+    assert!(!book.add("player", "/root/money/$value", "again"));
+    assert!(book.add("player", "/root/a~1b", ""));
+    assert!(book.add("world", "/root/dataScenes", "Scenes"));
+    let labels: Vec<&str> = book
+        .get("player")
+        .iter()
+        .map(|b| b.label.as_str())
+        .collect();
+    assert_eq!(labels, ["money", "a/b"]);
+    assert!(book.rename("player", "/root/a~1b", " Cash "));
+    assert_eq!(book.get("player")[1].label, "Cash");
+    book.rename("player", "/root/money/$value", "");
+    assert_eq!(book.get("player")[0].label, "money");
+    assert!(book.get("stats").is_empty());
+
+    let d = tempfile::tempdir().unwrap();
+    let path = d.path().join("sub/favorites.json");
+    book.save(&path).unwrap();
+    assert_eq!(PointerBook::load(&path), book);
+
+    assert!(book.remove("world", "/root/dataScenes"));
+    assert!(!book.remove("world", "/root/dataScenes"));
+    assert!(!book.to_json().as_object().unwrap().contains_key("world"));
+    // Bad files and entries are skipped, not fatal
+    std::fs::write(&path, "not json").unwrap();
+    assert_eq!(PointerBook::load(&path), PointerBook::default());
+    let v =
+        serde_json::json!({"player": [{"label": "no pointer"}, {"pointer": "/root/x"}], "bad": 3});
+    assert_eq!(PointerBook::from_json(&v).get("player").len(), 1);
+}
+
+#[test]
+fn validate_for_write_flags_lossy_edits() {
+    use wle_core::validate::Severity;
+    let original = serde_json::json!({"root": {
+        "money": 5, "ratio": 0.5, "name": "x", "none": null,
+        "blob": {"$type": "bytes", "len": 16},
+        "list": [1, 2, {"$truncated": true, "$omitted": 30}]
+    }});
+    let mut edited = original.clone();
+    edited["root"]["money"] = "5".into();
+    edited["root"]["ratio"] = 1.into();
+    edited["root"]["name"] = "y".into();
+    edited["root"]["none"] = 3.into();
+    let issues = wle_core::validate_for_write(&original, &edited);
+    let found: Vec<(Severity, &str)> = issues
+        .iter()
+        .map(|i| (i.severity, i.pointer.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (Severity::Warning, "/root/blob"),
+            (Severity::Error, "/root/list/2"),
+            (Severity::Warning, "/root/money"),
+            (Severity::Warning, "/root/ratio"),
+        ]
+    );
+    assert!(issues[1].message.contains("30 items"));
+    assert_eq!(issues[2].message, "type changes from integer to string");
+
+    // A full dump of a real save has nothing to report
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/MissionData.sav");
+    let v = wle_core::parse_file_to_json_value(&p, wle_core::json::JsonOpts::full()).unwrap();
+    assert!(wle_core::validate_for_write(&v, &v).is_empty());
+}
+
+#[test]
+fn edit_script_and_json_patch_apply() {
+    use wle_core::{PatchOp, apply_patch, parse_edit_script, parse_patch};
+    let script = r#"
+        # recipe
+        set    /root/a/c false
+        insert /root/a/b/- 4
+        remove /root/a/b/0
+        copy   /root/a/b "/root/a/b copy"
+    "#;
+    let ops = parse_edit_script(script).unwrap();
+    assert_eq!(ops.len(), 4);
+    let mut v = serde_json::json!({"root": {"a": {"b": [1, 2, 3], "c": true}}});
+    apply_patch(&mut v, &ops).unwrap();
+    assert_eq!(
+        v,
+        serde_json::json!({"root": {"a": {"b": [2, 3, 4], "c": false, "b copy": [2, 3, 4]}}})
+    );
+    // The JSON form reads back what to_json writes
+    let arr = serde_json::Value::Array(ops.iter().map(PatchOp::to_json).collect());
+    assert_eq!(parse_patch(&arr).unwrap(), ops);
+    // Errors name the offending line / op
+    let e = parse_edit_script("set /root/a 1\nfrobnicate /x").unwrap_err();
+    assert!(e.starts_with("line 2:"), "{}", e);
+    let e =
+        parse_patch(&serde_json::json!([{"op": "move", "from": "/a", "path": "/b"}])).unwrap_err();
+    assert!(e.starts_with("op 1:"), "{}", e);
+}
+
+#[test]
+fn edit_session_apply_undo_save() {
+    use wle_core::{EditSession, PatchOp};
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("doc.json");
+    std::fs::write(&path, r#"{"root":{"a":[1,2],"b":"x"}}"#).unwrap();
+
+    let mut s = EditSession::open(&path).unwrap();
+    assert!(!s.is_dirty() && !s.can_undo());
+    s.apply(&[PatchOp::replace("/root/b", "y".into())]).unwrap();
+    s.apply(&[
+        PatchOp::Remove {
+            path: "/root/a/0".into(),
+        },
+        PatchOp::Add {
+            path: "/root/c".into(),
+            value: true.into(),
+        },
+    ])
+    .unwrap();
+    assert_eq!(
+        s.value()["root"],
+        serde_json::json!({"a": [2], "b": "y", "c": true})
+    );
+    // A failing step is rolled back as a whole and not recorded
+    let bad = [
+        PatchOp::replace("/root/b", "z".into()),
+        PatchOp::Remove {
+            path: "/root/missing".into(),
+        },
+    ];
+    assert!(s.apply(&bad).is_err());
+    assert_eq!(s.value()["root"]["b"], "y");
+    let changes = s.history()[1].changes();
+    let ptrs: Vec<&str> = changes.iter().map(|c| c.pointer.as_str()).collect();
+    assert_eq!(ptrs, ["/root/a/0", "/root/a/1", "/root/c"]);
+    assert_eq!(changes[2].new, Some(true.into()));
+    assert!(s.undo());
+    assert_eq!(
+        s.value()["root"],
+        serde_json::json!({"a": [1, 2], "b": "y"})
+    );
+    // Redo replays the step; a new edit drops what was left to redo
+    assert!(s.redo() && !s.redo());
+    assert_eq!(s.value()["root"]["c"], true);
+    assert!(s.undo() && s.can_redo());
+    assert_eq!(s.redo_history()[0].changes().len(), 3);
+    s.apply(&[PatchOp::replace("/root/b", "w".into())]).unwrap();
+    assert!(!s.can_redo());
+    assert!(s.undo());
+    s.save().unwrap();
+    assert!(!s.is_dirty());
+    assert!(s.undo() && !s.undo());
+    assert_eq!(s.value()["root"]["b"], "x");
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved["root"]["b"], "y");
+}
+
+#[test]
+fn diff_lists_changes_and_patches_a_into_b() {
+    use wle_core::{apply_patch, diff};
+    let a = serde_json::json!({"m": 1, "gone": true, "list": [1, 2, 3], "o": {"x/y": "a"}});
+    let b = serde_json::json!({"m": 2, "list": [1], "o": {"x/y": "b"}, "new": [0]});
+    let changes = diff(&a, &b, "/root");
+    let ptrs: Vec<&str> = changes.iter().map(|c| c.pointer.as_str()).collect();
+    assert_eq!(
+        ptrs,
+        [
+            "/root/m",
+            "/root/gone",
+            "/root/list/2",
+            "/root/list/1",
+            "/root/o/x~1y",
+            "/root/new"
+        ]
+    );
+    assert_eq!(changes[0].old, Some(1.into()));
+    assert!(changes[1].new.is_none() && changes[5].old.is_none());
+    let ops: Vec<_> = changes.iter().map(|c| c.to_patch_op()).collect();
+    let mut doc = serde_json::json!({"root": a});
+    apply_patch(&mut doc, &ops).unwrap();
+    assert_eq!(doc["root"], b);
+    assert!(diff(&b, &b, "").is_empty());
+
+    // Object ids are renumbered by every write and are not a change
+    let a = serde_json::json!({"r": {"$ref": 3, "$value": {"money": 1}}});
+    let b = serde_json::json!({"r": {"$ref": 7, "$value": {"money": 2}}});
+    let ptrs: Vec<String> = diff(&a, &b, "").into_iter().map(|c| c.pointer).collect();
+    assert_eq!(ptrs, ["/r/$value/money"]);
+}
+
+#[test]
+fn dump_dir_writes_one_json_per_sav() {
+    use wle_core::json::{JsonOpts, dump_dir_to_files};
+    let (dir, slot) = temp_slot(&[]);
+    let img = vec![0u8; 4 * 4 * 3];
+    std::fs::write(
+        slot.join("SlotInfo.sav"),
+        wle_core::editor::build_slot_info_bytes(0, "2025-09-22 12:00", &img),
+    )
+    .unwrap();
+    std::fs::write(slot.join("Broken.sav"), b"\x00\x01").unwrap();
+    let out = dir.path().join("json");
+    let res = dump_dir_to_files(&slot, &out, JsonOpts::default()).unwrap();
+    assert_eq!(res.len(), 2);
+    assert!(res[0].1.is_err(), "Broken.sav sorts first and fails");
+    assert_eq!(res[1].1.as_ref().unwrap(), &out.join("SlotInfo.json"));
+    let js: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.join("SlotInfo.json")).unwrap()).unwrap();
+    assert_eq!(js["$rootClass"], "SaveSlotInfoData");
+    assert!(!out.join("Broken.json").exists());
+}
+
+#[test]
+fn parse_and_dump_from_memory() {
+    use wle_core::json::{JsonOpts, dump_bytes_json};
+    let img = vec![0u8; 4 * 4 * 3];
+    let sav = wle_core::editor::build_slot_info_bytes(1, "2025-09-22 12:00", &img);
+    let v = wle_core::parse_bytes_to_json_value(sav.clone(), JsonOpts::full()).unwrap();
+    assert_eq!(v["$rootClass"], "SaveSlotInfoData");
+    // JSON input passes through unchanged
+    let js = dump_bytes_json(sav, JsonOpts::default()).unwrap();
+    assert_eq!(
+        dump_bytes_json(js.clone().into_bytes(), JsonOpts::default()).unwrap(),
+        js
+    );
+    assert!(wle_core::parse_bytes_to_json_value(b"  \n".to_vec(), JsonOpts::full()).is_err());
+}
+
+#[test]
+fn validate_reports_parse_and_schema_problems() {
+    use wle_core::validate::{Severity, ValidateOpts, validate_dir, validate_json};
+    let dir = tempfile::tempdir().unwrap();
+    let img = vec![0u8; 4 * 4 * 3];
+    std::fs::write(
+        dir.path().join("SlotInfo.sav"),
+        wle_core::editor::build_slot_info_bytes(1, "2025-09-22 12:00", &img),
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("Broken.sav"), b"\x00\x01").unwrap();
+    let reports = validate_dir(dir.path(), ValidateOpts { schema: true });
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].worst(), Some(Severity::Error), "Broken.sav");
+    assert!(reports[1].issues.is_empty(), "{:?}", reports[1].issues);
+
+    // Out-of-range catalog value and a list size beyond its items
+    let v = serde_json::json!({
+        "$rootClass": "SaveSlotInfoData",
+        "root": {
+            "$class": "SaveSlotInfoData",
+            "lastSelectedPlayerSlot": 9,
+            "list": {"$class": "HawkNetworking.HawkMessageList`1", "List`1+_items": [1], "List`1+_size": 2}
+        }
+    });
+    let issues: Vec<_> = validate_json(&v, ValidateOpts { schema: true })
+        .into_iter()
+        .filter(|i| i.pointer.starts_with("/root/"))
+        .collect();
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+    assert_eq!(issues[0].pointer, "/root/lastSelectedPlayerSlot");
+    assert_eq!(issues[0].severity, Severity::Warning);
+    assert_eq!(issues[1].pointer, "/root/list/List`1+_size");
+    assert_eq!(issues[1].severity, Severity::Error);
+
+    // The reference saves are healthy, PlayerData_3's old-style wrappers included
+    let slot = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1");
+    let reports = validate_dir(&slot, ValidateOpts { schema: true });
+    assert!(reports.len() > 10);
+    for r in reports {
+        assert!(r.issues.is_empty(), "{}: {:?}", r.path.display(), r.issues);
+    }
+}
+
+#[test]
+fn table_export_edit_import() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let doc = wle_core::parse_file_to_json_value(&path, wle_core::json::JsonOpts::full()).unwrap();
+    let hats = "/root/<WardrobeData>k__BackingField/$value/WardrobeHats";
+    let table = wle_core::table::export_table(&doc, hats).unwrap();
+    assert_eq!(table.headers[0], "index");
+    assert!(
+        table
+            .headers
+            .contains(&"clothingPrimaryColor/r".to_string())
+    );
+    // Unchanged tables import to nothing, through CSV and back
+    let mut parsed = wle_core::table::Table::parse(&table.to_text(','), ',').unwrap();
+    assert_eq!(parsed, table);
+    assert!(
+        wle_core::table::import_table(&doc, hats, &parsed)
+            .unwrap()
+            .is_empty()
+    );
+    let col = parsed
+        .headers
+        .iter()
+        .position(|h| h == "clothingPrimaryColor/g")
+        .unwrap();
+    parsed.rows[1][col] = "0.5".into();
+    let ops = wle_core::table::import_table(&doc, hats, &parsed).unwrap();
+    assert_eq!(ops.len(), 1);
+    assert!(
+        ops[0]
+            .path()
+            .ends_with("/List`1+_items/$value/1/clothingPrimaryColor/g")
+    );
+    parsed.rows[1][col] = "pink".into();
+    assert!(wle_core::table::import_table(&doc, hats, &parsed).is_err());
+
+    let misc = "/root/<MiscData>k__BackingField";
+    let kv = wle_core::table::export_table(&doc, misc).unwrap();
+    assert_eq!(kv.headers, ["key", "value"]);
+    let edited = wle_core::table::Table::parse("key,value\r\nmoney,\"42\"\r\n", ',').unwrap();
+    let ops = wle_core::table::import_table(&doc, misc, &edited).unwrap();
+    assert_eq!(
+        ops[0].to_json(),
+        serde_json::json!({"op": "replace", "path": "/root/<MiscData>k__BackingField/$value/money", "value": 42})
+    );
+}
+
+#[test]
+fn report_unknown_aggregates_skipped_data() {
+    let reference = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/StatsData.sav");
+    let data = std::fs::read(&reference).unwrap();
+    let d = tempfile::tempdir().unwrap();
+    let clean = d.path().join("Clean.sav");
+    std::fs::write(&clean, &data).unwrap();
+    let report = wle_core::json::report_unknown(std::slice::from_ref(&clean));
+    assert_eq!(report.files, 1);
+    assert!(report.entries.is_empty() && report.failed.is_empty());
+
+    let mut padded = data.clone();
+    padded.extend_from_slice(&[0, 0, 0]);
+    let (a, b) = (d.path().join("A.sav"), d.path().join("B.sav"));
+    std::fs::write(&a, &padded).unwrap();
+    std::fs::write(&b, &padded).unwrap();
+    let truncated = d.path().join("C.sav");
+    std::fs::write(&truncated, &data[..data.len() / 2]).unwrap();
+    let report = wle_core::json::report_unknown(&[a.clone(), b, truncated.clone()]);
+    assert_eq!(report.entries.len(), 1);
+    let entry = &report.entries[0];
+    assert_eq!(entry.what, "3 bytes after MessageEnd");
+    assert_eq!((entry.count, entry.files.len()), (2, 2));
+    assert_eq!(entry.first, (a, data.len()));
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, truncated);
+}
+
+#[test]
+fn embedded_templates_are_valid_documents() {
+    use wle_core::saves::TemplateKind;
+    let kind: TemplateKind = "player-data".parse().unwrap();
+    assert!("world".parse::<TemplateKind>().is_err());
+    let data = kind.template(kind.names()[0]).unwrap();
+    let doc = wle_core::binfmt::Parser::new(data).parse_stream().unwrap();
+    assert!(doc.as_player_data().is_some());
+    let settings = TemplateKind::PlayerSettings.template("default").unwrap();
+    assert!(
+        wle_core::binfmt::Parser::new(settings)
+            .parse_stream()
+            .is_ok()
+    );
+    assert!(kind.template("nope").is_err());
+}
+
+#[test]
+fn parse_cache_skips_unchanged_bytes() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1");
+    let player = std::fs::read(dir.join("PlayerData_1.sav")).unwrap();
+    let stats = std::fs::read(dir.join("StatsData.sav")).unwrap();
+    let cache = wle_core::ParseCache::new(1);
+    let first = cache.parse(player.clone()).unwrap();
+    let again = cache.parse(player.clone()).unwrap();
+    assert_eq!(first, again);
+    assert_eq!(
+        first,
+        wle_core::parse_bytes_for_edit(player.clone()).unwrap()
+    );
+    assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    // Other bytes are parsed, and push the oldest document out
+    cache.parse(stats).unwrap();
+    cache.parse(player).unwrap();
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 3, 1));
+    // Bytes that do not parse are not kept
+    assert!(cache.parse(Vec::new()).is_err());
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn content_hash_ignores_encoding() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/WorldData.sav");
+    let v = wle_core::parse_file_to_json_value(&path, wle_core::json::JsonOpts::full()).unwrap();
+    let bytes = wle_core::write_binfmt_from_json(&v).unwrap();
+    let back =
+        wle_core::parse_bytes_to_json_value(bytes, wle_core::json::JsonOpts::full()).unwrap();
+    // Object ids are renumbered by the writer, but the content is the same
+    assert_ne!(v, back);
+    assert_eq!(wle_core::content_hash(&v), wle_core::content_hash(&back));
+    let a = serde_json::json!({"x": {"$ref": 3, "$value": {"b": 1, "a": 2.5}}});
+    let b = serde_json::json!({"x": {"a": 2.5, "b": 1.0}});
+    assert_eq!(wle_core::content_hash(&a), wle_core::content_hash(&b));
+    let c = serde_json::json!({"x": {"a": 2.5, "b": 2}});
+    assert_ne!(wle_core::content_hash(&a), wle_core::content_hash(&c));
+}
+
+#[test]
+fn json_files_round_trip_subtrees() {
+    use wle_core::json::JsonOpts;
+    use wle_core::{
+        json_from_bytes, parse_bytes_to_json_value, read_json_file, write_json_to_file,
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("hats.json");
+    // Any value round-trips, not only whole documents
+    let hats = serde_json::json!([{"Id": 3, "Name": "Top hat"}, 7]);
+    write_json_to_file(&out, &hats).unwrap();
+    assert_eq!(read_json_file(&out).unwrap(), hats);
+    // A byte order mark from a Windows editor is ignored
+    let mut data = b"\xEF\xBB\xBF".to_vec();
+    data.extend_from_slice(br#"{"root":{"a":1}}"#);
+    assert_eq!(json_from_bytes(&data).unwrap()["root"]["a"], 1);
+    let doc = parse_bytes_to_json_value(data, JsonOpts::full()).unwrap();
+    assert_eq!(doc["root"]["a"], 1);
+    assert!(json_from_bytes(b"{\"a\":").is_err());
+}
+
+#[test]
+fn presets_plan_builtin_and_user_edits() {
+    use wle_core::presets::{PresetAction, builtin, load_presets, parse_presets};
+    let (d, slot) = temp_slot(&["PlayerData_1.sav"]);
+
+    let max = builtin()
+        .into_iter()
+        .find(|p| matches!(p.action, PresetAction::MaxMoney))
+        .unwrap();
+    let changes = max.plan(&slot, 1).unwrap().preview().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].new, Some(serde_json::json!(i32::MAX)));
+
+    let file = d.path().join("presets.json");
+    let money = "/root/<MiscData>k__BackingField/$value/money";
+    let text = serde_json::json!({"presets": [{
+        "name": "Rich",
+        "ops": [{"op": "replace", "path": money, "value": 500000}]
+    }]});
+    std::fs::write(&file, text.to_string()).unwrap();
+    let user = load_presets(&file).unwrap();
+    assert_eq!(user[0].name, "Rich");
+    user[0].plan(&slot, 1).unwrap().apply().unwrap();
+    assert_eq!(wle_core::cheats::get_money(&slot, 1).unwrap(), 500000);
+    // The target file must exist; bad entries say which preset is wrong
+    assert!(user[0].plan(&slot, 2).is_err());
+    let bad = serde_json::json!([{"name": "x", "file": "nowhere", "ops": []}]);
+    assert!(parse_presets(&bad).unwrap_err().starts_with("preset 1:"));
+}
+
+#[test]
+fn byte_arrays_preview_as_images() {
+    use wle_core::json::JsonOpts;
+    use wle_core::model::SlotImage;
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/SlotInfo.sav");
+    let json = wle_core::parse_file_to_json_value(&path, JsonOpts::full()).unwrap();
+    let v = json.pointer("/root/smallImageData/$value").unwrap();
+    let bytes = wle_core::byte_array(v).expect("a byte array");
+    // The thumbnail is a raw square texture
+    let (img, ch) = SlotImage::guess(&bytes).unwrap();
+    assert_eq!(Some(img.clone()), SlotImage::decode(&bytes));
+    assert_eq!(img.encode(ch), bytes);
+    assert!(wle_core::byte_array(&serde_json::json!([1, 256])).is_none());
+    assert!(SlotImage::guess(&[0; 12]).is_none());
+    let wide = SlotImage::guess(&[7; 32 * 24 * 3]).unwrap();
+    assert_eq!((wide.0.width, wide.0.height, wide.1), (32, 24, 3));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_round_trips_documents() {
+    use wle_core::json::JsonOpts;
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let json = wle_core::parse_file_to_json_value(&path, JsonOpts::full()).unwrap();
+    let yaml = wle_core::yaml_from_json(&json).unwrap();
+    assert!(yaml.starts_with("$rootClass: SavePlayerPersistentData\n"));
+    assert_eq!(wle_core::json_from_yaml(yaml.as_bytes()).unwrap(), json);
+    // Parsing bytes tells YAML from JSON and .sav
+    let parsed = wle_core::parse_bytes_to_json_value(yaml.into_bytes(), JsonOpts::full());
+    assert_eq!(parsed.unwrap(), json);
+
+    // Strings that look like other types keep their type
+    let tricky = serde_json::json!({"a": "true", "b": "1.5", "c": "", "d": null, "e": 1.5});
+    let text = wle_core::yaml_from_json(&tricky).unwrap();
+    assert_eq!(wle_core::json_from_yaml(text.as_bytes()).unwrap(), tricky);
+
+    // The file helpers pick the format from the extension
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("doc.yml");
+    assert!(wle_core::is_yaml_path(&out));
+    wle_core::write_json_to_file(&out, &tricky).unwrap();
+    assert!(std::fs::read_to_string(&out).unwrap().contains("a: 'true'"));
+    assert_eq!(wle_core::read_json_file(&out).unwrap(), tricky);
+}
+
+#[test]
+fn plugin_json_transforms_plan_rule_edits() {
+    use wle_core::plugin::{Transform, expand_pointer, load_transform_dir, parse_transforms, plan};
+    use wle_core::saves::DocKind;
+    let slot = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1");
+    let doc = wle_core::parse_file_to_json_value(
+        &slot.join("PlayerData_1.sav"),
+        wle_core::json::JsonOpts::full(),
+    )
+    .unwrap();
+    let presents = "/root/<MiscData>k__BackingField/$value/presentsUnlocked";
+    let n = doc.pointer(presents).unwrap().as_array().unwrap().len();
+    assert_eq!(expand_pointer(&doc, &format!("{}/*/_b", presents)).len(), n);
+    assert!(expand_pointer(&doc, "/root/nope/*").is_empty());
+
+    let defs = serde_json::json!([{
+        "name": "Presents",
+        "documents": ["player"],
+        "rules": [
+            {"path": format!("{}/*/_e", presents), "max": 100},
+            {"path": format!("{}/0/_a", presents), "set": 7, "if": 123},
+            {"path": format!("{}/0/_b", presents), "set": 1}
+        ]
+    }]);
+    let t = &parse_transforms(&defs).unwrap()[0];
+    assert!(t.applies_to(DocKind::Player(3)) && !t.applies_to(DocKind::World));
+    let ops = t.transform(&doc).unwrap();
+    // Only the nodes not already as wanted: the `if` rule does not match
+    let over = expand_pointer(&doc, &format!("{}/*/_e", presents))
+        .iter()
+        .filter(|p| doc.pointer(p).unwrap().as_i64().unwrap() > 100)
+        .count();
+    assert_eq!(ops.len(), over + 1);
+    let mut edited = doc.clone();
+    wle_core::apply_patch(&mut edited, &ops).unwrap();
+    assert_eq!(
+        edited.pointer(&format!("{}/0/_b", presents)),
+        Some(&1.into())
+    );
+    assert!(t.transform(&edited).unwrap().is_empty());
+
+    // Planning checks the document kind from the file name
+    assert_eq!(
+        DocKind::from_file_name("PlayerSettingsData_2.sav"),
+        Some(DocKind::PlayerSettings(2))
+    );
+    let edit = plan(t, &slot.join("PlayerData_2.sav")).unwrap();
+    assert!(edit.path.ends_with("PlayerData_2.sav"));
+    assert!(plan(t, &slot.join("MissionData.sav")).is_err());
+
+    // A folder of files: broken ones say why, the rest load
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.json"), defs.to_string()).unwrap();
+    std::fs::write(
+        dir.path().join("b.json"),
+        r#"{"name": "x", "rules": [{"path": "/a"}]}"#,
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+    let loaded = load_transform_dir(dir.path());
+    assert_eq!(loaded.len(), 2);
+    assert!(loaded[0].1.is_ok());
+    assert!(loaded[1].1.as_ref().unwrap_err().starts_with("rule 1:"));
+}
+
+#[cfg(feature = "script")]
+#[test]
+fn scripts_edit_conditionally_and_return_a_patch() {
+    use wle_core::script::run_script;
+    let doc = serde_json::json!({
+        "root": {
+            "vehicles": [
+                {"$class": "VehicleData", "bLocked": true, "owner": 0},
+                {"$class": "VehicleData", "bLocked": false, "owner": 0},
+                {"$class": "HouseData", "bLocked": true, "owner": 0}
+            ]
+        }
+    });
+    let script = r#"
+        for p in search("vehicle", "class") {
+            if get(p + "/bLocked") {
+                set(p + "/owner", 1);
+            }
+        }
+        print(list("/root/vehicles").len());
+        print(exists("/root/missing"));
+    "#;
+    let run = run_script(script, &doc).unwrap();
+    assert_eq!(run.output, ["3", "false"]);
+    assert_eq!(
+        run.ops,
+        [wle_core::PatchOp::replace(
+            "/root/vehicles/0/owner",
+            serde_json::json!(1)
+        )]
+    );
+    // The document itself is untouched; errors carry the script position
+    assert_eq!(doc["root"]["vehicles"][0]["owner"], 0);
+    let err = run_script("set(\"/root/nope/x\", 1);", &doc).unwrap_err();
+    assert!(err.contains("line 1"), "{}", err);
+    assert!(run_script("search(\"x\", \"sideways\");", &doc).is_err());
+}
+
+#[test]
+fn document_builder_writes_parseable_saves() {
+    use wle_core::DocumentBuilder;
+    let mut b = DocumentBuilder::new();
+    b.begin_object("PlayerData")
+        .member_str("name", "Wobbly")
+        .member_i32("money", 500)
+        .member_i64("ticks", 1 << 40)
+        .member_bool("bLocked", true)
+        .member_f32("fov", 0.5)
+        .member_f64("time", 1.25)
+        .member_bytes("thumb", &[1, 2, 3])
+        .member_object("misc", "PlayerMiscData")
+        .member_i32("level", 3)
+        .end_object()
+        .begin_array("friends")
+        .begin_object("Friend")
+        .member_i32("id", 2)
+        .end_object()
+        .null_element()
+        .end_array()
+        .end_object();
+    let bytes = b.finish().unwrap();
+    let v = wle_core::parse_bytes_to_json_value(bytes, wle_core::json::JsonOpts::full()).unwrap();
+    assert_eq!(v["$rootClass"], "PlayerData");
+    assert_eq!(
+        v["root"],
+        serde_json::json!({
+            "$class": "PlayerData",
+            "name": "Wobbly",
+            "money": 500,
+            "ticks": 1i64 << 40,
+            "bLocked": true,
+            "fov": 0.5,
+            "time": 1.25,
+            "thumb": [1, 2, 3],
+            "misc": {"$class": "PlayerMiscData", "level": 3},
+            "friends": [{"$class": "Friend", "id": 2}, null]
+        })
+    );
+
+    let mut b = DocumentBuilder::new();
+    b.begin_object("Root").member_i32("x", 1).end_array();
+    assert!(b.finish().unwrap_err().contains("end_array"));
+    let mut b = DocumentBuilder::new();
+    b.begin_object("Root").begin_array("list");
+    assert!(b.finish().unwrap_err().contains("not closed"));
+}
+
+#[test]
+fn documents_deserialize_into_typed_structs() {
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    enum Colour {
+        Red,
+        Green,
+    }
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Friend {
+        id: u32,
+    }
+    #[derive(serde::Deserialize, Debug)]
+    struct Player<'a> {
+        name: &'a str,
+        money: i64,
+        fov: f64,
+        thumb: Vec<u8>,
+        colour: Colour,
+        #[serde(rename = "<Hat>k__BackingField")]
+        hat: Colour,
+        friends: Vec<Friend>,
+        missing: Option<i32>,
+    }
+    let mut b = wle_core::DocumentBuilder::new();
+    b.begin_object("PlayerData")
+        .member_str("name", "Wobbly")
+        .member_i32("money", 500)
+        .member_f32("fov", 0.5)
+        .member_bytes("thumb", &[1, 2, 3])
+        .member_i32("colour", 1)
+        .member_object("<Hat>k__BackingField", "Colour")
+        .member_i32("value__", 0)
+        .end_object()
+        .member_object("friends", "System.Collections.Generic.List`1[[Friend]]")
+        .begin_array("_items")
+        .begin_object("Friend")
+        .member_i32("id", 7)
+        .end_object()
+        .null_element()
+        .end_array()
+        .member_i32("_size", 1)
+        .end_object()
+        .end_object();
+    let bytes = b.finish().unwrap();
+    let doc = wle_core::binfmt::Parser::new(&bytes)
+        .parse_stream()
+        .unwrap();
+    let p: Player = wle_core::de::from_document(&doc).unwrap();
+    assert_eq!(p.name, "Wobbly");
+    assert_eq!(p.money, 500);
+    assert_eq!(p.fov, 0.5);
+    assert_eq!(p.thumb, [1, 2, 3]);
+    assert_eq!((p.colour, p.hat), (Colour::Green, Colour::Red));
+    assert_eq!(p.friends, [Friend { id: 7 }]);
+    assert_eq!(p.missing, None);
+
+    #[derive(serde::Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Wrong {
+        name: i32,
+    }
+    let err = wle_core::de::from_bytes::<Wrong>(&bytes).unwrap_err();
+    assert!(err.contains("invalid type"), "{}", err);
+}
+
+#[test]
+fn typed_structs_serialize_to_saves_and_back() {
+    use serde::{Deserialize, Serialize};
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Colour {
+        Red,
+        Green,
+    }
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename = "Friend")]
+    struct Friend {
+        id: u32,
+    }
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename = "PlayerData")]
+    struct Player {
+        name: String,
+        money: i32,
+        ticks: i64,
+        fov: f32,
+        locked: bool,
+        thumb: Vec<u8>,
+        levels: Vec<i32>,
+        tags: Vec<String>,
+        colour: Colour,
+        friends: Vec<Option<Friend>>,
+        hat: Option<Friend>,
+    }
+    let p = Player {
+        name: "Wobbly".into(),
+        money: 500,
+        ticks: 1 << 40,
+        fov: 0.5,
+        locked: true,
+        thumb: vec![1, 2, 3],
+        levels: vec![1000, -2],
+        tags: vec!["a".into(), "b".into()],
+        colour: Colour::Green,
+        friends: vec![Some(Friend { id: 7 }), None],
+        hat: None,
+    };
+    let bytes = wle_core::ser::to_bytes(&p).unwrap();
+    assert_eq!(wle_core::de::from_bytes::<Player>(&bytes).unwrap(), p);
+    let v = wle_core::parse_bytes_to_json_value(bytes, wle_core::json::JsonOpts::full()).unwrap();
+    assert_eq!(v["$rootClass"], "PlayerData");
+    assert_eq!(
+        v["root"]["colour"],
+        serde_json::json!({"$class": "Colour", "value__": 1})
+    );
+    assert_eq!(v["root"]["friends"][0]["$class"], "Friend");
+
+    let err = wle_core::ser::to_bytes(&vec![1, 2]).unwrap_err();
+    assert!(err.to_string().contains("struct"), "{}", err);
+}
+
+#[test]
+fn values_at_pointers_match_the_full_dump() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let data = std::fs::read(&path).unwrap();
+    let opts = wle_core::json::JsonOpts::full();
+    let full = wle_core::parse_bytes_to_json_value(data.clone(), opts).unwrap();
+    // Every pointer three levels deep, including `$class`, `$ref` and `$value`
+    let mut ptrs = vec![String::new(), "/$rootClass".to_string()];
+    let mut level = vec!["/root".to_string()];
+    for _ in 0..3 {
+        let mut next = Vec::new();
+        for p in &level {
+            for c in wle_core::list_children(&full, p).unwrap_or_default() {
+                next.push(format!(
+                    "{}/{}",
+                    p,
+                    c.key_or_index.replace('~', "~0").replace('/', "~1")
+                ));
+            }
+        }
+        ptrs.append(&mut level);
+        level = next;
+    }
+    ptrs.append(&mut level);
+    ptrs.push("/root/missing".into());
+    ptrs.push("/root/$class/x".into());
+    let found = wle_core::parse_bytes_at_pointers(data, &ptrs, opts).unwrap();
+    for (p, v) in ptrs.iter().zip(&found) {
+        assert_eq!(v.as_ref(), full.pointer(p), "{}", p);
+    }
+    assert!(found.iter().filter(|v| v.is_some()).count() > 20);
+
+    // Depth and array limits count from the pointer, not the root
+    let shallow = wle_core::json::JsonOpts {
+        max_depth: 2,
+        ..opts
+    };
+    let bytes = std::fs::read(&path).unwrap();
+    let doc = wle_core::binfmt::Parser::new(&bytes)
+        .parse_stream()
+        .unwrap();
+    let (deep, node) = ptrs
+        .iter()
+        .rev()
+        .find_map(|p| {
+            let node = full.pointer(p)?.as_object()?;
+            node.values().any(|x| x.is_number()).then_some((p, node))
+        })
+        .unwrap();
+    assert!(deep.matches('/').count() > 2);
+    let v = wle_core::document_value_at(&doc, deep, shallow).unwrap();
+    for (k, x) in node {
+        if x.is_number() {
+            assert_eq!(&v[k], x, "{}/{}", deep, k);
+        }
+    }
+
+    // A pointer without its leading '/' is not found rather than a panic
+    for bad in ["abc", "root", "root/money"] {
+        assert!(
+            wle_core::document_value_at(&doc, bad, opts).is_none(),
+            "{}",
+            bad
+        );
+    }
+    let found = wle_core::parse_bytes_at_pointers(bytes, &["abc".to_string()], opts).unwrap();
+    assert!(found[0].is_none());
+}
+
+// Random documents in the shape the writer accepts: objects with a `$class`,
+// scalars, same-typed primitive arrays and arrays of objects
+mod roundtrip_props {
+    use proptest::prelude::*;
+    use serde_json::{Map, Value as J};
+    use wle_core::json::JsonOpts;
+
+    fn name() -> impl Strategy<Value = String> {
+        "[A-Za-z_][A-Za-z0-9_<>]{0,12}"
+    }
+
+    fn scalar() -> impl Strategy<Value = J> {
+        prop_oneof![
+            any::<bool>().prop_map(J::from),
+            any::<i64>().prop_map(J::from),
+            (-1e9f64..1e9).prop_map(J::from),
+            ".{0,16}".prop_map(J::from),
+        ]
+    }
+
+    fn object(member: BoxedStrategy<J>) -> impl Strategy<Value = J> {
+        (name(), prop::collection::btree_map(name(), member, 0..6)).prop_map(|(class, members)| {
+            let mut map = Map::new();
+            map.insert("$class".into(), J::from(class));
+            map.extend(members);
+            J::Object(map)
+        })
+    }
+
+    fn value() -> impl Strategy<Value = J> {
+        scalar().prop_recursive(4, 48, 6, |inner| {
+            prop_oneof![
+                prop::collection::vec(any::<i32>(), 0..8).prop_map(J::from),
+                prop::collection::vec(any::<u8>(), 1..8).prop_map(J::from),
+                prop::collection::vec(-1e9f64..1e9, 1..5).prop_map(J::from),
+                prop::collection::vec(".{0,8}", 1..5).prop_map(J::from),
+                object(inner.clone().boxed()),
+                prop::collection::vec(object(inner.boxed()), 1..4).prop_map(J::from),
+            ]
+        })
+    }
+
+    fn document() -> impl Strategy<Value = J> {
+        object(value().boxed()).prop_map(
+            |root| serde_json::json!({"$rootClass": root["$class"].clone(), "root": root}),
+        )
+    }
+
+    fn reparse(bytes: Vec<u8>) -> J {
+        wle_core::parse_bytes_to_json_value(bytes, JsonOpts::full()).unwrap()
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn written_documents_parse_back_the_same(doc in document()) {
+            let bytes = wle_core::write_binfmt_from_json(&doc).unwrap();
+            let dumped = reparse(bytes.clone());
+            prop_assert_eq!(&dumped, &doc);
+            // A second write of the dump is byte-for-byte the first
+            prop_assert_eq!(wle_core::write_binfmt_from_json(&dumped).unwrap(), bytes);
+        }
+
+        #[test]
+        fn parser_never_panics_on_arbitrary_bytes(data in prop::collection::vec(any::<u8>(), 0..512)) {
+            let _ = wle_core::binfmt::Parser::new(&data).parse_stream();
+        }
+
+        #[test]
+        fn parser_never_panics_on_damaged_saves(
+            doc in document(),
+            flips in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8),
+            cut in any::<prop::sample::Index>(),
+        ) {
+            let mut bytes = wle_core::write_binfmt_from_json(&doc).unwrap();
+            for (at, b) in flips {
+                let i = at.index(bytes.len());
+                bytes[i] = b;
+            }
+            let _ = wle_core::binfmt::Parser::new(&bytes).parse_stream();
+            bytes.truncate(cut.index(bytes.len()));
+            let _ = wle_core::binfmt::Parser::new(&bytes).parse_stream();
+        }
+    }
+}
+
+// Every BinaryFormatter save under reference-data/GameSaves dumps (as `dump`
+// prints it) to its golden JSON in tests/golden, at the same relative path.
+// After a deliberate change to the dump, regenerate them with
+// `WLE_BLESS=1 cargo test -p wle-core golden` and review the diff.
+#[test]
+fn reference_saves_match_golden_dumps() {
+    let manifest = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let saves = manifest.join("../../reference-data/GameSaves");
+    let golden = manifest.join("tests/golden");
+    let bless = std::env::var_os("WLE_BLESS").is_some();
+
+    fn sav_files(dir: &std::path::Path, out: &mut Vec<std::path::PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let p = entry.unwrap().path();
+            if p.is_dir() {
+                sav_files(&p, out);
+            } else if p.extension().is_some_and(|e| e == "sav") {
+                out.push(p);
+            }
+        }
+    }
+    let mut files = Vec::new();
+    sav_files(&saves, &mut files);
+    files.sort();
+
+    let mut checked = 0;
+    let mut mismatched = Vec::new();
+    for sav in files {
+        let data = std::fs::read(&sav).unwrap();
+        // SaveInfo.sav is plain JSON
+        if data.first() != Some(&0) {
+            continue;
+        }
+        let rel = sav.strip_prefix(&saves).unwrap().with_extension("json");
+        let dumped = wle_core::json::dump_bytes_json(data, wle_core::json::JsonOpts::default())
+            .unwrap_or_else(|e| panic!("{}: {}", rel.display(), e));
+        let want = golden.join(&rel);
+        if bless {
+            std::fs::create_dir_all(want.parent().unwrap()).unwrap();
+            std::fs::write(&want, &dumped).unwrap();
+        } else {
+            match std::fs::read_to_string(&want) {
+                Ok(expected) if expected == dumped => {}
+                Ok(_) => mismatched.push(format!("{} differs", rel.display())),
+                Err(_) => mismatched.push(format!("{} has no golden file", rel.display())),
+            }
+        }
+        checked += 1;
+    }
+    assert!(checked >= 10, "only {} reference saves found", checked);
+    assert!(
+        mismatched.is_empty(),
+        "golden dumps out of date (WLE_BLESS=1 regenerates them):\n{}",
+        mismatched.join("\n")
+    );
+}
+
+#[test]
+fn progress_reports_bytes_records_and_objects() {
+    use std::sync::Arc;
+    use wle_core::binfmt::ParseProgress;
+    use wle_core::json::JsonOpts;
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let data = std::fs::read(&p).unwrap();
+
+    let parse = Arc::new(ParseProgress::default());
+    assert_eq!(parse.fraction(), 0.0);
+    let v = wle_core::parse_bytes_to_json_value_with_progress(
+        data.clone(),
+        JsonOpts::full(),
+        parse.clone(),
+    )
+    .unwrap();
+    assert_eq!(parse.bytes(), (data.len(), data.len()));
+    assert_eq!(parse.fraction(), 1.0);
+    assert!(parse.records() > 1);
+
+    let write = Arc::new(wle_core::WriteProgress::default());
+    assert_eq!(write.fraction(), 0.0);
+    let out = wle_core::write_binfmt_from_json_with_progress(&v, write.clone()).unwrap();
+    assert_eq!(out, wle_core::write_binfmt_from_json(&v).unwrap());
+    assert_eq!(write.fraction(), 1.0);
+    assert!(write.objects() > 0);
+    assert_eq!(write.bytes(), out.len());
+
+    // Cancelling through the progress stops the next run
+    write.cancel();
+    assert_eq!(
+        wle_core::write_binfmt_from_json_with_progress(&v, write).unwrap_err(),
+        "cancelled"
+    );
+}
+
+#[test]
+fn full_json_opts_leave_nothing_out() {
+    use wle_core::json::JsonOpts;
+    assert!(JsonOpts::full().is_full());
+    assert!(!JsonOpts::default().is_full());
+    let summarised = JsonOpts {
+        bytes_summary: true,
+        ..JsonOpts::full()
+    };
+    assert!(!summarised.is_full());
+
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let cut = wle_core::json::dump_file_json(&p, JsonOpts::default()).unwrap();
+    assert!(cut.contains("$truncated"));
+    let full = wle_core::json::dump_file_json(&p, JsonOpts::full()).unwrap();
+    assert!(!full.contains("$truncated"));
+
+    // A full dump encodes back to the same data, object ids aside
+    let v: serde_json::Value = serde_json::from_str(&full).unwrap();
+    let again = wle_core::write_binfmt_from_json(&v).unwrap();
+    let v2 = wle_core::parse_bytes_to_json_value(again, JsonOpts::full()).unwrap();
+    let orig = wle_core::parse_file_to_json_value(&p, JsonOpts::full()).unwrap();
+    assert_eq!(wle_core::content_hash(&v2), wle_core::content_hash(&orig));
+}
+
+#[test]
+fn edit_json_is_never_truncated() {
+    use wle_core::json::JsonOpts;
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    // Display limits cut the long array short behind a marker...
+    let shown = wle_core::parse_file_to_json_value(&p, JsonOpts::default()).unwrap();
+    assert!(shown.to_string().contains("$truncated"));
+    // ...the edit path never does, and encodes back to the same content
+    let v = wle_core::parse_file_for_edit(&p).unwrap();
+    assert!(!v.to_string().contains("$truncated"));
+    let data = std::fs::read(&p).unwrap();
+    assert_eq!(wle_core::parse_bytes_for_edit(data).unwrap(), v);
+    assert!(wle_core::validate_for_write(&v, &v).is_empty());
+    let again = wle_core::write_binfmt_from_json(&v).unwrap();
+    assert_eq!(
+        wle_core::content_hash(&wle_core::parse_bytes_for_edit(again).unwrap()),
+        wle_core::content_hash(&v)
+    );
+}
+
+#[test]
+fn detect_kind_reads_content_not_file_names() {
+    use wle_core::model::{DocKind, detect_kind, detect_kind_json};
+    let slot = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1");
+    for (file, kind) in [
+        ("SlotInfo.sav", DocKind::SlotInfo),
+        ("PlayerData_2.sav", DocKind::PlayerData),
+        ("PlayerSettingsData_1.sav", DocKind::PlayerSettings),
+        ("MissionData.sav", DocKind::MissionData),
+        ("StatsData.sav", DocKind::StatsData),
+        ("WorldData.sav", DocKind::WorldData),
+    ] {
+        let p = slot.join(file);
+        assert_eq!(DocKind::expected_for(&p), kind);
+        let doc = wle_core::json::parse_binary(&p).unwrap();
+        assert_eq!(detect_kind(&doc), kind, "{file}");
+        let v = wle_core::parse_file_for_edit(&p).unwrap();
+        assert_eq!(detect_kind_json(&v), kind, "{file}");
+    }
+    // A renamed root class is still known by its members
+    let v = serde_json::json!({"$rootClass": "SaveWorldDataV2", "root": {"$class": "SaveWorldDataV2", "dataScenes": []}});
+    assert_eq!(detect_kind_json(&v), DocKind::WorldData);
+    let v = serde_json::json!({"$rootClass": "SaveInfo", "root": {"lastLoadedSlot": 1}});
+    assert_eq!(detect_kind_json(&v), DocKind::SaveInfo);
+    assert_eq!(
+        DocKind::expected_for("SaveInfo.sav".as_ref()),
+        DocKind::SaveInfo
+    );
+    assert_eq!(
+        detect_kind_json(&serde_json::json!({"root": 3})),
+        DocKind::Unknown
+    );
+    assert_eq!(
+        DocKind::expected_for("notes.sav".as_ref()),
+        DocKind::Unknown
+    );
+    // File kinds map straight onto content kinds
+    assert_eq!(
+        DocKind::from(wle_core::saves::DocKind::PlayerSettings(2)),
+        DocKind::PlayerSettings
+    );
+}
+
+#[test]
+fn migrations_apply_in_order_once() {
+    use wle_core::migrate::{builtin, parse_rules, plan_json};
+    use wle_core::model::DocKind;
+    assert!(builtin().version >= 1);
+    let rules = parse_rules(&serde_json::json!({"version": 2, "migrations": [
+        {"id": "rename", "documents": ["stats"], "detect": [{"exists": "/root/old"}],
+         "steps": [{"rename": "/root/old", "to": "new"}, {"default": "/root/items/*/extra", "value": 0}]},
+        {"id": "class", "detect": [{"class": "OldStats"}, {"missing": "/root/gone"}],
+         "steps": [{"class": "OldStats", "to": "SaveStatsData"}, {"remove": "/root/items/*/drop"}]}
+    ]}))
+    .unwrap();
+    assert_eq!(rules.version, 2);
+    let doc = serde_json::json!({"$rootClass": "OldStats", "root": {
+        "$class": "OldStats", "old": 3, "keep": 1,
+        "items": [{"drop": 1}, {"extra": 7, "drop": 2}]
+    }});
+    let (applied, ops) = plan_json(&rules.migrations, DocKind::StatsData, &doc).unwrap();
+    assert_eq!(applied, ["rename", "class"]);
+    let mut after = doc.clone();
+    wle_core::apply_patch(&mut after, &ops).unwrap();
+    assert_eq!(
+        after,
+        serde_json::json!({"$rootClass": "SaveStatsData", "root": {
+            "$class": "SaveStatsData", "new": 3, "keep": 1,
+            "items": [{"extra": 0}, {"extra": 7}]
+        }})
+    );
+    // The renamed member keeps its place
+    let keys: Vec<&String> = after["root"].as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["$class", "new", "keep", "items"]);
+    // Migrated saves no longer match, and other documents never did
+    assert!(
+        plan_json(&rules.migrations, DocKind::StatsData, &after)
+            .unwrap()
+            .0
+            .is_empty()
+    );
+    assert_eq!(
+        plan_json(&rules.migrations, DocKind::WorldData, &doc)
+            .unwrap()
+            .0,
+        ["class"]
+    );
+
+    let bad = serde_json::json!({"migrations": [{"id": "x", "steps": [{"rename": "root/a"}]}]});
+    assert!(
+        parse_rules(&bad)
+            .unwrap_err()
+            .contains("migration 1: step 1")
+    );
+
+    // `documents` takes the names plugin transforms do, numbered players included
+    let numbered = parse_rules(&serde_json::json!({"migrations": [
+        {"id": "p", "documents": ["player:2", "Settings"], "steps": [{"remove": "/root/old"}]}
+    ]}))
+    .unwrap();
+    assert!(numbered.migrations[0].matches(DocKind::PlayerData, &doc));
+    assert!(!numbered.migrations[0].matches(DocKind::StatsData, &doc));
+    let unknown = serde_json::json!({"migrations": [{"id": "x", "documents": ["players"]}]});
+    assert!(
+        parse_rules(&unknown)
+            .unwrap_err()
+            .contains("unknown document")
+    );
+}
+
+#[test]
+fn templates_strip_own_ids_and_instantiate_fresh_ones() {
+    use wle_core::templates::{Template, extract, find_template, save_template};
+    let slot = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1");
+    let doc = wle_core::parse_file_for_edit(&slot.join("PlayerData_1.sav")).unwrap();
+    let pet = "/root/<PetData>k__BackingField/$value/petDatas/$value/0/guid";
+    let ext = "/root/<ExternalData>k__BackingField/$value";
+    let item = format!("{}/datas/0/$value/guid", ext);
+    let key = format!("{}/datasDic/$value/KeyValuePairs/$value/0/key", ext);
+    let hat =
+        "/root/<CurrentClothes>k__BackingField/<ClothingHat>k__BackingField/clothingPrefabGUID";
+
+    let (stripped, changed) = extract(&doc);
+    assert!(doc.pointer(pet).is_some() && doc.pointer(hat).is_some());
+    assert!(changed > 0);
+    assert_ne!(stripped.pointer(pet), doc.pointer(pet));
+    // Game content ids stay; a dictionary key follows its entry
+    assert_eq!(stripped.pointer(hat), doc.pointer(hat));
+    assert_eq!(stripped.pointer(&item), stripped.pointer(&key));
+    assert_eq!(extract(&stripped).0, stripped);
+
+    let dir = tempfile::tempdir().unwrap();
+    let template = Template {
+        name: "starter".into(),
+        description: String::new(),
+        document: stripped.clone(),
+    };
+    save_template(dir.path(), &template, false).unwrap();
+    assert!(save_template(dir.path(), &template, false).is_err());
+    assert!(find_template(dir.path(), "../starter").is_err());
+    let loaded = find_template(dir.path(), "starter").unwrap();
+    assert_eq!(loaded.kind(), wle_core::model::DocKind::PlayerData);
+
+    let a = loaded.instantiate();
+    let b = loaded.instantiate();
+    assert_ne!(a.pointer(pet), stripped.pointer(pet));
+    assert_ne!(a.pointer(pet), b.pointer(pet));
+    assert_eq!(a.pointer(&item), a.pointer(&key));
+    assert_eq!(a.pointer(hat), doc.pointer(hat));
+    let bytes = loaded.to_bytes().unwrap();
+    wle_core::binfmt::Parser::new(&bytes)
+        .parse_stream()
+        .unwrap();
+
+    let info = wle_core::parse_file_for_edit(&slot.join("SlotInfo.sav")).unwrap();
+    assert_eq!(extract(&info).0["root"]["dateTime"], "");
+}
+
+#[test]
+fn loadout_round_trips_and_dresses_another_player() {
+    use wle_core::loadout::Loadout;
+    let slot = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1");
+    let from = wle_core::parse_file_for_edit(&slot.join("PlayerData_1.sav")).unwrap();
+    let mut to = wle_core::parse_file_for_edit(&slot.join("PlayerData_2.sav")).unwrap();
+
+    let loadout = Loadout::from_player(&from, "pirate").unwrap();
+    assert!(!loadout.vehicles.is_empty() && !loadout.wardrobe[0].is_empty());
+    let file = loadout.to_json();
+    assert_eq!(file["format"], "wle-loadout");
+    assert_eq!(Loadout::from_json(&file).unwrap(), loadout);
+
+    let changes = loadout.changes(&to).unwrap();
+    let ops: Vec<_> = changes.iter().map(|c| c.op.clone()).collect();
+    wle_core::apply_patch(&mut to, &ops).unwrap();
+    let bytes = wle_core::write_binfmt_from_json(&to).unwrap();
+    let to = wle_core::parse_bytes_for_edit(bytes).unwrap();
+    let dressed = Loadout::from_player(&to, "").unwrap();
+    assert_eq!(dressed.worn, loadout.worn);
+    assert_eq!(dressed.vehicles.len(), loadout.vehicles.len());
+    assert!(loadout.changes(&to).unwrap().is_empty());
+
+    let mut bad = file.clone();
+    bad["version"] = 99.into();
+    assert!(Loadout::from_json(&bad).unwrap_err().contains("newer"));
+    for v in [
+        serde_json::json!(0),
+        serde_json::json!(-1),
+        serde_json::json!("1"),
+    ] {
+        bad["version"] = v;
+        assert!(Loadout::from_json(&bad).unwrap_err().contains("invalid"));
+    }
+    let mut bad = file.clone();
+    bad["worn"]["hat"]["color"] = serde_json::json!([2, 0, 0, 1]);
+    assert!(
+        Loadout::from_json(&bad)
+            .unwrap_err()
+            .contains("worn hat colour")
+    );
+    assert!(Loadout::from_json(&serde_json::json!({"worn": {}})).is_err());
+    let stats = wle_core::parse_file_for_edit(&slot.join("StatsData.sav")).unwrap();
+    assert!(loadout.changes(&stats).is_err());
+}
+
+#[test]
+fn anonymize_replaces_names_ids_and_thumbnail() {
+    use wle_core::plugin::{Anonymize, Transform, write_transformed};
+    let doc = serde_json::json!({"$rootClass": "Demo", "root": {
+        "$class": "Demo",
+        "playerName": "Alice",
+        "friends": [{"playerName": "Bob"}, {"playerName": "Alice"}],
+        "ownerSteamId": {"$ref": 3, "$value": "76561198012345678"},
+        "lastHost": 76561198087654321u64,
+        "sceneName": "WobblyIsland",
+        "money": 500,
+        "smallImageData": {"$ref": 4, "$value": [1, 2, 3]}
+    }});
+    let mut out = doc.clone();
+    wle_core::apply_patch(&mut out, &Anonymize.transform(&doc).unwrap()).unwrap();
+    let root = &out["root"];
+    assert_eq!(root["playerName"], "Player 1");
+    assert_eq!(root["friends"][0]["playerName"], "Player 2");
+    assert_eq!(root["friends"][1]["playerName"], "Player 1");
+    assert_eq!(root["ownerSteamId"]["$value"], "76561197960265729");
+    assert_eq!(root["lastHost"], 76561197960265730u64);
+    assert_eq!(
+        root["smallImageData"]["$value"],
+        serde_json::json!([128, 128, 128])
+    );
+    // Everything else, the shape included, is left alone
+    assert_eq!(root["sceneName"], "WobblyIsland");
+    assert_eq!(root["money"], 500);
+    assert!(Anonymize.transform(&out).unwrap().is_empty());
+
+    let slot = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1");
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("PlayerData_1.sav");
+    assert_eq!(
+        write_transformed(&Anonymize, &slot.join("PlayerData_1.sav"), &dest).unwrap(),
+        3
+    );
+    let player = wle_core::parse_file_for_edit(&dest).unwrap();
+    let pets = "/root/<PetData>k__BackingField/$value/petDatas/$value";
+    assert_eq!(
+        player.pointer(&format!("{}/0/petName", pets)).unwrap(),
+        "Pet 1"
+    );
+    assert!(Anonymize.transform(&player).unwrap().is_empty());
+}
+
+#[test]
+fn size_profile_attributes_every_byte_once() {
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let data = std::fs::read(&p).unwrap();
+    let prof = wle_core::profile::profile_bytes(&data).unwrap();
+    let root = prof.get("/root").unwrap();
+    assert_eq!(prof.nodes[0].pointer, "/root");
+    assert_eq!(root.class.as_deref(), Some("SavePlayerPersistentData"));
+    let own: usize = prof.nodes.iter().map(|n| n.own).sum();
+    assert_eq!(own, root.total);
+    assert_eq!(root.total + prof.other(), data.len());
+    let by_class: usize = prof.by_class().iter().map(|c| c.bytes).sum();
+    assert_eq!(by_class, root.total);
+    // A prefix of the list holds the parents of its nodes
+    let top = prof.top(20);
+    for n in top {
+        if let Some(up) = wle_core::profile::parent(&n.pointer) {
+            assert!(top.iter().any(|t| t.pointer == up), "{}", n.pointer);
+            assert!(prof.get(up).unwrap().total >= n.total);
+        }
+    }
+    // A reference and its record are one node
+    assert!(prof.nodes.iter().all(|n| !n.pointer.ends_with("/$value")));
+    assert!(prof.nodes.iter().all(|n| !n.pointer.ends_with("/$ref")));
+}
+
+#[test]
+fn duplicates_found_by_guid_and_place_and_removed() {
+    use wle_core::validate::{DuplicateKind, find_duplicates, remove_duplicates_ops};
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/PlayerData_1.sav");
+    let mut v = wle_core::parse_file_for_edit(&p).unwrap();
+    // The same record shown in a list and a dictionary is not a copy
+    assert!(find_duplicates(&v).is_empty());
+    let datas = "/root/<ExternalData>k__BackingField/$value/datas";
+    let copy = v.pointer(&format!("{}/0/$value", datas)).unwrap().clone();
+    let list = v.pointer_mut(datas).unwrap().as_array_mut().unwrap();
+    list.push(copy);
+    let last = format!("{}/{}", datas, list.len() - 1);
+    let dups = find_duplicates(&v);
+    assert_eq!(dups.len(), 1);
+    assert!(matches!(dups[0].kind, DuplicateKind::SameGuid(_)));
+    assert_eq!(dups[0].pointers, [format!("{}/0", datas), last.clone()]);
+    let ops = remove_duplicates_ops(&v, &dups);
+    assert_eq!(ops, [wle_core::PatchOp::Remove { path: last }]);
+    wle_core::apply_patch(&mut v, &ops).unwrap();
+    assert!(find_duplicates(&v).is_empty());
+
+    // Same class and position in a HawkMessageList; spare capacity is not looked at
+    let car = |x: f64| serde_json::json!({"$class": "Car", "spawnPosition": {"$class": "V3", "x": x, "y": 2.0, "z": 0.0}});
+    let mut world = serde_json::json!({"$rootClass": "W", "root": {"$class": "W", "cars": {"$ref": 2, "$value": {
+        "$class": "HawkNetworking.HawkMessageList`1[[Car]]",
+        "List`1+_items": {"$ref": 3, "$value": [car(1.0), car(5.0), car(1.0), car(1.0), car(5.0)]},
+        "List`1+_size": 4,
+    }}}});
+    let dups = find_duplicates(&world);
+    assert_eq!(dups.len(), 1);
+    assert_eq!(dups[0].to_string(), "3 Car at (1, 2, 0)");
+    let ops = remove_duplicates_ops(&world, &dups);
+    wle_core::apply_patch(&mut world, &ops).unwrap();
+    let cars = &world["root"]["cars"]["$value"];
+    assert_eq!(cars["List`1+_size"], 2);
+    assert_eq!(cars["List`1+_items"]["$value"].as_array().unwrap().len(), 3);
+    assert!(find_duplicates(&world).is_empty());
+}
+
+#[test]
+fn bytes_at_pointer_read_and_replaced_in_both_forms() {
+    let p = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1/SlotInfo.sav");
+    let mut v = wle_core::parse_file_for_edit(&p).unwrap();
+    let old = wle_core::get_bytes_at(&v, "/root/smallImageData").unwrap();
+    assert!(!old.is_empty());
+    assert!(wle_core::get_bytes_at(&v, "/root/dateTime").is_err());
+    // A new length goes through the writer as a byte array behind its reference
+    let blob: Vec<u8> = (0..=255).cycle().take(old.len() + 7).collect();
+    wle_core::set_bytes_at(&mut v, "/root/smallImageData", &blob).unwrap();
+    assert!(v.pointer("/root/smallImageData/$ref").is_some());
+    let bytes = wle_core::write_binfmt_from_json(&v).unwrap();
+    let back = wle_core::parse_bytes_for_edit(bytes).unwrap();
+    assert_eq!(
+        wle_core::get_bytes_at(&back, "/root/smallImageData").unwrap(),
+        blob
+    );
+
+    // A summary only knows its length, but can be replaced
+    let mut summary = serde_json::json!({"root": {"photo": {"$type": "bytes", "len": 3}}});
+    let err = wle_core::get_bytes_at(&summary, "/root/photo").unwrap_err();
+    assert!(err.contains("only the length"), "{}", err);
+    wle_core::set_bytes_at(&mut summary, "/root/photo", &[1, 2, 255]).unwrap();
+    assert_eq!(
+        wle_core::get_bytes_at(&summary, "/root/photo").unwrap(),
+        [1, 2, 255]
+    );
+    wle_core::set_bytes_at(&mut summary, "/root/photo", &[]).unwrap();
+    assert_eq!(summary["root"]["photo"]["len"], 0);
+}
+
+#[test]
+fn typed_set_holds_numbers_to_catalog_limits() {
+    use wle_core::{JsonEditValue as V, Limits, set_typed_by_pointer};
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1");
+    let mut player = wle_core::parse_file_for_edit(&dir.join("PlayerData_1.sav")).unwrap();
+    let money = "/root/<MiscData>k__BackingField/$value/money";
+    let info = wle_core::catalog::field_at(&player, money).unwrap();
+    assert_eq!(info.limits().as_deref(), Some("0..=2147483647"));
+    let err = set_typed_by_pointer(&mut player, money, V::Int(-5), Limits::Reject).unwrap_err();
+    assert!(err.contains("0..=2147483647"), "{}", err);
+    let wrote = set_typed_by_pointer(&mut player, money, V::Int(-5), Limits::Clamp).unwrap();
+    assert!(matches!(wrote, V::Int(0)));
+    assert_eq!(player.pointer(money).unwrap(), 0);
+    set_typed_by_pointer(&mut player, money, V::Int(-5), Limits::Off).unwrap();
+    assert_eq!(player.pointer(money).unwrap(), -5);
+    // Other fields and values in range are written as given
+    set_typed_by_pointer(&mut player, money, V::Int(1234), Limits::Reject).unwrap();
+    assert_eq!(player.pointer(money).unwrap(), 1234);
+
+    let mut slot = wle_core::parse_file_for_edit(&dir.join("SlotInfo.sav")).unwrap();
+    let selected = "/root/lastSelectedPlayerSlot";
+    let wrote = set_typed_by_pointer(&mut slot, selected, V::Int(7), Limits::Clamp).unwrap();
+    assert!(matches!(wrote, V::Int(3)));
+    assert!(set_typed_by_pointer(&mut slot, selected, V::Int(4), Limits::Reject).is_err());
+
+    // A field with a list of allowed values snaps to the nearest one
+    let info = wle_core::catalog::FieldInfo {
+        allowed: vec![0.0, 1.0, 4.0],
+        ..Default::default()
+    };
+    assert!(info.allows(1.0) && !info.allows(2.0));
+    assert_eq!(info.clamp(2.9), 4.0);
+    assert_eq!(info.clamp(2.5), 1.0);
+    assert_eq!(info.limits().as_deref(), Some("one of 0, 1, 4"));
+}
+
+// A temp SaveSlot_1 holding copies of the named reference save files; the
+// TempDir removes it when dropped
+fn temp_slot(files: &[&str]) -> (tempfile::TempDir, std::path::PathBuf) {
+    let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../reference-data/GameSaves/SaveSlot_1");
+    let d = tempfile::tempdir().unwrap();
+    let slot = d.path().join("SaveSlot_1");
+    std::fs::create_dir_all(&slot).unwrap();
+    for f in files {
+        std::fs::copy(src.join(f), slot.join(f)).unwrap();
+    }
+    (d, slot)
+}
@@ -11,63 +11,412 @@ struct State {
     player: i32,
     image: Option<TextureHandle>,
     backup_on_save: bool,
+    // Where slot zips go; None keeps them next to the slot folders
+    backup_dir: Option<PathBuf>,
     status: String,
-    json: Option<serde_json::Value>,
+    // Every status message shown, oldest first, and the last one logged
+    log: Vec<LogEntry>,
+    log_open: bool,
+    logged_status: String,
+    // Debug console over `DEBUG_LOG`
+    debug_open: bool,
+    // The open document with its undo/redo history
+    session: Option<wle_core::EditSession>,
+    // Document being parsed in the background, or the one whose load was cancelled
+    loading: Option<Loading>,
+    load_cancelled: Option<PathBuf>,
+    // Document being written on a worker thread
+    saving: Option<Saving>,
     ptr: String,
     primitive_entries: Vec<(String, wle_core::JsonEditValue)>,
     // UX helpers
     child_filter: String,
+    // Replace-all over search results; matches unticked by the user
+    replace_text: String,
+    search_unchecked: std::collections::BTreeSet<String>,
     new_key: String,
     new_value_json: String,
     array_index: usize,
     array_value_json: String,
     // Confirmation flags
     confirm_save: bool,
+    save_check: Option<SaveCheck>,
     confirm_remove: Option<String>,
+    history_open: bool,
+    // Script console: Rhai source and what its last run printed
+    script_open: bool,
+    script_text: String,
+    script_output: Vec<String>,
+    // Compare window: the open document against its .sav (None) or a backup
+    compare_open: bool,
+    compare_with: Option<PathBuf>,
+    compare_sources: Vec<PathBuf>,
+    // The open document's file in every other slot that has it
+    compare_slots: Vec<PathBuf>,
+    compare_changes: Option<Result<Vec<wle_core::Change>, String>>,
+    // Duplicates window: groups of copied objects in the open document
+    duplicates_open: bool,
+    duplicates: Option<Vec<wle_core::validate::Duplicates>>,
+    // Browse with the document tree instead of the children list
+    tree_view: bool,
+    // Keyboard cursor among the children of the pointer it was set for, and
+    // whether it moved since its row was last scrolled into view
+    child_cursor: Option<(String, usize)>,
+    cursor_moved: bool,
+    theme: Theme,
+    mode: EditMode,
+    // wle-core's read-only mode: nothing is saved, backed up or deleted.
+    // `read_only` is the user's choice (persisted); `read_only_auto` is set
+    // while the open save folder cannot be written to
+    read_only: bool,
+    read_only_auto: bool,
+    // Raw JSON pane: text of the node at `raw_loaded_for` ("" = whole document)
+    raw_open: bool,
+    raw_whole: bool,
+    raw_text: String,
+    raw_loaded_for: Option<String>,
+    raw_error: Option<String>,
+    // Highlighted layout of the last text shown, keyed by its hash
+    raw_highlight: Option<(u64, egui::text::LayoutJob)>,
+    // The browsed byte array read as a raw image (thumbnails, photos)
+    byte_image: Option<ByteImage>,
+    // JSON last copied in the editor, pasted from the tree's menu
+    clipboard: Option<String>,
+    // Pinned pointers per document kind (kept in favorites.json), and the one
+    // being renamed with its new label
+    favorites: wle_core::PointerBook,
+    favorite_rename: Option<(String, String)>,
+    // Hex pane: bytes of the open document's file and where each pointer was read
+    hex_open: bool,
+    hex: Option<HexView>,
+    hex_loading: Option<(PathBuf, std::sync::mpsc::Receiver<HexView>)>,
+    hex_scrolled_for: Option<String>,
+    // Window title last sent, and whether a close was confirmed
+    title: String,
+    closing: bool,
     // Search
     doc: DocKind,
     last_backup_time: Option<DateTime<Local>>,
+    // Restore dialog
+    retention: wle_core::editor::RetentionPolicy,
+    restore_open: bool,
+    backups: Vec<wle_core::editor::BackupInfo>,
+    // Zip to restore and, for a single file, its name
+    confirm_restore: Option<(PathBuf, Option<String>)>,
+    // Backup shown in detail, with its SlotInfo thumbnail once loaded
+    backup_selected: Option<PathBuf>,
+    backup_preview: Option<(PathBuf, Option<TextureHandle>)>,
+    // Single-file backups of the open document
+    file_backups: Vec<PathBuf>,
+    file_backups_open: bool,
+    // Slot delete / renumber confirmation
+    confirm_delete_slot: Option<PathBuf>,
+    confirm_renumber: bool,
+    confirm_remove_player: Option<u32>,
+    confirm_swap_players: Option<(u32, u32)>,
+    // Save folders found in the standard install locations
+    detected_roots: Vec<wle_core::saves::SaveRootCandidate>,
+    // Save folders opened before, newest first (kept in recent.json)
+    recent: Vec<PathBuf>,
+    // One summary per entry of `slots` (None if the folder could not be read)
+    slot_summaries: Vec<Option<wle_core::saves::SlotSummary>>,
+    // Thumbnail textures of the slot list, made on first draw
+    slot_thumbs: std::collections::HashMap<PathBuf, TextureHandle>,
+    // Files of the selected slot changed on disk by someone else (the game)
+    watcher: Option<wle_core::saves::SlotWatcher>,
+    external_changes: Vec<PathBuf>,
+    // The open document changed on disk and its edits were kept; asked
+    // again when the game writes it once more
+    kept_edits: Option<PathBuf>,
+    // Pointer to go back to once a document reloaded from disk is in
+    reload_ptr: Option<String>,
+    // Documents already parsed, by file content; kept across slot and tab
+    // switches so an unchanged file opens without parsing it again
+    parse_cache: std::sync::Arc<wle_core::ParseCache>,
+    // Money of the selected player as read from disk, and the edited amount
+    money: Option<Result<i64, String>>,
+    money_input: i64,
+    // Friendly tabs: typed views of the slot's files as read from disk
+    tab: Tab,
+    players_money: Option<Vec<(u32, Result<i64, String>)>>,
+    players_money_input: std::collections::BTreeMap<u32, i64>,
+    missions: Option<Result<Vec<(wle_core::model::Guid, wle_core::model::MissionState)>, String>>,
+    stats: Option<Result<Vec<(String, wle_core::model::StatValue)>, String>>,
+    stat_inputs: std::collections::BTreeMap<String, String>,
+    // User presets (presets.json unless another file was loaded), and the
+    // preset being reviewed for a player with the changes it would make
+    user_presets: Option<(PathBuf, Result<Vec<wle_core::presets::Preset>, String>)>,
+    preset_review: Option<PresetReview>,
+    // Worn clothes and wardrobe of the selected player
+    appearance: Option<Result<wle_core::model::CharacterAppearance, String>>,
 }
 
 impl State {
+    // The status line is replaced by the next message; keep each in the log
+    fn log_status(&mut self) {
+        if self.status == self.logged_status {
+            return;
+        }
+        self.logged_status = self.status.clone();
+        if self.status.is_empty() {
+            return;
+        }
+        if self.log.len() >= MAX_LOG {
+            self.log.drain(..self.log.len() + 1 - MAX_LOG);
+        }
+        self.log.push(LogEntry {
+            time: Local::now(),
+            severity: status_severity(&self.status),
+            text: self.status.clone(),
+        });
+    }
     fn clear_slot_cache(&mut self) {
         self.image = None;
-        self.json = None;
+        self.file_backups_open = false;
+        self.session = None;
+        if let Some(l) = self.loading.take() {
+            l.progress.cancel();
+        }
+        self.load_cancelled = None;
+        self.hex = None;
+        self.hex_loading = None;
+        self.byte_image = None;
+        self.compare_changes = None;
+        self.duplicates = None;
         self.primitive_entries.clear();
         self.ptr = "/root".into();
+        self.external_changes.clear();
+        self.kept_edits = None;
+        self.reload_ptr = None;
+        self.money = None;
+        self.appearance = None;
+        self.players_money = None;
+        self.missions = None;
+        self.stats = None;
+        self.preset_review = None;
+    }
+    fn json(&self) -> Option<&serde_json::Value> {
+        self.session.as_ref().map(|s| s.value())
+    }
+    // Our own writes into the slot must not be reported as external changes
+    fn resync_watcher(&mut self) {
+        if let Some(w) = &mut self.watcher {
+            w.resync();
+        }
+    }
+    // Keep the watcher on the selected slot and collect changes since last frame
+    fn poll_watcher(&mut self) {
+        let slot = self.selected_slot_path().map(|p| p.to_path_buf());
+        if self.watcher.as_ref().map(|w| w.slot_dir()) != slot.as_deref() {
+            self.watcher = slot.and_then(|s| wle_core::saves::SlotWatcher::new(&s).ok());
+            self.external_changes.clear();
+        }
+        if let Some(w) = &mut self.watcher {
+            for c in w.try_changes() {
+                if self.kept_edits.as_ref() == Some(&c.path) {
+                    self.kept_edits = None;
+                }
+                if !self.external_changes.contains(&c.path) {
+                    self.external_changes.push(c.path);
+                }
+            }
+        }
+    }
+    fn doc_changed_on_disk(&self) -> bool {
+        self.current_doc_path()
+            .is_some_and(|p| self.external_changes.contains(&p))
+    }
+    // Expert mode skips confirmations, so it always backs up
+    fn backs_up_on_save(&self) -> bool {
+        self.backup_on_save || self.mode == EditMode::Expert
+    }
+    fn is_read_only(&self) -> bool {
+        self.read_only || self.read_only_auto
+    }
+    fn apply_read_only(&self) {
+        wle_core::set_read_only(self.is_read_only());
+    }
+    // Folder holding the backup zips of `slot`
+    fn backup_parent(&self, slot: &Path) -> PathBuf {
+        self.backup_dir
+            .clone()
+            .or_else(|| slot.parent().map(|p| p.to_path_buf()))
+            .unwrap_or_else(|| PathBuf::from("."))
     }
     fn selected_slot_path(&self) -> Option<&Path> {
         self.selected_slot
             .and_then(|i| self.slots.get(i))
             .map(|p| p.as_path())
     }
+    // .sav file of the selected document kind in the selected slot
+    fn current_doc_path(&self) -> Option<PathBuf> {
+        if self.doc == DocKind::SaveInfo {
+            let root = self.root_dir.as_ref()?;
+            return Some(root.join(wle_core::model::SaveInfo::FILE_NAME));
+        }
+        let slot = self.selected_slot_path()?;
+        Some(match self.doc {
+            DocKind::Player => slot.join(format!("PlayerData_{}.sav", self.player)),
+            DocKind::Mission => slot.join("MissionData.sav"),
+            DocKind::Stats => slot.join("StatsData.sav"),
+            DocKind::World => slot.join("WorldData.sav"),
+            DocKind::SaveInfo => unreachable!(),
+        })
+    }
+}
+
+// A file's bytes with the span of every edit-JSON pointer (spans are an error
+// for files that are not BinaryFormatter, such as SaveInfo)
+struct HexView {
+    path: PathBuf,
+    data: Vec<u8>,
+    spans: Result<std::collections::HashMap<String, wle_core::binfmt::ByteSpan>, String>,
+}
+
+impl HexView {
+    fn read(path: PathBuf) -> Self {
+        let data = match std::fs::read(&path) {
+            Ok(d) => d,
+            Err(e) => {
+                return Self {
+                    path,
+                    data: Vec::new(),
+                    spans: Err(e.to_string()),
+                };
+            }
+        };
+        let spans = wle_core::binfmt::Parser::new(&data)
+            .parse_stream()
+            .map(|doc| doc.byte_spans());
+        Self { path, data, spans }
+    }
+    // Span of `ptr`, or of its nearest ancestor that was read from the file
+    fn span_for(&self, ptr: &str) -> Option<(String, wle_core::binfmt::ByteSpan)> {
+        let spans = self.spans.as_ref().ok()?;
+        let mut p = ptr;
+        loop {
+            if let Some(s) = spans.get(p) {
+                return Some((p.to_string(), s.clone()));
+            }
+            p = parent_pointer(p)?;
+        }
+    }
+}
+
+// Pre-save report, still being worked out or ready to show
+enum SaveCheck {
+    Running(std::sync::mpsc::Receiver<Vec<wle_core::validate::Issue>>),
+    Done(Vec<wle_core::validate::Issue>),
+}
+
+// A document parsed on a worker thread; the result arrives on `rx`
+struct Loading {
+    path: PathBuf,
+    progress: std::sync::Arc<wle_core::binfmt::ParseProgress>,
+    rx: std::sync::mpsc::Receiver<Result<serde_json::Value, String>>,
+    started: std::time::Instant,
+}
+
+// A document written on a worker thread; the result comes back on `rx` with
+// the value that was written
+struct Saving {
+    path: PathBuf,
+    progress: std::sync::Arc<wle_core::WriteProgress>,
+    rx: std::sync::mpsc::Receiver<(Result<(), wle_core::WriteError>, serde_json::Value)>,
+    started: std::time::Instant,
 }
 
+// Something to do once the open document's changes are saved or dropped
+type Deferred = Box<dyn FnOnce(&mut AppGui)>;
+
 struct AppGui {
     state: State,
+    // Action waiting on the unsaved-changes prompt, and what it is
+    discard_prompt: Option<(String, Deferred)>,
 }
 
 impl AppGui {
-    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self {
+    fn new(cc: &eframe::CreationContext<'_>, settings: Settings) -> Self {
+        cc.egui_ctx.set_zoom_factor(settings.ui_scale);
+        let mut app = Self {
             state: State {
                 player: 1,
-                backup_on_save: true,
+                backup_on_save: settings.backup_on_save,
+                backup_dir: settings.backup_dir,
                 ptr: "/root".into(),
                 child_filter: String::new(),
                 new_key: String::new(),
                 new_value_json: String::new(),
                 array_index: 0,
                 array_value_json: String::new(),
+                tree_view: settings.tree_view,
+                theme: settings.theme,
+                mode: settings.mode,
+                read_only: settings.read_only,
+                raw_open: settings.raw_open,
                 confirm_save: false,
-                doc: DocKind::Player,
+                doc: settings.doc,
+                retention: settings.retention,
+                detected_roots: wle_core::saves::detect_save_roots(),
+                recent: load_recent(),
+                favorites: gui_config_file("favorites.json")
+                    .map(|p| wle_core::PointerBook::load(&p))
+                    .unwrap_or_default(),
                 ..Default::default()
             },
+            discard_prompt: None,
+        };
+        app.state.apply_read_only();
+        if let Some(root) = settings.root_dir.filter(|p| p.is_dir()) {
+            app.state.root_dir = Some(root);
+            app.refresh_slots();
+            app.check_writable();
+        }
+        app
+    }
+    // A save folder that cannot be written to is opened read-only, rather
+    // than failing at the first save
+    fn check_writable(&mut self) {
+        self.state.read_only_auto = false;
+        if !self.state.read_only
+            && let Some(root) = self.state.root_dir.as_deref()
+            && let Err(e) = wle_core::saves::check_writable(root)
+        {
+            self.state.read_only_auto = true;
+            self.state.status = format!("{}; opened read-only", e);
+        }
+        self.state.apply_read_only();
+    }
+    // Current preferences, with the window size as it is now
+    fn settings(&self, ctx: &egui::Context) -> Settings {
+        let size = ctx
+            .input(|i| i.viewport().inner_rect)
+            .map(|r| [r.width(), r.height()]);
+        Settings {
+            window_size: size.unwrap_or(Settings::default().window_size),
+            backup_on_save: self.state.backup_on_save,
+            backup_dir: self.state.backup_dir.clone(),
+            root_dir: self.state.root_dir.clone(),
+            doc: self.state.doc,
+            retention: self.state.retention,
+            tree_view: self.state.tree_view,
+            raw_open: self.state.raw_open,
+            theme: self.state.theme,
+            ui_scale: ctx.zoom_factor(),
+            mode: self.state.mode,
+            read_only: self.state.read_only,
         }
     }
     fn refresh_slots(&mut self) {
         if let Some(root) = &self.state.root_dir {
             self.state.slots = wle_core::saves::list_slots(root);
+            self.state.slot_summaries = self
+                .state
+                .slots
+                .iter()
+                .map(|p| wle_core::saves::slot_summary(p).ok())
+                .collect();
+            self.state.slot_thumbs.clear();
             if self.state.slots.is_empty() {
                 self.state.selected_slot = None;
                 self.state.status = "No SaveSlot_* found".into();
@@ -81,9 +430,53 @@ impl AppGui {
         }
     }
     fn pick_root_dir(&mut self) {
-        if let Some(dir) = rfd::FileDialog::new().set_directory(".").pick_folder() {
-            self.state.root_dir = Some(dir);
-            self.refresh_slots();
+        // Start the picker next to a detected save folder when there is one
+        let start = self
+            .state
+            .detected_roots
+            .first()
+            .and_then(|c| c.path.parent())
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+        if let Some(dir) = rfd::FileDialog::new().set_directory(start).pick_folder() {
+            self.open_root(dir);
+        }
+    }
+    fn open_root(&mut self, root: PathBuf) {
+        self.state.recent.retain(|p| p != &root);
+        self.state.recent.insert(0, root.clone());
+        self.state.recent.truncate(MAX_RECENT);
+        if let Err(e) = save_recent(&self.state.recent) {
+            self.state.status = format!("Could not remember recent folders: {}", e);
+        }
+        self.state.root_dir = Some(root);
+        self.state.selected_slot = None;
+        self.refresh_slots();
+        self.check_writable();
+    }
+    // Open whatever was dropped: a save folder, a slot or a single .sav
+    fn open_dropped(&mut self, path: &Path) {
+        let Some(loc) = wle_core::saves::locate_save_path(path) else {
+            self.state.status = format!("Not a Wobbly Life save: {}", path.display());
+            return;
+        };
+        self.open_root(loc.root);
+        if let Some(slot) = &loc.slot {
+            self.state.selected_slot = self.state.slots.iter().position(|p| p == slot);
+        }
+        if let Some(file) = &loc.file {
+            let name = file.file_name().unwrap_or_default().to_string_lossy();
+            match doc_for_file(&name) {
+                Some((doc, player)) => {
+                    self.state.doc = doc;
+                    self.state.tab = Tab::Browser;
+                    if let Some(n) = player {
+                        self.state.player = n;
+                    }
+                    self.state.status = format!("Opened {}", name);
+                }
+                None => self.state.status = format!("{} has no editor view", name),
+            }
         }
     }
     fn ensure_loaded(&mut self, ctx: &egui::Context) {
@@ -96,174 +489,3325 @@ impl AppGui {
             if fp.exists()
                 && let Ok(doc) = wle_core::json::parse_binary(&fp)
                 && let Some(info) = doc.as_save_slot_info()
+                && let Some(decoded) = info.decode_image()
             {
-                let bytes = &info.small_image_data;
-                let len = bytes.len();
-                let mut dims: Option<(usize, usize, usize)> = None;
-                for ch in [3usize, 4usize] {
-                    if len % ch == 0 {
-                        let side = ((len / ch) as f32).sqrt().floor() as usize;
-                        if side > 0 && side * side * ch == len {
-                            dims = Some((side, side, ch));
-                            break;
+                let img = ColorImage::from_rgba_unmultiplied(
+                    [decoded.width, decoded.height],
+                    &decoded.rgba,
+                );
+                let tex = ctx.load_texture("slot_image", img, egui::TextureOptions::LINEAR);
+                self.state.image = Some(tex);
+            }
+        }
+        // Take a document parsed in the background, if it is still the one wanted
+        if let Some(l) = &self.state.loading
+            && let Ok(res) = l.rx.try_recv()
+        {
+            let l = self.state.loading.take().unwrap();
+            match res {
+                Ok(v) if self.state.current_doc_path().as_ref() == Some(&l.path) => {
+                    let (found, expected) = (
+                        wle_core::model::detect_kind_json(&v),
+                        wle_core::model::DocKind::expected_for(&l.path),
+                    );
+                    if found != expected && expected != wle_core::model::DocKind::Unknown {
+                        self.state.status = format!(
+                            "Warning: {} is a {} document, not {}",
+                            l.path.file_name().unwrap_or_default().to_string_lossy(),
+                            found,
+                            expected
+                        );
+                    }
+                    self.state.ptr = self
+                        .state
+                        .reload_ptr
+                        .take()
+                        .filter(|p| v.pointer(p).is_some())
+                        .unwrap_or_else(|| "/root".into());
+                    self.state.session = Some(wle_core::EditSession::from_value(&l.path, v));
+                    self.refresh_primitive_entries();
+                }
+                Ok(_) => {}
+                Err(e) => self.state.status = format!("Load error: {}", e),
+            }
+        }
+        // Start loading the selected document once
+        let Some(path) = self.state.current_doc_path() else {
+            return;
+        };
+        if self.state.session.is_some()
+            || !path.exists()
+            || self.state.load_cancelled.as_ref() == Some(&path)
+        {
+            return;
+        }
+        if let Some(l) = &self.state.loading {
+            if l.path == path {
+                return;
+            }
+            // Another document was picked meanwhile
+            l.progress.cancel();
+            self.state.loading = None;
+        }
+        if self.state.doc == DocKind::SaveInfo {
+            // Small JSON file, read right away
+            let loaded = std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|data| {
+                    document_from_bytes(DocKind::SaveInfo, data, &self.state.parse_cache)
+                });
+            match loaded {
+                Ok(v) => {
+                    self.state.ptr = self
+                        .state
+                        .reload_ptr
+                        .take()
+                        .filter(|p| v.pointer(p).is_some())
+                        .unwrap_or_else(|| "/root".into());
+                    self.state.session = Some(wle_core::EditSession::from_value(&path, v));
+                    self.refresh_primitive_entries();
+                }
+                Err(e) => self.state.status = format!("Load error: {}", e),
+            }
+            return;
+        }
+        let progress = std::sync::Arc::new(wle_core::binfmt::ParseProgress::default());
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (p, prog, ctx) = (path.clone(), progress.clone(), ctx.clone());
+        let cache = self.state.parse_cache.clone();
+        std::thread::spawn(move || {
+            let res = std::fs::read(&p)
+                .map_err(|e| e.to_string())
+                .and_then(|data| cache.parse_with_progress(data, prog));
+            let _ = tx.send(res);
+            ctx.request_repaint();
+        });
+        self.state.loading = Some(Loading {
+            path,
+            progress,
+            rx,
+            started: std::time::Instant::now(),
+        });
+    }
+    // Progress of a background load with a Cancel button, or a way to retry one
+    fn loading_bar(&mut self, ui: &mut egui::Ui) {
+        if let Some(l) = &self.state.loading {
+            let name = l.path.file_name().unwrap_or_default().to_string_lossy();
+            let frac = l.progress.fraction();
+            let text = if frac >= 1.0 {
+                format!("Converting {}…", name)
+            } else {
+                format!(
+                    "Reading {}… {:.0}% ({} records)",
+                    name,
+                    frac * 100.0,
+                    l.progress.records()
+                )
+            };
+            let mut cancel = false;
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.add(egui::ProgressBar::new(frac).text(text).desired_width(300.0));
+                ui.label(format!("{:.1} s", l.started.elapsed().as_secs_f32()));
+                cancel = ui.button("Cancel").clicked();
+            });
+            // The bar only moves if something repaints
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_millis(100));
+            if cancel {
+                let l = self.state.loading.take().unwrap();
+                l.progress.cancel();
+                self.state.status = "Loading cancelled".into();
+                self.state.load_cancelled = Some(l.path);
+            }
+        } else if let Some(path) = &self.state.load_cancelled
+            && self.state.current_doc_path().as_ref() == Some(path)
+        {
+            ui.horizontal(|ui| {
+                ui.label("Loading was cancelled");
+                if ui.button("Load").clicked() {
+                    self.state.load_cancelled = None;
+                }
+            });
+        }
+    }
+    fn refresh_backups(&mut self) {
+        self.state.backups = match self.state.selected_slot_path() {
+            Some(slot) => {
+                match wle_core::editor::list_backups_in(slot, &self.state.backup_parent(slot)) {
+                    Ok(list) => list,
+                    Err(e) => {
+                        self.state.status = format!("Backup listing error: {}", e);
+                        Vec::new()
+                    }
+                }
+            }
+            None => Vec::new(),
+        };
+    }
+    fn restore_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.state.restore_open;
+        egui::Window::new("Backups")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if self.state.backups.is_empty() {
+                    ui.label("No backups found for this slot");
+                }
+                egui::ScrollArea::vertical()
+                    .id_source("restore_scroll")
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("backups_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Backup time");
+                                ui.strong("In-game time");
+                                ui.strong("Size");
+                                ui.strong("Files");
+                                ui.end_row();
+                                // Newest first
+                                for b in self.state.backups.iter().rev() {
+                                    let when = b
+                                        .timestamp
+                                        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                                        .unwrap_or_else(|| {
+                                            b.path
+                                                .file_name()
+                                                .unwrap_or_default()
+                                                .to_string_lossy()
+                                                .into_owned()
+                                        });
+                                    let selected =
+                                        self.state.backup_selected.as_ref() == Some(&b.path);
+                                    if ui
+                                        .selectable_label(selected, when)
+                                        .on_hover_text("Show thumbnail and files")
+                                        .clicked()
+                                    {
+                                        self.state.backup_selected =
+                                            (!selected).then(|| b.path.clone());
+                                    }
+                                    ui.label(b.slot_date_time.as_deref().unwrap_or("-"));
+                                    ui.label(format!("{:.1} KB", b.size as f64 / 1024.0));
+                                    ui.label(b.files.len().to_string())
+                                        .on_hover_text(b.files.join("\n"));
+                                    if ui
+                                        .add_enabled(self.state.mode != EditMode::Safe, egui::Button::new("Restore"))
+                                        .on_disabled_hover_text(SAFE_MODE_OFF)
+                                        .clicked()
+                                    {
+                                        self.state.confirm_restore = Some((b.path.clone(), None));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if let Some(b) = self
+                    .state
+                    .backup_selected
+                    .as_ref()
+                    .and_then(|p| self.state.backups.iter().find(|b| &b.path == p))
+                {
+                    ui.separator();
+                    if self.state.backup_preview.as_ref().map(|(p, _)| p) != Some(&b.path) {
+                        let tex = wle_core::editor::read_backup_thumbnail(&b.path)
+                            .ok()
+                            .flatten()
+                            .map(|img| {
+                                let img = ColorImage::from_rgba_unmultiplied(
+                                    [img.width, img.height],
+                                    &img.rgba,
+                                );
+                                ctx.load_texture("backup_image", img, egui::TextureOptions::LINEAR)
+                            });
+                        self.state.backup_preview = Some((b.path.clone(), tex));
+                    }
+                    ui.horizontal_top(|ui| {
+                        match self.state.backup_preview.as_ref().and_then(|(_, t)| t.as_ref()) {
+                            Some(tex) => {
+                                ui.image((tex.id(), tex.size_vec2()));
+                            }
+                            None => {
+                                ui.label("No thumbnail");
+                            }
+                        }
+                        ui.vertical(|ui| {
+                            ui.strong(b.path.file_name().unwrap_or_default().to_string_lossy());
+                            for f in &b.files {
+                                ui.horizontal(|ui| {
+                                    ui.monospace(f);
+                                    if ui
+                                        .add_enabled(self.state.mode != EditMode::Safe, egui::Button::new("Restore file").small())
+                                        .on_hover_text("Put back only this file")
+                                        .on_disabled_hover_text(SAFE_MODE_OFF)
+                                        .clicked()
+                                    {
+                                        self.state.confirm_restore =
+                                            Some((b.path.clone(), Some(f.clone())));
+                                    }
+                                });
+                            }
+                        });
+                    });
+                }
+                if let Some((zip, file)) = self.state.confirm_restore.clone() {
+                    ui.separator();
+                    let zip_name = zip.file_name().unwrap_or_default().to_string_lossy();
+                    ui.label(match &file {
+                        None => format!(
+                            "Overwrite the slot with {}? The current slot is backed up first.",
+                            zip_name
+                        ),
+                        Some(f) => format!(
+                            "Overwrite {} with the copy in {}? The current slot is backed up first.",
+                            f, zip_name
+                        ),
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button("Confirm").clicked() {
+                            self.confirm_discard("restoring the backup", move |app| {
+                                app.restore_slot(&zip, file)
+                            });
+                            self.state.confirm_restore = None;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.state.confirm_restore = None;
+                        }
+                    });
+                }
+            });
+        self.state.restore_open = open;
+    }
+    // Unpack a backup over the selected slot, or only `file` from it
+    fn restore_slot(&mut self, zip: &Path, file: Option<String>) {
+        let Some(slot) = self.state.selected_slot_path().map(|p| p.to_path_buf()) else {
+            return;
+        };
+        let opts = wle_core::editor::RestoreOpts {
+            dry_run: false,
+            files: file.map(|f| vec![f]),
+            backup_dir: Some(self.state.backup_parent(&slot)),
+        };
+        match wle_core::editor::restore_backup(zip, &slot, opts) {
+            Ok(rep) => {
+                self.state.status = format!("Restored {} file(s)", rep.restored.len());
+                self.state.clear_slot_cache();
+                self.state.resync_watcher();
+                self.refresh_backups();
+            }
+            Err(e) => self.state.status = format!("Restore error: {}", e),
+        }
+    }
+    // Player files changed on disk: reload everything derived from them
+    fn after_player_change(&mut self) {
+        if let Some(i) = self.state.selected_slot
+            && let Some(sum) = self.state.slot_summaries.get_mut(i)
+        {
+            *sum = wle_core::saves::slot_summary(&self.state.slots[i]).ok();
+        }
+        self.state.clear_slot_cache();
+        self.state.resync_watcher();
+    }
+
+    fn player_buttons(&mut self, ui: &mut egui::Ui) {
+        let Some(slot) = self.state.selected_slot_path().map(|p| p.to_path_buf()) else {
+            return;
+        };
+        let n = self.state.player as u32;
+        let has = |i: u32| slot.join(format!("PlayerData_{}.sav", i)).exists();
+        let exists = has(n);
+        ui.horizontal(|ui| {
+            if !exists {
+                // A blank new player, a copy of one already in the slot, or
+                // one made from a saved PlayerData template
+                enum Source {
+                    Player(Option<u32>),
+                    Template(Box<wle_core::templates::Template>),
+                }
+                let mut add: Option<Source> = None;
+                ui.menu_button(format!("Add player {}", n), |ui| {
+                    if ui.button("Blank player").clicked() {
+                        add = Some(Source::Player(None));
+                        ui.close_menu();
+                    }
+                    for t in (1..=wle_core::saves::MAX_PLAYERS).filter(|t| has(*t)) {
+                        if ui.button(format!("Copy of player {}", t)).clicked() {
+                            add = Some(Source::Player(Some(t)));
+                            ui.close_menu();
+                        }
+                    }
+                    let templates = wle_core::templates::default_dir()
+                        .map(|d| wle_core::templates::list_templates(&d))
+                        .unwrap_or_default();
+                    for t in templates.into_iter().filter_map(|(_, t)| t.ok()) {
+                        if t.kind() != wle_core::model::DocKind::PlayerData {
+                            continue;
+                        }
+                        let button = ui.button(format!("From template {}", t.name));
+                        if button.on_hover_text(&t.description).clicked() {
+                            add = Some(Source::Template(Box::new(t)));
+                            ui.close_menu();
                         }
                     }
+                });
+                if let Some(source) = add {
+                    let slot = slot.clone();
+                    self.confirm_discard("adding a player", move |app| {
+                        let parent = app.state.backup_parent(&slot);
+                        let backup = wle_core::editor::zip_backup_slot_pruned_to(
+                            &slot,
+                            &parent,
+                            app.state.retention,
+                        );
+                        let create = || match &source {
+                            Source::Player(from) => wle_core::saves::create_player(&slot, n, *from),
+                            Source::Template(t) => t
+                                .to_bytes()
+                                .map_err(std::io::Error::other)
+                                .and_then(|data| {
+                                    wle_core::saves::create_player_from(&slot, n, &data, None)
+                                }),
+                        };
+                        app.state.status = match backup.and_then(|(zip, _)| create().map(|_| zip)) {
+                            Ok(zip) => format!(
+                                "Created player {} (backup {})",
+                                n,
+                                zip.file_name().unwrap_or_default().to_string_lossy()
+                            ),
+                            Err(e) => format!("Add player error: {}", e),
+                        };
+                        app.after_player_change();
+                    });
+                }
+                return;
+            }
+            if ui
+                .add_enabled(
+                    self.state.mode != EditMode::Safe,
+                    egui::Button::new("Remove…"),
+                )
+                .on_disabled_hover_text(SAFE_MODE_OFF)
+                .clicked()
+            {
+                self.confirm_discard("removing the player", move |app| {
+                    app.state.confirm_remove_player = Some(n)
+                });
+            }
+            ui.menu_button("Swap with", |ui| {
+                for other in (1..=wle_core::saves::MAX_PLAYERS).filter(|o| *o != n) {
+                    let label = if has(other) {
+                        format!("Player {}", other)
+                    } else {
+                        format!("Player {} (empty: move)", other)
+                    };
+                    if ui.button(label).clicked() {
+                        self.confirm_discard("swapping players", move |app| {
+                            app.state.confirm_swap_players = Some((n, other))
+                        });
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+    }
+
+    fn thumbnail_buttons(&mut self, ui: &mut egui::Ui) {
+        let Some(slot) = self.state.selected_slot_path().map(|p| p.to_path_buf()) else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            if ui.button("Export thumbnail…").clicked()
+                && let Some(out) = rfd::FileDialog::new()
+                    .add_filter("PNG", &["png"])
+                    .set_file_name("thumbnail.png")
+                    .save_file()
+            {
+                self.state.status = match wle_core::saves::export_thumbnail(&slot, &out) {
+                    Ok(()) => format!("Exported {}", out.display()),
+                    Err(e) => format!("Export error: {}", e),
+                };
+            }
+            if ui.button("Import thumbnail…").clicked()
+                && let Some(png) = rfd::FileDialog::new()
+                    .add_filter("PNG", &["png"])
+                    .pick_file()
+            {
+                self.state.status = match wle_core::saves::import_thumbnail(&slot, &png) {
+                    Ok(_) => "Thumbnail replaced (SlotInfo.sav backed up)".into(),
+                    Err(e) => format!("Import error: {}", e),
+                };
+                self.state.image = None;
+                self.state.slot_thumbs.remove(&slot);
+                if let Some(i) = self.state.selected_slot
+                    && let Some(sum) = self.state.slot_summaries.get_mut(i)
+                {
+                    *sum = wle_core::saves::slot_summary(&slot).ok();
+                }
+                self.state.resync_watcher();
+            }
+        });
+    }
+
+    // Preview of the browsed node when it is a byte array holding a raw image,
+    // with PNG export and replace
+    fn byte_image_preview(&mut self, ui: &mut egui::Ui) {
+        let Some(v) = self.state.json() else {
+            return;
+        };
+        let eff = browse_effective_ptr(v, &self.state.ptr);
+        let Some(items) = v.pointer(&eff).and_then(|n| n.as_array()) else {
+            return;
+        };
+        if items.len() < MIN_IMAGE_BYTES || !items[0].is_u64() {
+            return;
+        }
+        let hash = egui::util::hash(items);
+        let fresh = matches!(&self.state.byte_image, Some(b) if b.ptr == eff && b.hash == hash);
+        if !fresh {
+            let image = v
+                .pointer(&eff)
+                .and_then(wle_core::byte_array)
+                .and_then(|b| wle_core::model::SlotImage::guess(&b));
+            self.state.byte_image = Some(ByteImage {
+                ptr: eff.clone(),
+                hash,
+                image,
+                texture: None,
+            });
+        }
+        let Some(b) = &mut self.state.byte_image else {
+            return;
+        };
+        let Some((img, ch)) = &b.image else {
+            return;
+        };
+        let (img, ch) = (img.clone(), *ch);
+        let tex = b.texture.get_or_insert_with(|| {
+            let c = ColorImage::from_rgba_unmultiplied([img.width, img.height], &img.rgba);
+            ui.ctx()
+                .load_texture("byte_image", c, egui::TextureOptions::LINEAR)
+        });
+        let scale = (256.0 / img.width.max(img.height) as f32).min(4.0);
+        let size = egui::vec2(img.width as f32, img.height as f32) * scale;
+        let tex = tex.clone();
+        let mut replace = None;
+        ui.collapsing("Image", |ui| {
+            ui.image((tex.id(), size));
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{}×{} {}, {} bytes",
+                    img.width,
+                    img.height,
+                    if ch == 4 { "RGBA" } else { "RGB" },
+                    img.width * img.height * ch
+                ));
+                if ui.button("Export PNG…").clicked()
+                    && let Some(out) = rfd::FileDialog::new()
+                        .add_filter("PNG", &["png"])
+                        .set_file_name("image.png")
+                        .save_file()
+                {
+                    self.state.status = match img
+                        .to_png()
+                        .and_then(|png| std::fs::write(&out, png).map_err(|e| e.to_string()))
+                    {
+                        Ok(()) => format!("Exported {}", out.display()),
+                        Err(e) => format!("Export error: {}", e),
+                    };
                 }
-                if dims.is_none() {
-                    if len == 256 * 256 * 3 {
-                        dims = Some((256, 256, 3));
-                    } else if len == 256 * 256 * 4 {
-                        dims = Some((256, 256, 4));
+                if ui
+                    .button("Replace from PNG…")
+                    .on_hover_text("The PNG is scaled to the size of this image")
+                    .clicked()
+                    && let Some(png) = rfd::FileDialog::new()
+                        .add_filter("PNG", &["png"])
+                        .pick_file()
+                {
+                    match std::fs::read(&png)
+                        .map_err(|e| e.to_string())
+                        .and_then(|data| wle_core::model::SlotImage::from_png(&data))
+                    {
+                        Ok(new) => replace = Some(new.resized(img.width, img.height).encode(ch)),
+                        Err(e) => self.state.status = format!("Import error: {}", e),
                     }
                 }
-                if let Some((w, h, ch)) = dims {
-                    let mut img = ColorImage::new([w, h], egui::Color32::BLACK);
-                    if ch == 3 {
-                        for y in 0..h {
-                            for x in 0..w {
-                                let sy = h - 1 - y;
-                                let idx = (sy * w + x) * 3;
-                                let r = bytes[idx];
-                                let g = bytes[idx + 1];
-                                let b = bytes[idx + 2];
-                                img.pixels[y * w + x] = egui::Color32::from_rgb(r, g, b);
+            });
+        });
+        if let Some(bytes) = replace
+            && self.apply_edit(vec![wle_core::PatchOp::replace(
+                eff.clone(),
+                serde_json::json!(bytes),
+            )])
+        {
+            self.state.status = format!("Replaced image at {} (not saved yet)", eff);
+        }
+    }
+
+    fn appearance_widget(&mut self, ui: &mut egui::Ui) {
+        use wle_core::model::ClothingSlot;
+        let Some(slot) = self.state.selected_slot_path().map(|p| p.to_path_buf()) else {
+            return;
+        };
+        let player = self.state.player as u32;
+        let look = self
+            .state
+            .appearance
+            .get_or_insert_with(|| {
+                let path = slot.join(format!("PlayerData_{}.sav", player));
+                let doc = wle_core::json::parse_binary(&path)?;
+                doc.as_character_appearance()
+                    .ok_or_else(|| "not player data".to_string())
+            })
+            .clone();
+        let Ok(look) = look else {
+            return;
+        };
+        let label = |i: Option<usize>, p: Option<&wle_core::model::ClothingPiece>| match (i, p) {
+            (_, Some(p)) if p.prefab.is_nil() => "none".to_string(),
+            (Some(i), Some(p)) => format!("#{} {}", i, &p.prefab.to_string()[..8]),
+            (None, Some(p)) => p.prefab.to_string()[..8].to_string(),
+            _ => "-".to_string(),
+        };
+        let mut chosen: Option<(ClothingSlot, Option<usize>)> = None;
+        let mut import: Option<PathBuf> = None;
+        ui.collapsing("Appearance", |ui| {
+            ui.horizontal(|ui| {
+                if ui
+                    .button("Export loadout…")
+                    .on_hover_text("Clothes, wardrobe and vehicles as a file to share")
+                    .clicked()
+                    && let Some(out) = rfd::FileDialog::new()
+                        .add_filter("Loadout", &["json"])
+                        .set_file_name(format!("player{}-loadout.json", player))
+                        .save_file()
+                {
+                    let name = out.file_stem().unwrap_or_default().to_string_lossy();
+                    self.state.status = match wle_core::loadout::export(&slot, player, &name)
+                        .and_then(|l| {
+                            std::fs::write(&out, l.to_json().to_string()).map_err(|e| e.to_string())
+                        }) {
+                        Ok(()) => format!("Exported loadout to {}", out.display()),
+                        Err(e) => format!("Loadout error: {}", e),
+                    };
+                }
+                if ui.button("Import loadout…").clicked() {
+                    import = rfd::FileDialog::new()
+                        .add_filter("Loadout", &["json"])
+                        .pick_file();
+                }
+            });
+            for s in ClothingSlot::ALL {
+                let current = label(look.worn_index(s), look.worn(s));
+                egui::ComboBox::from_label(s.name())
+                    .selected_text(current)
+                    .show_ui(ui, |ui| {
+                        if ui.selectable_label(false, "none").clicked() {
+                            chosen = Some((s, None));
+                        }
+                        for (i, p) in look.options(s).iter().enumerate() {
+                            let [r, g, b, _] =
+                                p.color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+                            ui.horizontal(|ui| {
+                                ui.colored_label(egui::Color32::from_rgb(r, g, b), "■");
+                                let selected = look.worn_index(s) == Some(i);
+                                if ui
+                                    .selectable_label(selected, label(Some(i), Some(p)))
+                                    .clicked()
+                                {
+                                    chosen = Some((s, Some(i)));
+                                }
+                            });
+                        }
+                    });
+            }
+        });
+        if let Some(file) = import {
+            let slot = slot.clone();
+            self.confirm_discard("importing a loadout", move |app| {
+                app.state.status = match wle_core::loadout::load(&file).and_then(|l| {
+                    let (changes, edit) = wle_core::loadout::import_edit(&slot, player, &l)?;
+                    if changes.is_empty() {
+                        return Ok(format!("Player {} already has loadout {}", player, l.name));
+                    }
+                    edit.apply()?;
+                    Ok(format!(
+                        "Applied loadout {} ({} changes, backup kept)",
+                        l.name,
+                        changes.len()
+                    ))
+                }) {
+                    Ok(msg) => msg,
+                    Err(e) => format!("Loadout error: {}", e),
+                };
+                app.state.clear_slot_cache();
+                app.state.resync_watcher();
+            });
+        }
+        if let Some((s, index)) = chosen {
+            self.confirm_discard("changing clothes", move |app| {
+                app.state.status =
+                    match wle_core::cheats::set_appearance(&slot, player, &[(s, index)]) {
+                        Ok(_) => format!("Changed {} (backup kept)", s.name()),
+                        Err(e) => format!("Appearance error: {}", e),
+                    };
+                app.state.clear_slot_cache();
+                app.state.resync_watcher();
+            });
+        }
+    }
+
+    fn money_widget(&mut self, ui: &mut egui::Ui) {
+        let Some(slot) = self.state.selected_slot_path().map(|p| p.to_path_buf()) else {
+            return;
+        };
+        let player = self.state.player as u32;
+        if self.state.money.is_none() {
+            let loaded = wle_core::cheats::get_money(&slot, player);
+            if let Ok(m) = loaded {
+                self.state.money_input = m;
+            }
+            self.state.money = Some(loaded);
+        }
+        match self
+            .state
+            .money
+            .clone()
+            .unwrap_or_else(|| Err(String::new()))
+        {
+            Ok(current) => {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Money: {}", current));
+                    if ui.small_button("↺").on_hover_text("Re-read").clicked() {
+                        self.state.money = None;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut self.state.money_input)
+                            .range(0..=i32::MAX as i64),
+                    );
+                    if ui.button("Set money").clicked() {
+                        let amount = self.state.money_input;
+                        let slot = slot.clone();
+                        self.confirm_discard("setting money", move |app| {
+                            app.state.status =
+                                match wle_core::cheats::set_money(&slot, player, amount) {
+                                    Ok(backup) => format!(
+                                        "Money set to {} (backup {})",
+                                        amount,
+                                        backup.file_name().unwrap_or_default().to_string_lossy()
+                                    ),
+                                    Err(e) => format!("Money error: {}", e),
+                                };
+                            app.state.clear_slot_cache();
+                            app.state.resync_watcher();
+                        });
+                    }
+                });
+            }
+            Err(e) => {
+                ui.label("Money: —").on_hover_text(e);
+            }
+        }
+    }
+
+    // Build a typed edit once unsaved changes are dealt with (so it sees a
+    // just-saved file), then back up, write and verify it
+    fn write_typed_edit(
+        &mut self,
+        what: &str,
+        done: String,
+        make: impl FnOnce() -> Result<wle_core::cheats::PendingEdit, String> + 'static,
+    ) {
+        let what_owned = what.to_string();
+        self.confirm_discard(what, move |app| {
+            app.state.status = match make().and_then(|e| e.apply()) {
+                Ok(backup) => format!(
+                    "{} (backup {})",
+                    done,
+                    backup.file_name().unwrap_or_default().to_string_lossy()
+                ),
+                Err(e) => format!("Error {}: {}", what_owned, e),
+            };
+            app.state.clear_slot_cache();
+            app.state.resync_watcher();
+        });
+    }
+
+    fn money_tab(&mut self, ui: &mut egui::Ui) {
+        let Some(slot) = self.state.selected_slot_path().map(|p| p.to_path_buf()) else {
+            ui.label("Select a save slot");
+            return;
+        };
+        if self.state.players_money.is_none() {
+            let players = wle_core::saves::slot_summary(&slot)
+                .map(|s| s.players)
+                .unwrap_or_default();
+            let all: Vec<_> = players
+                .into_iter()
+                .map(|n| (n, wle_core::cheats::get_money(&slot, n)))
+                .collect();
+            for (n, m) in &all {
+                if let Ok(m) = m {
+                    self.state.players_money_input.insert(*n, *m);
+                }
+            }
+            self.state.players_money = Some(all);
+        }
+        let all = self.state.players_money.clone().unwrap_or_default();
+        if all.is_empty() {
+            ui.label("No players in this slot");
+        }
+        let mut set: Option<(u32, i64)> = None;
+        egui::Grid::new("money_grid").striped(true).show(ui, |ui| {
+            ui.strong("Player");
+            ui.strong("Money");
+            ui.strong("New amount");
+            ui.end_row();
+            for (n, money) in &all {
+                ui.label(format!("Player {}", n));
+                match money {
+                    Ok(m) => {
+                        ui.label(m.to_string());
+                        let input = self.state.players_money_input.entry(*n).or_insert(*m);
+                        ui.add(egui::DragValue::new(input).range(0..=i32::MAX as i64));
+                        if ui
+                            .add_enabled(*input != *m, egui::Button::new("Set"))
+                            .clicked()
+                        {
+                            set = Some((*n, *input));
+                        }
+                    }
+                    Err(e) => {
+                        ui.label("—").on_hover_text(e);
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        if let Some((player, amount)) = set {
+            self.write_typed_edit(
+                "setting money",
+                format!("Player {} money set to {}", player, amount),
+                move || wle_core::cheats::money_edit(&slot, player, amount),
+            );
+        }
+    }
+
+    fn missions_tab(&mut self, ui: &mut egui::Ui) {
+        use wle_core::model::MissionState;
+        let Some(slot) = self.state.selected_slot_path().map(|p| p.to_path_buf()) else {
+            ui.label("Select a save slot");
+            return;
+        };
+        let missions = self
+            .state
+            .missions
+            .get_or_insert_with(|| {
+                let path = slot.join("MissionData.sav");
+                let doc = wle_core::json::parse_binary(&path)?;
+                let view = doc
+                    .as_mission_data()
+                    .ok_or_else(|| "not mission data".to_string())?;
+                Ok(view.missions.iter().map(|m| (m.id, m.state)).collect())
+            })
+            .clone();
+        let missions = match missions {
+            Ok(m) => m,
+            Err(e) => {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("MissionData.sav: {}", e),
+                );
+                return;
+            }
+        };
+        let done = missions
+            .iter()
+            .filter(|(_, s)| *s == MissionState::Completed)
+            .count();
+        let mut toggle: Option<(wle_core::model::Guid, bool)> = None;
+        let mut complete_all = false;
+        ui.horizontal(|ui| {
+            ui.label(format!("{} of {} missions completed", done, missions.len()));
+            complete_all = ui
+                .add_enabled(done < missions.len(), egui::Button::new("Complete all"))
+                .clicked();
+        });
+        ui.weak("Only missions this save already mentions are listed");
+        egui::ScrollArea::vertical()
+            .id_source("missions_scroll")
+            .show(ui, |ui| {
+                for (id, state) in &missions {
+                    ui.horizontal(|ui| {
+                        let mut completed = *state == MissionState::Completed;
+                        if ui.checkbox(&mut completed, id.to_string()).changed() {
+                            toggle = Some((*id, completed));
+                        }
+                        match state {
+                            MissionState::Started => ui.label("in progress"),
+                            MissionState::NotStarted => ui.weak("not started"),
+                            MissionState::Completed => ui.weak("completed"),
+                        }
+                        .on_hover_text("Unticking resets the mission and its progress");
+                    });
+                }
+            });
+        if complete_all {
+            let open = missions.len() - done;
+            self.write_typed_edit(
+                "completing missions",
+                format!("Completed {} mission(s)", open),
+                move || wle_core::cheats::complete_all_missions_edit(&slot).map(|(_, e)| e),
+            );
+        } else if let Some((id, complete)) = toggle {
+            let done = if complete { "completed" } else { "reset" };
+            self.write_typed_edit(
+                "changing a mission",
+                format!("Mission {} {}", id, done),
+                move || wle_core::cheats::mission_edit(&slot, id, complete),
+            );
+        }
+    }
+
+    // Built-in cheats and the user's presets, each reviewed before writing
+    fn presets_tab(&mut self, ui: &mut egui::Ui) {
+        let Some(slot) = self.state.selected_slot_path().map(|p| p.to_path_buf()) else {
+            ui.label("Select a save slot");
+            return;
+        };
+        let player = self.state.player as u32;
+        if self.state.user_presets.is_none()
+            && let Some(path) = gui_config_file("presets.json")
+        {
+            let loaded = load_user_presets(&path);
+            self.state.user_presets = Some((path, loaded));
+        }
+        ui.label(format!(
+            "Presets apply to player {} of this slot. Each shows its changes before anything is written",
+            player
+        ));
+        let mut presets = wle_core::presets::builtin();
+        if let Some((_, Ok(user))) = &self.state.user_presets {
+            presets.extend(user.iter().cloned());
+        }
+        let mut review: Option<wle_core::presets::Preset> = None;
+        egui::Grid::new("presets_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                for p in presets {
+                    ui.strong(&p.name);
+                    ui.label(&p.description);
+                    if ui.button("Review…").clicked() {
+                        review = Some(p);
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.separator();
+        ui.horizontal(|ui| {
+            match &self.state.user_presets {
+                Some((path, Ok(user))) => {
+                    ui.label(format!(
+                        "{} user preset(s) from {}",
+                        user.len(),
+                        path.display()
+                    ));
+                }
+                Some((path, Err(e))) => {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        format!("{}: {}", path.display(), e),
+                    );
+                }
+                None => {}
+            }
+            if ui.button("Reload").clicked()
+                && let Some((path, loaded)) = &mut self.state.user_presets
+            {
+                *loaded = load_user_presets(path);
+            }
+            if ui.button("Load file…").clicked()
+                && let Some(path) = rfd::FileDialog::new()
+                    .add_filter("JSON", &["json"])
+                    .pick_file()
+            {
+                let loaded = wle_core::presets::load_presets(&path);
+                self.state.user_presets = Some((path, loaded));
+            }
+        });
+        if let Some(preset) = review {
+            let changes = preset.plan(&slot, player).and_then(|edit| edit.preview());
+            self.state.preset_review = Some(PresetReview {
+                preset,
+                slot,
+                player,
+                changes,
+            });
+        }
+    }
+    fn preset_window(&mut self, ctx: &egui::Context) {
+        let Some(review) = &self.state.preset_review else {
+            return;
+        };
+        let preview = |v: &Option<serde_json::Value>| {
+            v.as_ref()
+                .map(wle_core::value_preview)
+                .unwrap_or_else(|| "—".into())
+        };
+        let (mut apply, mut close) = (false, false);
+        let mut open = true;
+        egui::Window::new(format!("Preset: {}", review.preset.name))
+            .open(&mut open)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.label(format!("Player {}", review.player));
+                match &review.changes {
+                    Err(e) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                    Ok(changes) if changes.is_empty() => {
+                        ui.label("Nothing to change");
+                    }
+                    Ok(changes) => {
+                        ui.label(format!("{} change(s)", changes.len()));
+                        egui::ScrollArea::vertical()
+                            .max_height(400.0)
+                            .show(ui, |ui| {
+                                egui::Grid::new("preset_changes")
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        for c in changes {
+                                            ui.monospace(&c.pointer);
+                                            ui.monospace(preview(&c.old));
+                                            ui.monospace(preview(&c.new));
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                    }
+                }
+                ui.horizontal(|ui| {
+                    let ready = review.changes.as_ref().is_ok_and(|c| !c.is_empty());
+                    apply = ui
+                        .add_enabled(ready, egui::Button::new("Apply"))
+                        .on_hover_text("The file is backed up next to itself first")
+                        .clicked();
+                    close = ui.button("Cancel").clicked();
+                });
+            });
+        if apply && let Some(review) = self.state.preset_review.take() {
+            let PresetReview {
+                preset,
+                slot,
+                player,
+                ..
+            } = review;
+            let done = format!("Applied preset {} to player {}", preset.name, player);
+            let what = format!("applying preset {}", preset.name);
+            // Planned again in case saving the open document changed the file
+            self.write_typed_edit(&what, done, move || preset.plan(&slot, player));
+        } else if close || !open {
+            self.state.preset_review = None;
+        }
+    }
+    fn stats_tab(&mut self, ui: &mut egui::Ui) {
+        let Some(slot) = self.state.selected_slot_path().map(|p| p.to_path_buf()) else {
+            ui.label("Select a save slot");
+            return;
+        };
+        if self.state.stats.is_none() {
+            let loaded =
+                wle_core::json::parse_binary(&slot.join("StatsData.sav")).and_then(|doc| {
+                    let view = doc
+                        .as_stats_data()
+                        .ok_or_else(|| "not stats data".to_string())?;
+                    Ok(view
+                        .stats
+                        .iter()
+                        .map(|(n, v)| (n.to_string(), *v))
+                        .collect::<Vec<_>>())
+                });
+            self.state.stat_inputs = loaded
+                .iter()
+                .flatten()
+                .map(|(n, v)| (n.clone(), v.to_string()))
+                .collect();
+            self.state.stats = Some(loaded);
+        }
+        let stats = match self.state.stats.clone().unwrap_or_else(|| Ok(Vec::new())) {
+            Ok(s) => s,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("StatsData.sav: {}", e));
+                return;
+            }
+        };
+        let changed: Vec<(String, String)> = stats
+            .iter()
+            .filter_map(|(n, v)| {
+                let input = self.state.stat_inputs.get(n)?;
+                (input.trim() != v.to_string()).then(|| (n.clone(), input.clone()))
+            })
+            .collect();
+        let mut save = false;
+        ui.horizontal(|ui| {
+            save = ui
+                .add_enabled(
+                    !changed.is_empty(),
+                    egui::Button::new(format!("Save {} stat(s)", changed.len())),
+                )
+                .clicked();
+            if ui.button("Revert").clicked() {
+                self.state.stats = None;
+            }
+        });
+        egui::ScrollArea::vertical()
+            .id_source("stats_scroll")
+            .show(ui, |ui| {
+                egui::Grid::new("stats_grid").striped(true).show(ui, |ui| {
+                    for (name, value) in &stats {
+                        let info = wle_core::catalog::field_info("SaveStatsData", name);
+                        let label = ui.label(wle_core::catalog::label("SaveStatsData", name));
+                        label.on_hover_text(match info {
+                            Some(i) => format!("{}\n{}", name, i.tooltip()),
+                            None => name.clone(),
+                        });
+                        let input = self.state.stat_inputs.entry(name.clone()).or_default();
+                        ui.add(egui::TextEdit::singleline(input).desired_width(120.0));
+                        if input.trim() != value.to_string() {
+                            ui.colored_label(ui.visuals().warn_fg_color, format!("was {}", value));
+                        } else {
+                            ui.label("");
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        if save {
+            let n = changed.len();
+            self.write_typed_edit("saving stats", format!("Saved {} stat(s)", n), move || {
+                wle_core::cheats::stats_edit(&slot, &changed)
+            });
+        }
+    }
+
+    fn slot_confirm_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Confirm")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                if let Some(slot) = self.state.confirm_delete_slot.clone() {
+                    ui.label(format!(
+                        "Delete {}? A zip backup is taken first.",
+                        slot.file_name().unwrap_or_default().to_string_lossy()
+                    ));
+                } else if let Some(n) = self.state.confirm_remove_player {
+                    ui.label(format!(
+                        "Remove player {} from this slot? A zip backup is taken first.",
+                        n
+                    ));
+                } else if let Some((a, b)) = self.state.confirm_swap_players {
+                    ui.label(format!(
+                        "Swap the files of players {} and {}? A zip backup is taken first.",
+                        a, b
+                    ));
+                } else {
+                    ui.label(
+                        "Rename slot folders to SaveSlot_1..N? Moved slots are backed up first.",
+                    );
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Confirm").clicked() {
+                        if let Some(slot) = self.state.confirm_delete_slot.take() {
+                            let dir = self.state.backup_parent(&slot);
+                            match wle_core::saves::delete_slot_to(&slot, &dir) {
+                                Ok(_) => {
+                                    self.state.selected_slot = None;
+                                    self.refresh_slots();
+                                    self.state.status = format!(
+                                        "Deleted {} (backup kept)",
+                                        slot.file_name().unwrap_or_default().to_string_lossy()
+                                    );
+                                }
+                                Err(e) => self.state.status = format!("Delete error: {}", e),
+                            }
+                        } else if let Some(n) = self.state.confirm_remove_player.take() {
+                            if let Some(slot) =
+                                self.state.selected_slot_path().map(Path::to_path_buf)
+                            {
+                                let dir = self.state.backup_parent(&slot);
+                                self.state.status =
+                                    match wle_core::saves::remove_player_to(&slot, n, &dir) {
+                                        Ok(zip) => {
+                                            format!(
+                                                "Removed player {} (backup {})",
+                                                n,
+                                                zip.file_name()
+                                                    .unwrap_or_default()
+                                                    .to_string_lossy()
+                                            )
+                                        }
+                                        Err(e) => format!("Remove error: {}", e),
+                                    };
+                                self.after_player_change();
+                            }
+                        } else if let Some((a, b)) = self.state.confirm_swap_players.take() {
+                            if let Some(slot) =
+                                self.state.selected_slot_path().map(Path::to_path_buf)
+                            {
+                                let dir = self.state.backup_parent(&slot);
+                                self.state.status =
+                                    match wle_core::saves::swap_players_to(&slot, a, b, &dir) {
+                                        Ok(zip) => format!(
+                                            "Swapped players {} and {} (backup {})",
+                                            a,
+                                            b,
+                                            zip.file_name().unwrap_or_default().to_string_lossy()
+                                        ),
+                                        Err(e) => format!("Swap error: {}", e),
+                                    };
+                                self.after_player_change();
+                            }
+                        } else if let Some(root) = self.state.root_dir.clone() {
+                            let dir = self
+                                .state
+                                .backup_dir
+                                .clone()
+                                .unwrap_or_else(|| root.clone());
+                            match wle_core::saves::renumber_slots_to(&root, &dir) {
+                                Ok(moved) => {
+                                    self.state.selected_slot = None;
+                                    self.refresh_slots();
+                                    self.state.status =
+                                        format!("Renumbered {} slot(s)", moved.len());
+                                }
+                                Err(e) => self.state.status = format!("Renumber error: {}", e),
+                            }
+                        }
+                        self.state.confirm_renumber = false;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.state.confirm_delete_slot = None;
+                        self.state.confirm_renumber = false;
+                        self.state.confirm_remove_player = None;
+                        self.state.confirm_swap_players = None;
+                    }
+                });
+            });
+    }
+    fn refresh_primitive_entries(&mut self) {
+        if let Some(v) = self.state.json() {
+            let eff = browse_effective_ptr(v, &self.state.ptr);
+            self.state.primitive_entries =
+                wle_core::list_object_primitives_at(v, &eff).unwrap_or_default();
+        }
+    }
+    // Primitive fields edited in the bottom panel, as replace ops on the document
+    fn primitive_ops(&self) -> Vec<wle_core::PatchOp> {
+        let Some(v) = self.state.json() else {
+            return Vec::new();
+        };
+        let eff = browse_effective_ptr(v, &self.state.ptr);
+        self.state
+            .primitive_entries
+            .iter()
+            .filter_map(|(key, val)| {
+                let path = format!("{}/{}", eff, escape_token(key));
+                let new: serde_json::Value = val.into();
+                (v.pointer(&path) != Some(&new)).then(|| wle_core::PatchOp::replace(path, new))
+            })
+            .collect()
+    }
+    // Apply `ops` to the open document as one undo step
+    fn apply_edit(&mut self, ops: Vec<wle_core::PatchOp>) -> bool {
+        let Some(session) = &mut self.state.session else {
+            return false;
+        };
+        let res = session.apply(&ops);
+        if let Err(e) = &res {
+            self.state.status = format!("Edit error: {}", e);
+        }
+        self.after_edit();
+        res.is_ok()
+    }
+    fn undo(&mut self) {
+        if let Some(s) = &mut self.state.session
+            && s.undo()
+        {
+            self.state.status = "Undone".into();
+            self.after_edit();
+        }
+    }
+    fn redo(&mut self) {
+        if let Some(s) = &mut self.state.session
+            && s.redo()
+        {
+            self.state.status = "Redone".into();
+            self.after_edit();
+        }
+    }
+    // Keep the browser on a node that still exists and re-read its fields
+    fn after_edit(&mut self) {
+        if let Some(v) = self.state.json() {
+            let mut ptr = self.state.ptr.clone();
+            while v.pointer(&ptr).is_none()
+                && let Some(p) = parent_pointer(&ptr)
+            {
+                ptr = p.to_string();
+            }
+            self.state.ptr = ptr;
+        }
+        self.state.raw_loaded_for = None;
+        self.state.compare_changes = None;
+        self.state.duplicates = None;
+        self.refresh_primitive_entries();
+    }
+    fn raw_panel(&mut self, ctx: &egui::Context) {
+        egui::SidePanel::right("raw_json")
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.heading("Raw JSON");
+                let Some(v) = self.state.json() else {
+                    ui.label("No document open");
+                    return;
+                };
+                let target = if self.state.raw_whole {
+                    String::new()
+                } else {
+                    browse_effective_ptr(v, &self.state.ptr)
+                };
+                let current = v.pointer(&target).cloned();
+                if self.state.raw_loaded_for.as_deref() != Some(target.as_str()) {
+                    self.state.raw_text = current
+                        .as_ref()
+                        .and_then(|n| serde_json::to_string_pretty(n).ok())
+                        .unwrap_or_default();
+                    self.state.raw_loaded_for = Some(target.clone());
+                    self.state.raw_error = None;
+                }
+                let edited = serde_json::from_str::<serde_json::Value>(&self.state.raw_text)
+                    .map_or(true, |t| Some(t) != current);
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.state.raw_whole, "Whole document");
+                    if !self.state.raw_whole {
+                        ui.monospace(&target);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(edited, egui::Button::new("Apply"))
+                        .on_hover_text("Parse, check the document still writes back, and apply")
+                        .clicked()
+                    {
+                        self.apply_raw();
+                    }
+                    if ui.button("Reload").clicked() {
+                        self.state.raw_loaded_for = None;
+                    }
+                    if edited {
+                        ui.colored_label(ui.visuals().warn_fg_color, "edited");
+                    }
+                });
+                if let Some(err) = &self.state.raw_error {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+                let cache = &mut self.state.raw_highlight;
+                let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                    let hash = egui::util::hash(text);
+                    let mut job = match cache {
+                        Some((h, job)) if *h == hash => job.clone(),
+                        _ => {
+                            let job = json_highlight(ui, text);
+                            *cache = Some((hash, job.clone()));
+                            job
+                        }
+                    };
+                    job.wrap.max_width = wrap_width;
+                    ui.fonts(|f| f.layout_job(job))
+                };
+                egui::ScrollArea::both()
+                    .id_source("raw_scroll")
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.state.raw_text)
+                                .code_editor()
+                                .desired_width(f32::INFINITY)
+                                .layouter(&mut layouter),
+                        );
+                    });
+            });
+    }
+    // Raw bytes of the document's file with the selected pointer's span highlighted
+    // Status history with times, newest at the bottom
+    fn log_panel(&mut self, ctx: &egui::Context) {
+        use wle_core::validate::Severity;
+        egui::TopBottomPanel::bottom("log")
+            .resizable(true)
+            .default_height(160.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading("Log");
+                    if ui
+                        .add_enabled(!self.state.log.is_empty(), egui::Button::new("Copy"))
+                        .on_hover_text("Copy the whole log, e.g. for a bug report")
+                        .clicked()
+                    {
+                        let text: String = self
+                            .state
+                            .log
+                            .iter()
+                            .map(|e| {
+                                format!(
+                                    "{} {}: {}\n",
+                                    e.time.format("%Y-%m-%d %H:%M:%S"),
+                                    e.severity,
+                                    e.text
+                                )
+                            })
+                            .collect();
+                        ui.ctx().copy_text(text);
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.state.log.clear();
+                    }
+                });
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        if self.state.log.is_empty() {
+                            ui.weak("Nothing yet");
+                        }
+                        for e in &self.state.log {
+                            ui.horizontal(|ui| {
+                                ui.weak(e.time.format("%H:%M:%S").to_string());
+                                match e.severity {
+                                    Severity::Error => {
+                                        ui.colored_label(ui.visuals().error_fg_color, &e.text)
+                                    }
+                                    Severity::Warning => {
+                                        ui.colored_label(ui.visuals().warn_fg_color, &e.text)
+                                    }
+                                    Severity::Info => ui.label(&e.text),
+                                };
+                            });
+                        }
+                    });
+            });
+    }
+    fn hex_panel(&mut self, ctx: &egui::Context) {
+        const ROW: usize = 16;
+        let path = self.state.current_doc_path();
+        // (Re)index the file in the background when another document is shown
+        if let Some((p, rx)) = &self.state.hex_loading
+            && let Ok(view) = rx.try_recv()
+        {
+            if Some(p) == path.as_ref() {
+                self.state.hex = Some(view);
+                self.state.hex_scrolled_for = None;
+            }
+            self.state.hex_loading = None;
+        }
+        if let Some(path) = &path
+            && self.state.hex.as_ref().map(|h| &h.path) != Some(path)
+            && self.state.hex_loading.as_ref().map(|(p, _)| p) != Some(path)
+        {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let (p, ctx) = (path.clone(), ctx.clone());
+            std::thread::spawn(move || {
+                let _ = tx.send(HexView::read(p));
+                ctx.request_repaint();
+            });
+            self.state.hex_loading = Some((path.clone(), rx));
+        }
+        egui::TopBottomPanel::bottom("hex")
+            .resizable(true)
+            .default_height(260.0)
+            .show(ctx, |ui| {
+                ui.heading("Hex");
+                if self.state.hex_loading.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Indexing file…");
+                    });
+                    return;
+                }
+                let Some(hex) = &self.state.hex else {
+                    ui.label("No document open");
+                    return;
+                };
+                let ptr = match self.state.json() {
+                    Some(v) => browse_effective_ptr(v, &self.state.ptr),
+                    None => self.state.ptr.clone(),
+                };
+                let span = hex.span_for(&ptr);
+                ui.horizontal_wrapped(|ui| {
+                    let name = hex.path.file_name().unwrap_or_default().to_string_lossy();
+                    ui.label(format!("{} ({} bytes)", name, hex.data.len()));
+                    match (&hex.spans, &span) {
+                        (Err(e), _) => {
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                format!("No offsets: {}", e),
+                            );
+                        }
+                        (Ok(_), None) => {
+                            ui.label("Selection has no bytes in the file");
+                        }
+                        (Ok(_), Some((at, s))) => {
+                            ui.monospace(format!(
+                                "{} = {:#x}..{:#x} ({} bytes)",
+                                at,
+                                s.start,
+                                s.end,
+                                s.len()
+                            ));
+                        }
+                    }
+                    if self.state.session.as_ref().is_some_and(|s| s.is_dirty()) {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            "shows the file on disk, not unsaved edits",
+                        );
+                    }
+                });
+                ui.separator();
+                let font = egui::TextStyle::Monospace.resolve(ui.style());
+                let row_height = ui.fonts(|f| f.row_height(&font)) + ui.spacing().item_spacing.y;
+                let rows = hex.data.len().div_ceil(ROW);
+                let mut area = egui::ScrollArea::vertical()
+                    .id_source("hex_scroll")
+                    .auto_shrink([false, false]);
+                // Bring the span into view once each time the selection changes
+                if self.state.hex_scrolled_for.as_deref() != Some(ptr.as_str()) {
+                    if let Some((_, s)) = &span {
+                        let row = (s.start / ROW).saturating_sub(2);
+                        area = area.vertical_scroll_offset(row as f32 * row_height);
+                    }
+                    self.state.hex_scrolled_for = Some(ptr.clone());
+                }
+                let text = ui.visuals().text_color();
+                let weak = ui.visuals().weak_text_color();
+                let mark = ui.visuals().selection.bg_fill;
+                area.show_rows(ui, row_height, rows, |ui, range| {
+                    for row in range {
+                        let start = row * ROW;
+                        let bytes = &hex.data[start..(start + ROW).min(hex.data.len())];
+                        let in_span =
+                            |i: usize| span.as_ref().is_some_and(|(_, s)| s.contains(&(start + i)));
+                        let fmt = |color, hit: bool| egui::TextFormat {
+                            font_id: font.clone(),
+                            color,
+                            background: if hit { mark } else { Default::default() },
+                            ..Default::default()
+                        };
+                        let mut job = egui::text::LayoutJob::default();
+                        job.append(&format!("{:08x}  ", start), 0.0, fmt(weak, false));
+                        for i in 0..ROW {
+                            match bytes.get(i) {
+                                Some(b) => {
+                                    job.append(&format!("{:02x}", b), 0.0, fmt(text, in_span(i)))
+                                }
+                                None => job.append("  ", 0.0, fmt(text, false)),
+                            }
+                            // Keep the highlight continuous between bytes of the span
+                            let gap = if i == 7 { "  " } else { " " };
+                            job.append(gap, 0.0, fmt(text, in_span(i) && in_span(i + 1)));
+                        }
+                        job.append(" ", 0.0, fmt(text, false));
+                        for (i, b) in bytes.iter().enumerate() {
+                            let c = if b.is_ascii_graphic() || *b == b' ' {
+                                *b as char
+                            } else {
+                                '.'
+                            };
+                            job.append(&c.to_string(), 0.0, fmt(weak, in_span(i)));
+                        }
+                        ui.label(job);
+                    }
+                });
+            });
+    }
+    // Replace the raw pane's node with its parsed text, as long as the
+    // document would still write back as well as it did before
+    fn apply_raw(&mut self) {
+        let (Some(current), Some(target)) = (self.state.json(), self.state.raw_loaded_for.clone())
+        else {
+            return;
+        };
+        let value: serde_json::Value = match serde_json::from_str(&self.state.raw_text) {
+            Ok(v) => v,
+            Err(e) => {
+                self.state.raw_error = Some(format!("Invalid JSON: {}", e));
+                return;
+            }
+        };
+        let mut candidate = current.clone();
+        if let Err(e) = wle_core::set_raw_by_pointer(&mut candidate, &target, value.clone()) {
+            self.state.raw_error = Some(e);
+            return;
+        }
+        let problems = write_problems(current, &candidate);
+        if !problems.is_empty() {
+            self.state.raw_error = Some(format!(
+                "Not applied; the file could not be written back:\n{}",
+                problems.join("\n")
+            ));
+            return;
+        }
+        self.state.raw_error = None;
+        if self.apply_edit(vec![wle_core::PatchOp::replace(target, value)]) {
+            self.state.status = "Applied raw JSON".into();
+        }
+    }
+    // Read the open document again from disk, staying on the same node
+    fn reload_document(&mut self) {
+        if let Some(path) = self.state.current_doc_path() {
+            self.state.external_changes.retain(|p| p != &path);
+        }
+        self.state.kept_edits = None;
+        self.state.reload_ptr = Some(self.state.ptr.clone());
+        self.state.session = None;
+        self.state.primitive_entries.clear();
+        self.state.load_cancelled = None;
+        self.state.hex = None;
+        self.state.raw_loaded_for = None;
+        self.state.compare_changes = None;
+    }
+    // The game wrote the open document: reload it when nothing is edited,
+    // otherwise ask before the new progress or the edits are lost
+    fn disk_change_banner(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.state.current_doc_path() else {
+            return;
+        };
+        if self.state.session.is_none()
+            || !self.state.doc_changed_on_disk()
+            || self.state.kept_edits.as_ref() == Some(&path)
+        {
+            return;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if !self.is_dirty() {
+            self.state.status = format!("Reloaded {}: it changed on disk", name);
+            self.reload_document();
+            return;
+        }
+        egui::TopBottomPanel::top("doc_changed").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "{} changed on disk (the game saved?) while you have unsaved edits",
+                        name
+                    ),
+                );
+                if ui
+                    .button("Reload from disk")
+                    .on_hover_text("Drop your edits and load the file as it is now")
+                    .clicked()
+                {
+                    self.state.status = format!("Reloaded {}; edits dropped", name);
+                    self.reload_document();
+                }
+                if ui
+                    .button("Keep my edits")
+                    .on_hover_text("Saving will overwrite what the game wrote")
+                    .clicked()
+                {
+                    self.state.kept_edits = Some(path.clone());
+                }
+            });
+        });
+    }
+    // Remove a node, asking first unless in expert mode
+    fn remove_node(&mut self, ptr: String) {
+        match self.state.mode {
+            EditMode::Safe => {}
+            EditMode::Expert => self.remove_node_now(&ptr),
+            EditMode::Normal => self.state.confirm_remove = Some(ptr),
+        }
+    }
+    fn remove_node_now(&mut self, ptr: &str) {
+        let removed = self.apply_edit(vec![wle_core::PatchOp::Remove {
+            path: ptr.to_string(),
+        }]);
+        if removed {
+            self.state.ptr = parent_pointer(ptr).unwrap_or("/root").to_string();
+            self.refresh_primitive_entries();
+        }
+    }
+    // Unsaved changes in the open document: applied steps or edited fields
+    fn is_dirty(&self) -> bool {
+        self.state.session.as_ref().is_some_and(|s| s.is_dirty())
+            || !self.primitive_ops().is_empty()
+    }
+    // Run `action` now, or once the user has saved or dropped the open
+    // document's changes; `what` finishes "Apply them before …"
+    fn confirm_discard(&mut self, what: &str, action: impl FnOnce(&mut AppGui) + 'static) {
+        if self.is_dirty() {
+            self.discard_prompt = Some((what.to_string(), Box::new(action)));
+        } else {
+            action(self);
+        }
+    }
+    fn discard_window(&mut self, ctx: &egui::Context) {
+        let Some((what, _)) = &self.discard_prompt else {
+            return;
+        };
+        let name = self
+            .state
+            .current_doc_path()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "The document".into());
+        // Some(true): apply, Some(false): discard
+        let mut choice: Option<bool> = None;
+        let mut cancel = false;
+        egui::Window::new("Unsaved changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} has unsaved changes. Apply them before {}, discard them, or cancel?",
+                    name, what
+                ));
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Apply")
+                        .on_hover_text("Save them to the .sav first")
+                        .clicked()
+                    {
+                        choice = Some(true);
+                    }
+                    if ui.button("Discard").clicked() {
+                        choice = Some(false);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+        if choice == Some(true) {
+            let ops = self.primitive_ops();
+            if ops.is_empty() || self.apply_edit(ops) {
+                self.save_document(false);
+            }
+            // A failed save leaves the prompt up with the error in the status
+            if self.is_dirty() {
+                return;
+            }
+        }
+        if choice == Some(false) {
+            // Reloaded from disk on the next frame
+            self.state.session = None;
+            self.state.primitive_entries.clear();
+        }
+        if cancel {
+            self.discard_prompt = None;
+        } else if choice.is_some()
+            && let Some((_, action)) = self.discard_prompt.take()
+        {
+            action(self);
+        }
+    }
+    // Copy a node next to itself: after it in an array, as "<key> copy" in an
+    // object. GUIDs in the copy are replaced so it does not clash with the original.
+    // Put the node (the value inside a $ref wrapper) on the clipboard as JSON
+    fn copy_node(&mut self, ctx: &egui::Context, ptr: &str) {
+        let Some(v) = self.state.json() else {
+            return;
+        };
+        let target = browse_effective_ptr(v, ptr);
+        let Some(text) = v
+            .pointer(&target)
+            .and_then(|n| serde_json::to_string_pretty(n).ok())
+        else {
+            return;
+        };
+        ctx.copy_text(text.clone());
+        self.state.clipboard = Some(text);
+        self.state.status = format!("Copied {}", target);
+    }
+    // Paste JSON over or into the node, refusing anything that would stop the
+    // document writing back
+    fn paste_node(&mut self, ptr: &str, text: &str, into: bool) {
+        let value: serde_json::Value = match serde_json::from_str(text) {
+            Ok(v) => v,
+            Err(e) => {
+                self.state.status = format!("Paste error: not JSON: {}", e);
+                return;
+            }
+        };
+        if let Some(target) = self.paste_value(ptr, value, into, "Paste") {
+            let how = if into { "into" } else { "over" };
+            self.state.status = format!("Pasted {} {}", how, target);
+        }
+    }
+    // The checked edit behind pastes and imports; the effective pointer it
+    // changed, or None with the reason (after `what`) in the status line
+    fn paste_value(
+        &mut self,
+        ptr: &str,
+        value: serde_json::Value,
+        into: bool,
+        what: &str,
+    ) -> Option<String> {
+        let current = self.state.json()?;
+        let target = browse_effective_ptr(current, ptr);
+        let ops = match wle_core::paste_ops(current, &target, value, into) {
+            Ok(ops) => ops,
+            Err(e) => {
+                self.state.status = format!("{} error: {}", what, e);
+                return None;
+            }
+        };
+        let mut candidate = current.clone();
+        if let Err(e) = wle_core::apply_patch(&mut candidate, &ops) {
+            self.state.status = format!("{} error: {}", what, e);
+            return None;
+        }
+        let problems = write_problems(current, &candidate);
+        if !problems.is_empty() {
+            self.state.status = format!("{} refused: {}", what, problems.join("; "));
+            return None;
+        }
+        self.apply_edit(ops).then_some(target)
+    }
+    // Write the current node (or the whole document) to a .json file that
+    // wle-cli and "Import JSON…" read back
+    fn export_json(&mut self, whole: bool) {
+        // Fields edited but not applied yet are exported too
+        let ops = self.primitive_ops();
+        if !ops.is_empty() && !self.apply_edit(ops) {
+            return;
+        }
+        let (Some(v), Some(path)) = (self.state.json(), self.state.current_doc_path()) else {
+            return;
+        };
+        let target = if whole {
+            String::new()
+        } else {
+            browse_effective_ptr(v, &self.state.ptr)
+        };
+        let Some(node) = v.pointer(&target) else {
+            return;
+        };
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match plain_pointer(&target).rsplit_once('/') {
+            Some((_, last)) if !whole => {
+                let key = last.replace("~1", "_").replace("~0", "~");
+                format!("{} {}.json", stem, key)
+            }
+            _ => format!("{}.json", stem),
+        };
+        let Some(out) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name(name)
+            .save_file()
+        else {
+            return;
+        };
+        self.state.status = match wle_core::write_json_to_file(&out, node) {
+            Ok(()) => format!("Exported {} to {}", target, out.display()),
+            Err(e) => format!("Export error: {}", e),
+        };
+    }
+    // Replace the current node (or the whole document) with a .json file, as
+    // one undo step and only if the document still writes back
+    fn import_json(&mut self, whole: bool) {
+        if self.state.json().is_none() {
+            return;
+        }
+        let Some(file) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        let value = match wle_core::read_json_file(&file) {
+            Ok(v) => v,
+            Err(e) => {
+                self.state.status = format!("Import error: {}: {}", file.display(), e);
+                return;
+            }
+        };
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+        if !whole {
+            let ptr = self.state.ptr.clone();
+            if let Some(target) = self.paste_value(&ptr, value, false, "Import") {
+                self.state.status = format!("Imported {} over {}", name, target);
+            }
+            return;
+        }
+        let Some(current) = self.state.json() else {
+            return;
+        };
+        let problems = write_problems(current, &value);
+        if !problems.is_empty() {
+            self.state.status = format!("Import refused: {}", problems.join("; "));
+            return;
+        }
+        if self.apply_edit(vec![wle_core::PatchOp::replace(String::new(), value)]) {
+            self.state.status = format!("Imported {} as the whole document", name);
+        }
+    }
+    fn duplicate_node(&mut self, ptr: &str) {
+        let Some(v) = self.state.json() else {
+            return;
+        };
+        let (Some(node), Some((parent, last))) = (v.pointer(ptr), ptr.rsplit_once('/')) else {
+            return;
+        };
+        let mut copy = serde_json::json!({ "node": node });
+        let _ = wle_core::regenerate_guids(&mut copy, "/node");
+        let path = match v.pointer(parent) {
+            Some(serde_json::Value::Array(_)) => match last.parse::<usize>() {
+                Ok(i) => format!("{}/{}", parent, i + 1),
+                Err(_) => return,
+            },
+            Some(serde_json::Value::Object(map)) => {
+                let key = last.replace("~1", "/").replace("~0", "~");
+                let mut name = format!("{} copy", key);
+                let mut n = 2;
+                while map.contains_key(&name) {
+                    name = format!("{} copy {}", key, n);
+                    n += 1;
+                }
+                format!("{}/{}", parent, escape_token(&name))
+            }
+            _ => return,
+        };
+        let value = copy["node"].take();
+        if self.apply_edit(vec![wle_core::PatchOp::Add {
+            path: path.clone(),
+            value,
+        }]) {
+            self.state.status = format!("Duplicated to {}", path);
+        }
+    }
+    // Open the Compare window against `with` (None = the .sav on disk)
+    fn open_compare(&mut self, with: Option<PathBuf>) {
+        let Some(path) = self.state.current_doc_path() else {
+            return;
+        };
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        self.refresh_backups();
+        // Newest first: slot backups holding this file, then its own backups
+        let mut sources: Vec<PathBuf> = self
+            .state
+            .backups
+            .iter()
+            .rev()
+            .filter(|b| b.files.contains(&name))
+            .map(|b| b.path.clone())
+            .collect();
+        sources.extend(wle_core::editor::find_file_backups(&path).into_iter().rev());
+        self.state.compare_sources = sources;
+        self.state.compare_slots = self
+            .state
+            .slots
+            .iter()
+            .filter(|s| path.parent() != Some(s.as_path()))
+            .map(|s| s.join(&name))
+            .filter(|p| p.is_file())
+            .collect();
+        self.state.compare_with = with;
+        self.state.compare_changes = None;
+        self.state.compare_open = true;
+    }
+    // Changes from the compared file to the open document; object ids are
+    // renumbered on every write, so `$ref` differences are left out
+    fn compare_changes(&self) -> Result<Vec<wle_core::Change>, String> {
+        let (Some(current), Some(path)) = (self.state.json(), self.state.current_doc_path()) else {
+            return Err("No document open".into());
+        };
+        let data = match &self.state.compare_with {
+            None => std::fs::read(&path),
+            Some(backup) => {
+                let name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+                wle_core::editor::read_backup_file(backup, name)
+            }
+        }
+        .map_err(|e| e.to_string())?;
+        let base = document_from_bytes(self.state.doc, data, &self.state.parse_cache)?;
+        Ok(wle_core::diff(&base, current, ""))
+    }
+    // Check the document on a worker thread, then ask before writing it
+    fn start_save_check(&mut self, ctx: &egui::Context) {
+        // Fields edited but not applied yet are saved too
+        let ops = self.primitive_ops();
+        if !ops.is_empty() && !self.apply_edit(ops) {
+            return;
+        }
+        let (Some(path), Some(edited)) = (self.state.current_doc_path(), self.state.json()) else {
+            return;
+        };
+        let (doc, edited, ctx) = (self.state.doc, edited.clone(), ctx.clone());
+        let cache = self.state.parse_cache.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(save_issues(doc, &path, &edited, &cache));
+            ctx.request_repaint();
+        });
+        self.state.save_check = Some(SaveCheck::Running(rx));
+        self.state.confirm_save = true;
+    }
+    fn save_window(&mut self, ctx: &egui::Context) {
+        use wle_core::validate::Severity;
+        if let Some(SaveCheck::Running(rx)) = &self.state.save_check
+            && let Ok(issues) = rx.try_recv()
+        {
+            self.state.save_check = Some(SaveCheck::Done(issues));
+        }
+        let mut open = true;
+        let (mut save, mut cancel, mut review) = (false, false, false);
+        let mut show: Option<String> = None;
+        let changed_on_disk = self.state.doc_changed_on_disk();
+        // Expert mode only stops for errors or a file the game rewrote
+        if self.state.mode == EditMode::Expert
+            && !changed_on_disk
+            && let Some(SaveCheck::Done(issues)) = &self.state.save_check
+            && issues.iter().all(|i| i.severity != Severity::Error)
+        {
+            self.state.confirm_save = false;
+            self.state.save_check = None;
+            self.save_document(true);
+            return;
+        }
+        egui::Window::new("Save to .sav")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                if changed_on_disk {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        "This file changed on disk since it was loaded; saving overwrites those changes.",
+                    );
+                }
+                let mut errors = 0;
+                match &self.state.save_check {
+                    Some(SaveCheck::Done(issues)) => {
+                        errors = issues
+                            .iter()
+                            .filter(|i| i.severity == Severity::Error)
+                            .count();
+                        if issues.is_empty() {
+                            ui.label("No problems found.");
+                        } else {
+                            ui.label(format!(
+                                "{} error(s), {} warning(s) or note(s) compared with the file on disk:",
+                                errors,
+                                issues.len() - errors
+                            ));
+                            egui::ScrollArea::vertical()
+                                .max_height(300.0)
+                                .show(ui, |ui| {
+                                    egui::Grid::new("save_issues").striped(true).show(ui, |ui| {
+                                        for i in issues {
+                                            let color = match i.severity {
+                                                Severity::Error => ui.visuals().error_fg_color,
+                                                Severity::Warning => ui.visuals().warn_fg_color,
+                                                Severity::Info => ui.visuals().text_color(),
+                                            };
+                                            ui.colored_label(color, i.severity.to_string());
+                                            if i.pointer.is_empty() {
+                                                ui.label("(file)");
+                                            } else if ui.link(&i.pointer).clicked() {
+                                                show = Some(i.pointer.clone());
+                                            }
+                                            ui.label(&i.message);
+                                            ui.end_row();
+                                        }
+                                    });
+                                });
+                        }
+                    }
+                    _ => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Checking the document…");
+                        });
+                    }
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let done = matches!(self.state.save_check, Some(SaveCheck::Done(_)));
+                    let text = if errors > 0 { "Save anyway" } else { "Save" };
+                    save = ui.add_enabled(done, egui::Button::new(text)).clicked();
+                    review = ui.button("Review changes…").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        if review {
+            self.open_compare(None);
+        }
+        if let Some(ptr) = show {
+            self.show_pointer(ptr);
+        }
+        if save {
+            self.save_document(true);
+        }
+        if save || cancel || !open {
+            self.state.confirm_save = false;
+            self.state.save_check = None;
+        }
+    }
+    fn compare_window(&mut self, ctx: &egui::Context) {
+        if self.state.compare_changes.is_none() {
+            self.state.compare_changes = Some(self.compare_changes());
+        }
+        let name = |p: &Path| {
+            p.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        };
+        // Backups by file name; another slot's file as SaveSlot_N/<file>
+        let with_is_slot = self
+            .state
+            .compare_with
+            .as_ref()
+            .is_some_and(|p| self.state.compare_slots.contains(p));
+        let label = |p: &Option<PathBuf>| match p {
+            None => "File on disk".to_string(),
+            Some(p) if self.state.compare_slots.contains(p) => {
+                format!("{}/{}", name(p.parent().unwrap_or(p)), name(p))
+            }
+            Some(p) => name(p),
+        };
+        let preview = |v: &Option<serde_json::Value>| {
+            v.as_ref()
+                .map(wle_core::value_preview)
+                .unwrap_or_else(|| "—".into())
+        };
+        let open_label = match self.state.current_doc_path() {
+            Some(p) if with_is_slot => {
+                format!("{}/{} (open)", name(p.parent().unwrap_or(&p)), name(&p))
+            }
+            _ => "Open document".to_string(),
+        };
+        let pending = self.primitive_ops().len();
+        let mut open = self.state.compare_open;
+        let mut with = self.state.compare_with.clone();
+        let mut show: Option<String> = None;
+        egui::Window::new("Compare")
+            .open(&mut open)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Open document against");
+                    egui::ComboBox::from_id_source("compare_with")
+                        .selected_text(label(&with))
+                        .width(280.0)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut with, None, label(&None));
+                            for src in &self.state.compare_sources {
+                                let src = Some(src.clone());
+                                let text = label(&src);
+                                ui.selectable_value(&mut with, src, text);
+                            }
+                            if !self.state.compare_slots.is_empty() {
+                                ui.separator();
+                                ui.weak("Other slots");
+                            }
+                            for src in &self.state.compare_slots {
+                                let src = Some(src.clone());
+                                let text = label(&src);
+                                ui.selectable_value(&mut with, src, text);
+                            }
+                        });
+                    if ui.button("Refresh").clicked() {
+                        self.state.compare_changes = None;
+                    }
+                });
+                if pending > 0 {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!(
+                            "{} edited field(s) not applied yet are not listed; Save applies them too",
+                            pending
+                        ),
+                    );
+                }
+                ui.separator();
+                match &self.state.compare_changes {
+                    Some(Err(e)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                    Some(Ok(changes)) if changes.is_empty() => {
+                        ui.label("No differences");
+                    }
+                    Some(Ok(changes)) => {
+                        ui.label(format!("{} change(s)", changes.len()));
+                        egui::ScrollArea::vertical()
+                            .id_source("compare_scroll")
+                            .max_height(400.0)
+                            .show(ui, |ui| {
+                                egui::Grid::new("compare_grid")
+                                    .striped(true)
+                                    .show(ui, |ui| {
+                                        ui.strong("");
+                                        ui.strong("Pointer");
+                                        ui.strong(label(&with));
+                                        ui.strong(&open_label);
+                                        ui.end_row();
+                                        for c in changes {
+                                            let (mark, color) = match (&c.old, &c.new) {
+                                                (None, _) => ("+", egui::Color32::LIGHT_GREEN),
+                                                (_, None) => ("-", ui.visuals().error_fg_color),
+                                                _ => ("~", ui.visuals().warn_fg_color),
+                                            };
+                                            ui.colored_label(color, mark);
+                                            if ui
+                                                .link(egui::RichText::new(&c.pointer).monospace())
+                                                .on_hover_text("Show in the editor")
+                                                .clicked()
+                                            {
+                                                show = Some(c.pointer.clone());
+                                            }
+                                            ui.monospace(preview(&c.old));
+                                            ui.monospace(preview(&c.new));
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                    }
+                    None => {}
+                }
+            });
+        if with != self.state.compare_with {
+            self.state.compare_with = with;
+            self.state.compare_changes = None;
+        }
+        self.state.compare_open = open;
+        if let Some(ptr) = show {
+            self.show_pointer(ptr);
+        }
+    }
+    fn duplicates_window(&mut self, ctx: &egui::Context) {
+        use wle_core::validate::{find_duplicates, remove_duplicates_ops};
+        if self.state.duplicates.is_none() {
+            self.state.duplicates = self.state.json().map(find_duplicates);
+        }
+        let groups = self.state.duplicates.clone().unwrap_or_default();
+        let can_remove = self.state.mode != EditMode::Safe;
+        let mut open = self.state.duplicates_open;
+        let mut show: Option<String> = None;
+        let mut remove: Option<Vec<wle_core::validate::Duplicates>> = None;
+        egui::Window::new("Duplicates")
+            .open(&mut open)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                if groups.is_empty() {
+                    ui.label("No duplicated objects");
+                    return;
+                }
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} group(s)", groups.len()));
+                    if ui
+                        .add_enabled(can_remove, egui::Button::new("Remove all copies"))
+                        .on_hover_text("Keep the first object of each group; Undo brings them back")
+                        .clicked()
+                    {
+                        remove = Some(groups.clone());
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .id_source("duplicates_scroll")
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for g in &groups {
+                            ui.horizontal(|ui| {
+                                ui.strong(g.to_string());
+                                if ui
+                                    .add_enabled(
+                                        can_remove,
+                                        egui::Button::new("Remove copies").small(),
+                                    )
+                                    .clicked()
+                                {
+                                    remove = Some(vec![g.clone()]);
+                                }
+                            });
+                            for (i, p) in g.pointers.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    ui.weak(if i == 0 { "keep" } else { "copy" });
+                                    if ui
+                                        .link(egui::RichText::new(p).monospace())
+                                        .on_hover_text("Show in the editor")
+                                        .clicked()
+                                    {
+                                        show = Some(p.clone());
+                                    }
+                                });
+                            }
+                        }
+                    });
+            });
+        self.state.duplicates_open = open;
+        if let Some(groups) = remove
+            && let Some(v) = self.state.json()
+        {
+            let ops = remove_duplicates_ops(v, &groups);
+            let n = ops
+                .iter()
+                .filter(|op| matches!(op, wle_core::PatchOp::Remove { .. }))
+                .count();
+            if self.apply_edit(ops) {
+                self.state.status = format!("Removed {} copies; Save to write them", n);
+            }
+        }
+        if let Some(ptr) = show {
+            self.show_pointer(ptr);
+        }
+    }
+    // Browse to the nearest object or array at or above `ptr` that exists in
+    // the open document
+    fn show_pointer(&mut self, mut ptr: String) {
+        let Some(v) = self.state.json() else {
+            return;
+        };
+        while v
+            .pointer(&ptr)
+            .is_none_or(|n| !n.is_object() && !n.is_array())
+            && let Some(p) = parent_pointer(&ptr)
+        {
+            ptr = p.to_string();
+        }
+        self.state.ptr = ptr;
+        self.state.tab = Tab::Browser;
+        self.refresh_primitive_entries();
+    }
+    // Children of the current node as (pointer, object or array?)
+    fn browse_children(&self) -> Vec<(String, bool)> {
+        let Some(v) = self.state.json() else {
+            return Vec::new();
+        };
+        let eff = browse_effective_ptr(v, &self.state.ptr);
+        wle_core::list_children(v, &eff)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| {
+                let container = matches!(
+                    c.kind,
+                    wle_core::JsonKind::Object | wle_core::JsonKind::Array
+                );
+                (child_pointer(&eff, &c.key_or_index), container)
+            })
+            .collect()
+    }
+    // Pointer of the child under the keyboard cursor, if it is on this node
+    fn browse_cursor(&self, v: &serde_json::Value, eff: &str) -> Option<String> {
+        let (p, i) = self.state.child_cursor.as_ref()?;
+        if *p != self.state.ptr {
+            return None;
+        }
+        let c = wle_core::list_children(v, eff).ok()?.into_iter().nth(*i)?;
+        Some(child_pointer(eff, &c.key_or_index))
+    }
+    // Arrow keys move the cursor over the children, Enter opens the one under
+    // it and Backspace goes up to the parent with the cursor on where we were
+    fn browse_keys(&mut self, ctx: &egui::Context) {
+        use egui::{Key, Modifiers};
+        let (down, up, enter, back) = ctx.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::Enter),
+                i.consume_key(Modifiers::NONE, Key::Backspace),
+            )
+        });
+        if !(down || up || enter || back) {
+            return;
+        }
+        let children = self.browse_children();
+        let at = match &self.state.child_cursor {
+            Some((p, i)) if *p == self.state.ptr => Some(*i),
+            _ => None,
+        };
+        if back {
+            let here = self.state.ptr.trim_end_matches("/$value").to_string();
+            let Some(parent) = parent_pointer(&here).filter(|_| here != "/root") else {
+                return;
+            };
+            self.state.ptr = parent.trim_end_matches("/$value").to_string();
+            let here = plain_pointer(&here);
+            let index = self
+                .browse_children()
+                .iter()
+                .position(|(c, _)| plain_pointer(c) == here);
+            self.state.child_cursor = index.map(|i| (self.state.ptr.clone(), i));
+            self.state.cursor_moved = true;
+            self.refresh_primitive_entries();
+        } else if enter {
+            // Leaves are edited in the field list of the node already open
+            if let Some((child, true)) = at.and_then(|i| children.get(i)).cloned() {
+                self.state.ptr = child;
+                self.state.child_cursor = None;
+                self.refresh_primitive_entries();
+            }
+        } else if !children.is_empty() {
+            let last = children.len() - 1;
+            let i = match at {
+                None => 0,
+                Some(i) if down => (i + 1).min(last),
+                Some(i) => i.saturating_sub(1),
+            };
+            self.state.child_cursor = Some((self.state.ptr.clone(), i));
+            self.state.cursor_moved = true;
+        }
+    }
+    // Pinned pointers of the open document kind, one click away
+    fn favorites_panel(&mut self, ui: &mut egui::Ui) {
+        let kind = self.state.doc.key();
+        let ptr = self.state.ptr.clone();
+        let mut pin = false;
+        ui.horizontal(|ui| {
+            ui.label("Favorites");
+            let can_pin =
+                self.state.session.is_some() && !self.state.favorites.contains(kind, &ptr);
+            pin = ui
+                .add_enabled(can_pin, egui::Button::new("☆ Pin"))
+                .on_hover_text(format!("Pin {}", ptr))
+                .clicked();
+        });
+        if pin {
+            self.pin_pointer(&ptr);
+        }
+        let mut open = None;
+        let mut remove = None;
+        let mut renamed = None;
+        let mut renaming = self.state.favorite_rename.take();
+        let doc = self.state.json();
+        for b in self.state.favorites.get(kind) {
+            ui.horizontal(|ui| {
+                if let Some((p, text)) = &mut renaming
+                    && *p == b.pointer
+                {
+                    let resp = ui.text_edit_singleline(text);
+                    resp.request_focus();
+                    if resp.lost_focus() {
+                        renamed = Some((p.clone(), text.clone()));
+                    }
+                    return;
+                }
+                let missing = doc.is_some_and(|v| v.pointer(&b.pointer).is_none());
+                let mut label = egui::RichText::new(&b.label);
+                let mut hover = b.pointer.clone();
+                if missing {
+                    label = label.weak();
+                    hover.push_str("\n(not in this document)");
+                }
+                let resp = ui.link(label).on_hover_text(hover);
+                if resp.clicked() {
+                    open = Some(b.pointer.clone());
+                }
+                resp.context_menu(|ui| {
+                    if ui.button("Rename").clicked() {
+                        renaming = Some((b.pointer.clone(), b.label.clone()));
+                        ui.close_menu();
+                    }
+                    if ui.button("Remove").clicked() {
+                        remove = Some(b.pointer.clone());
+                        ui.close_menu();
+                    }
+                });
+            });
+        }
+        self.state.favorite_rename = renaming;
+        if let Some((p, label)) = renamed {
+            self.state.favorite_rename = None;
+            self.state.favorites.rename(kind, &p, &label);
+            self.save_favorites();
+        }
+        if let Some(p) = remove {
+            self.state.favorites.remove(kind, &p);
+            self.save_favorites();
+        }
+        if let Some(p) = open {
+            self.show_pointer(p);
+        }
+    }
+    fn pin_pointer(&mut self, ptr: &str) {
+        if self.state.favorites.add(self.state.doc.key(), ptr, "") {
+            self.save_favorites();
+            self.state.status = format!("Pinned {}", ptr);
+        }
+    }
+    fn save_favorites(&mut self) {
+        if let Some(path) = gui_config_file("favorites.json")
+            && let Err(e) = self.state.favorites.save(&path)
+        {
+            self.state.status = format!("Could not save favorites: {}", e);
+        }
+    }
+    // Progress of a background save with a Cancel button; takes its result
+    fn saving_bar(&mut self, ui: &mut egui::Ui) {
+        let Some(s) = &self.state.saving else {
+            return;
+        };
+        if let Ok((res, written)) = s.rx.try_recv() {
+            let s = self.state.saving.take().unwrap();
+            if s.progress.is_cancelled() && res.is_err() {
+                self.state.status = "Saving cancelled".into();
+            } else {
+                self.finish_save(&s.path, res, Some(&written));
+            }
+            return;
+        }
+        let name = s.path.file_name().unwrap_or_default().to_string_lossy();
+        let frac = s.progress.fraction();
+        let text = if frac >= 1.0 {
+            format!("Writing {}…", name)
+        } else {
+            format!(
+                "Saving {}… {:.0}% ({} objects)",
+                name,
+                frac * 100.0,
+                s.progress.objects()
+            )
+        };
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.add(egui::ProgressBar::new(frac).text(text).desired_width(300.0));
+            ui.label(format!("{:.1} s", s.started.elapsed().as_secs_f32()));
+            // Past encoding the file is being replaced; let that finish
+            if frac < 1.0 && !s.progress.is_cancelled() && ui.button("Cancel").clicked() {
+                s.progress.cancel();
+            }
+        });
+        ui.ctx()
+            .request_repaint_after(std::time::Duration::from_millis(100));
+    }
+    // Back up per the settings and write the open document to its .sav, on a
+    // worker thread when `background` (the result shows in `saving_bar`)
+    fn save_document(&mut self, background: bool) {
+        if self.state.saving.is_some() {
+            self.state.status = "Already saving".into();
+            return;
+        }
+        let (Some(slot), Some(path)) = (
+            self.state.selected_slot_path().map(|p| p.to_path_buf()),
+            self.state.current_doc_path(),
+        ) else {
+            return;
+        };
+        if !path.exists() {
+            return;
+        }
+        if self.state.backs_up_on_save() && self.state.doc == DocKind::SaveInfo {
+            // Lives outside the slot; back up the file alone
+            let _ = wle_core::editor::backup_file(&path, false);
+            self.state.last_backup_time = Some(Local::now());
+        } else if self.state.backs_up_on_save() {
+            let parent = self.state.backup_parent(&slot);
+            let _ =
+                wle_core::editor::zip_backup_slot_pruned_to(&slot, &parent, self.state.retention);
+            self.state.last_backup_time = Some(Local::now());
+        }
+        let Some(session) = &self.state.session else {
+            return;
+        };
+        if background && self.state.doc != DocKind::SaveInfo {
+            let progress = std::sync::Arc::new(wle_core::WriteProgress::default());
+            let (tx, rx) = std::sync::mpsc::channel();
+            let (p, prog, v) = (path.clone(), progress.clone(), session.value().clone());
+            std::thread::spawn(move || {
+                let res = wle_core::write_binfmt_from_json_with_progress(&v, prog)
+                    .map_err(wle_core::WriteError::Encode)
+                    .and_then(|data| wle_core::write_file_atomic(&p, &data));
+                let _ = tx.send((res, v));
+            });
+            self.state.saving = Some(Saving {
+                path,
+                progress,
+                rx,
+                started: std::time::Instant::now(),
+            });
+            return;
+        }
+        let res = write_document(&path, session.value());
+        self.finish_save(&path, res, None);
+    }
+    // Report a finished write of `path`; the open document counts as saved
+    // unless it changed from `written` meanwhile
+    fn finish_save(
+        &mut self,
+        path: &Path,
+        res: Result<(), wle_core::WriteError>,
+        written: Option<&serde_json::Value>,
+    ) {
+        match res {
+            Ok(_) => {
+                if let Some(session) = &mut self.state.session
+                    && session.path() == path
+                {
+                    if written.is_none_or(|v| session.value() == v) {
+                        session.mark_saved();
+                    }
+                    self.state.compare_changes = None;
+                    self.state.hex = None;
+                }
+                self.state.status = "Saved".into();
+                self.state.resync_watcher();
+                self.state.external_changes.retain(|p| p != path);
+            }
+            Err(wle_core::WriteError::Locked(_)) => {
+                self.state.status = "Close the game first: the save file is in use".into()
+            }
+            Err(e) => self.state.status = format!("Save error: {}", e),
+        }
+    }
+    fn history_window(&mut self, ctx: &egui::Context) {
+        let preview = |v: &Option<serde_json::Value>| {
+            v.as_ref()
+                .map(wle_core::value_preview)
+                .unwrap_or_else(|| "—".into())
+        };
+        let mut open = self.state.history_open;
+        egui::Window::new("Edit history")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let Some(session) = &self.state.session else {
+                    ui.label("No document open");
+                    return;
+                };
+                if !session.can_undo() && !session.can_redo() {
+                    ui.label("No edits yet");
+                }
+                egui::ScrollArea::vertical()
+                    .id_source("history_scroll")
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for (i, step) in session.history().iter().enumerate() {
+                            ui.strong(format!("{}.", i + 1));
+                            for c in step.changes() {
+                                ui.monospace(format!(
+                                    "{}: {} → {}",
+                                    c.pointer,
+                                    preview(&c.old),
+                                    preview(&c.new)
+                                ));
+                            }
+                        }
+                        // Next to redo first
+                        for step in session.redo_history().iter().rev() {
+                            ui.weak("undone:");
+                            for c in step.changes() {
+                                ui.weak(format!(
+                                    "{}: {} → {}",
+                                    c.pointer,
+                                    preview(&c.old),
+                                    preview(&c.new)
+                                ));
                             }
                         }
-                    } else {
-                        for y in 0..h {
-                            for x in 0..w {
-                                let sy = h - 1 - y;
-                                let idx = (sy * w + x) * 4;
-                                let r = bytes[idx];
-                                let g = bytes[idx + 1];
-                                let b = bytes[idx + 2];
-                                let a = bytes[idx + 3];
-                                img.pixels[y * w + x] =
-                                    egui::Color32::from_rgba_unmultiplied(r, g, b, a);
+                    });
+            });
+        self.state.history_open = open;
+    }
+    fn script_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.state.script_open;
+        let mut run = false;
+        egui::Window::new("Script")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.weak("get(ptr), set(ptr, value), remove(ptr), exists(ptr), list(ptr), search(text[, scope]), print(x)");
+                ui.horizontal(|ui| {
+                    if ui.button("Open…").clicked()
+                        && let Some(p) = rfd::FileDialog::new()
+                            .add_filter("Rhai script", &["rhai"])
+                            .pick_file()
+                    {
+                        match std::fs::read_to_string(&p) {
+                            Ok(text) => self.state.script_text = text,
+                            Err(e) => {
+                                self.state.status = format!("Script error: {}: {}", p.display(), e)
                             }
                         }
                     }
-                    let tex = ctx.load_texture("slot_image", img, egui::TextureOptions::LINEAR);
-                    self.state.image = Some(tex);
+                    if ui
+                        .add_enabled(self.state.session.is_some(), egui::Button::new("Run"))
+                        .on_hover_text("All of the script's edits are one undo step")
+                        .clicked()
+                    {
+                        run = true;
+                    }
+                });
+                egui::ScrollArea::vertical()
+                    .id_source("script_source")
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.state.script_text)
+                                .code_editor()
+                                .desired_rows(12)
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+                if !self.state.script_output.is_empty() {
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .id_source("script_output")
+                        .max_height(150.0)
+                        .show(ui, |ui| {
+                            for line in &self.state.script_output {
+                                ui.monospace(line);
+                            }
+                        });
                 }
-            }
+            });
+        self.state.script_open = open;
+        if run {
+            self.run_script();
         }
-        // Load selected document JSON once
-        if self.state.json.is_none() {
-            let path = match self.state.doc {
-                DocKind::Player => slot.join(format!("PlayerData_{}.sav", self.state.player)),
-                DocKind::Mission => slot.join("MissionData.sav"),
-                DocKind::Stats => slot.join("StatsData.sav"),
-                DocKind::World => slot.join("WorldData.sav"),
-            };
-            if path.exists() {
-                let opts = wle_core::json::JsonOpts::default();
-                match wle_core::parse_file_to_json_value(&path, opts) {
-                    Ok(v) => {
-                        self.state.json = Some(v);
-                        self.state.ptr = "/root".into();
-                        self.refresh_primitive_entries();
+    }
+    fn debug_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.state.debug_open;
+        egui::Window::new("Debug console")
+            .open(&mut open)
+            .default_width(640.0)
+            .default_height(320.0)
+            .show(ctx, |ui| {
+                let mut log = DEBUG_LOG.lock().unwrap();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!log.is_empty(), egui::Button::new("Copy"))
+                        .on_hover_text("Copy the whole console, e.g. for a bug report")
+                        .clicked()
+                    {
+                        let text: String = log.iter().map(|l| format!("{}\n", l)).collect();
+                        ui.ctx().copy_text(text);
                     }
-                    Err(e) => {
-                        self.state.status = format!("Load error: {}", e);
+                    if ui.button("Clear").clicked() {
+                        log.clear();
                     }
-                }
-            }
-        }
+                });
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        if log.is_empty() {
+                            ui.weak("Nothing yet");
+                        }
+                        for line in log.iter() {
+                            ui.monospace(line);
+                        }
+                    });
+            });
+        self.state.debug_open = open;
     }
-    fn refresh_primitive_entries(&mut self) {
-        if let Some(v) = &self.state.json {
-            let eff = browse_effective_ptr(v, &self.state.ptr);
-            match wle_core::list_object_primitives_at(v, &eff) {
-                Ok(kvs) => self.state.primitive_entries = kvs,
-                Err(_) => self.state.primitive_entries.clear(),
+    // Run the console script on the open document; its edits are one undo step
+    fn run_script(&mut self) {
+        let Some(v) = self.state.json() else {
+            return;
+        };
+        let run = match wle_core::script::run_script(&self.state.script_text, v) {
+            Ok(run) => run,
+            Err(e) => {
+                self.state.status = format!("Script error: {}", e);
+                return;
+            }
+        };
+        self.state.script_output = run.output;
+        let removes = run
+            .ops
+            .iter()
+            .any(|op| matches!(op, wle_core::PatchOp::Remove { .. }));
+        if self.state.mode == EditMode::Safe && removes {
+            self.state.status =
+                "Script error: it removes nodes, which safe mode does not allow".into();
+        } else if run.ops.is_empty() {
+            self.state.status = "Script made no edits".into();
+        } else {
+            let n = run.ops.len();
+            if self.apply_edit(run.ops) {
+                self.state.status = format!("Script applied {} edit(s)", n);
             }
         }
     }
 }
 
 impl App for AppGui {
-    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        // Messages set last frame; the idle repaint below picks up the rest
+        self.state.log_status();
+        // eframe follows the OS theme; a chosen theme wins over it
+        let visuals = self.state.theme.visuals(frame.info().system_theme);
+        if ctx.style().visuals.dark_mode != visuals.dark_mode {
+            ctx.set_visuals(visuals);
+        }
+        self.state.poll_watcher();
+        let dropped = ctx.input(|i| i.raw.dropped_files.iter().find_map(|f| f.path.clone()));
+        if let Some(path) = dropped {
+            self.confirm_discard("opening the dropped file", move |app| {
+                app.open_dropped(&path)
+            });
+        }
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let screen = ctx.screen_rect();
+            let painter =
+                ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, "drop".into()));
+            painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop a GameSaves folder, a SaveSlot folder or a .sav to open it",
+                egui::TextStyle::Heading.resolve(&ctx.style()),
+                egui::Color32::WHITE,
+            );
+        }
+        if ctx.input(|i| i.viewport().close_requested())
+            && let Err(e) = self.settings(ctx).save()
+        {
+            eprintln!("could not save settings: {}", e);
+        }
+        if ctx.input(|i| i.viewport().close_requested()) && !self.state.closing && self.is_dirty() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            let ctx = ctx.clone();
+            self.confirm_discard("closing", move |app| {
+                app.state.closing = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            });
+        }
+        let title = match self.state.current_doc_path() {
+            Some(path) if self.state.session.is_some() => format!(
+                "{}{} — Wobbly Life Editor",
+                if self.is_dirty() { "● " } else { "" },
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            _ => "Wobbly Life Editor".to_string(),
+        };
+        if title != self.state.title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.state.title = title;
+        }
+        if self.discard_prompt.is_some() {
+            self.discard_window(ctx);
+        }
+        if ctx.input_mut(|i| {
+            i.consume_shortcut(&egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::F,
+            ))
+        }) {
+            self.state.tab = Tab::Browser;
+            ctx.memory_mut(|m| m.request_focus(egui::Id::new(SEARCH_FIELD)));
+        }
+        // Ctrl+Z / Ctrl+Y (or Ctrl+Shift+Z) for document edits; a focused text
+        // field keeps them for its own undo
+        if ctx.memory(|m| m.focused().is_none()) {
+            use egui::{Key, KeyboardShortcut, Modifiers};
+            let redo = ctx.input_mut(|i| {
+                i.consume_shortcut(&KeyboardShortcut::new(
+                    Modifiers::COMMAND | Modifiers::SHIFT,
+                    Key::Z,
+                )) || i.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Y))
+            });
+            let undo = ctx.input_mut(|i| {
+                i.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Z))
+            });
+            if redo {
+                self.redo();
+            } else if undo {
+                self.undo();
+            }
+            // Ctrl+C copies the node's JSON (with Shift its pointer); Ctrl+V
+            // pastes over it (with Shift into it)
+            if self.state.tab == Tab::Browser && self.state.session.is_some() {
+                let (copy, paste, shift) = ctx.input(|i| {
+                    let paste = i.events.iter().find_map(|e| match e {
+                        egui::Event::Paste(t) => Some(t.clone()),
+                        _ => None,
+                    });
+                    let copy = i.events.iter().any(|e| matches!(e, egui::Event::Copy));
+                    (copy, paste, i.modifiers.shift)
+                });
+                let ptr = self.state.ptr.clone();
+                if copy && shift {
+                    ctx.copy_text(ptr.clone());
+                    self.state.status = format!("Copied pointer {}", ptr);
+                } else if copy {
+                    self.copy_node(ctx, &ptr);
+                }
+                if let Some(text) = paste {
+                    self.paste_node(&ptr, &text, shift);
+                }
+                if self.state.child_filter.is_empty() {
+                    self.browse_keys(ctx);
+                }
+            }
+        }
+        // Watcher events arrive without input; poll while idle too
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        // The open document's own changes get the banner above
+        self.disk_change_banner(ctx);
+        let open_doc = self
+            .state
+            .session
+            .as_ref()
+            .and_then(|_| self.state.current_doc_path());
+        let others: Vec<PathBuf> = self
+            .state
+            .external_changes
+            .iter()
+            .filter(|p| open_doc.as_ref() != Some(*p))
+            .cloned()
+            .collect();
+        if !others.is_empty() {
+            egui::TopBottomPanel::top("external_changes").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let names: Vec<String> = others
+                        .iter()
+                        .map(|p| {
+                            p.file_name()
+                                .unwrap_or_default()
+                                .to_string_lossy()
+                                .into_owned()
+                        })
+                        .collect();
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("Changed on disk (game running?): {}", names.join(", ")),
+                    );
+                    if ui.button("Reload").clicked() {
+                        self.confirm_discard("reloading", |app| {
+                            app.state.clear_slot_cache();
+                            app.refresh_slots();
+                        });
+                    }
+                    if ui.button("Ignore").clicked() {
+                        self.state
+                            .external_changes
+                            .retain(|p| open_doc.as_ref() == Some(p));
+                    }
+                });
+            });
+        }
         egui::TopBottomPanel::top("top").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 if ui.button("Open GameSave Folder").clicked() {
-                    self.pick_root_dir();
+                    self.confirm_discard("opening another folder", |app| app.pick_root_dir());
+                }
+                let mut reopen: Option<PathBuf> = None;
+                ui.menu_button("Recent", |ui| {
+                    if self.state.recent.is_empty() {
+                        ui.label("No folders opened yet");
+                    }
+                    for p in &self.state.recent {
+                        if ui
+                            .add_enabled(p.is_dir(), egui::Button::new(p.display().to_string()))
+                            .clicked()
+                        {
+                            reopen = Some(p.clone());
+                            ui.close_menu();
+                        }
+                    }
+                    if !self.state.recent.is_empty() {
+                        ui.separator();
+                        if ui.button("Clear").clicked() {
+                            self.state.recent.clear();
+                            let _ = save_recent(&self.state.recent);
+                            ui.close_menu();
+                        }
+                    }
+                });
+                if let Some(root) = reopen {
+                    self.confirm_discard("opening another folder", move |app| app.open_root(root));
+                }
+                ui.menu_button("View", |ui| {
+                    ui.label("Theme");
+                    for theme in Theme::ALL {
+                        ui.radio_value(&mut self.state.theme, theme, theme.label());
+                    }
+                    ui.separator();
+                    // Ctrl+Plus / Ctrl+Minus change the same zoom
+                    let mut scale = ctx.zoom_factor();
+                    if ui
+                        .add(egui::Slider::new(&mut scale, 0.5..=3.0).text("UI scale"))
+                        .changed()
+                    {
+                        ctx.set_zoom_factor(scale);
+                    }
+                    if ui.button("Reset scale").clicked() {
+                        ctx.set_zoom_factor(1.0);
+                    }
+                });
+                ui.menu_button(format!("Mode: {}", self.state.mode.label()), |ui| {
+                    for mode in EditMode::ALL {
+                        ui.radio_value(&mut self.state.mode, mode, mode.label())
+                            .on_hover_text(mode.hint());
+                    }
+                });
+                // Shows the automatic fallback too; unticking it retries the folder
+                let mut read_only = self.state.is_read_only();
+                if ui
+                    .checkbox(&mut read_only, "Read-only")
+                    .on_hover_text(
+                        "Only look at saves: saving, backups, restores and slot or player changes are refused",
+                    )
+                    .changed()
+                {
+                    self.state.read_only = read_only;
+                    self.check_writable();
                 }
                 ui.separator();
                 if ui.button("Create Backup Now").clicked()
                     && let Some(slot) = self.state.selected_slot_path().map(|p| p.to_path_buf())
                 {
-                    match wle_core::editor::zip_backup_slot(&slot) {
-                        Ok(_) => {
-                            self.state.status = "Backup created".into();
+                    let parent = self.state.backup_parent(&slot);
+                    match wle_core::editor::zip_backup_slot_pruned_to(
+                        &slot,
+                        &parent,
+                        self.state.retention,
+                    ) {
+                        Ok((_, pruned)) => {
+                            self.state.status = if pruned.is_empty() {
+                                "Backup created".into()
+                            } else {
+                                format!("Backup created, pruned {} old backup(s)", pruned.len())
+                            };
                             self.state.last_backup_time = Some(Local::now());
                         }
                         Err(e) => self.state.status = format!("Backup error: {}", e),
                     }
                 }
-                ui.checkbox(&mut self.state.backup_on_save, "Zip backup on save");
+                if ui.button("Backups…").clicked() {
+                    self.refresh_backups();
+                    self.state.confirm_restore = None;
+                    self.state.restore_open = true;
+                }
+                let expert = self.state.mode == EditMode::Expert;
+                let mut backup = self.state.backs_up_on_save();
+                if ui
+                    .add_enabled(
+                        !expert,
+                        egui::Checkbox::new(&mut backup, "Zip backup on save"),
+                    )
+                    .on_disabled_hover_text("Always on in expert mode")
+                    .changed()
+                {
+                    self.state.backup_on_save = backup;
+                }
                 if let Some(time) = self.state.last_backup_time {
                     ui.label(format!("Last backup: {}", time.format("%Y-%m-%d %H:%M:%S")));
                 }
+                if self.is_dirty() {
+                    ui.colored_label(ui.visuals().warn_fg_color, "● unsaved changes");
+                }
+                let errors = self
+                    .state
+                    .log
+                    .iter()
+                    .filter(|e| e.severity == wle_core::validate::Severity::Error)
+                    .count();
+                let log = if errors > 0 {
+                    format!("Log ({} ✖)", errors)
+                } else {
+                    "Log".to_string()
+                };
+                ui.toggle_value(&mut self.state.log_open, log)
+                    .on_hover_text("Every status message since the editor started");
+                ui.toggle_value(&mut self.state.debug_open, "Debug").on_hover_text(
+                    "What parsing, edits and writes did, with sizes and timings, for bug reports",
+                );
                 ui.label(&self.state.status);
             });
         });
 
+        if self.state.restore_open {
+            self.restore_window(ctx);
+        }
+        if self.state.preset_review.is_some() {
+            self.preset_window(ctx);
+        }
+        if self.state.history_open {
+            self.history_window(ctx);
+        }
+        if self.state.script_open {
+            self.script_window(ctx);
+        }
+        if self.state.debug_open {
+            self.debug_window(ctx);
+        }
+        if self.state.compare_open {
+            self.compare_window(ctx);
+        }
+        if self.state.duplicates_open {
+            self.duplicates_window(ctx);
+        }
+        if self.state.confirm_save {
+            self.save_window(ctx);
+        }
+        if self.state.confirm_delete_slot.is_some()
+            || self.state.confirm_renumber
+            || self.state.confirm_remove_player.is_some()
+            || self.state.confirm_swap_players.is_some()
+        {
+            self.slot_confirm_window(ctx);
+        }
+
         egui::SidePanel::left("left").show(ctx, |ui| {
             ui.heading("Slots");
             if let Some(root) = &self.state.root_dir {
                 ui.label(format!("Root: {}", root.display()));
             }
+            if !self.state.detected_roots.is_empty() {
+                let mut picked: Option<PathBuf> = None;
+                ui.collapsing("Detected save folders", |ui| {
+                    for c in &self.state.detected_roots {
+                        let sel = self.state.root_dir.as_ref() == Some(&c.path);
+                        if ui
+                            .selectable_label(sel, &c.label)
+                            .on_hover_text(c.path.display().to_string())
+                            .clicked()
+                        {
+                            picked = Some(c.path.clone());
+                        }
+                    }
+                });
+                if let Some(root) = picked {
+                    self.confirm_discard("switching folders", move |app| app.open_root(root));
+                }
+            }
             let mut clicked_index: Option<usize> = None;
+            let mut clone_index: Option<usize> = None;
+            let mut delete_index: Option<usize> = None;
+            let mut compare_index: Option<usize> = None;
+            let mut renumber = false;
             for (i, p) in self.state.slots.iter().enumerate() {
                 let sel = Some(i) == self.state.selected_slot;
-                if ui
-                    .selectable_label(sel, p.file_name().unwrap().to_string_lossy())
-                    .clicked()
-                {
+                let mut text = p.file_name().unwrap().to_string_lossy().into_owned();
+                let summary = self.state.slot_summaries.get(i).and_then(|s| s.as_ref());
+                if let Some(sum) = summary {
+                    text.push_str(&format!(
+                        "\n{}\n{} player(s) · {:.1} KB",
+                        sum.date_time.as_deref().unwrap_or("no SlotInfo"),
+                        sum.players.len(),
+                        sum.total_size() as f64 / 1024.0
+                    ));
+                }
+                let thumb = summary.and_then(|sum| sum.thumbnail.as_ref()).map(|img| {
+                    self.state
+                        .slot_thumbs
+                        .entry(p.clone())
+                        .or_insert_with(|| {
+                            let img = ColorImage::from_rgba_unmultiplied(
+                                [img.width, img.height],
+                                &img.rgba,
+                            );
+                            let name = format!("slot_thumb_{}", p.display());
+                            ui.ctx()
+                                .load_texture(name, img, egui::TextureOptions::LINEAR)
+                        })
+                        .clone()
+                });
+                let mut resp = ui
+                    .horizontal(|ui| {
+                        if let Some(tex) = &thumb {
+                            ui.add(egui::Image::new((tex.id(), egui::vec2(48.0, 48.0))));
+                        }
+                        ui.selectable_label(sel, text)
+                    })
+                    .inner;
+                if let Some(sum) = summary {
+                    // Size of each file, and why any of them does not parse
+                    let files: Vec<String> = sum
+                        .files
+                        .iter()
+                        .map(|f| {
+                            let size = format!("{}  {:.1} KB", f.name, f.size as f64 / 1024.0);
+                            match &f.error {
+                                Some(e) => format!("{}  ⚠ {}", size, e),
+                                None => size,
+                            }
+                        })
+                        .collect();
+                    if !sum.is_healthy() {
+                        ui.colored_label(ui.visuals().error_fg_color, "⚠ unreadable files");
+                    }
+                    resp = resp.on_hover_text(files.join("\n"));
+                }
+                if resp.clicked() {
                     clicked_index = Some(i);
                 }
+                resp.context_menu(|ui| {
+                    if Some(i) != self.state.selected_slot
+                        && self.state.session.is_some()
+                        && ui.button("Compare open document with this slot").clicked()
+                    {
+                        compare_index = Some(i);
+                        ui.close_menu();
+                    }
+                    if ui.button("Clone slot").clicked() {
+                        clone_index = Some(i);
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add_enabled(
+                            self.state.mode != EditMode::Safe,
+                            egui::Button::new("Delete slot…"),
+                        )
+                        .on_disabled_hover_text(SAFE_MODE_OFF)
+                        .clicked()
+                    {
+                        delete_index = Some(i);
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add_enabled(
+                            self.state.mode != EditMode::Safe,
+                            egui::Button::new("Renumber slots…"),
+                        )
+                        .on_disabled_hover_text(SAFE_MODE_OFF)
+                        .clicked()
+                    {
+                        renumber = true;
+                        ui.close_menu();
+                    }
+                });
             }
-            if let Some(i) = clicked_index {
-                self.state.selected_slot = Some(i);
-                self.state.clear_slot_cache();
+            if let Some(i) = clicked_index
+                && Some(i) != self.state.selected_slot
+            {
+                self.confirm_discard("switching slots", move |app| {
+                    app.state.selected_slot = Some(i);
+                    app.state.clear_slot_cache();
+                });
+            }
+            if let Some(i) = compare_index
+                && let Some(name) = self
+                    .state
+                    .current_doc_path()
+                    .and_then(|p| p.file_name().map(|n| n.to_os_string()))
+            {
+                let other = self.state.slots[i].join(&name);
+                if other.is_file() {
+                    self.open_compare(Some(other));
+                } else {
+                    self.state.status = format!(
+                        "{} has no {}",
+                        self.state.slots[i].display(),
+                        name.to_string_lossy()
+                    );
+                }
+            }
+            if let Some(i) = delete_index {
+                let slot = self.state.slots[i].clone();
+                self.confirm_discard("deleting the slot", move |app| {
+                    app.state.confirm_delete_slot = Some(slot)
+                });
+            }
+            if renumber {
+                self.confirm_discard("renumbering slots", |app| app.state.confirm_renumber = true);
+            }
+            if let Some(i) = clone_index
+                && let Some(root) = self.state.root_dir.clone()
+            {
+                let src = self.state.slots[i].clone();
+                self.confirm_discard("cloning the slot", move |app| {
+                    match wle_core::saves::clone_slot(&src, &root, None) {
+                        Ok(dest) => {
+                            app.refresh_slots();
+                            app.state.status = format!(
+                                "Cloned to {}",
+                                dest.file_name().unwrap_or_default().to_string_lossy()
+                            );
+                        }
+                        Err(e) => app.state.status = format!("Clone error: {}", e),
+                    }
+                });
             }
             ui.separator();
             ui.label("Player");
             for i in 1..=4 {
                 if ui
-                    .radio_value(&mut self.state.player, i, format!("Player {}", i))
+                    .radio(self.state.player == i, format!("Player {}", i))
                     .clicked()
+                    && self.state.player != i
                 {
-                    self.state.clear_slot_cache();
+                    self.confirm_discard("switching players", move |app| {
+                        app.state.player = i;
+                        app.state.clear_slot_cache();
+                    });
                 }
             }
+            self.player_buttons(ui);
+            self.money_widget(ui);
+            self.appearance_widget(ui);
             ui.separator();
             ui.label("Document");
-            if ui
-                .radio_value(&mut self.state.doc, DocKind::Player, "Player Data")
-                .clicked()
-            {
-                self.state.clear_slot_cache();
-            }
-            if ui
-                .radio_value(&mut self.state.doc, DocKind::Mission, "Mission Data")
-                .clicked()
-            {
-                self.state.clear_slot_cache();
-            }
-            if ui
-                .radio_value(&mut self.state.doc, DocKind::Stats, "Stats Data")
-                .clicked()
-            {
-                self.state.clear_slot_cache();
-            }
-            if ui
-                .radio_value(&mut self.state.doc, DocKind::World, "World Data")
-                .clicked()
-            {
-                self.state.clear_slot_cache();
+            for (doc, label) in [
+                (DocKind::Player, "Player Data"),
+                (DocKind::Mission, "Mission Data"),
+                (DocKind::Stats, "Stats Data"),
+                (DocKind::World, "World Data"),
+                (DocKind::SaveInfo, "Save Info (all slots)"),
+            ] {
+                if ui.radio(self.state.doc == doc, label).clicked() && self.state.doc != doc {
+                    self.confirm_discard("switching documents", move |app| {
+                        app.state.doc = doc;
+                        app.state.clear_slot_cache();
+                    });
+                }
             }
+            ui.separator();
+            self.favorites_panel(ui);
+            ui.separator();
+            ui.collapsing("Backup folder & retention", |ui| {
+                ui.horizontal(|ui| {
+                    let shown = match &self.state.backup_dir {
+                        Some(d) => d.display().to_string(),
+                        None => "next to the slots".into(),
+                    };
+                    ui.label(format!("Folder: {}", shown));
+                    if ui.small_button("Choose…").clicked()
+                        && let Some(dir) = rfd::FileDialog::new().pick_folder()
+                    {
+                        self.state.backup_dir = Some(dir);
+                        self.refresh_backups();
+                    }
+                    if self.state.backup_dir.is_some() && ui.small_button("Reset").clicked() {
+                        self.state.backup_dir = None;
+                        self.refresh_backups();
+                    }
+                });
+                let prune_dir = self
+                    .state
+                    .backup_dir
+                    .clone()
+                    .or(self.state.root_dir.clone());
+                let r = &mut self.state.retention;
+                optional_drag(ui, "Keep last", &mut r.keep_last, 10);
+                optional_drag(ui, "Max age (days)", &mut r.keep_days, 30);
+                let mut mb = r.max_total_bytes.map(|b| b / (1024 * 1024));
+                optional_drag(ui, "Max total (MB)", &mut mb, 500);
+                r.max_total_bytes = mb.map(|m| m * 1024 * 1024);
+                if r.is_unbounded() {
+                    ui.label("Backups are kept forever");
+                }
+                if ui.button("Prune now").clicked()
+                    && let Some(root) = &prune_dir
+                {
+                    self.state.status = if r.is_unbounded() {
+                        "No retention limits set".into()
+                    } else {
+                        match wle_core::editor::prune_backups(root, *r) {
+                            Ok(removed) => format!("Pruned {} backup(s)", removed.len()),
+                            Err(e) => format!("Prune error: {}", e),
+                        }
+                    };
+                }
+            });
         });
 
         egui::TopBottomPanel::bottom("bottom_panel")
@@ -271,26 +3815,50 @@ impl App for AppGui {
             .default_height(250.0)
             .show(ctx, |ui| {
                 ui.heading("Edit primitives at pointer");
+                let class = self
+                    .state
+                    .json()
+                    .and_then(|j| node_class(j, &browse_effective_ptr(j, &self.state.ptr)))
+                    .unwrap_or_default();
+                // Expert mode edits numbers without the catalog limits
+                let limited = self.state.mode != EditMode::Expert;
                 egui::ScrollArea::vertical()
                     .id_source("primitives_scroll")
                     .show(ui, |ui| {
                         for (key, val) in &mut self.state.primitive_entries {
+                            let limits = wle_core::catalog::field_info(&class, key)
+                                .filter(|_| limited);
                             ui.horizontal(|ui| {
-                                ui.label(&*key);
+                                field_label(ui, &class, key, val);
                                 match val {
                                     wle_core::JsonEditValue::Bool(b) => {
                                         ui.checkbox(b, "");
                                     }
                                     wle_core::JsonEditValue::Int(n) => {
                                         let mut v = *n;
-                                        let resp = ui.add(egui::DragValue::new(&mut v).speed(1));
-                                        if resp.changed() {
+                                        let changed = match limits {
+                                            Some(info) if !info.allowed.is_empty() => {
+                                                allowed_values(ui, key, &mut v, info)
+                                            }
+                                            _ => ui
+                                                .add(bounded(
+                                                    egui::DragValue::new(&mut v).speed(1),
+                                                    limits,
+                                                    *n as f64,
+                                                ))
+                                                .changed(),
+                                        };
+                                        if changed {
                                             *n = v;
                                         }
                                     }
                                     wle_core::JsonEditValue::Float(f) => {
                                         let mut v = *f;
-                                        let resp = ui.add(egui::DragValue::new(&mut v).speed(0.5));
+                                        let resp = ui.add(bounded(
+                                            egui::DragValue::new(&mut v).speed(0.5),
+                                            limits,
+                                            *f,
+                                        ));
                                         if resp.changed() {
                                             *f = v;
                                         }
@@ -305,98 +3873,123 @@ impl App for AppGui {
                             });
                         }
                     });
+                ui.horizontal(|ui| {
+                    let edited = !self.primitive_ops().is_empty();
+                    if ui
+                        .add_enabled(edited, egui::Button::new("Apply changes"))
+                        .on_hover_text("Apply the edited fields as one undoable step")
+                        .clicked()
+                    {
+                        let ops = self.primitive_ops();
+                        self.apply_edit(ops);
+                    }
+                    if ui
+                        .add_enabled(edited, egui::Button::new("Revert"))
+                        .clicked()
+                    {
+                        self.refresh_primitive_entries();
+                    }
+                });
                 ui.separator();
-                // Object/Array operations
-                if let Some(j) = &self.state.json {
+                // Object/Array operations, applied as undoable steps
+                let target = self.state.json().and_then(|j| {
                     let eff = browse_effective_ptr(j, &self.state.ptr);
-                    if let Some(node) = j.pointer(&eff) {
-                        if node.is_object() {
-                            ui.collapsing("Object ops", |ui| {
-                                ui.horizontal(|ui| {
-                                    ui.label("New key:");
-                                    ui.text_edit_singleline(&mut self.state.new_key);
-                                    ui.label("Value (JSON):");
-                                    ui.text_edit_singleline(&mut self.state.new_value_json);
-                                    if ui.button("Add key").clicked() {
-                                        if let Ok(val) = serde_json::from_str::<serde_json::Value>(
-                                            &self.state.new_value_json,
-                                        ) {
-                                            if let Some(j2) = &mut self.state.json {
-                                                let _ = wle_core::add_key(
-                                                    j2,
-                                                    &eff,
-                                                    &self.state.new_key,
-                                                    val,
-                                                );
-                                                self.refresh_primitive_entries();
-                                            }
-                                        } else {
+                    let node = j.pointer(&eff)?;
+                    Some((node.is_object(), node.is_array(), eff))
+                });
+                let mut ops: Option<Vec<wle_core::PatchOp>> = None;
+                let mut remove: Option<String> = None;
+                if let Some((is_object, is_array, eff)) = target {
+                    if is_object {
+                        ui.collapsing("Object ops", |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("New key:");
+                                ui.text_edit_singleline(&mut self.state.new_key);
+                                ui.label("Value (JSON):");
+                                ui.text_edit_singleline(&mut self.state.new_value_json);
+                                if ui.button("Add key").clicked() {
+                                    match serde_json::from_str::<serde_json::Value>(
+                                        &self.state.new_value_json,
+                                    ) {
+                                        Ok(value) => {
+                                            ops = Some(vec![wle_core::PatchOp::Add {
+                                                path: format!(
+                                                    "{}/{}",
+                                                    eff,
+                                                    escape_token(&self.state.new_key)
+                                                ),
+                                                value,
+                                            }]);
+                                        }
+                                        Err(_) => {
                                             self.state.status = "Invalid JSON for value".into();
                                         }
                                     }
-                                    if eff != "/root"
-                                        && !eff.is_empty()
-                                        && ui.button("Remove this node").clicked()
-                                    {
-                                        self.state.confirm_remove = Some(eff.clone());
-                                    }
-                                });
+                                }
+                                if eff != "/root"
+                                    && !eff.is_empty()
+                                    && ui
+                                        .add_enabled(
+                                            self.state.mode != EditMode::Safe,
+                                            egui::Button::new("Remove this node"),
+                                        )
+                                        .on_disabled_hover_text(SAFE_MODE_OFF)
+                                        .clicked()
+                                {
+                                    remove = Some(eff.clone());
+                                }
                             });
-                        } else if node.is_array() {
-                            ui.collapsing("Array ops", |ui| {
-                                ui.horizontal(|ui| {
-                                    ui.label("Index:");
-                                    let _ = ui.add(
-                                        egui::DragValue::new(&mut self.state.array_index).speed(1),
-                                    );
-                                    ui.label("Value (JSON):");
-                                    ui.text_edit_singleline(&mut self.state.array_value_json);
-                                    if ui.button("Insert").clicked() {
-                                        match serde_json::from_str::<serde_json::Value>(
-                                            &self.state.array_value_json,
-                                        ) {
-                                            Ok(val) => {
-                                                if let Some(j2) = &mut self.state.json {
-                                                    let _ = wle_core::array_insert(
-                                                        j2,
-                                                        &eff,
-                                                        self.state.array_index,
-                                                        val,
-                                                    );
-                                                    self.refresh_primitive_entries();
-                                                }
-                                            }
-                                            Err(_) => {
-                                                self.state.status = "Invalid JSON for value".into();
-                                            }
+                        });
+                    } else if is_array {
+                        ui.collapsing("Array ops", |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Index:");
+                                let _ = ui.add(
+                                    egui::DragValue::new(&mut self.state.array_index).speed(1),
+                                );
+                                ui.label("Value (JSON):");
+                                ui.text_edit_singleline(&mut self.state.array_value_json);
+                                let item = format!("{}/{}", eff, self.state.array_index);
+                                if ui.button("Insert").clicked() {
+                                    match serde_json::from_str::<serde_json::Value>(
+                                        &self.state.array_value_json,
+                                    ) {
+                                        Ok(value) => {
+                                            ops = Some(vec![wle_core::PatchOp::Add {
+                                                path: item.clone(),
+                                                value,
+                                            }]);
+                                        }
+                                        Err(_) => {
+                                            self.state.status = "Invalid JSON for value".into();
                                         }
                                     }
-                                    if ui.button("Remove").clicked()
-                                        && let Some(j2) = &mut self.state.json
-                                    {
-                                        let _ = wle_core::array_remove(
-                                            j2,
-                                            &eff,
-                                            self.state.array_index,
-                                        );
-                                        self.refresh_primitive_entries();
-                                    }
-                                });
+                                }
+                                if ui
+                                    .add_enabled(
+                                        self.state.mode != EditMode::Safe,
+                                        egui::Button::new("Remove"),
+                                    )
+                                    .on_disabled_hover_text(SAFE_MODE_OFF)
+                                    .clicked()
+                                {
+                                    ops = Some(vec![wle_core::PatchOp::Remove { path: item }]);
+                                }
                             });
-                        }
+                        });
                     }
                 }
+                if let Some(ops) = ops {
+                    self.apply_edit(ops);
+                }
+                if let Some(ptr) = remove {
+                    self.remove_node(ptr);
+                }
                 if let Some(ptr_to_remove) = self.state.confirm_remove.clone() {
                     ui.horizontal(|ui| {
                         ui.label(format!("Confirm removal of {}?", ptr_to_remove));
                         if ui.button("Confirm").clicked() {
-                            if let Some(j2) = &mut self.state.json {
-                                let _ = wle_core::remove_at_pointer(j2, &ptr_to_remove);
-                                self.state.ptr = parent_pointer(&ptr_to_remove)
-                                    .unwrap_or("/root")
-                                    .to_string();
-                                self.refresh_primitive_entries();
-                            }
+                            self.remove_node_now(&ptr_to_remove);
                             self.state.confirm_remove = None;
                         }
                         if ui.button("Cancel").clicked() {
@@ -408,71 +4001,148 @@ impl App for AppGui {
                 ui.separator();
                 ui.horizontal(|ui| {
                     if ui.button("Save to .sav").clicked() {
-                        self.state.confirm_save = true;
+                        self.start_save_check(ui.ctx());
+                    }
+                    let (can_undo, can_redo) = self
+                        .state
+                        .session
+                        .as_ref()
+                        .map_or((false, false), |s| (s.can_undo(), s.can_redo()));
+                    if ui
+                        .add_enabled(can_undo, egui::Button::new("Undo"))
+                        .on_hover_text("Ctrl+Z")
+                        .clicked()
+                    {
+                        self.undo();
+                    }
+                    if ui
+                        .add_enabled(can_redo, egui::Button::new("Redo"))
+                        .on_hover_text("Ctrl+Y")
+                        .clicked()
+                    {
+                        self.redo();
+                    }
+                    if ui.button("History").clicked() {
+                        self.state.history_open = !self.state.history_open;
+                    }
+                    if ui
+                        .button("Script…")
+                        .on_hover_text("Edit the document with a Rhai script")
+                        .clicked()
+                    {
+                        self.state.script_open = !self.state.script_open;
+                    }
+                    if ui
+                        .button("Compare…")
+                        .on_hover_text(
+                            "Diff the open document against the file on disk, a backup or the same file in another slot",
+                        )
+                        .clicked()
+                    {
+                        self.open_compare(None);
+                    }
+                    if ui
+                        .button("Duplicates…")
+                        .on_hover_text(
+                            "Find objects that share a GUID, or a class and position: copies a glitched save can pile up",
+                        )
+                        .clicked()
+                    {
+                        self.state.duplicates = None;
+                        self.state.duplicates_open = true;
+                    }
+                    ui.separator();
+                    if ui.button("Backup this file").clicked()
+                        && let Some(path) = self.state.current_doc_path()
+                    {
+                        self.state.status = match wle_core::editor::backup_file(&path, false) {
+                            Ok(bak) => format!(
+                                "File backup: {}",
+                                bak.file_name().unwrap_or_default().to_string_lossy()
+                            ),
+                            Err(e) => format!("File backup error: {}", e),
+                        };
+                    }
+                    if ui.button("Restore this file…").clicked()
+                        && let Some(path) = self.state.current_doc_path()
+                    {
+                        self.state.file_backups = wle_core::editor::find_file_backups(&path);
+                        self.state.file_backups_open = !self.state.file_backups_open;
                     }
                 });
-
-                if self.state.confirm_save {
-                    ui.horizontal(|ui| {
-                        ui.label("Confirm save to .sav?");
-                        if ui.button("Confirm").clicked() {
-                            let selected_slot_path =
-                                self.state.selected_slot_path().map(|p| p.to_path_buf());
-                            if let Some(j) = &mut self.state.json {
-                                let eff = browse_effective_ptr(j, &self.state.ptr);
-                                match wle_core::apply_object_primitive_updates(
-                                    j,
-                                    &eff,
-                                    &self.state.primitive_entries,
-                                ) {
-                                    Ok(_) => {
-                                        if let Some(slot) = selected_slot_path {
-                                            let path = match self.state.doc {
-                                                DocKind::Player => slot.join(format!(
-                                                    "PlayerData_{}.sav",
-                                                    self.state.player
-                                                )),
-                                                DocKind::Mission => slot.join("MissionData.sav"),
-                                                DocKind::Stats => slot.join("StatsData.sav"),
-                                                DocKind::World => slot.join("WorldData.sav"),
-                                            };
-                                            if path.exists() {
-                                                if self.state.backup_on_save {
-                                                    let _ =
-                                                        wle_core::editor::zip_backup_slot(&slot);
-                                                    self.state.last_backup_time =
-                                                        Some(Local::now());
-                                                }
-                                                match wle_core::write_binfmt_file_from_json(
-                                                    &path, j,
-                                                ) {
-                                                    Ok(_) => self.state.status = "Saved".into(),
-                                                    Err(e) => {
-                                                        self.state.status =
-                                                            format!("Save error: {}", e)
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Err(e) => self.state.status = format!("Update error: {}", e),
+                if self.state.file_backups_open {
+                    if self.state.file_backups.is_empty() {
+                        ui.label("No backups of this file yet");
+                    }
+                    let mut restore: Option<PathBuf> = None;
+                    for bak in self.state.file_backups.iter().rev() {
+                        ui.horizontal(|ui| {
+                            ui.label(bak.file_name().unwrap_or_default().to_string_lossy());
+                            if ui
+                                .add_enabled(self.state.mode != EditMode::Safe, egui::Button::new("Restore").small())
+                                .on_disabled_hover_text(SAFE_MODE_OFF)
+                                .clicked()
+                            {
+                                restore = Some(bak.clone());
+                            }
+                        });
+                    }
+                    if let Some(bak) = restore
+                        && let Some(path) = self.state.current_doc_path()
+                    {
+                        self.confirm_discard("restoring the file", move |app| {
+                            match wle_core::editor::restore_file(&bak, &path) {
+                                Ok(_) => {
+                                    app.state.status = "File restored".into();
+                                    app.state.file_backups_open = false;
+                                    app.state.clear_slot_cache();
+                                    app.state.resync_watcher();
                                 }
+                                Err(e) => app.state.status = format!("File restore error: {}", e),
                             }
-                            self.state.confirm_save = false;
-                        }
-                        if ui.button("Cancel").clicked() {
-                            self.state.confirm_save = false;
-                        }
-                    });
+                        });
+                    }
                 }
             });
 
+        if self.state.log_open {
+            self.log_panel(ctx);
+        }
+        if self.state.raw_open {
+            self.raw_panel(ctx);
+        }
+        if self.state.hex_open {
+            self.hex_panel(ctx);
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             self.ensure_loaded(ctx);
+            self.loading_bar(ui);
+            self.saving_bar(ui);
             if let Some(tex) = &self.state.image {
                 ui.image((tex.id(), tex.size_vec2()));
             }
+            self.thumbnail_buttons(ui);
+            ui.separator();
+            ui.horizontal(|ui| {
+                for (tab, label) in [
+                    (Tab::Browser, "JSON browser"),
+                    (Tab::Money, "Money"),
+                    (Tab::Missions, "Missions"),
+                    (Tab::Stats, "Stats"),
+                    (Tab::Presets, "Presets"),
+                ] {
+                    ui.selectable_value(&mut self.state.tab, tab, label);
+                }
+            });
             ui.separator();
+            match self.state.tab {
+                Tab::Browser => {}
+                Tab::Money => return self.money_tab(ui),
+                Tab::Missions => return self.missions_tab(ui),
+                Tab::Stats => return self.stats_tab(ui),
+                Tab::Presets => return self.presets_tab(ui),
+            }
             ui.collapsing("JSON Browser Controls", |ui| {
                 ui.heading("JSON Browser");
                 // Breadcrumbs
@@ -512,10 +4182,35 @@ impl App for AppGui {
 
             // Handle search/filter UI
             let mut pending_ptr_change: Option<String> = None;
+            let mut tree_action: Option<TreeAction> = None;
+            let mut pending_replace: Option<Vec<String>> = None;
 
             ui.horizontal(|ui| {
+                ui.checkbox(&mut self.state.tree_view, "Tree view");
+                ui.checkbox(&mut self.state.raw_open, "Raw JSON");
+                ui.checkbox(&mut self.state.hex_open, "Hex");
+                ui.menu_button("Export JSON…", |ui| {
+                    for (label, whole) in [("This node", false), ("Whole document", true)] {
+                        if ui.button(label).clicked() {
+                            ui.close_menu();
+                            self.export_json(whole);
+                        }
+                    }
+                });
+                ui.menu_button("Import JSON…", |ui| {
+                    for (label, whole) in [("Over this node", false), ("Whole document", true)] {
+                        if ui.button(label).clicked() {
+                            ui.close_menu();
+                            self.import_json(whole);
+                        }
+                    }
+                });
                 ui.label("Search / Filter:");
-                let search_response = ui.text_edit_singleline(&mut self.state.child_filter);
+                let search_response = ui.add(
+                    egui::TextEdit::singleline(&mut self.state.child_filter)
+                        .id(egui::Id::new(SEARCH_FIELD))
+                        .hint_text("Ctrl+F"),
+                );
                 if ui.button("Clear").clicked() {
                     self.state.child_filter.clear();
                 }
@@ -527,69 +4222,149 @@ impl App for AppGui {
                 if search_response.lost_focus()
                     && ui.input(|i| i.key_pressed(egui::Key::Enter))
                     && !self.state.child_filter.is_empty()
-                    && let Some(json) = &self.state.json
+                    && let Some(json) = self.state.json()
                 {
                     let search_results =
-                        find_key_or_value_paths(Some(json), &self.state.child_filter, 64);
+                        find_key_or_value_paths(Some(json), &self.state.child_filter, SEARCH_LIMIT);
                     if let Some(first_result) = search_results.first() {
                         pending_ptr_change = Some(first_result.clone());
                     }
                 }
             });
 
-            if let Some(v) = &self.state.json {
+            if self.state.child_filter.is_empty() {
+                self.byte_image_preview(ui);
+            }
+            if let Some(v) = self.state.json() {
                 let eff = browse_effective_ptr(v, &self.state.ptr);
 
                 if !self.state.child_filter.is_empty() {
                     // If we have a filter, show search results across the entire tree
                     let search_results =
-                        find_key_or_value_paths(Some(v), &self.state.child_filter, 64);
+                        find_key_or_value_paths(Some(v), &self.state.child_filter, SEARCH_LIMIT);
+                    let replacing = !self.state.replace_text.is_empty();
+                    // (pointer, shown value, primitive?, replacement or why not)
+                    let rows: Vec<_> = search_results
+                        .into_iter()
+                        .map(|p| {
+                            let node = v.pointer(&p).unwrap_or(&serde_json::Value::Null);
+                            let inner = node.get("$value").unwrap_or(node);
+                            let leaf = !inner.is_object() && !inner.is_array();
+                            let shown = match inner {
+                                serde_json::Value::Object(_) => "(object)".to_string(),
+                                serde_json::Value::Array(_) => "(array)".to_string(),
+                                _ => format!("= {}", wle_core::value_preview(inner)),
+                            };
+                            let replacement = (replacing && leaf)
+                                .then(|| wle_core::coerce_like(inner, &self.state.replace_text));
+                            (p, shown, leaf, replacement)
+                        })
+                        .collect();
                     ui.label(format!(
-                        "Search Results ({}) - Click path to navigate or edit values directly:",
-                        search_results.len()
+                        "Search Results ({}{}) - Click path to navigate or edit values directly:",
+                        rows.len(),
+                        if rows.len() == SEARCH_LIMIT {
+                            ", first ones only"
+                        } else {
+                            ""
+                        }
                     ));
+                    ui.horizontal(|ui| {
+                        ui.label("Replace values with:");
+                        ui.text_edit_singleline(&mut self.state.replace_text)
+                            .on_hover_text(
+                                "Each field keeps its type: true/false, a number, or text",
+                            );
+                        let checked: Vec<String> = rows
+                            .iter()
+                            .filter(|(p, _, _, r)| {
+                                matches!(r, Some(Ok(_))) && !self.state.search_unchecked.contains(p)
+                            })
+                            .map(|(p, ..)| p.clone())
+                            .collect();
+                        if ui
+                            .add_enabled(
+                                !checked.is_empty(),
+                                egui::Button::new(format!("Replace {} checked", checked.len())),
+                            )
+                            .on_hover_text("One undo step")
+                            .clicked()
+                        {
+                            pending_replace = Some(checked);
+                        }
+                        if replacing {
+                            if ui.small_button("Check all").clicked() {
+                                self.state.search_unchecked.clear();
+                            }
+                            if ui.small_button("Check none").clicked() {
+                                self.state
+                                    .search_unchecked
+                                    .extend(rows.iter().map(|(p, ..)| p.clone()));
+                            }
+                        }
+                    });
                     egui::ScrollArea::vertical()
                         .id_source("search_scroll")
                         .show(ui, |ui| {
-                            for p in &search_results {
+                            for (p, shown, leaf, replacement) in &rows {
                                 ui.horizontal(|ui| {
+                                    if let Some(r) = replacement {
+                                        let mut on =
+                                            r.is_ok() && !self.state.search_unchecked.contains(p);
+                                        let check = ui.add_enabled(
+                                            r.is_ok(),
+                                            egui::Checkbox::without_text(&mut on),
+                                        );
+                                        if check.changed() {
+                                            if on {
+                                                self.state.search_unchecked.remove(p);
+                                            } else {
+                                                self.state.search_unchecked.insert(p.clone());
+                                            }
+                                        }
+                                    }
                                     // Show the path as a clickable link
                                     if ui.link(p.as_str()).clicked() {
                                         pending_ptr_change = Some(p.clone());
                                     }
-
-                                    // Show value and allow direct editing for primitive types
-                                    if let Some(node) = v.pointer(p) {
-                                        if node.is_object() {
-                                            ui.label("(object)");
-                                        } else if node.is_array() {
-                                            ui.label("(array)");
-                                        } else {
-                                            ui.label("(value)");
-                                            // Show the current value in a compact way
-                                            let value_str = match node {
-                                                serde_json::Value::String(s) => {
-                                                    format!("\"{}\"", s)
-                                                }
-                                                serde_json::Value::Number(n) => n.to_string(),
-                                                serde_json::Value::Bool(b) => b.to_string(),
-                                                serde_json::Value::Null => "null".to_string(),
-                                                _ => "...".to_string(),
-                                            };
-                                            ui.label(format!("= {}", value_str));
-
-                                            // Show edit button for primitive values
-                                            if ui.small_button("Edit").clicked() {
-                                                pending_ptr_change = Some(p.clone());
-                                            }
+                                    ui.label(shown);
+                                    match replacement {
+                                        Some(Ok(new)) => {
+                                            ui.label(format!("→ {}", wle_core::value_preview(new)));
                                         }
+                                        Some(Err(e)) => {
+                                            ui.colored_label(ui.visuals().error_fg_color, "✖")
+                                                .on_hover_text(e);
+                                        }
+                                        None => {}
+                                    }
+                                    // Show edit button for primitive values
+                                    if *leaf && ui.small_button("Edit").clicked() {
+                                        pending_ptr_change = Some(p.clone());
                                     }
                                 });
                             }
                         });
+                } else if self.state.tree_view {
+                    let cursor = self.browse_cursor(v, &eff);
+                    egui::ScrollArea::vertical()
+                        .id_source("tree_scroll")
+                        .show(ui, |ui| {
+                            if let Some(root) = v.get("root") {
+                                let tree = TreeCtx {
+                                    doc: v,
+                                    can_paste: self.state.clipboard.is_some(),
+                                    can_remove: self.state.mode != EditMode::Safe,
+                                    cursor: cursor.as_deref().map(plain_pointer),
+                                    reveal: self.state.cursor_moved,
+                                };
+                                json_tree(ui, &tree, "root", root, "/root", &mut tree_action);
+                            }
+                        });
                 } else if let Ok(children) = wle_core::list_children(v, &eff) {
                     // Display children of current pointer (original logic)
                     ui.label("Children:");
+                    let cursor = self.browse_cursor(v, &eff);
                     egui::ScrollArea::vertical()
                         .id_source("children_scroll")
                         .show(ui, |ui| {
@@ -600,24 +4375,61 @@ impl App for AppGui {
                                     c.kind,
                                     c.len.map(|n| format!(", {}", n)).unwrap_or_default()
                                 );
-                                if ui.selectable_label(false, label).clicked() {
-                                    let tok = escape_token(&c.key_or_index);
-                                    let base = if eff.ends_with("/$value") {
-                                        eff.trim_end_matches("/$value")
-                                    } else {
-                                        eff.as_str()
-                                    };
-                                    pending_ptr_change = Some(if base == "/" || base.is_empty() {
-                                        format!("/{}", tok)
-                                    } else {
-                                        format!("{}/{}", base.trim_end_matches('/'), tok)
-                                    });
+                                let child = child_pointer(&eff, &c.key_or_index);
+                                let selected = cursor.as_ref() == Some(&child);
+                                let resp = ui.selectable_label(selected, label);
+                                if selected && self.state.cursor_moved {
+                                    resp.scroll_to_me(None);
+                                }
+                                if resp.clicked() {
+                                    pending_ptr_change = Some(child);
                                 }
                             }
                         });
                 }
             }
 
+            self.state.cursor_moved = false;
+
+            match tree_action {
+                // Leaves are edited in their object's field list
+                Some(TreeAction::Open(ptr)) => {
+                    let leaf = self
+                        .state
+                        .json()
+                        .and_then(|v| v.pointer(&ptr))
+                        .is_some_and(|n| !n.is_object() && !n.is_array());
+                    pending_ptr_change = Some(match parent_pointer(&ptr) {
+                        Some(parent) if leaf => parent.to_string(),
+                        _ => ptr,
+                    });
+                }
+                Some(TreeAction::Remove(ptr)) => self.remove_node(ptr),
+                Some(TreeAction::Duplicate(ptr)) => self.duplicate_node(&ptr),
+                Some(TreeAction::Copy(ptr)) => self.copy_node(ui.ctx(), &ptr),
+                Some(TreeAction::Pin(ptr)) => self.pin_pointer(&ptr),
+                Some(TreeAction::Paste(ptr, into)) => {
+                    if let Some(text) = self.state.clipboard.clone() {
+                        self.paste_node(&ptr, &text, into);
+                    }
+                }
+                None => {}
+            }
+            if let Some(ptrs) = pending_replace
+                && let Some(v) = self.state.json()
+            {
+                let (ops, skipped) =
+                    wle_core::coerced_replace_ops(v, &ptrs, &self.state.replace_text);
+                let n = ops.len();
+                if n == 0 {
+                    self.state.status = "Nothing to replace".into();
+                } else if self.apply_edit(ops) {
+                    self.state.status = format!("Replaced {} value(s)", n);
+                    if !skipped.is_empty() {
+                        self.state.status += &format!(", skipped {}", skipped.len());
+                    }
+                }
+            }
             // Apply any pending pointer changes
             if let Some(new_ptr) = pending_ptr_change {
                 self.state.ptr = new_ptr;
@@ -629,20 +4441,202 @@ impl App for AppGui {
     }
 }
 
+// wle-core's tracing output since launch, oldest first, for the debug console
+static DEBUG_LOG: std::sync::Mutex<std::collections::VecDeque<String>> =
+    std::sync::Mutex::new(std::collections::VecDeque::new());
+const DEBUG_LOG_LINES: usize = 5000;
+
+// One formatted event, moved into DEBUG_LOG once the subscriber has written it
+struct DebugLine(Vec<u8>);
+
+impl std::io::Write for DebugLine {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for DebugLine {
+    fn drop(&mut self) {
+        let mut log = DEBUG_LOG.lock().unwrap();
+        for line in String::from_utf8_lossy(&self.0).lines() {
+            if log.len() == DEBUG_LOG_LINES {
+                log.pop_front();
+            }
+            log.push_back(line.to_string());
+        }
+    }
+}
+
+// Debug events and span timings from wle-core only; the UI crates are noisy
+fn install_debug_log() {
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    let fmt = tracing_subscriber::fmt::layer()
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .with_ansi(false)
+        .with_writer(|| DebugLine(Vec::new()));
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::filter::Targets::new()
+                .with_target("wle_core", tracing_subscriber::filter::LevelFilter::DEBUG),
+        )
+        .with(fmt)
+        .init();
+}
+
 fn main() -> eframe::Result<()> {
+    install_debug_log();
+    let settings = Settings::load();
     let native_options = eframe::NativeOptions {
         viewport: egui::viewport::ViewportBuilder::default()
-            .with_inner_size([1200.0, 800.0])
+            .with_inner_size(settings.window_size)
             .with_min_inner_size([900.0, 600.0]),
+        // Needed for the "Follow system" theme on every platform
+        follow_system_theme: true,
         ..Default::default()
     };
     eframe::run_native(
         "Wobbly Life Editor",
         native_options,
-        Box::new(|cc| Ok(Box::new(AppGui::new(cc)))),
+        Box::new(|cc| Ok(Box::new(AppGui::new(cc, settings)))),
     )
 }
 
+// Preferences kept between launches in settings.json, next to recent.json
+#[derive(Clone, PartialEq)]
+struct Settings {
+    window_size: [f32; 2],
+    backup_on_save: bool,
+    backup_dir: Option<PathBuf>,
+    // Folder and document open when the editor was last closed
+    root_dir: Option<PathBuf>,
+    doc: DocKind,
+    retention: wle_core::editor::RetentionPolicy,
+    tree_view: bool,
+    raw_open: bool,
+    theme: Theme,
+    // egui zoom on top of the OS scale factor
+    ui_scale: f32,
+    mode: EditMode,
+    read_only: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            window_size: [1200.0, 800.0],
+            backup_on_save: true,
+            backup_dir: None,
+            root_dir: None,
+            doc: DocKind::Player,
+            retention: Default::default(),
+            tree_view: true,
+            raw_open: false,
+            theme: Theme::System,
+            ui_scale: 1.0,
+            mode: EditMode::Normal,
+            read_only: false,
+        }
+    }
+}
+
+impl Settings {
+    // A missing or unreadable file gives the defaults; bad entries are skipped
+    fn load() -> Self {
+        let v: serde_json::Value = gui_config_file("settings.json")
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|t| serde_json::from_str(&t).ok())
+            .unwrap_or_default();
+        let mut s = Settings::default();
+        let path = |k: &str| v.get(k).and_then(|x| x.as_str()).map(PathBuf::from);
+        let flag = |k: &str| v.get(k).and_then(|x| x.as_bool());
+        if let Some([w, h]) = v["window_size"]
+            .as_array()
+            .and_then(|a| a.iter().map(|x| x.as_f64()).collect::<Option<Vec<_>>>())
+            .as_deref()
+            && *w >= 300.0
+            && *h >= 200.0
+        {
+            s.window_size = [*w as f32, *h as f32];
+        }
+        s.backup_on_save = flag("backup_on_save").unwrap_or(s.backup_on_save);
+        s.backup_dir = path("backup_dir");
+        s.root_dir = path("root_dir");
+        if let Some(doc) = v["document"].as_str().and_then(DocKind::from_key) {
+            s.doc = doc;
+        }
+        let r = &v["retention"];
+        s.retention.keep_last = r["keep_last"].as_u64().map(|n| n as usize);
+        s.retention.keep_days = r["keep_days"].as_u64().map(|n| n as u32);
+        s.retention.max_total_bytes = r["max_total_bytes"].as_u64();
+        s.tree_view = flag("tree_view").unwrap_or(s.tree_view);
+        s.raw_open = flag("raw_json").unwrap_or(s.raw_open);
+        if let Some(theme) = v["theme"].as_str().and_then(Theme::from_key) {
+            s.theme = theme;
+        }
+        if let Some(scale) = v["ui_scale"].as_f64().filter(|x| (0.5..=3.0).contains(x)) {
+            s.ui_scale = scale as f32;
+        }
+        if let Some(mode) = v["edit_mode"].as_str().and_then(EditMode::from_key) {
+            s.mode = mode;
+        }
+        s.read_only = flag("read_only").unwrap_or(s.read_only);
+        s
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let Some(path) = gui_config_file("settings.json") else {
+            return Ok(());
+        };
+        let r = &self.retention;
+        let v = serde_json::json!({
+            "window_size": self.window_size,
+            "backup_on_save": self.backup_on_save,
+            "backup_dir": self.backup_dir,
+            "root_dir": self.root_dir,
+            "document": self.doc.key(),
+            "retention": {
+                "keep_last": r.keep_last,
+                "keep_days": r.keep_days,
+                "max_total_bytes": r.max_total_bytes,
+            },
+            "tree_view": self.tree_view,
+            "raw_json": self.raw_open,
+            "theme": self.theme.key(),
+            "ui_scale": self.ui_scale,
+            "edit_mode": self.mode.key(),
+            "read_only": self.read_only,
+        });
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(&v).map_err(std::io::Error::other)?;
+        std::fs::write(path, text)
+    }
+}
+
+// Checkbox-gated numeric field for optional limits
+fn optional_drag<T: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut Option<T>,
+    default: T,
+) {
+    ui.horizontal(|ui| {
+        let mut on = value.is_some();
+        if ui.checkbox(&mut on, label).changed() {
+            *value = if on { Some(default) } else { None };
+        }
+        if let Some(v) = value {
+            ui.add(egui::DragValue::new(v).speed(1).range(1..=100_000));
+        }
+    });
+}
+
 fn parent_pointer(ptr: &str) -> Option<&str> {
     if ptr.is_empty() || ptr == "/" {
         return None;
@@ -657,10 +4651,111 @@ fn parent_pointer(ptr: &str) -> Option<&str> {
     None
 }
 
+// Pointer of child `key` of the node at effective pointer `eff`, through
+// its {"$ref", "$value"} wrapper
+fn child_pointer(eff: &str, key: &str) -> String {
+    let tok = escape_token(key);
+    let base = eff.strip_suffix("/$value").unwrap_or(eff);
+    if base == "/" || base.is_empty() {
+        format!("/{}", tok)
+    } else {
+        format!("{}/{}", base.trim_end_matches('/'), tok)
+    }
+}
+
+// `ptr` without its "$value" steps, so the tree's pointers and the children
+// list's compare equal
+fn plain_pointer(ptr: &str) -> String {
+    ptr.split('/')
+        .filter(|t| *t != "$value")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 fn escape_token(s: &str) -> String {
     s.replace('~', "~0").replace('/', "~1")
 }
 
+fn write_document(path: &Path, j: &serde_json::Value) -> Result<(), wle_core::WriteError> {
+    if is_save_info(j) {
+        let root = j.get("root").cloned().unwrap_or_default();
+        let info =
+            wle_core::model::SaveInfo::from_value(root).map_err(wle_core::WriteError::Encode)?;
+        return info.save(path);
+    }
+    wle_core::write_binfmt_file_from_json(path, j)
+}
+
+// Class of the object at `ptr`; the document root carries it as `$rootClass`
+fn node_class(root: &serde_json::Value, ptr: &str) -> Option<String> {
+    let node = root.pointer(ptr)?;
+    let class = match node.get("$class") {
+        Some(c) => c,
+        None if ptr.trim_end_matches("/$value") == "/root" => root.get("$rootClass")?,
+        None => return None,
+    };
+    class.as_str().map(String::from)
+}
+
+// Friendly label from the field catalog; the raw member name stays in the tooltip
+fn field_label(ui: &mut egui::Ui, class: &str, key: &str, val: &wle_core::JsonEditValue) {
+    let Some(info) = wle_core::catalog::field_info(class, key) else {
+        ui.label(key);
+        return;
+    };
+    let num = match val {
+        wle_core::JsonEditValue::Int(n) => Some(*n as f64),
+        wle_core::JsonEditValue::Float(f) => Some(*f),
+        _ => None,
+    };
+    let tip = format!("{}\n{}", key, info.tooltip());
+    if num.is_some_and(|n| !info.in_range(n)) {
+        ui.colored_label(ui.visuals().error_fg_color, format!("{} ⚠", info.label))
+            .on_hover_text(format!("{}\nValue is out of range", tip.trim_end()));
+    } else {
+        ui.label(&info.label).on_hover_text(tip.trim_end());
+    }
+}
+
+// Catalog limits as drag bounds; a value already outside them is left as it
+// is until edited
+fn bounded<'a>(
+    drag: egui::DragValue<'a>,
+    info: Option<&wle_core::catalog::FieldInfo>,
+    current: f64,
+) -> egui::DragValue<'a> {
+    let Some(info) = info else {
+        return drag;
+    };
+    let lo = info.min.unwrap_or(f64::NEG_INFINITY);
+    let hi = info.max.unwrap_or(f64::INFINITY);
+    drag.range(lo..=hi).clamp_to_range(info.in_range(current))
+}
+
+// A choice among a field's allowed values; the current one stays listed
+// when it is not one of them
+fn allowed_values(
+    ui: &mut egui::Ui,
+    key: &str,
+    v: &mut i64,
+    info: &wle_core::catalog::FieldInfo,
+) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_source(("allowed", key))
+        .selected_text(v.to_string())
+        .show_ui(ui, |ui| {
+            let current = *v;
+            let mut values: Vec<i64> = info.allowed.iter().map(|a| *a as i64).collect();
+            if !values.contains(&current) {
+                values.push(current);
+            }
+            for a in values {
+                changed |= ui.selectable_value(v, a, a.to_string()).changed();
+            }
+        });
+    changed
+}
+
 fn browse_effective_ptr(root: &serde_json::Value, ptr: &str) -> String {
     if let Some(node) = root.pointer(ptr)
         && let Some(obj) = node.as_object()
@@ -671,6 +4766,17 @@ fn browse_effective_ptr(root: &serde_json::Value, ptr: &str) -> String {
     ptr.to_string()
 }
 
+// Central panel: the JSON browser or one of the friendly editors
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum Tab {
+    #[default]
+    Browser,
+    Money,
+    Missions,
+    Stats,
+    Presets,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
 enum DocKind {
     #[default]
@@ -678,192 +4784,568 @@ enum DocKind {
     Mission,
     Stats,
     World,
+    SaveInfo,
 }
 
-fn find_key_paths(root: Option<&serde_json::Value>, query: &str, limit: usize) -> Vec<String> {
-    use std::collections::HashSet;
-    let mut out = Vec::new();
-    let mut seen: HashSet<String> = HashSet::new();
-    if query.trim().is_empty() {
-        return out;
+impl DocKind {
+    const ALL: [DocKind; 5] = [
+        DocKind::Player,
+        DocKind::Mission,
+        DocKind::Stats,
+        DocKind::World,
+        DocKind::SaveInfo,
+    ];
+    // Name stored in settings.json
+    fn key(self) -> &'static str {
+        match self {
+            DocKind::Player => "player",
+            DocKind::Mission => "mission",
+            DocKind::Stats => "stats",
+            DocKind::World => "world",
+            DocKind::SaveInfo => "saveinfo",
+        }
     }
-    let q = query.to_lowercase();
-    // start from /root subtree if possible
-    if let Some(val) = root {
-        if let Some(sub) = val.pointer("/root") {
-            dfs_collect_parent_hits("/root", sub, &q, &mut out, &mut seen, limit);
-        } else {
-            dfs_collect_parent_hits("/root", val, &q, &mut out, &mut seen, limit);
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.key() == key)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum Theme {
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+impl Theme {
+    const ALL: [Theme; 3] = [Theme::System, Theme::Dark, Theme::Light];
+    // Name stored in settings.json
+    fn key(self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        }
+    }
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.key() == key)
+    }
+    fn label(self) -> &'static str {
+        match self {
+            Theme::System => "Follow system",
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+        }
+    }
+    // An unknown system theme falls back to dark, as eframe does
+    fn visuals(self, system: Option<eframe::Theme>) -> egui::Visuals {
+        match self {
+            Theme::System => system.unwrap_or(eframe::Theme::Dark).egui_visuals(),
+            Theme::Dark => egui::Visuals::dark(),
+            Theme::Light => egui::Visuals::light(),
         }
     }
-    out
 }
 
-fn dfs_collect_parent_hits(
-    base: &str,
-    val: &serde_json::Value,
-    q: &str,
-    out: &mut Vec<String>,
-    seen: &mut std::collections::HashSet<String>,
-    limit: usize,
-) {
-    if out.len() >= limit {
-        return;
+// How much the editor asks before acting
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum EditMode {
+    #[default]
+    Normal,
+    // Saves without a check window unless something is wrong; removals
+    // skip their confirmation (undo still has them). Backups always on
+    Expert,
+    // Nothing can be removed, deleted, renumbered or restored
+    Safe,
+}
+
+impl EditMode {
+    const ALL: [EditMode; 3] = [EditMode::Normal, EditMode::Expert, EditMode::Safe];
+    // Name stored in settings.json
+    fn key(self) -> &'static str {
+        match self {
+            EditMode::Normal => "normal",
+            EditMode::Expert => "expert",
+            EditMode::Safe => "safe",
+        }
     }
-    match val {
-        serde_json::Value::Object(map) => {
-            let mut hit = false;
-            for (k, v) in map.iter() {
-                if k.to_lowercase().contains(q) {
-                    hit = true;
-                }
-                let next = format!("{}/{}", base.trim_end_matches('/'), escape_token(k));
-                dfs_collect_parent_hits(&next, v, q, out, seen, limit);
-                if out.len() >= limit {
-                    return;
-                }
-            }
-            if hit {
-                let key = base.to_string();
-                if seen.insert(key.clone()) {
-                    out.push(key);
-                }
-            }
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.key() == key)
+    }
+    fn label(self) -> &'static str {
+        match self {
+            EditMode::Normal => "Normal",
+            EditMode::Expert => "Expert",
+            EditMode::Safe => "Safe",
         }
-        serde_json::Value::Array(arr) => {
-            for (i, v) in arr.iter().enumerate() {
-                let next = format!("{}/{}", base.trim_end_matches('/'), i);
-                dfs_collect_parent_hits(&next, v, q, out, seen, limit);
-                if out.len() >= limit {
-                    return;
-                }
+    }
+    fn hint(self) -> &'static str {
+        match self {
+            EditMode::Normal => "Ask before saving and before removing anything",
+            EditMode::Expert => {
+                "Save right away when the check finds no errors and remove nodes without asking; zip backups on save are always on"
+            }
+            EditMode::Safe => {
+                "Removing nodes or players, deleting or renumbering slots and restoring backups are turned off"
             }
         }
-        _ => {}
     }
 }
 
-fn find_value_paths(root: Option<&serde_json::Value>, query: &str, limit: usize) -> Vec<String> {
-    use std::collections::HashSet;
-    let mut out = Vec::new();
-    let mut seen: HashSet<String> = HashSet::new();
-    if query.trim().is_empty() {
-        return out;
+// Hover text of the buttons safe mode turns off
+const SAFE_MODE_OFF: &str = "Turned off in safe mode";
+
+// No presets file yet is no user presets
+fn load_user_presets(path: &Path) -> Result<Vec<wle_core::presets::Preset>, String> {
+    if path.exists() {
+        wle_core::presets::load_presets(path)
+    } else {
+        Ok(Vec::new())
     }
-    let q = query.trim();
-    let kind = if q.eq_ignore_ascii_case("true") {
-        Some(ValueMatch::Bool(true))
-    } else if q.eq_ignore_ascii_case("false") {
-        Some(ValueMatch::Bool(false))
-    } else if let Ok(i) = q.parse::<i64>() {
-        Some(ValueMatch::I64(i))
-    } else if let Ok(f) = q.parse::<f64>() {
-        Some(ValueMatch::F64(f))
+}
+
+// A preset planned for a player, waiting for Apply
+struct PresetReview {
+    preset: wle_core::presets::Preset,
+    slot: PathBuf,
+    player: u32,
+    changes: Result<Vec<wle_core::Change>, String>,
+}
+
+// A byte array of the open document guessed to be a raw image, keyed by its
+// pointer and a hash of its bytes so edits and undo make a new one
+struct ByteImage {
+    ptr: String,
+    hash: u64,
+    image: Option<(wle_core::model::SlotImage, usize)>,
+    texture: Option<TextureHandle>,
+}
+
+// Byte arrays shorter than this (an 8x8 RGB image) are not previewed
+const MIN_IMAGE_BYTES: usize = 192;
+
+// Messages kept in the log panel
+const MAX_LOG: usize = 1000;
+
+struct LogEntry {
+    time: DateTime<Local>,
+    severity: wle_core::validate::Severity,
+    text: String,
+}
+
+// Status messages do not carry a level; their wording does
+fn status_severity(text: &str) -> wle_core::validate::Severity {
+    use wle_core::validate::Severity;
+    let t = text.to_lowercase();
+    if ["error", "refused", "could not", "failed", "not a "]
+        .iter()
+        .any(|w| t.contains(w))
+    {
+        Severity::Error
+    } else if ["skipped", "warning", "⚠"].iter().any(|w| t.contains(w)) {
+        Severity::Warning
     } else {
-        Some(ValueMatch::Str(q.to_lowercase()))
+        Severity::Info
+    }
+}
+
+// Folders kept in the Recent menu
+const MAX_RECENT: usize = 10;
+
+// `name` in the GUI's folder of the platform config dir
+fn gui_config_file(name: &str) -> Option<PathBuf> {
+    wle_core::saves::user_config_dir().map(|d| d.join("wle-gui").join(name))
+}
+
+// A missing or unreadable list just starts empty
+fn load_recent() -> Vec<PathBuf> {
+    gui_config_file("recent.json")
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|t| serde_json::from_str::<Vec<PathBuf>>(&t).ok())
+        .unwrap_or_default()
+}
+
+fn save_recent(recent: &[PathBuf]) -> std::io::Result<()> {
+    let Some(path) = gui_config_file("recent.json") else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let text = serde_json::to_string_pretty(recent).map_err(std::io::Error::other)?;
+    std::fs::write(path, text)
+}
+
+// Document view (and player number) that edits a .sav of this name
+fn doc_for_file(name: &str) -> Option<(DocKind, Option<i32>)> {
+    if let Some(n) = name
+        .strip_prefix("PlayerData_")
+        .and_then(|r| r.strip_suffix(".sav"))
+        .and_then(|n| n.parse().ok())
+    {
+        return Some((DocKind::Player, Some(n)));
+    }
+    let doc = match name {
+        "MissionData.sav" => DocKind::Mission,
+        "StatsData.sav" => DocKind::Stats,
+        "WorldData.sav" => DocKind::World,
+        n if n == wle_core::model::SaveInfo::FILE_NAME => DocKind::SaveInfo,
+        _ => return None,
+    };
+    Some((doc, None))
+}
+
+// Search hits listed (and replaced) at most
+const SEARCH_LIMIT: usize = 500;
+// Id of the browser's search field, focused by Ctrl+F
+const SEARCH_FIELD: &str = "browser_search";
+
+fn find_key_or_value_paths(
+    root: Option<&serde_json::Value>,
+    query: &str,
+    limit: usize,
+) -> Vec<String> {
+    root.map(|v| wle_core::search(v, query, wle_core::SearchScope::All, limit))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|h| h.pointer)
+        .collect()
+}
+
+// Picked in the document tree, handled once the tree is drawn
+enum TreeAction {
+    Open(String),
+    Remove(String),
+    Duplicate(String),
+    Copy(String),
+    Pin(String),
+    // Paste the editor's clipboard over (false) or into (true) the node
+    Paste(String, bool),
+}
+
+// What every node of the document tree needs besides itself
+struct TreeCtx<'a> {
+    doc: &'a serde_json::Value,
+    can_paste: bool,
+    can_remove: bool,
+    // Keyboard cursor as a plain pointer, and whether to scroll to it
+    cursor: Option<String>,
+    reveal: bool,
+}
+
+// Children listed per object or array; the rest is left to the pointer box
+const TREE_MAX_CHILDREN: usize = 200;
+
+// One node of the document tree. Collapsed nodes lay out nothing below them,
+// so only the expanded part of a large save costs anything per frame.
+fn json_tree(
+    ui: &mut egui::Ui,
+    tree: &TreeCtx,
+    name: &str,
+    node: &serde_json::Value,
+    ptr: &str,
+    action: &mut Option<TreeAction>,
+) {
+    use serde_json::Value as J;
+    // {"$ref", "$value"} wrappers show as the value they hold
+    let (inner, inner_ptr) = match node.get("$value") {
+        Some(v) if node.is_object() => (v, format!("{}/$value", ptr)),
+        _ => (node, ptr.to_string()),
     };
-    if let (Some(val), Some(k)) = (root, kind) {
-        if let Some(sub) = val.pointer("/root") {
-            dfs_collect_value_hits("/root", sub, &k, &mut out, &mut seen, limit);
+    let plain = plain_pointer(ptr);
+    let at_cursor = tree.cursor.as_deref() == Some(plain.as_str());
+    // Expand the way down to a cursor that just moved
+    let reveal = (tree.reveal
+        && tree
+            .cursor
+            .as_deref()
+            .is_some_and(|c| c.starts_with(&format!("{}/", plain))))
+    .then_some(true);
+    let mark = ui.visuals().selection.bg_fill;
+    let title = |text: String| {
+        let text = egui::RichText::new(text);
+        if at_cursor {
+            text.background_color(mark)
         } else {
-            dfs_collect_value_hits("/root", val, &k, &mut out, &mut seen, limit);
+            text
+        }
+    };
+    let more = |ui: &mut egui::Ui, total: usize| {
+        if total > TREE_MAX_CHILDREN {
+            ui.weak(format!(
+                "… {} more; enter a pointer to open them",
+                total - TREE_MAX_CHILDREN
+            ));
+        }
+    };
+    let response = match inner {
+        J::Object(map) => {
+            let class = node_class(tree.doc, &inner_ptr)
+                .map(|c| format!("  {}", c.rsplit('.').next().unwrap_or(&c)))
+                .unwrap_or_default();
+            let fields = map.iter().filter(|(k, _)| !k.starts_with('$'));
+            egui::CollapsingHeader::new(title(format!("{{}} {}{}", name, class)))
+                .id_source(ptr)
+                .open(reveal)
+                .show(ui, |ui| {
+                    for (k, c) in fields.clone().take(TREE_MAX_CHILDREN) {
+                        let child = format!("{}/{}", inner_ptr, escape_token(k));
+                        json_tree(ui, tree, k, c, &child, action);
+                    }
+                    more(ui, fields.count());
+                })
+                .header_response
+        }
+        J::Array(items) => {
+            egui::CollapsingHeader::new(title(format!("[] {} ({})", name, items.len())))
+                .id_source(ptr)
+                .open(reveal)
+                .show(ui, |ui| {
+                    for (i, c) in items.iter().enumerate().take(TREE_MAX_CHILDREN) {
+                        let child = format!("{}/{}", inner_ptr, i);
+                        json_tree(ui, tree, &i.to_string(), c, &child, action);
+                    }
+                    more(ui, items.len());
+                })
+                .header_response
+        }
+        leaf => {
+            let icon = match leaf {
+                J::String(_) => "ab",
+                J::Number(_) => "#",
+                J::Bool(_) => "✔",
+                _ => "○",
+            };
+            ui.selectable_label(
+                at_cursor,
+                format!("{} {} = {}", icon, name, wle_core::value_preview(leaf)),
+            )
+        }
+    };
+    if at_cursor && tree.reveal {
+        response.scroll_to_me(None);
+    }
+    if response.clicked() && !inner.is_object() && !inner.is_array() {
+        *action = Some(TreeAction::Open(ptr.to_string()));
+    }
+    response.context_menu(|ui| {
+        if ui.button("Copy pointer").clicked() {
+            ui.ctx().copy_text(ptr.to_string());
+            ui.close_menu();
+        }
+        if ui.button("Copy value").clicked() {
+            *action = Some(TreeAction::Copy(ptr.to_string()));
+            ui.close_menu();
+        }
+        if ui.button("Pin to Favorites").clicked() {
+            *action = Some(TreeAction::Pin(ptr.to_string()));
+            ui.close_menu();
+        }
+        if ptr != "/root"
+            && ui
+                .add_enabled(tree.can_paste, egui::Button::new("Paste over"))
+                .clicked()
+        {
+            *action = Some(TreeAction::Paste(ptr.to_string(), false));
+            ui.close_menu();
+        }
+        if (inner.is_object() || inner.is_array())
+            && ui
+                .add_enabled(tree.can_paste, egui::Button::new("Paste into"))
+                .clicked()
+        {
+            *action = Some(TreeAction::Paste(ptr.to_string(), true));
+            ui.close_menu();
+        }
+        if ui.button("Edit").clicked() {
+            *action = Some(TreeAction::Open(ptr.to_string()));
+            ui.close_menu();
         }
+        if ptr != "/root" {
+            if ui.button("Duplicate").clicked() {
+                *action = Some(TreeAction::Duplicate(ptr.to_string()));
+                ui.close_menu();
+            }
+            if ui
+                .add_enabled(tree.can_remove, egui::Button::new("Remove…"))
+                .on_disabled_hover_text(SAFE_MODE_OFF)
+                .clicked()
+            {
+                *action = Some(TreeAction::Remove(ptr.to_string()));
+                ui.close_menu();
+            }
+        }
+    });
+}
+
+// Problems writing `edited` to `path` would cause that the file on disk does
+// not already have, errors first
+fn save_issues(
+    doc: DocKind,
+    path: &std::path::Path,
+    edited: &serde_json::Value,
+    cache: &wle_core::ParseCache,
+) -> Vec<wle_core::validate::Issue> {
+    use wle_core::validate::{Issue, Severity, ValidateOpts, validate_json};
+    if is_save_info(edited) {
+        return write_problems(edited, edited)
+            .into_iter()
+            .map(|message| Issue {
+                severity: Severity::Error,
+                pointer: String::new(),
+                message,
+            })
+            .collect();
     }
+    let original = std::fs::read(path)
+        .ok()
+        .and_then(|data| document_from_bytes(doc, data, cache).ok());
+    let opts = ValidateOpts { schema: true };
+    let before = original
+        .as_ref()
+        .map(|o| validate_json(o, opts))
+        .unwrap_or_default();
+    let mut out = wle_core::validate_for_write(original.as_ref().unwrap_or(edited), edited);
+    out.extend(
+        validate_json(edited, opts)
+            .into_iter()
+            .filter(|i| !before.contains(i)),
+    );
+    out.sort_by_key(|i| std::cmp::Reverse(i.severity));
     out
 }
 
-enum ValueMatch {
-    Bool(bool),
-    I64(i64),
-    F64(f64),
-    Str(String),
+// SaveInfo.sav is plain JSON; every other document is BinaryFormatter
+fn is_save_info(j: &serde_json::Value) -> bool {
+    wle_core::model::detect_kind_json(j) == wle_core::model::DocKind::SaveInfo
 }
 
-fn value_equals(v: &serde_json::Value, k: &ValueMatch) -> bool {
-    match k {
-        ValueMatch::Bool(b) => v.as_bool() == Some(*b),
-        ValueMatch::I64(i) => v.as_i64() == Some(*i),
-        ValueMatch::F64(f) => v.as_f64() == Some(*f),
-        ValueMatch::Str(s) => v
-            .as_str()
-            .map(|x| x.to_lowercase().contains(s))
-            .unwrap_or(false),
+// A document as the editor holds it, from the bytes of its file. SaveInfo
+// gets the same wrapper as binary documents so browsing starts at /root;
+// binary documents go through `cache`.
+fn document_from_bytes(
+    doc: DocKind,
+    data: Vec<u8>,
+    cache: &wle_core::ParseCache,
+) -> Result<serde_json::Value, String> {
+    if doc == DocKind::SaveInfo {
+        let text = String::from_utf8(data).map_err(|e| e.to_string())?;
+        let info = wle_core::model::SaveInfo::parse(&text)?;
+        return Ok(serde_json::json!({"$rootClass": "SaveInfo", "root": info.to_value()}));
     }
+    cache.parse(data)
 }
 
-fn dfs_collect_value_hits(
-    base: &str,
-    val: &serde_json::Value,
-    k: &ValueMatch,
-    out: &mut Vec<String>,
-    seen: &mut std::collections::HashSet<String>,
-    limit: usize,
-) -> bool {
-    if out.len() >= limit {
-        return false;
+// Write-back problems of `candidate` that `current` does not have already,
+// or a different kind of document altogether
+fn write_problems(current: &serde_json::Value, candidate: &serde_json::Value) -> Vec<String> {
+    use wle_core::validate::{Severity, ValidateOpts, validate_json};
+    let (was, now) = (
+        wle_core::model::detect_kind_json(current),
+        wle_core::model::detect_kind_json(candidate),
+    );
+    if now != was {
+        return vec![format!("a {} document cannot replace a {} one", now, was)];
     }
-    match val {
-        serde_json::Value::Object(map) => {
-            let mut hit = false;
-            for (ck, cv) in map.iter() {
-                if value_equals(cv, k) {
-                    hit = true;
+    if now == wle_core::model::DocKind::SaveInfo {
+        let root = candidate.get("root").cloned().unwrap_or_default();
+        return wle_core::model::SaveInfo::from_value(root)
+            .err()
+            .into_iter()
+            .collect();
+    }
+    let errors = |v| {
+        validate_json(v, ValidateOpts::default())
+            .into_iter()
+            .filter(|i| i.severity == Severity::Error)
+            .map(|i| {
+                if i.pointer.is_empty() {
+                    i.message
+                } else {
+                    format!("{}: {}", i.pointer, i.message)
                 }
-                let next = format!("{}/{}", base.trim_end_matches('/'), escape_token(ck));
-                if dfs_collect_value_hits(&next, cv, k, out, seen, limit) {
-                    hit = true;
+            })
+            .collect::<Vec<_>>()
+    };
+    let before = errors(current);
+    let mut after = errors(candidate);
+    after.retain(|e| !before.contains(e));
+    after.truncate(10);
+    after
+}
+
+// JSON syntax colouring for the raw pane; keys, strings, numbers and
+// true/false/null get their own colour
+fn json_highlight(ui: &egui::Ui, text: &str) -> egui::text::LayoutJob {
+    let font = egui::TextStyle::Monospace.resolve(ui.style());
+    let base = ui.visuals().text_color();
+    let dark = ui.visuals().dark_mode;
+    let pick = |d: (u8, u8, u8), l: (u8, u8, u8)| {
+        let (r, g, b) = if dark { d } else { l };
+        egui::Color32::from_rgb(r, g, b)
+    };
+    let key = pick((156, 220, 254), (4, 81, 165));
+    let string = pick((206, 145, 120), (163, 21, 21));
+    let number = pick((181, 206, 168), (9, 134, 88));
+    let literal = pick((86, 156, 214), (0, 0, 255));
+    let bytes = text.as_bytes();
+    let mut job = egui::text::LayoutJob::default();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let color = match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() {
+                    match bytes[i] {
+                        b'\\' => i += 2,
+                        b'"' => {
+                            i += 1;
+                            break;
+                        }
+                        _ => i += 1,
+                    }
                 }
-                if out.len() >= limit {
-                    break;
+                i = i.min(bytes.len());
+                if text[i..].trim_start().starts_with(':') {
+                    key
+                } else {
+                    string
                 }
             }
-            if hit && seen.insert(base.to_string()) {
-                out.push(base.to_string());
-            }
-            hit
-        }
-        serde_json::Value::Array(arr) => {
-            let mut hit_any = false;
-            for (i, el) in arr.iter().enumerate() {
-                let next = format!("{}/{}", base.trim_end_matches('/'), i);
-                let mut elem_hit = false;
-                if value_equals(el, k) {
-                    elem_hit = true;
-                }
-                if dfs_collect_value_hits(&next, el, k, out, seen, limit) {
-                    elem_hit = true;
+            b'-' | b'0'..=b'9' => {
+                i += 1;
+                while i < bytes.len()
+                    && matches!(bytes[i], b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-')
+                {
+                    i += 1;
                 }
-                if elem_hit {
-                    if seen.insert(next.clone()) {
-                        out.push(next);
-                    }
-                    hit_any = true;
+                number
+            }
+            c if c.is_ascii_alphabetic() => {
+                while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+                    i += 1;
                 }
-                if out.len() >= limit {
-                    break;
+                literal
+            }
+            // Whitespace, punctuation and anything else up to the next token
+            _ => {
+                i += 1;
+                while i < bytes.len()
+                    && !matches!(bytes[i], b'"' | b'-' | b'0'..=b'9')
+                    && !bytes[i].is_ascii_alphabetic()
+                {
+                    i += 1;
                 }
+                base
             }
-            hit_any
-        }
-        _ => value_equals(val, k),
-    }
-}
-
-fn find_key_or_value_paths(
-    root: Option<&serde_json::Value>,
-    query: &str,
-    limit: usize,
-) -> Vec<String> {
-    if query.trim().is_empty() {
-        return Vec::new();
+        };
+        job.append(
+            &text[start..i],
+            0.0,
+            egui::TextFormat::simple(font.clone(), color),
+        );
     }
-    let key_paths = find_key_paths(root, query, limit);
-    let value_paths = find_value_paths(root, query, limit);
-
-    let mut combined = key_paths;
-    combined.extend(value_paths);
-
-    use std::collections::HashSet;
-    let set: HashSet<String> = combined.into_iter().collect();
-    set.into_iter().collect()
+    job
 }
@@ -0,0 +1,80 @@
+//! wle-wasm: the byte-level part of wle-core for JavaScript
+//!
+//! Documents go in and out as JSON text in the same edit JSON `wle-cli dump`
+//! writes, so a browser editor reads a .sav into an `ArrayBuffer`, edits the
+//! JSON and hands it back for the bytes to download:
+//!
+//! ```js
+//! import init, { parseSave, setPointer, writeSave } from "wle-wasm";
+//! await init();
+//! let json = parseSave(new Uint8Array(await file.arrayBuffer()));
+//! json = setPointer(json, "/root/<MiscData>k__BackingField/$value/money", "100000");
+//! const bytes = writeSave(json);
+//! ```
+//!
+//! Errors are thrown as `Error`s with wle-core's message.
+
+use serde_json::Value as J;
+use wasm_bindgen::prelude::*;
+
+fn err(e: impl std::fmt::Display) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+fn parse_json(text: &str) -> Result<J, JsError> {
+    wle_core::json_from_bytes(text.as_bytes()).map_err(err)
+}
+
+/// The edit JSON of a .sav file's bytes.
+#[wasm_bindgen(js_name = parseSave)]
+pub fn parse_save(data: &[u8]) -> Result<String, JsError> {
+    let v = wle_core::parse_bytes_for_edit(data.to_vec()).map_err(err)?;
+    Ok(v.to_string())
+}
+
+/// The value at `pointer` as JSON text, or `undefined` if there is none.
+#[wasm_bindgen(js_name = getPointer)]
+pub fn get_pointer(json: &str, pointer: &str) -> Result<Option<String>, JsError> {
+    Ok(parse_json(json)?.pointer(pointer).map(|v| v.to_string()))
+}
+
+/// `json` with the value at `pointer` replaced by `value` (JSON text).
+#[wasm_bindgen(js_name = setPointer)]
+pub fn set_pointer(json: &str, pointer: &str, value: &str) -> Result<String, JsError> {
+    let mut doc = parse_json(json)?;
+    wle_core::set_raw_by_pointer(&mut doc, pointer, parse_json(value)?).map_err(err)?;
+    Ok(doc.to_string())
+}
+
+/// `json` with a JSON Patch (RFC 6902, as text) applied; all ops or none.
+#[wasm_bindgen(js_name = applyPatch)]
+pub fn apply_patch(json: &str, patch: &str) -> Result<String, JsError> {
+    let mut doc = parse_json(json)?;
+    let ops = wle_core::parse_patch(&parse_json(patch)?).map_err(err)?;
+    wle_core::apply_patch(&mut doc, &ops).map_err(err)?;
+    Ok(doc.to_string())
+}
+
+/// What writing `edited` would get wrong compared with `original` (the JSON
+/// `parseSave` gave), as a JSON array of `{severity, pointer, message}`.
+#[wasm_bindgen(js_name = checkWrite)]
+pub fn check_write(original: &str, edited: &str) -> Result<String, JsError> {
+    let issues = wle_core::validate_for_write(&parse_json(original)?, &parse_json(edited)?);
+    let list: Vec<J> = issues
+        .iter()
+        .map(|i| {
+            serde_json::json!({
+                "severity": i.severity.to_string(),
+                "pointer": i.pointer,
+                "message": i.message,
+            })
+        })
+        .collect();
+    Ok(J::Array(list).to_string())
+}
+
+/// The .sav bytes of an edit JSON document.
+#[wasm_bindgen(js_name = writeSave)]
+pub fn write_save(json: &str) -> Result<Vec<u8>, JsError> {
+    wle_core::write_binfmt_from_json(&parse_json(json)?).map_err(err)
+}